@@ -3,8 +3,8 @@
 use crate::handler::CompactorConfig;
 use backoff::BackoffConfig;
 use data_types::{
-    Namespace, NamespaceId, PartitionId, PartitionKey, PartitionParam, SequencerId, Table, TableId,
-    TableSchema,
+    Namespace, NamespaceId, ParquetFileId, PartitionId, PartitionKey, PartitionParam, SequencerId,
+    Table, TableId, TableSchema,
 };
 use iox_catalog::interface::{get_schema_by_id, Catalog};
 use iox_query::exec::Executor;
@@ -69,6 +69,31 @@ pub enum Error {
         source: iox_catalog::interface::Error,
         sequencer_id: SequencerId,
     },
+
+    #[snafu(display("Error listing namespaces {}", source))]
+    ListingNamespaces {
+        source: iox_catalog::interface::Error,
+    },
+
+    #[snafu(display(
+        "Error listing parquet files of namespace {:?} {}",
+        namespace_id,
+        source
+    ))]
+    ListingParquetFiles {
+        source: iox_catalog::interface::Error,
+        namespace_id: NamespaceId,
+    },
+
+    #[snafu(display(
+        "Error flagging parquet file {:?} for deletion {}",
+        parquet_file_id,
+        source
+    ))]
+    FlaggingParquetFile {
+        source: iox_catalog::interface::Error,
+        parquet_file_id: ParquetFileId,
+    },
 }
 
 /// A specialized `Error` for Compactor Data errors
@@ -345,6 +370,9 @@ impl Compactor {
     }
 
     /// Add namespace and table information to partition candidates.
+    ///
+    /// Candidates of dropped tables are skipped, as their files are flagged for deletion rather
+    /// than compacted.
     pub async fn add_info_to_partitions(
         &self,
         partitions: &[PartitionParam],
@@ -376,6 +404,10 @@ impl Compactor {
                 .await
                 .context(QueryingTableSnafu)?
                 .context(TableNotFoundSnafu { table_id: id })?;
+            if table.deleted_at.is_some() {
+                debug!(table_id = id.get(), "skipping compaction of dropped table");
+                continue;
+            }
             let schema = namespaces
                 .get(&table.namespace_id)
                 .expect("just queried")
@@ -387,8 +419,13 @@ impl Compactor {
             tables.insert(id, (Arc::new(table), Arc::new(schema)));
         }
 
+        let partitions: Vec<_> = partitions
+            .iter()
+            .filter(|p| tables.contains_key(&p.table_id))
+            .collect();
+
         let mut parts = HashMap::with_capacity(partitions.len());
-        for p in partitions {
+        for p in &partitions {
             let partition = repos
                 .partitions()
                 .get_by_id(p.partition_id)
@@ -401,7 +438,7 @@ impl Compactor {
         }
 
         Ok(partitions
-            .iter()
+            .into_iter()
             .map(|p| {
                 let (table, table_schema) = tables.get(&p.table_id).expect("just queried");
                 let part = parts.get(&p.partition_id).expect("just queried");
@@ -419,6 +456,83 @@ impl Compactor {
             })
             .collect())
     }
+
+    /// Flag the parquet files of this compactor's sequencers that only contain data older than
+    /// the retention period of their namespace, or that belong to a dropped table, for deletion,
+    /// returning the number of flagged files.
+    ///
+    /// Dropping a table flags its files at the time of the drop, but files persisted afterwards
+    /// (from data buffered by the ingester before the drop) are only flagged here.
+    ///
+    /// Flagged files are no longer queried or compacted, and are eventually removed by the
+    /// garbage collector.
+    pub async fn flag_expired_parquet_files(&self) -> Result<usize> {
+        let mut repos = self.catalog.repositories().await;
+        let now = self.time_provider.now().timestamp_nanos();
+
+        let namespaces = repos
+            .namespaces()
+            .list()
+            .await
+            .context(ListingNamespacesSnafu)?;
+
+        let mut flagged = 0;
+        for namespace in namespaces {
+            let cutoff = namespace
+                .retention_period_ns()
+                .map(|v| now.saturating_sub(v));
+
+            let dropped_table_ids: HashSet<_> = repos
+                .tables()
+                .list_by_namespace_id(namespace.id)
+                .await
+                .context(QueryingTableSnafu)?
+                .into_iter()
+                .filter(|t| t.deleted_at.is_some())
+                .map(|t| t.id)
+                .collect();
+
+            if cutoff.is_none() && dropped_table_ids.is_empty() {
+                continue;
+            }
+
+            let parquet_files = repos
+                .parquet_files()
+                .list_by_namespace_not_to_delete(namespace.id)
+                .await
+                .context(ListingParquetFilesSnafu {
+                    namespace_id: namespace.id,
+                })?;
+
+            for file in parquet_files {
+                let dropped = dropped_table_ids.contains(&file.table_id);
+                let expired = cutoff.map_or(false, |cutoff| file.max_time.get() < cutoff);
+                if !self.sequencers.contains(&file.sequencer_id) || !(dropped || expired) {
+                    continue;
+                }
+
+                repos
+                    .parquet_files()
+                    .flag_for_delete(file.id)
+                    .await
+                    .context(FlaggingParquetFileSnafu {
+                        parquet_file_id: file.id,
+                    })?;
+                flagged += 1;
+
+                debug!(
+                    namespace = %namespace.name,
+                    parquet_file_id = file.id.get(),
+                    max_time = file.max_time.get(),
+                    ?cutoff,
+                    dropped,
+                    "flagged expired parquet file for deletion",
+                );
+            }
+        }
+
+        Ok(flagged)
+    }
 }
 
 /// [`PartitionParam`] with some information about its table and namespace.
@@ -469,11 +583,11 @@ impl PartitionCompactionCandidateWithInfo {
 mod tests {
     use super::*;
     use data_types::{
-        ColumnId, ColumnSet, CompactionLevel, KafkaPartition, ParquetFileParams, SequenceNumber,
-        Timestamp,
+        ColumnId, ColumnSet, ColumnType, CompactionLevel, KafkaPartition, ParquetFileParams,
+        SequenceNumber, Timestamp,
     };
-    use iox_tests::util::TestCatalog;
-    use iox_time::SystemProvider;
+    use iox_tests::util::{TestCatalog, TestParquetFileBuilder};
+    use iox_time::{SystemProvider, Time};
     use std::time::Duration;
     use uuid::Uuid;
 
@@ -744,6 +858,122 @@ mod tests {
         ); // this sort key is Some(tag1, time)
     }
 
+    #[tokio::test]
+    async fn test_flag_expired_parquet_files() {
+        let catalog = TestCatalog::new();
+
+        // the namespace retention period is 1 year
+        let ns = catalog.create_namespace("ns").await;
+        let retention_period_ns = ns.namespace.retention_period_ns().unwrap();
+        let sequencer = ns.create_sequencer(1).await;
+        let another_sequencer = ns.create_sequencer(2).await;
+        let table = ns.create_table("table").await;
+        table.create_column("field_int", ColumnType::I64).await;
+        table.create_column("time", ColumnType::Time).await;
+        let partition = table
+            .with_sequencer(&sequencer)
+            .create_partition("part")
+            .await;
+        let another_partition = table
+            .with_sequencer(&another_sequencer)
+            .create_partition("part")
+            .await;
+
+        // data before timestamp 100 is outside of the retention period
+        catalog
+            .mock_time_provider()
+            .set(Time::from_timestamp_nanos(retention_period_ns + 100));
+
+        let compactor = Compactor::new(
+            vec![sequencer.sequencer.id],
+            Arc::clone(&catalog.catalog),
+            ParquetStorage::new(Arc::clone(&catalog.object_store)),
+            Arc::new(Executor::new(1)),
+            catalog.time_provider(),
+            BackoffConfig::default(),
+            make_compactor_config(),
+            Arc::new(metric::Registry::new()),
+        );
+
+        // flagged, all its data is expired
+        let builder = TestParquetFileBuilder::default()
+            .with_line_protocol("table field_int=1i 10\ntable field_int=2i 20")
+            .with_min_time(10)
+            .with_max_time(20);
+        let expired = partition.create_parquet_file(builder).await;
+
+        // kept, only some of its data is expired
+        let builder = TestParquetFileBuilder::default()
+            .with_line_protocol("table field_int=1i 10\ntable field_int=2i 200")
+            .with_min_time(10)
+            .with_max_time(200);
+        let partially_expired = partition.create_parquet_file(builder).await;
+
+        // kept, none of its data is expired
+        let builder = TestParquetFileBuilder::default()
+            .with_line_protocol("table field_int=1i 200")
+            .with_min_time(200)
+            .with_max_time(200);
+        let live = partition.create_parquet_file(builder).await;
+
+        // kept, its sequencer is not handled by this compactor
+        let builder = TestParquetFileBuilder::default()
+            .with_line_protocol("table field_int=1i 10")
+            .with_min_time(10)
+            .with_max_time(10);
+        let other_sequencer = another_partition.create_parquet_file(builder).await;
+
+        assert_eq!(compactor.flag_expired_parquet_files().await.unwrap(), 1);
+
+        let mut remaining: Vec<_> = catalog
+            .catalog
+            .repositories()
+            .await
+            .parquet_files()
+            .list_by_namespace_not_to_delete(ns.namespace.id)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|f| f.id)
+            .collect();
+        remaining.sort();
+        let mut expected = vec![
+            partially_expired.parquet_file.id,
+            live.parquet_file.id,
+            other_sequencer.parquet_file.id,
+        ];
+        expected.sort();
+        assert_eq!(remaining, expected);
+        assert!(!remaining.contains(&expired.parquet_file.id));
+
+        // flagging is idempotent
+        assert_eq!(compactor.flag_expired_parquet_files().await.unwrap(), 0);
+
+        // all files of this compactor's sequencers are flagged once the table is dropped
+        catalog
+            .catalog
+            .repositories()
+            .await
+            .tables()
+            .soft_delete(table.table.id)
+            .await
+            .unwrap();
+        assert_eq!(compactor.flag_expired_parquet_files().await.unwrap(), 2);
+
+        let remaining: Vec<_> = catalog
+            .catalog
+            .repositories()
+            .await
+            .parquet_files()
+            .list_by_namespace_not_to_delete(ns.namespace.id)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|f| f.id)
+            .collect();
+        assert_eq!(remaining, vec![other_sequencer.parquet_file.id]);
+    }
+
     fn make_compactor_config() -> CompactorConfig {
         let max_desired_file_size_bytes = 10_000;
         let percentage_max_file_size = 30;
@@ -268,13 +268,33 @@ impl CompactorConfig {
 /// no work to do
 const PAUSE_BETWEEN_NO_WORK: Duration = Duration::from_secs(1);
 
+/// How often to flag parquet files outside of their namespace retention period for deletion
+const RETENTION_CHECK_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
 /// Checks for candidate partitions to compact and spawns tokio tasks to compact as many
 /// as the configuration will allow. Once those are done it rechecks the catalog for the
 /// next top partitions to compact.
+///
+/// Every [`RETENTION_CHECK_INTERVAL`] the parquet files that are fully outside of their
+/// namespace retention period are flagged for deletion as well.
 async fn run_compactor(compactor: Arc<Compactor>, shutdown: CancellationToken) {
+    let mut last_retention_check = None;
+
     while !shutdown.is_cancelled() {
         debug!("compactor main loop tick.");
 
+        let now = compactor.time_provider.now();
+        let retention_check_due = last_retention_check
+            .and_then(|last| now.checked_duration_since(last))
+            .map_or(true, |elapsed| elapsed >= RETENTION_CHECK_INTERVAL);
+        if retention_check_due {
+            match compactor.flag_expired_parquet_files().await {
+                Ok(n_flagged) => debug!(n_flagged, "flagged expired parquet files"),
+                Err(e) => warn!(?e, "flagging expired parquet files failed"),
+            }
+            last_retention_check = Some(now);
+        }
+
         let mut compacted_partitions = 0;
         for _ in 0..compactor.config.hot_multiple {
             compacted_partitions += compact_hot_partitions(Arc::clone(&compactor)).await;
@@ -11,13 +11,19 @@
     clippy::clone_on_ref_ptr
 )]
 
+use chrono::{
+    format::{Item, StrftimeItems},
+    TimeZone, Utc,
+};
 use influxdb_line_protocol::FieldValue;
 use observability_deps::tracing::warn;
-use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
+use percent_encoding::{utf8_percent_encode, AsciiSet, CONTROLS, NON_ALPHANUMERIC};
+use regex::Regex;
 use schema::{
     builder::SchemaBuilder, sort::SortKey, InfluxColumnType, InfluxFieldType, Schema,
     TIME_COLUMN_NAME,
 };
+use serde::{Deserialize, Serialize};
 use snafu::{ResultExt, Snafu};
 use sqlx::postgres::PgHasArrayType;
 use std::{
@@ -147,6 +153,12 @@ impl ColumnId {
     }
 }
 
+impl std::fmt::Display for ColumnId {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
 impl PgHasArrayType for ColumnId {
     fn array_type_info() -> sqlx::postgres::PgTypeInfo {
         <i64 as PgHasArrayType>::array_type_info()
@@ -413,6 +425,69 @@ pub struct Namespace {
     pub max_tables: i32,
     /// The maximum number of columns per table in this namespace
     pub max_columns_per_table: i32,
+    /// When the namespace was soft deleted, if it was. Soft deleted namespaces are hidden from
+    /// queries and writes until they are restored.
+    #[sqlx(default)]
+    pub deleted_at: Option<Timestamp>,
+    /// The template used to derive the partition key of rows written to this namespace, or
+    /// `None` to use the default template.
+    #[sqlx(default)]
+    pub partition_template: Option<PartitionTemplate>,
+}
+
+impl Namespace {
+    /// The retention period of this namespace in nanoseconds, or `None` if data is kept
+    /// forever.
+    ///
+    /// An unparsable `retention_duration` is logged and treated as infinite, so that a bad
+    /// catalog value never causes data to be dropped.
+    pub fn retention_period_ns(&self) -> Option<i64> {
+        let duration = self.retention_duration.as_deref()?;
+        match parse_retention_duration(duration) {
+            Ok(period) => period,
+            Err(e) => {
+                warn!(namespace=%self.name, %e, "invalid namespace retention duration, keeping data forever");
+                None
+            }
+        }
+    }
+}
+
+#[derive(Debug, Snafu)]
+#[allow(missing_docs)]
+pub enum RetentionDurationError {
+    #[snafu(display("Invalid retention duration '{}': {}", duration, source))]
+    InvalidDuration {
+        duration: String,
+        source: humantime::DurationError,
+    },
+
+    #[snafu(display("Retention duration '{}' is too large", duration))]
+    DurationTooLarge { duration: String },
+
+    #[snafu(display("Retention duration must be greater than zero"))]
+    ZeroDuration,
+}
+
+/// Parse a namespace retention duration such as `"30d"` or `"1h 30m"` into nanoseconds.
+///
+/// `inf` and the empty string mean data is kept forever and return `None`.
+pub fn parse_retention_duration(duration: &str) -> Result<Option<i64>, RetentionDurationError> {
+    let duration = duration.trim();
+    if duration.is_empty() || duration == "inf" {
+        return Ok(None);
+    }
+
+    let parsed = humantime::parse_duration(duration).context(InvalidDurationSnafu { duration })?;
+    let nanos =
+        i64::try_from(parsed.as_nanos()).map_err(|_| RetentionDurationError::DurationTooLarge {
+            duration: duration.to_string(),
+        })?;
+    if nanos == 0 {
+        return Err(RetentionDurationError::ZeroDuration);
+    }
+
+    Ok(Some(nanos))
 }
 
 /// Schema collection for a namespace. This is an in-memory object useful for a schema
@@ -427,16 +502,27 @@ pub struct NamespaceSchema {
     pub query_pool_id: QueryPoolId,
     /// the tables in the namespace by name
     pub tables: BTreeMap<String, TableSchema>,
+    /// the retention period of the namespace in nanoseconds, `None` keeps data forever
+    pub retention_period_ns: Option<i64>,
+    /// the partition template of the namespace, `None` uses the default template
+    pub partition_template: Option<PartitionTemplate>,
 }
 
 impl NamespaceSchema {
     /// Create a new `NamespaceSchema`
-    pub fn new(id: NamespaceId, kafka_topic_id: KafkaTopicId, query_pool_id: QueryPoolId) -> Self {
+    pub fn new(
+        id: NamespaceId,
+        kafka_topic_id: KafkaTopicId,
+        query_pool_id: QueryPoolId,
+        retention_period_ns: Option<i64>,
+    ) -> Self {
         Self {
             id,
             tables: BTreeMap::new(),
             kafka_topic_id,
             query_pool_id,
+            retention_period_ns,
+            partition_template: None,
         }
     }
 
@@ -448,6 +534,22 @@ impl NamespaceSchema {
                 .iter()
                 .map(|(k, v)| size_of_val(k) + k.capacity() + v.size())
                 .sum::<usize>()
+            + self
+                .partition_template
+                .as_ref()
+                .map(|t| t.size())
+                .unwrap_or_default()
+    }
+
+    /// The partition template for writes to `table_name`: the template of the table if it
+    /// has one, falling back to the template of the namespace.
+    ///
+    /// Returns `None` if neither has a template, in which case the default template applies.
+    pub fn partition_template_for(&self, table_name: &str) -> Option<&PartitionTemplate> {
+        self.tables
+            .get(table_name)
+            .and_then(|t| t.partition_template.as_ref())
+            .or(self.partition_template.as_ref())
     }
 }
 
@@ -460,6 +562,14 @@ pub struct Table {
     pub namespace_id: NamespaceId,
     /// The name of the table, which is unique within the associated namespace
     pub name: String,
+    /// When the table was dropped, if it was. Dropped tables are hidden from the namespace
+    /// schema, and their name can be used by a new table.
+    #[sqlx(default)]
+    pub deleted_at: Option<Timestamp>,
+    /// The template used to derive the partition key of rows written to this table, overriding
+    /// the template of its namespace, if set.
+    #[sqlx(default)]
+    pub partition_template: Option<PartitionTemplate>,
 }
 
 /// Column definitions for a table
@@ -469,6 +579,8 @@ pub struct TableSchema {
     pub id: TableId,
     /// the table's columns by their name
     pub columns: BTreeMap<String, ColumnSchema>,
+    /// the partition template of the table, `None` uses the namespace template
+    pub partition_template: Option<PartitionTemplate>,
 }
 
 impl TableSchema {
@@ -477,6 +589,16 @@ impl TableSchema {
         Self {
             id,
             columns: BTreeMap::new(),
+            partition_template: None,
+        }
+    }
+
+    /// Initialize new `TableSchema` for `table`, without any columns.
+    pub fn new_for_table(table: &Table) -> Self {
+        Self {
+            id: table.id,
+            columns: BTreeMap::new(),
+            partition_template: table.partition_template.clone(),
         }
     }
 
@@ -503,6 +625,11 @@ impl TableSchema {
                 .iter()
                 .map(|(k, v)| size_of_val(k) + k.capacity() + size_of_val(v))
                 .sum::<usize>()
+            + self
+                .partition_template
+                .as_ref()
+                .map(|t| t.size())
+                .unwrap_or_default()
     }
 
     /// Create `ID->name` map for columns.
@@ -525,6 +652,10 @@ pub struct Column {
     pub name: String,
     /// the logical type of the column
     pub column_type: i16,
+    /// When the column was dropped, if it was. Dropped columns are hidden from the table
+    /// schema, and their name can be used by a new column.
+    #[sqlx(default)]
+    pub deleted_at: Option<Timestamp>,
 }
 
 impl Column {
@@ -735,6 +866,13 @@ pub struct Sequencer {
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct PartitionKey(Arc<str>);
 
+impl PartitionKey {
+    /// Returns the underlying string.
+    pub fn inner(&self) -> &str {
+        &self.0
+    }
+}
+
 impl Display for PartitionKey {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.write_str(&self.0)
@@ -806,6 +944,12 @@ pub struct Partition {
     /// happens when the data of this partition is first persisted (must at least include `time` column if no tags)
     /// and, later on, when data with new tags are ingested into this partition
     pub sort_key: Vec<String>,
+    /// The template the partition key was derived with, if all data written to the partition is
+    /// known to have been partitioned with it. `None` means the template is unknown, either
+    /// because the partition predates templates or because data partitioned with a different
+    /// template was written to it, and the key must not be interpreted.
+    #[sqlx(default)]
+    pub partition_template: Option<PartitionTemplate>,
 }
 
 impl Partition {
@@ -1106,15 +1250,206 @@ impl ChunkOrder {
 ///
 /// The key is constructed in order of the template parts; thus ordering changes
 /// what partition key is generated.
-#[derive(Debug, Default, Eq, PartialEq, Clone)]
+///
+/// Templates are stored in the catalog as JSONB.
+#[derive(Debug, Default, Eq, PartialEq, Clone, Serialize, Deserialize)]
 #[allow(missing_docs)]
 pub struct PartitionTemplate {
     pub parts: Vec<TemplatePart>,
 }
 
+#[derive(Debug, Snafu, PartialEq, Eq)]
+#[allow(missing_docs)]
+pub enum PartitionTemplateError {
+    #[snafu(display("partition template must have at least one part"))]
+    NoParts,
+
+    #[snafu(display("partition template column name must not be empty"))]
+    EmptyColumnName,
+
+    #[snafu(display("invalid partition template time format '{}'", format))]
+    InvalidTimeFormat { format: String },
+
+    #[snafu(display("invalid partition template regex '{}': {}", regex, reason))]
+    InvalidRegex { regex: String, reason: String },
+}
+
+impl PartitionTemplate {
+    /// Check that every part of this template can be rendered, so that an
+    /// invalid template is rejected when it is set rather than when a write
+    /// is partitioned.
+    pub fn validate(&self) -> Result<(), PartitionTemplateError> {
+        if self.parts.is_empty() {
+            return Err(PartitionTemplateError::NoParts);
+        }
+
+        for part in &self.parts {
+            match part {
+                TemplatePart::Table => {}
+                TemplatePart::Column(name) => validate_column_name(name)?,
+                TemplatePart::TimeFormat(format) => validate_time_format(format)?,
+                // The regex is compiled when the template is constructed
+                TemplatePart::RegexCapture(RegexCapture { column, .. }) => {
+                    validate_column_name(column)?
+                }
+                TemplatePart::StrftimeColumn(StrftimeColumn { column, format }) => {
+                    validate_column_name(column)?;
+                    validate_time_format(format)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Recover the value of each [`TemplatePart::Column`] of this template from
+    /// `key`, a partition key this template generated for rows of `table_name`.
+    ///
+    /// Returns the column names and values in template order, with a `None`
+    /// value for a column that was null or missing in the partitioned rows.
+    ///
+    /// Values are not escaped when a partition key is rendered, so `None` is
+    /// returned if the key cannot be split unambiguously (for example because
+    /// a value contains a `-`), or if the key does not match this template.
+    pub fn column_values<'a, 'k>(
+        &'a self,
+        table_name: &str,
+        key: &'k str,
+    ) -> Option<Vec<(&'a str, Option<&'k str>)>> {
+        // The byte offsets of each "-" separated segment of the key.
+        let mut segments = Vec::new();
+        let mut start = 0;
+        for (idx, _) in key.match_indices('-') {
+            segments.push((start, idx));
+            start = idx + 1;
+        }
+        segments.push((start, key.len()));
+
+        let mut values = Vec::new();
+        let mut next = 0;
+        for part in &self.parts {
+            // The number of segments this part renders to, which is fixed for
+            // everything but the column values themselves.
+            let n = match part {
+                TemplatePart::Table => table_name.matches('-').count() + 1,
+                TemplatePart::Column(name) => name.matches('-').count() + 1,
+                TemplatePart::TimeFormat(format) => {
+                    let mut rendered = String::new();
+                    write!(
+                        rendered,
+                        "{}",
+                        Utc.timestamp_nanos(0)
+                            .format_with_items(StrftimeItems::new(format))
+                    )
+                    .ok()?;
+                    rendered.matches('-').count() + 1
+                }
+                TemplatePart::RegexCapture(_) | TemplatePart::StrftimeColumn(_) => return None,
+            };
+
+            let (start, _) = *segments.get(next)?;
+            let (_, end) = *segments.get(next + n - 1)?;
+            let rendered = &key[start..end];
+            next += n;
+
+            match part {
+                TemplatePart::Table if rendered != table_name => return None,
+                TemplatePart::Column(name) if rendered == name => {
+                    values.push((name.as_str(), None))
+                }
+                TemplatePart::Column(name) => {
+                    let value = rendered.strip_prefix(name.as_str())?.strip_prefix('_')?;
+                    values.push((name.as_str(), Some(value)));
+                }
+                _ => {}
+            }
+        }
+
+        // Any unconsumed segments mean a value contained a "-".
+        if next != segments.len() {
+            return None;
+        }
+
+        Some(values)
+    }
+
+    /// Estimated size in bytes including `self`.
+    pub fn size(&self) -> usize {
+        size_of_val(self)
+            + self
+                .parts
+                .iter()
+                .map(|p| {
+                    size_of_val(p)
+                        + match p {
+                            TemplatePart::Table => 0,
+                            TemplatePart::Column(s) | TemplatePart::TimeFormat(s) => s.capacity(),
+                            TemplatePart::RegexCapture(RegexCapture {
+                                column,
+                                regex,
+                                replacement,
+                            }) => column.capacity() + regex.size() + replacement.capacity(),
+                            TemplatePart::StrftimeColumn(StrftimeColumn { column, format }) => {
+                                column.capacity() + format.capacity()
+                            }
+                        }
+                })
+                .sum::<usize>()
+    }
+}
+
+fn validate_column_name(name: &str) -> Result<(), PartitionTemplateError> {
+    if name.is_empty() {
+        return Err(PartitionTemplateError::EmptyColumnName);
+    }
+    Ok(())
+}
+
+/// Reject empty `strftime` formats and formats containing unknown specifiers,
+/// which would otherwise fail to render.
+fn validate_time_format(format: &str) -> Result<(), PartitionTemplateError> {
+    if format.is_empty() || StrftimeItems::new(format).any(|i| matches!(i, Item::Error)) {
+        return Err(PartitionTemplateError::InvalidTimeFormat {
+            format: format.to_string(),
+        });
+    }
+    Ok(())
+}
+
+impl<DB> sqlx::Type<DB> for PartitionTemplate
+where
+    DB: sqlx::Database<TypeInfo = sqlx::postgres::PgTypeInfo>,
+{
+    fn type_info() -> DB::TypeInfo {
+        // Store this type as JSONB
+        sqlx::postgres::PgTypeInfo::with_name("JSONB")
+    }
+}
+
+impl sqlx::Encode<'_, sqlx::Postgres> for PartitionTemplate {
+    fn encode_by_ref(
+        &self,
+        buf: &mut <sqlx::Postgres as sqlx::database::HasArguments<'_>>::ArgumentBuffer,
+    ) -> sqlx::encode::IsNull {
+        <sqlx::types::Json<&Self> as sqlx::Encode<sqlx::Postgres>>::encode(
+            sqlx::types::Json(self),
+            buf,
+        )
+    }
+}
+
+impl sqlx::Decode<'_, sqlx::Postgres> for PartitionTemplate {
+    fn decode(
+        value: <sqlx::Postgres as sqlx::database::HasValueRef<'_>>::ValueRef,
+    ) -> Result<Self, Box<dyn std::error::Error + 'static + Send + Sync>> {
+        Ok(<sqlx::types::Json<Self> as sqlx::Decode<sqlx::Postgres>>::decode(value)?.0)
+    }
+}
+
 /// `TemplatePart` specifies what part of a row should be used to compute this
 /// part of a partition key.
-#[derive(Debug, Eq, PartialEq, Clone)]
+#[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum TemplatePart {
     /// The name of a table
     Table,
@@ -1126,7 +1461,8 @@ pub enum TemplatePart {
     /// partition key parts such as "2021-03-14 12:25:21" and
     /// "2021-04-14 12:24:21"
     TimeFormat(String),
-    /// Applies a regex to the value in a string column
+    /// Applies a regex to the value in a string or tag column, rendering the
+    /// captures of the first match with a replacement string
     RegexCapture(RegexCapture),
     /// Applies a `strftime` pattern to some column other than "time"
     StrftimeColumn(StrftimeColumn),
@@ -1134,11 +1470,79 @@ pub enum TemplatePart {
 
 /// `RegexCapture` is for pulling parts of a string column into the partition
 /// key.
-#[derive(Debug, Eq, PartialEq, Clone)]
+///
+/// The `replacement` is expanded with the captures of the first match of
+/// `regex` in the value of the named string or tag column, using the syntax of
+/// [`regex::Captures::expand`] (`$1`, `${name}`). For example, a regex of
+/// `^([a-z]+)-(\d+)` and replacement `$1` render the value "web-42" as
+/// "host_web" for a column named "host".
+///
+/// Like [`TemplatePart::Column`], the column name alone is rendered if the
+/// value is null, the column is missing or not a string or tag column, or the
+/// regex does not match.
+#[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize)]
 #[allow(missing_docs)]
 pub struct RegexCapture {
     pub column: String,
-    pub regex: String,
+    pub regex: TemplateRegex,
+    pub replacement: String,
+}
+
+/// The compiled regex of a [`RegexCapture`].
+///
+/// The regex is compiled once when the template is constructed or
+/// deserialised, so an invalid regex is rejected when a template is set or
+/// loaded from the catalog rather than when a write is partitioned. Compared
+/// and serialised as its pattern.
+#[derive(Debug, Clone)]
+pub struct TemplateRegex(Regex);
+
+impl TemplateRegex {
+    /// Compile `pattern`.
+    pub fn new(pattern: &str) -> Result<Self, PartitionTemplateError> {
+        Regex::new(pattern)
+            .map(Self)
+            .map_err(|e| PartitionTemplateError::InvalidRegex {
+                regex: pattern.to_string(),
+                reason: e.to_string(),
+            })
+    }
+
+    /// The pattern this regex was compiled from.
+    pub fn as_str(&self) -> &str {
+        self.0.as_str()
+    }
+
+    /// The compiled regex.
+    pub fn regex(&self) -> &Regex {
+        &self.0
+    }
+
+    /// Estimated size in bytes excluding `self`, counting only the pattern.
+    fn size(&self) -> usize {
+        self.as_str().len()
+    }
+}
+
+impl PartialEq for TemplateRegex {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl Eq for TemplateRegex {}
+
+impl Serialize for TemplateRegex {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for TemplateRegex {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let pattern = String::deserialize(deserializer)?;
+        Self::new(&pattern).map_err(serde::de::Error::custom)
+    }
 }
 
 /// [`StrftimeColumn`] is used to create a time based partition key off some
@@ -1150,7 +1554,11 @@ pub struct RegexCapture {
 /// For example, a time format of "%Y-%m-%d %H:%M:%S" will produce
 /// partition key parts such as "2021-03-14 12:25:21" and
 /// "2021-04-14 12:24:21"
-#[derive(Debug, Eq, PartialEq, Clone)]
+///
+/// The column must be the time column or an integer column holding
+/// nanoseconds since the epoch. The column name alone is rendered if the value
+/// is null, or the column is missing or of another type.
+#[derive(Debug, Eq, PartialEq, Clone, Serialize, Deserialize)]
 #[allow(missing_docs)]
 pub struct StrftimeColumn {
     pub column: String,
@@ -1348,6 +1756,47 @@ pub fn org_and_bucket_to_database<'a, O: AsRef<str>, B: AsRef<str>>(
     DatabaseName::new(db_name).context(InvalidDatabaseNameSnafu)
 }
 
+#[derive(Debug, Snafu)]
+#[allow(missing_docs)]
+pub enum DbRpMappingError {
+    #[snafu(display("Invalid database name: {}", source))]
+    InvalidName { source: DatabaseNameError },
+
+    #[snafu(display("missing db value"))]
+    DbNotSpecified,
+}
+
+/// Characters percent encoded in the database and retention policy names of
+/// [`db_and_rp_to_database`]
+const DB_RP_ENCODE_SET: &AsciiSet = &CONTROLS.add(b'/').add(b'%');
+
+/// Map an InfluxDB 1.X database & retention policy into an IOx DatabaseName.
+///
+/// The default retention policy (empty or `autogen`) maps to the name of the
+/// database, and others to `db/rp`. `/` and `%` are percent encoded in both
+/// names to keep the mapping unambiguous.
+pub fn db_and_rp_to_database<'a, D: AsRef<str>, R: AsRef<str>>(
+    db: D,
+    rp: R,
+) -> Result<DatabaseName<'a>, DbRpMappingError> {
+    const SEPARATOR: char = '/';
+
+    let db: Cow<'_, str> = utf8_percent_encode(db.as_ref(), DB_RP_ENCODE_SET).into();
+    if db.is_empty() {
+        return Err(DbRpMappingError::DbNotSpecified);
+    }
+
+    let db_name = match rp.as_ref() {
+        "" | "autogen" => db.into_owned(),
+        rp => {
+            let rp: Cow<'_, str> = utf8_percent_encode(rp, DB_RP_ENCODE_SET).into();
+            format!("{}{}{}", db, SEPARATOR, rp)
+        }
+    };
+
+    DatabaseName::new(db_name).context(InvalidNameSnafu)
+}
+
 /// A string that cannot be empty
 ///
 /// This is particularly useful for types that map to/from protobuf, where string fields
@@ -2451,6 +2900,36 @@ mod tests {
         assert_eq!(got.as_str(), "my%255Forg%5F_bucket");
     }
 
+    #[test]
+    fn test_db_rp_map_db() {
+        let got = db_and_rp_to_database("telegraf", "").unwrap();
+        assert_eq!(got.as_str(), "telegraf");
+
+        let got = db_and_rp_to_database("telegraf", "autogen").unwrap();
+        assert_eq!(got.as_str(), "telegraf");
+
+        let got = db_and_rp_to_database("my_db", "one_week").unwrap();
+        assert_eq!(got.as_str(), "my_db/one_week");
+    }
+
+    #[test]
+    fn test_db_rp_map_db_encodes_separator() {
+        let got = db_and_rp_to_database("a/b", "").unwrap();
+        assert_eq!(got.as_str(), "a%2Fb");
+
+        let got = db_and_rp_to_database("a", "b/c").unwrap();
+        assert_eq!(got.as_str(), "a/b%2Fc");
+
+        let got = db_and_rp_to_database("a%2Fb", "").unwrap();
+        assert_eq!(got.as_str(), "a%252Fb");
+    }
+
+    #[test]
+    fn test_db_rp_map_db_not_specified() {
+        let err = db_and_rp_to_database("", "autogen").unwrap_err();
+        assert!(matches!(err, DbRpMappingError::DbNotSpecified));
+    }
+
     #[test]
     fn test_bad_database_name_is_encoded() {
         let got = org_and_bucket_to_database("org", "bucket?").unwrap();
@@ -3240,6 +3719,7 @@ mod tests {
         let schema1 = TableSchema {
             id: TableId::new(1),
             columns: BTreeMap::from([]),
+            partition_template: None,
         };
         let schema2 = TableSchema {
             id: TableId::new(2),
@@ -3250,6 +3730,7 @@ mod tests {
                     column_type: ColumnType::Bool,
                 },
             )]),
+            partition_template: None,
         };
         assert!(schema1.size() < schema2.size());
     }
@@ -3260,17 +3741,244 @@ mod tests {
             id: NamespaceId::new(1),
             kafka_topic_id: KafkaTopicId::new(2),
             query_pool_id: QueryPoolId::new(3),
+            retention_period_ns: None,
             tables: BTreeMap::from([]),
+            partition_template: None,
         };
         let schema2 = NamespaceSchema {
             id: NamespaceId::new(1),
             kafka_topic_id: KafkaTopicId::new(2),
             query_pool_id: QueryPoolId::new(3),
+            retention_period_ns: None,
             tables: BTreeMap::from([(String::from("foo"), TableSchema::new(TableId::new(1)))]),
+            partition_template: None,
         };
         assert!(schema1.size() < schema2.size());
     }
 
+    #[test]
+    fn test_parse_retention_duration() {
+        assert_eq!(parse_retention_duration("inf").unwrap(), None);
+        assert_eq!(parse_retention_duration("").unwrap(), None);
+        assert_eq!(
+            parse_retention_duration("1h").unwrap(),
+            Some(3_600_000_000_000)
+        );
+        assert_eq!(
+            parse_retention_duration("1d 30m").unwrap(),
+            Some(88_200_000_000_000)
+        );
+
+        assert!(matches!(
+            parse_retention_duration("forever"),
+            Err(RetentionDurationError::InvalidDuration { .. })
+        ));
+        assert!(matches!(
+            parse_retention_duration("0s"),
+            Err(RetentionDurationError::ZeroDuration)
+        ));
+        assert!(matches!(
+            parse_retention_duration("1000years"),
+            Err(RetentionDurationError::DurationTooLarge { .. })
+        ));
+    }
+
+    #[test]
+    fn test_namespace_retention_period_ns() {
+        let mut namespace = Namespace {
+            id: NamespaceId::new(1),
+            name: "ns".to_string(),
+            retention_duration: None,
+            kafka_topic_id: KafkaTopicId::new(2),
+            query_pool_id: QueryPoolId::new(3),
+            max_tables: 10,
+            max_columns_per_table: 10,
+            deleted_at: None,
+            partition_template: None,
+        };
+        assert_eq!(namespace.retention_period_ns(), None);
+
+        namespace.retention_duration = Some("2s".to_string());
+        assert_eq!(namespace.retention_period_ns(), Some(2_000_000_000));
+
+        // invalid values keep data forever rather than dropping it
+        namespace.retention_duration = Some("bananas".to_string());
+        assert_eq!(namespace.retention_period_ns(), None);
+    }
+
+    #[test]
+    fn test_partition_template_validate() {
+        let template = |parts| PartitionTemplate { parts };
+
+        template(vec![
+            TemplatePart::Table,
+            TemplatePart::Column("region".to_string()),
+            TemplatePart::TimeFormat("%Y-%m-%d %H".to_string()),
+        ])
+        .validate()
+        .expect("valid template");
+
+        assert_eq!(
+            template(vec![]).validate(),
+            Err(PartitionTemplateError::NoParts)
+        );
+        assert_eq!(
+            template(vec![TemplatePart::Column(String::new())]).validate(),
+            Err(PartitionTemplateError::EmptyColumnName)
+        );
+        assert_eq!(
+            template(vec![TemplatePart::TimeFormat("%Y-%Q".to_string())]).validate(),
+            Err(PartitionTemplateError::InvalidTimeFormat {
+                format: "%Y-%Q".to_string()
+            })
+        );
+        assert_eq!(
+            template(vec![TemplatePart::TimeFormat(String::new())]).validate(),
+            Err(PartitionTemplateError::InvalidTimeFormat {
+                format: String::new()
+            })
+        );
+
+        template(vec![
+            TemplatePart::RegexCapture(RegexCapture {
+                column: "host".to_string(),
+                regex: TemplateRegex::new("^([a-z]+)").unwrap(),
+                replacement: "$1".to_string(),
+            }),
+            TemplatePart::StrftimeColumn(StrftimeColumn {
+                column: "created".to_string(),
+                format: "%Y".to_string(),
+            }),
+        ])
+        .validate()
+        .expect("valid template");
+
+        assert!(matches!(
+            TemplateRegex::new("([a-z]+"),
+            Err(PartitionTemplateError::InvalidRegex { regex, .. }) if regex == "([a-z]+"
+        ));
+        assert_eq!(
+            template(vec![TemplatePart::RegexCapture(RegexCapture {
+                column: String::new(),
+                regex: TemplateRegex::new("(.*)").unwrap(),
+                replacement: "$1".to_string(),
+            })])
+            .validate(),
+            Err(PartitionTemplateError::EmptyColumnName)
+        );
+        assert_eq!(
+            template(vec![TemplatePart::StrftimeColumn(StrftimeColumn {
+                column: "created".to_string(),
+                format: "%Q".to_string(),
+            })])
+            .validate(),
+            Err(PartitionTemplateError::InvalidTimeFormat {
+                format: "%Q".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn test_partition_template_column_values() {
+        let template = PartitionTemplate {
+            parts: vec![
+                TemplatePart::Table,
+                TemplatePart::TimeFormat("%Y-%m-%d".to_string()),
+                TemplatePart::Column("region".to_string()),
+                TemplatePart::Column("host-name".to_string()),
+            ],
+        };
+
+        assert_eq!(
+            template.column_values("my-table", "my-table-2022-08-01-region_west-host-name_a"),
+            Some(vec![("region", Some("west")), ("host-name", Some("a"))])
+        );
+
+        // Null or missing column values
+        assert_eq!(
+            template.column_values("my-table", "my-table-2022-08-01-region-host-name"),
+            Some(vec![("region", None), ("host-name", None)])
+        );
+
+        // Empty string values
+        assert_eq!(
+            template.column_values("my-table", "my-table-2022-08-01-region_-host-name"),
+            Some(vec![("region", Some("")), ("host-name", None)])
+        );
+
+        // A value containing a "-" cannot be recovered
+        assert_eq!(
+            template.column_values("my-table", "my-table-2022-08-01-region_us-west-host-name"),
+            None
+        );
+
+        // Keys generated by other templates or for other tables are rejected
+        assert_eq!(template.column_values("my-table", "2022-08-01"), None);
+        assert_eq!(
+            template.column_values("other", "my-table-2022-08-01-region_west-host-name_a"),
+            None
+        );
+        assert_eq!(
+            template.column_values("my-table", "my-table-2022-08-01-host_a-region_west"),
+            None
+        );
+
+        // Formats expanding to several "-" separated segments
+        let template = PartitionTemplate {
+            parts: vec![
+                TemplatePart::Column("region".to_string()),
+                TemplatePart::TimeFormat("%F".to_string()),
+            ],
+        };
+        assert_eq!(
+            template.column_values("t", "region_west-2022-08-01"),
+            Some(vec![("region", Some("west"))])
+        );
+    }
+
+    #[test]
+    fn test_partition_template_serde() {
+        let template = PartitionTemplate {
+            parts: vec![
+                TemplatePart::Table,
+                TemplatePart::Column("region".to_string()),
+                TemplatePart::TimeFormat("%Y-%m-%d".to_string()),
+            ],
+        };
+
+        let json = serde_json::to_string(&template).unwrap();
+        assert_eq!(
+            json,
+            r#"{"parts":["table",{"column":"region"},{"time_format":"%Y-%m-%d"}]}"#
+        );
+        assert_eq!(
+            serde_json::from_str::<PartitionTemplate>(&json).unwrap(),
+            template
+        );
+
+        let template = PartitionTemplate {
+            parts: vec![TemplatePart::RegexCapture(RegexCapture {
+                column: "host".to_string(),
+                regex: TemplateRegex::new("^([a-z]+)").unwrap(),
+                replacement: "$1".to_string(),
+            })],
+        };
+        let json = serde_json::to_string(&template).unwrap();
+        assert_eq!(
+            json,
+            r#"{"parts":[{"regex_capture":{"column":"host","regex":"^([a-z]+)","replacement":"$1"}}]}"#
+        );
+        assert_eq!(
+            serde_json::from_str::<PartitionTemplate>(&json).unwrap(),
+            template
+        );
+
+        // An invalid regex fails to load
+        let json = r#"{"parts":[{"regex_capture":{"column":"host","regex":"([a-z]+","replacement":"$1"}}]}"#;
+        let err = serde_json::from_str::<PartitionTemplate>(json).unwrap_err();
+        assert!(err.to_string().contains("invalid partition template regex"));
+    }
+
     #[test]
     #[should_panic = "timestamp wraparound"]
     fn test_timestamp_wraparound_panic_add_i64() {
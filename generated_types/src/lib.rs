@@ -90,6 +90,19 @@ pub mod influxdata {
             }
         }
 
+        pub mod partition_template {
+            pub mod v1 {
+                include!(concat!(
+                    env!("OUT_DIR"),
+                    "/influxdata.iox.partition_template.v1.rs"
+                ));
+                include!(concat!(
+                    env!("OUT_DIR"),
+                    "/influxdata.iox.partition_template.v1.serde.rs"
+                ));
+            }
+        }
+
         pub mod predicate {
             pub mod v1 {
                 include!(concat!(env!("OUT_DIR"), "/influxdata.iox.predicate.v1.rs"));
@@ -107,6 +120,29 @@ pub mod influxdata {
                     env!("OUT_DIR"),
                     "/influxdata.iox.querier.v1.serde.rs"
                 ));
+
+                impl QueryParam {
+                    /// A parameter bound to the `$name` placeholders, NULL
+                    /// if `value` is `None`
+                    pub fn named(
+                        name: impl Into<String>,
+                        value: Option<query_param::Value>,
+                    ) -> Self {
+                        Self {
+                            name: name.into(),
+                            value,
+                        }
+                    }
+
+                    /// A parameter bound to the next positional placeholder,
+                    /// NULL if `value` is `None`
+                    pub fn positional(value: Option<query_param::Value>) -> Self {
+                        Self {
+                            name: String::new(),
+                            value,
+                        }
+                    }
+                }
             }
         }
 
@@ -235,6 +271,8 @@ pub mod delete_predicate;
 #[cfg(any(feature = "data_types_conversions", test))]
 pub mod ingester;
 #[cfg(any(feature = "data_types_conversions", test))]
+pub mod partition_template;
+#[cfg(any(feature = "data_types_conversions", test))]
 pub mod write_info;
 
 pub use prost::{DecodeError, EncodeError};
@@ -0,0 +1,126 @@
+//! Conversions between the protobuf and [`data_types`] representations of a
+//! partition template.
+
+use crate::google::{FieldViolation, FieldViolationExt, FromRepeatedField, OptionalField};
+use crate::influxdata::iox::partition_template::v1 as proto;
+use data_types::{PartitionTemplate, RegexCapture, StrftimeColumn, TemplatePart, TemplateRegex};
+use proto::template_part::Part;
+
+impl From<PartitionTemplate> for proto::PartitionTemplate {
+    fn from(template: PartitionTemplate) -> Self {
+        Self {
+            parts: template.parts.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl TryFrom<proto::PartitionTemplate> for PartitionTemplate {
+    type Error = FieldViolation;
+
+    fn try_from(value: proto::PartitionTemplate) -> Result<Self, Self::Error> {
+        Ok(Self {
+            parts: value.parts.repeated("parts")?,
+        })
+    }
+}
+
+impl From<TemplatePart> for proto::TemplatePart {
+    fn from(part: TemplatePart) -> Self {
+        let part = match part {
+            TemplatePart::Table => Part::Table(pbjson_types::Empty {}),
+            TemplatePart::Column(column) => Part::Column(column),
+            TemplatePart::TimeFormat(format) => Part::TimeFormat(format),
+            TemplatePart::RegexCapture(RegexCapture {
+                column,
+                regex,
+                replacement,
+            }) => Part::RegexCapture(proto::RegexCapture {
+                column,
+                regex: regex.as_str().to_string(),
+                replacement,
+            }),
+            TemplatePart::StrftimeColumn(StrftimeColumn { column, format }) => {
+                Part::StrftimeColumn(proto::StrftimeColumn { column, format })
+            }
+        };
+
+        Self { part: Some(part) }
+    }
+}
+
+impl TryFrom<proto::TemplatePart> for TemplatePart {
+    type Error = FieldViolation;
+
+    fn try_from(value: proto::TemplatePart) -> Result<Self, Self::Error> {
+        Ok(match value.part.unwrap_field("part")? {
+            Part::Table(_) => Self::Table,
+            Part::Column(column) => Self::Column(column),
+            Part::TimeFormat(format) => Self::TimeFormat(format),
+            Part::RegexCapture(proto::RegexCapture {
+                column,
+                regex,
+                replacement,
+            }) => Self::RegexCapture(RegexCapture {
+                column,
+                regex: TemplateRegex::new(&regex).scope("regex_capture.regex")?,
+                replacement,
+            }),
+            Part::StrftimeColumn(proto::StrftimeColumn { column, format }) => {
+                Self::StrftimeColumn(StrftimeColumn { column, format })
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_roundtrip() {
+        let template = PartitionTemplate {
+            parts: vec![
+                TemplatePart::Table,
+                TemplatePart::Column("region".to_string()),
+                TemplatePart::TimeFormat("%Y-%m-%d".to_string()),
+                TemplatePart::RegexCapture(RegexCapture {
+                    column: "host".to_string(),
+                    regex: TemplateRegex::new("^([a-z]+)").unwrap(),
+                    replacement: "$1".to_string(),
+                }),
+                TemplatePart::StrftimeColumn(StrftimeColumn {
+                    column: "ts".to_string(),
+                    format: "%Y".to_string(),
+                }),
+            ],
+        };
+
+        let encoded = proto::PartitionTemplate::from(template.clone());
+        let decoded = PartitionTemplate::try_from(encoded).unwrap();
+        assert_eq!(template, decoded);
+    }
+
+    #[test]
+    fn test_missing_part() {
+        let encoded = proto::PartitionTemplate {
+            parts: vec![proto::TemplatePart { part: None }],
+        };
+        let err = PartitionTemplate::try_from(encoded).unwrap_err();
+        assert_eq!(err.field, "parts.0.part");
+    }
+
+    #[test]
+    fn test_invalid_regex() {
+        let encoded = proto::PartitionTemplate {
+            parts: vec![proto::TemplatePart {
+                part: Some(Part::RegexCapture(proto::RegexCapture {
+                    column: "host".to_string(),
+                    regex: "([a-z]+".to_string(),
+                    replacement: "$1".to_string(),
+                })),
+            }],
+        };
+        let err = PartitionTemplate::try_from(encoded).unwrap_err();
+        assert_eq!(err.field, "parts.0.regex_capture.regex");
+    }
+}
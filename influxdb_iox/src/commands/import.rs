@@ -0,0 +1,39 @@
+//! This module implements the `import` CLI command
+
+use futures::Future;
+use influxdb_iox_client::connection::Connection;
+use thiserror::Error;
+
+mod tsm;
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Error in tsm subcommand: {0}")]
+    Tsm(#[from] tsm::Error),
+}
+
+/// Import data from other databases
+#[derive(Debug, clap::Parser)]
+pub struct Config {
+    #[clap(subcommand)]
+    command: Command,
+}
+
+/// All possible subcommands for import
+#[derive(Debug, clap::Parser)]
+enum Command {
+    /// Import the TSM files of InfluxDB 1.x or 2.x
+    Tsm(tsm::Config),
+}
+
+pub async fn command<C, CFut>(connection: C, config: Config) -> Result<(), Error>
+where
+    C: Send + FnOnce() -> CFut,
+    CFut: Send + Future<Output = Connection>,
+{
+    match config.command {
+        Command::Tsm(config) => tsm::command(connection, config).await?,
+    }
+
+    Ok(())
+}
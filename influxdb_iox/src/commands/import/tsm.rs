@@ -0,0 +1,509 @@
+//! This module implements the `import tsm` CLI subcommand
+
+use crate::commands::write::{
+    columns::{to_mutable_batch, Values},
+    write_tables,
+};
+use futures::Future;
+use influxdb_iox_client::{connection::Connection, error::Error as ClientError, write};
+use influxdb_tsm::{
+    mapper::{ColumnData, MeasurementTable, TableSection, TsmMeasurementMapper},
+    reader::{TsmBlockReader, TsmIndexReader},
+    BlockType, TsmError,
+};
+use mutable_batch::MutableBatch;
+use observability_deps::tracing::info;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    ffi::OsStr,
+    fs::File,
+    io::BufReader,
+    iter::Peekable,
+    path::{Path, PathBuf},
+};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Error reading {:?}: {}", path, source)]
+    Reading {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+
+    #[error("No .tsm files found in {:?}", path)]
+    NoTsmFiles { path: PathBuf },
+
+    #[error(
+        "Deletes of {:?} are recorded in {:?}, which the import cannot apply. \
+         Compact the shard in InfluxDB before importing it",
+        path,
+        tombstone
+    )]
+    Tombstone { path: PathBuf, tombstone: PathBuf },
+
+    #[error("Error reading TSM file {:?}: {}", path, source)]
+    ReadingTsm { path: PathBuf, source: TsmError },
+
+    #[error(
+        "Error converting measurement {} of {:?}: {}",
+        measurement,
+        path,
+        source
+    )]
+    Converting {
+        path: PathBuf,
+        measurement: String,
+        source: mutable_batch::Error,
+    },
+
+    #[error("Error writing measurement {} of {:?}: {}", measurement, path, source)]
+    Writing {
+        path: PathBuf,
+        measurement: String,
+        source: ClientError,
+    },
+}
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// Import the TSM files of InfluxDB 1.x or 2.x
+///
+/// Each measurement is written to a table with a tag column for each of its
+/// tag keys and a field column for each of its field keys. Series of 2.x
+/// files from all organizations and buckets are written to the same
+/// namespace.
+///
+/// Files with deletes recorded in a `.tombstone` file are rejected, as the
+/// deleted data would be written again.
+#[derive(Debug, clap::Parser)]
+pub struct Config {
+    /// A TSM file, or a directory searched for .tsm files, such as the data
+    /// directory of an InfluxDB shard
+    #[clap(action)]
+    path: PathBuf,
+
+    /// The namespace to write to
+    #[clap(long, action)]
+    namespace: String,
+
+    /// Read the files and report the tables, columns and number of rows the
+    /// import would write, without connecting to IOx
+    #[clap(long, action)]
+    dry_run: bool,
+
+    /// Maximum number of rows of one write request
+    #[clap(long, default_value = "100000", action)]
+    max_batch_rows: usize,
+
+    /// Number of times a write request is retried when the router is
+    /// unavailable or overloaded before the import gives up
+    #[clap(long, default_value = "10", action)]
+    max_retries: usize,
+}
+
+pub async fn command<C, CFut>(connection: C, config: Config) -> Result<()>
+where
+    C: Send + FnOnce() -> CFut,
+    CFut: Send + Future<Output = Connection>,
+{
+    let files = tsm_files(&config.path)?;
+    if files.is_empty() {
+        return Err(Error::NoTsmFiles { path: config.path });
+    }
+    for path in &files {
+        check_tombstone(path)?;
+    }
+
+    let client = if config.dry_run {
+        None
+    } else {
+        Some(write::Client::new(connection().await))
+    };
+
+    let mut summary = BTreeMap::new();
+    for path in &files {
+        let mut file = TsmFile::open(path, config.max_batch_rows)?;
+        while let Some(table) = file.next_table() {
+            let mut table = table?;
+            let table_summary = summary
+                .entry(table.name.clone())
+                .or_insert_with(TableSummary::default);
+            table_summary.add_columns(&table);
+
+            // each batch is written before the next one is read
+            let name = table.name.clone();
+            for batch in file.batches(&mut table) {
+                let batch = batch?;
+                table_summary.rows += batch.rows();
+
+                let client = match &client {
+                    Some(client) => client,
+                    None => continue,
+                };
+                let tables = std::iter::once((name.clone(), batch)).collect();
+                let rows = write_tables(client, &config.namespace, tables, config.max_retries)
+                    .await
+                    .map_err(|source| Error::Writing {
+                        path: path.clone(),
+                        measurement: name.clone(),
+                        source,
+                    })?;
+                info!(?path, measurement=%name, rows, "wrote batch");
+            }
+        }
+        println!("{}", path.display());
+    }
+
+    println!();
+    for (name, table) in &summary {
+        println!("{}", name);
+        println!("  rows:   {}", table.rows);
+        println!(
+            "  tags:   {}",
+            table.tags.iter().cloned().collect::<Vec<_>>().join(", ")
+        );
+        println!(
+            "  fields: {}",
+            table
+                .fields
+                .iter()
+                .map(|(name, block_type)| format!("{} ({})", name, type_name(*block_type)))
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+    }
+
+    let rows: usize = summary.values().map(|table| table.rows).sum();
+    if config.dry_run {
+        println!(
+            "Dry run: {} rows in {} tables from {} files would be written",
+            rows,
+            summary.len(),
+            files.len()
+        );
+    } else {
+        println!(
+            "{} rows in {} tables from {} files written",
+            rows,
+            summary.len(),
+            files.len()
+        );
+    }
+
+    Ok(())
+}
+
+/// `path` if it is a file, or else the .tsm files in the directory tree at
+/// `path`, sorted by path so the files of a shard are written from the
+/// oldest generation to the newest
+fn tsm_files(path: &Path) -> Result<Vec<PathBuf>> {
+    let reading = |path: &Path| {
+        let path = path.to_path_buf();
+        move |source| Error::Reading { path, source }
+    };
+
+    if std::fs::metadata(path).map_err(reading(path))?.is_file() {
+        return Ok(vec![path.to_path_buf()]);
+    }
+
+    let mut files = vec![];
+    let mut dirs = vec![path.to_path_buf()];
+    while let Some(dir) = dirs.pop() {
+        for entry in std::fs::read_dir(&dir).map_err(reading(&dir))? {
+            let path = entry.map_err(reading(&dir))?.path();
+            if path.is_dir() {
+                dirs.push(path);
+            } else if path.extension() == Some(OsStr::new("tsm")) {
+                files.push(path);
+            }
+        }
+    }
+    files.sort();
+
+    Ok(files)
+}
+
+/// Fails if deletes of the TSM file at `path` are recorded in a tombstone
+/// file next to it
+fn check_tombstone(path: &Path) -> Result<()> {
+    let tombstone = path.with_extension("tombstone");
+    if tombstone.exists() {
+        return Err(Error::Tombstone {
+            path: path.to_path_buf(),
+            tombstone,
+        });
+    }
+    Ok(())
+}
+
+/// What was read of one measurement of all files
+#[derive(Debug, Default)]
+struct TableSummary {
+    rows: usize,
+    tags: BTreeSet<String>,
+    fields: BTreeMap<String, BlockType>,
+}
+
+impl TableSummary {
+    fn add_columns(&mut self, table: &MeasurementTable) {
+        self.tags.extend(table.tag_columns().into_iter().cloned());
+        self.fields.extend(
+            table
+                .field_columns()
+                .iter()
+                .map(|(name, block_type)| (name.clone(), *block_type)),
+        );
+    }
+}
+
+fn type_name(block_type: BlockType) -> &'static str {
+    match block_type {
+        BlockType::Float => "float",
+        BlockType::Integer => "integer",
+        BlockType::Bool => "boolean",
+        BlockType::Str => "string",
+        BlockType::Unsigned => "unsigned integer",
+    }
+}
+
+/// Reads a TSM file one measurement at a time
+struct TsmFile {
+    path: PathBuf,
+    mapper: TsmMeasurementMapper<BufReader<File>>,
+    block_reader: TsmBlockReader<BufReader<File>>,
+    max_rows: usize,
+}
+
+impl TsmFile {
+    fn open(path: &Path, max_rows: usize) -> Result<Self> {
+        let open = || {
+            File::open(path).map_err(|source| Error::Reading {
+                path: path.to_path_buf(),
+                source,
+            })
+        };
+
+        let file = open()?;
+        let len = file
+            .metadata()
+            .map_err(|source| Error::Reading {
+                path: path.to_path_buf(),
+                source,
+            })?
+            .len();
+        let index_reader =
+            TsmIndexReader::try_new(BufReader::new(file), len as usize).map_err(|source| {
+                Error::ReadingTsm {
+                    path: path.to_path_buf(),
+                    source,
+                }
+            })?;
+        let index: Peekable<_> = index_reader.peekable();
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            mapper: TsmMeasurementMapper::new(index, 0),
+            block_reader: TsmBlockReader::new(BufReader::new(open()?)),
+            max_rows: max_rows.max(1),
+        })
+    }
+
+    /// The next measurement of the file, without its data
+    fn next_table(&mut self) -> Option<Result<MeasurementTable>> {
+        let table = self.mapper.next()?.map_err(|source| Error::ReadingTsm {
+            path: self.path.clone(),
+            source,
+        });
+        Some(table)
+    }
+
+    /// Decodes the rows of `table` one series at a time, returning a batch
+    /// once it reaches the maximum batch size. Series that don't fit in the
+    /// current batch are split, so at most one batch and one series are held
+    /// in memory.
+    fn batches<'a>(
+        &'a mut self,
+        table: &'a mut MeasurementTable,
+    ) -> impl Iterator<Item = Result<MutableBatch>> + 'a {
+        let path = &self.path;
+        let max_rows = self.max_rows;
+        let measurement = table.name.clone();
+        let mut sections = table.sections(&mut self.block_reader);
+        let converting = move |source| Error::Converting {
+            path: path.clone(),
+            measurement: measurement.clone(),
+            source,
+        };
+
+        // a series and the number of its rows already returned in batches
+        let mut pending: Option<(MutableBatch, usize)> = None;
+        let mut done = false;
+
+        std::iter::from_fn(move || {
+            if done {
+                return None;
+            }
+
+            let mut batch = MutableBatch::new();
+            let result = loop {
+                if batch.rows() >= max_rows {
+                    break Ok(());
+                }
+
+                let (section, offset) = match pending.take() {
+                    Some(pending) => pending,
+                    None => match sections.next() {
+                        Some(Ok(section)) => match section_batch(&section) {
+                            Ok(section) => (section, 0),
+                            Err(source) => break Err(converting(source)),
+                        },
+                        Some(Err(source)) => {
+                            break Err(Error::ReadingTsm {
+                                path: path.clone(),
+                                source,
+                            })
+                        }
+                        None => {
+                            done = true;
+                            break Ok(());
+                        }
+                    },
+                };
+
+                let end = section.rows().min(offset + max_rows - batch.rows());
+                if let Err(source) = batch.extend_from_range(&section, offset..end) {
+                    break Err(converting(source));
+                }
+                if end < section.rows() {
+                    pending = Some((section, end));
+                }
+            };
+
+            match result {
+                Ok(()) => (batch.rows() > 0).then(|| Ok(batch)),
+                Err(e) => {
+                    done = true;
+                    Some(Err(e))
+                }
+            }
+        })
+    }
+}
+
+/// Converts the rows of one series to a batch
+fn section_batch(section: &TableSection) -> Result<MutableBatch, mutable_batch::Error> {
+    let rows = section.len();
+
+    let tags = section
+        .tag_cols
+        .iter()
+        .map(|(key, value)| (key.as_str(), Values::Tag(vec![Some(value.clone()); rows])));
+    let fields = section.field_cols.iter().map(|(name, data)| {
+        let values = match data {
+            ColumnData::Float(values) => Values::F64(values.clone()),
+            ColumnData::Integer(values) => Values::I64(values.clone()),
+            ColumnData::Unsigned(values) => Values::U64(values.clone()),
+            ColumnData::Bool(values) => Values::Bool(values.clone()),
+            ColumnData::Str(values) => Values::String(
+                values
+                    .iter()
+                    .map(|value| {
+                        value
+                            .as_ref()
+                            .map(|value| String::from_utf8_lossy(value).into_owned())
+                    })
+                    .collect(),
+            ),
+        };
+        (name.as_str(), values)
+    });
+    let columns = tags.chain(fields).collect::<Vec<_>>();
+
+    to_mutable_batch(
+        &section.ts,
+        columns.iter().map(|(name, values)| (*name, values)),
+    )
+    .map_err(|source| mutable_batch::Error::WriterError { source })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::read::GzDecoder;
+
+    #[test]
+    fn reads_measurements_in_batches() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("shard").join("000000005-000000002.tsm");
+        std::fs::create_dir(path.parent().unwrap()).unwrap();
+        std::fs::write(dir.path().join("notes.txt"), "not a tsm file").unwrap();
+
+        let mut decoder = GzDecoder::new(
+            File::open("../test_fixtures/000000000000005-000000002.tsm.gz").unwrap(),
+        );
+        std::io::copy(&mut decoder, &mut File::create(&path).unwrap()).unwrap();
+
+        assert_eq!(tsm_files(dir.path()).unwrap(), vec![path.clone()]);
+
+        let mut file = TsmFile::open(&path, 100).unwrap();
+        let mut summary = BTreeMap::new();
+        while let Some(table) = file.next_table() {
+            let mut table = table.unwrap();
+            let batches = file
+                .batches(&mut table)
+                .collect::<Result<Vec<_>>>()
+                .unwrap();
+
+            // series are split so that batches are full, except the last one
+            let (last, full) = batches.split_last().unwrap();
+            assert!(last.rows() > 0 && last.rows() <= 100);
+            assert!(full.iter().all(|batch| batch.rows() == 100));
+
+            let table_summary = summary
+                .entry(table.name.clone())
+                .or_insert_with(TableSummary::default);
+            table_summary.add_columns(&table);
+            table_summary.rows += batches.iter().map(|batch| batch.rows()).sum::<usize>();
+        }
+        assert_eq!(summary.len(), 121);
+
+        let cpu = &summary["cpu"];
+        let fields = cpu.fields.keys().map(String::as_str).collect::<Vec<_>>();
+        assert_eq!(
+            fields,
+            vec![
+                "usage_guest",
+                "usage_guest_nice",
+                "usage_idle",
+                "usage_iowait",
+                "usage_irq",
+                "usage_nice",
+                "usage_softirq",
+                "usage_steal",
+                "usage_system",
+                "usage_user",
+            ]
+        );
+        assert!(cpu.rows > 0);
+        assert_eq!(cpu.fields["usage_idle"], BlockType::Float);
+
+        assert!(check_tombstone(&path).is_ok());
+        std::fs::write(path.with_extension("tombstone"), "").unwrap();
+        assert!(matches!(
+            check_tombstone(&path),
+            Err(Error::Tombstone { .. })
+        ));
+    }
+
+    #[test]
+    fn reports_missing_files() {
+        let dir = tempfile::tempdir().unwrap();
+
+        assert!(tsm_files(dir.path()).unwrap().is_empty());
+        assert!(matches!(
+            tsm_files(&dir.path().join("missing")),
+            Err(Error::Reading { .. })
+        ));
+    }
+}
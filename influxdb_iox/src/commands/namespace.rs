@@ -0,0 +1,237 @@
+//! This module implements the `namespace` CLI command
+
+use influxdb_iox_client::{
+    connection::Connection,
+    namespace::{self, generated_types::PartitionTemplate},
+};
+use thiserror::Error;
+
+#[allow(clippy::enum_variant_names)]
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("JSON Serialization error: {0}")]
+    Serde(#[from] serde_json::Error),
+
+    #[error("Client error: {0}")]
+    ClientError(#[from] influxdb_iox_client::error::Error),
+}
+
+/// Manage IOx namespaces
+#[derive(Debug, clap::Parser)]
+pub struct Config {
+    #[clap(subcommand)]
+    command: Command,
+}
+
+/// Create a new namespace
+#[derive(Debug, clap::Parser)]
+struct Create {
+    /// The name of the namespace to create
+    #[clap(action)]
+    namespace: String,
+
+    /// How long to keep data for, for example "30d". Data is kept forever by default.
+    #[clap(long = "--retention", default_value = "inf", action)]
+    retention: String,
+
+    /// The template used to derive partition keys, as JSON. For example
+    /// '{"parts": [{"timeFormat": "%Y-%m-%d %H"}, {"column": "region"}]}' partitions data by
+    /// hour and region. Data is partitioned by day by default.
+    #[clap(long = "--partition-template", value_parser = parse_partition_template)]
+    partition_template: Option<PartitionTemplate>,
+}
+
+/// Update the retention duration of a namespace
+#[derive(Debug, clap::Parser)]
+struct Retention {
+    /// The name of the namespace to update
+    #[clap(action)]
+    namespace: String,
+
+    /// How long to keep data for, for example "30d", or "inf" to keep it forever
+    #[clap(action)]
+    retention: String,
+}
+
+/// Update the table and/or column limits of a namespace
+#[derive(Debug, clap::Parser)]
+struct Limits {
+    /// The name of the namespace to update
+    #[clap(action)]
+    namespace: String,
+
+    /// The maximum number of tables in the namespace
+    #[clap(long = "--max-tables", action)]
+    max_tables: Option<i32>,
+
+    /// The maximum number of columns per table in the namespace
+    #[clap(long = "--max-columns-per-table", action)]
+    max_columns_per_table: Option<i32>,
+}
+
+/// Soft delete a namespace, hiding it from queries and writes until it is restored
+#[derive(Debug, clap::Parser)]
+struct Delete {
+    /// The name of the namespace to delete
+    #[clap(action)]
+    namespace: String,
+}
+
+/// Restore a soft deleted namespace
+#[derive(Debug, clap::Parser)]
+struct Restore {
+    /// The name of the namespace to restore
+    #[clap(action)]
+    namespace: String,
+}
+
+/// Drop a table, hiding it from queries and deleting its data. Later writes to the table name
+/// create a new, empty table.
+#[derive(Debug, clap::Parser)]
+struct DropTable {
+    /// The name of the namespace containing the table
+    #[clap(action)]
+    namespace: String,
+
+    /// The name of the table to drop
+    #[clap(action)]
+    table: String,
+}
+
+/// Drop a column, hiding it from queries. Later writes to the column name create a new column,
+/// which may have a different type.
+#[derive(Debug, clap::Parser)]
+struct DropColumn {
+    /// The name of the namespace containing the table
+    #[clap(action)]
+    namespace: String,
+
+    /// The name of the table containing the column
+    #[clap(action)]
+    table: String,
+
+    /// The name of the column to drop
+    #[clap(action)]
+    column: String,
+}
+
+/// Set the partition template of a table, overriding the template of its namespace
+#[derive(Debug, clap::Parser)]
+struct TablePartitionTemplate {
+    /// The name of the namespace containing the table
+    #[clap(action)]
+    namespace: String,
+
+    /// The name of the table to update, which is created if it does not exist
+    #[clap(action)]
+    table: String,
+
+    /// The partition template as JSON, see `namespace create --help`. If not given, the table
+    /// uses the template of its namespace.
+    #[clap(value_parser = parse_partition_template)]
+    partition_template: Option<PartitionTemplate>,
+}
+
+/// All possible subcommands for namespace
+#[derive(Debug, clap::Parser)]
+enum Command {
+    /// Fetch namespaces
+    List,
+
+    /// Create a new namespace
+    Create(Create),
+
+    /// Update the retention duration of a namespace
+    Retention(Retention),
+
+    /// Update the table and/or column limits of a namespace
+    Limits(Limits),
+
+    /// Soft delete a namespace
+    Delete(Delete),
+
+    /// Restore a soft deleted namespace
+    Restore(Restore),
+
+    /// Drop a table
+    DropTable(DropTable),
+
+    /// Drop a column
+    DropColumn(DropColumn),
+
+    /// Set the partition template of a table
+    TablePartitionTemplate(TablePartitionTemplate),
+}
+
+fn parse_partition_template(s: &str) -> Result<PartitionTemplate, String> {
+    serde_json::from_str(s).map_err(|e| format!("invalid partition template: {}", e))
+}
+
+pub async fn command(connection: Connection, config: Config) -> Result<(), Error> {
+    let mut client = namespace::Client::new(connection);
+    match config.command {
+        Command::List => {
+            let namespaces = client.get_namespaces().await?;
+            println!("{}", serde_json::to_string_pretty(&namespaces)?);
+        }
+        Command::Create(command) => {
+            let namespace = client
+                .create_namespace(
+                    &command.namespace,
+                    &command.retention,
+                    command.partition_template,
+                )
+                .await?;
+            println!("{}", serde_json::to_string_pretty(&namespace)?);
+        }
+        Command::Retention(command) => {
+            let namespace = client
+                .update_namespace_retention(&command.namespace, &command.retention)
+                .await?;
+            println!("{}", serde_json::to_string_pretty(&namespace)?);
+        }
+        Command::Limits(command) => {
+            let namespace = client
+                .update_namespace_limits(
+                    &command.namespace,
+                    command.max_tables,
+                    command.max_columns_per_table,
+                )
+                .await?;
+            println!("{}", serde_json::to_string_pretty(&namespace)?);
+        }
+        Command::Delete(command) => {
+            let namespace = client.delete_namespace(&command.namespace).await?;
+            println!("{}", serde_json::to_string_pretty(&namespace)?);
+        }
+        Command::Restore(command) => {
+            let namespace = client.restore_namespace(&command.namespace).await?;
+            println!("{}", serde_json::to_string_pretty(&namespace)?);
+        }
+        Command::DropTable(command) => {
+            let dropped = client
+                .drop_table(&command.namespace, &command.table)
+                .await?;
+            println!("{}", serde_json::to_string_pretty(&dropped)?);
+        }
+        Command::DropColumn(command) => {
+            let dropped = client
+                .drop_column(&command.namespace, &command.table, &command.column)
+                .await?;
+            println!("{}", serde_json::to_string_pretty(&dropped)?);
+        }
+        Command::TablePartitionTemplate(command) => {
+            let updated = client
+                .update_table_partition_template(
+                    &command.namespace,
+                    &command.table,
+                    command.partition_template,
+                )
+                .await?;
+            println!("{}", serde_json::to_string_pretty(&updated)?);
+        } // Deliberately not adding _ => so the compiler will direct people here to impl new
+          // commands
+    }
+
+    Ok(())
+}
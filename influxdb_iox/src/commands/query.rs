@@ -1,9 +1,17 @@
 use influxdb_iox_client::{
     connection::Connection,
-    flight::{self, generated_types::ReadInfo},
-    format::QueryOutputFormat,
+    flight::{
+        self,
+        generated_types::{query_param::Value, QueryParam, QueryType, ReadInfo},
+    },
+    format::{format_lp, QueryOutputFormat},
+};
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    path::PathBuf,
+    str::FromStr,
 };
-use std::str::FromStr;
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -13,6 +21,12 @@ pub enum Error {
 
     #[error("Error querying: {0}")]
     Query(#[from] influxdb_iox_client::flight::Error),
+
+    #[error("Error creating output file {:?}: {}", path, source)]
+    CreatingOutput {
+        path: PathBuf,
+        source: std::io::Error,
+    },
 }
 
 pub type Result<T, E = Error> = std::result::Result<T, E>;
@@ -28,9 +42,31 @@ pub struct Config {
     #[clap(action)]
     query: String,
 
-    /// Optional format ('pretty', 'json', or 'csv')
+    /// A value for the `$name` placeholders of the query as `name=value`, or
+    /// for the next of the `$1`, `$2`, ... placeholders as just `value`.
+    ///
+    /// Values are `true`, `false`, integers, floats, `NULL` or text, which
+    /// can be single quoted to keep it text, such as `'42'`.
+    #[clap(long = "param", value_parser = parse_param, action = clap::ArgAction::Append)]
+    params: Vec<QueryParam>,
+
+    /// Interpret the query as InfluxQL instead of SQL
+    #[clap(long, action)]
+    influxql: bool,
+
+    /// Optional format ('pretty', 'json', 'csv', 'lp', or the binary
+    /// 'parquet', 'arrow' and 'arrow_stream')
     #[clap(short, long, default_value = "pretty", action)]
     format: String,
+
+    /// Measurement of the lines of 'lp' output, needed unless the results
+    /// have one in their schema metadata, which SQL results usually don't
+    #[clap(long, action)]
+    measurement: Option<String>,
+
+    /// Write the results to this file instead of stdout
+    #[clap(short, long, action)]
+    output: Option<PathBuf>,
 }
 
 pub async fn command(connection: Connection, config: Config) -> Result<()> {
@@ -39,6 +75,10 @@ pub async fn command(connection: Connection, config: Config) -> Result<()> {
         namespace,
         format,
         query,
+        params,
+        influxql,
+        measurement,
+        output,
     } = config;
 
     let format = QueryOutputFormat::from_str(&format)?;
@@ -47,6 +87,13 @@ pub async fn command(connection: Connection, config: Config) -> Result<()> {
         .perform_query(ReadInfo {
             namespace_name: namespace,
             sql_query: query,
+            params,
+            query_type: if influxql {
+                QueryType::Influxql
+            } else {
+                QueryType::Sql
+            }
+            .into(),
         })
         .await?;
 
@@ -57,9 +104,125 @@ pub async fn command(connection: Connection, config: Config) -> Result<()> {
         batches.push(data);
     }
 
-    let formatted_result = format.format(&batches)?;
+    let mut writer: Box<dyn Write> = match output {
+        Some(path) => {
+            let file = File::create(&path).map_err(|source| Error::CreatingOutput {
+                path: path.clone(),
+                source,
+            })?;
+            Box::new(BufWriter::new(file))
+        }
+        None => Box::new(std::io::stdout().lock()),
+    };
 
-    println!("{}", formatted_result);
+    match format {
+        QueryOutputFormat::Lp => {
+            let lp = format_lp(&batches, measurement.as_deref())?;
+            writer
+                .write_all(lp.as_bytes())
+                .and_then(|_| writer.flush())
+                .map_err(influxdb_iox_client::format::Error::Io)?;
+        }
+        format => format.write(&batches, writer)?,
+    }
 
     Ok(())
 }
+
+/// Parses `name=value` into a named parameter, or a value without a name
+/// into a positional one
+fn parse_param(s: &str) -> Result<QueryParam, String> {
+    let is_name =
+        |name: &str| !name.is_empty() && name.chars().all(|c| c.is_alphanumeric() || c == '_');
+
+    match s.split_once('=') {
+        Some((name, _)) if name.chars().all(|c| c.is_ascii_digit()) && !name.is_empty() => {
+            Err(format!(
+                "positional parameter ${} is set by passing the values without names, in order",
+                name
+            ))
+        }
+        Some((name, value)) if is_name(name) => {
+            Ok(QueryParam::named(name, parse_param_value(value)))
+        }
+        _ => Ok(QueryParam::positional(parse_param_value(s))),
+    }
+}
+
+fn parse_param_value(value: &str) -> Option<Value> {
+    if value.eq_ignore_ascii_case("null") {
+        return None;
+    }
+    if let Some(text) = value
+        .strip_prefix('\'')
+        .and_then(|value| value.strip_suffix('\''))
+    {
+        return Some(Value::StringValue(text.to_string()));
+    }
+
+    // rather than text such as "inf" or "NaN"
+    let numeric = value.starts_with(|c: char| c.is_ascii_digit() || "+-.".contains(c));
+
+    Some(if value.eq_ignore_ascii_case("true") {
+        Value::BoolValue(true)
+    } else if value.eq_ignore_ascii_case("false") {
+        Value::BoolValue(false)
+    } else if let Ok(v) = value.parse() {
+        Value::IntValue(v)
+    } else if let Ok(v) = value.parse() {
+        Value::UintValue(v)
+    } else if let (true, Ok(v)) = (numeric, value.parse()) {
+        Value::FloatValue(v)
+    } else {
+        Value::StringValue(value.to_string())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_params() {
+        let string = |s: &str| Some(Value::StringValue(s.to_string()));
+
+        assert_eq!(
+            parse_param("host=server01").unwrap(),
+            QueryParam::named("host", string("server01"))
+        );
+        assert_eq!(
+            parse_param("min=-1.5").unwrap(),
+            QueryParam::named("min", Some(Value::FloatValue(-1.5)))
+        );
+        assert_eq!(
+            parse_param("42").unwrap(),
+            QueryParam::positional(Some(Value::IntValue(42)))
+        );
+        assert_eq!(
+            parse_param("18446744073709551615").unwrap(),
+            QueryParam::positional(Some(Value::UintValue(u64::MAX)))
+        );
+        assert_eq!(
+            parse_param("'42'").unwrap(),
+            QueryParam::positional(string("42"))
+        );
+        assert_eq!(
+            parse_param("TRUE").unwrap(),
+            QueryParam::positional(Some(Value::BoolValue(true)))
+        );
+        assert_eq!(parse_param("x=null").unwrap(), QueryParam::named("x", None));
+
+        // text with an equals sign that isn't a name
+        assert_eq!(
+            parse_param("a b=c").unwrap(),
+            QueryParam::positional(string("a b=c"))
+        );
+
+        assert_eq!(
+            parse_param("host=inf").unwrap(),
+            QueryParam::named("host", string("inf"))
+        );
+
+        assert!(parse_param("1=foo").is_err());
+    }
+}
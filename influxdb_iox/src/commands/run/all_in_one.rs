@@ -525,6 +525,7 @@ pub async fn command(config: Config) -> Result<()> {
         Arc::clone(&object_store),
         &write_buffer_config,
         QUERY_POOL_NAME,
+        iox_catalog::INFINITE_RETENTION_POLICY,
         1_000, // max 1,000 concurrent HTTP requests
     )
     .await?;
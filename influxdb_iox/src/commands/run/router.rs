@@ -71,6 +71,19 @@ pub struct Config {
     )]
     pub(crate) query_pool_name: String,
 
+    /// Retention period of the namespaces created by the router, such as
+    /// "30d" or "inf" to keep data forever.
+    ///
+    /// Writes containing data older than the retention period of their
+    /// namespace are rejected.
+    #[clap(
+        long = "--new-namespace-retention",
+        env = "INFLUXDB_IOX_NEW_NAMESPACE_RETENTION",
+        default_value = "inf",
+        action
+    )]
+    pub(crate) new_namespace_retention: String,
+
     /// The maximum number of simultaneous requests the HTTP server is
     /// configured to accept.
     ///
@@ -115,6 +128,7 @@ pub async fn command(config: Config) -> Result<()> {
         object_store,
         &config.write_buffer_config,
         &config.query_pool_name,
+        &config.new_namespace_retention,
         config.http_request_limit,
     )
     .await?;
@@ -1,5 +1,10 @@
 //! Entrypoint for interactive SQL repl loop
 
+use std::{
+    io::Read,
+    path::{Path, PathBuf},
+};
+
 use observability_deps::tracing::debug;
 use snafu::{ResultExt, Snafu};
 
@@ -13,6 +18,9 @@ mod repl_command;
 ///
 /// Supports command history and interactive editing. History is
 /// stored in $HOME/.iox_sql_history.
+///
+/// With --file, runs the commands of a script instead and stops at the first
+/// one that fails.
 #[derive(Debug, clap::Parser)]
 pub struct Config {
     // TODO add an option to avoid saving history
@@ -21,9 +29,14 @@ pub struct Config {
     /// Format to use for output. Can be overridden using
     /// `SET FORMAT` command
     ///
-    /// Optional format ('pretty', 'json', or 'csv')
-    #[clap(short, long, default_value = "pretty", action)]
+    /// Optional format ('pretty', 'json', 'csv' or 'lp')
+    #[clap(long, default_value = "pretty", action)]
     format: String,
+
+    /// Run the commands of this file, or of stdin if it is '-', instead of
+    /// starting an interactive session
+    #[clap(short = 'f', long, action)]
+    file: Option<PathBuf>,
 }
 
 #[derive(Debug, Snafu)]
@@ -38,6 +51,12 @@ pub enum Error {
 
     #[snafu(display("Repl Error: {}", source))]
     Repl { source: repl::Error },
+
+    #[snafu(display("Error reading script {:?}: {}", path, source))]
+    ReadingScript {
+        path: PathBuf,
+        source: std::io::Error,
+    },
 }
 
 pub type Result<T, E = Error> = std::result::Result<T, E>;
@@ -48,13 +67,34 @@ pub async fn command(connection: Connection, config: Config) -> Result<()> {
 
     check_health(connection.clone()).await?;
 
-    println!("Connected to IOx Server");
+    let script = match &config.file {
+        Some(path) => Some(read_script(path)?),
+        None => {
+            println!("Connected to IOx Server");
+            None
+        }
+    };
 
     let mut repl = repl::Repl::new(connection).context(ReplSnafu)?;
 
     repl.set_output_format(config.format).context(ReplSnafu)?;
 
-    repl.run().await.context(ReplSnafu)
+    match script {
+        Some(script) => repl.run_script(&script).await.context(ReplSnafu),
+        None => repl.run().await.context(ReplSnafu),
+    }
+}
+
+fn read_script(path: &Path) -> Result<String> {
+    if path.as_os_str() == "-" {
+        let mut script = String::new();
+        std::io::stdin()
+            .read_to_string(&mut script)
+            .context(ReadingScriptSnafu { path })?;
+        Ok(script)
+    } else {
+        std::fs::read_to_string(path).context(ReadingScriptSnafu { path })
+    }
 }
 
 async fn check_health(connection: Connection) -> Result<()> {
@@ -9,7 +9,10 @@ use datafusion::{
     datasource::MemTable,
     prelude::{SessionConfig, SessionContext},
 };
-use influxdb_iox_client::{connection::Connection, flight::generated_types::ReadInfo};
+use influxdb_iox_client::{
+    connection::Connection,
+    flight::generated_types::{QueryType, ReadInfo},
+};
 use observability_deps::tracing::{debug, info};
 use snafu::{ResultExt, Snafu};
 use std::{collections::HashMap, sync::Arc, time::Instant};
@@ -123,6 +126,8 @@ async fn load_remote_system_tables(
                         .perform_query(ReadInfo {
                             namespace_name: db_name.clone(),
                             sql_query: sql,
+                            params: vec![],
+                            query_type: QueryType::Sql.into(),
                         })
                         .await
                         .context(RunningRemoteQuerySnafu)?;
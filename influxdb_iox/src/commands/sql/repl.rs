@@ -1,4 +1,13 @@
-use std::{borrow::Cow, convert::TryInto, path::PathBuf, sync::Arc, time::Instant};
+use std::{
+    borrow::Cow,
+    collections::BTreeSet,
+    convert::TryInto,
+    fs::File,
+    io::{BufWriter, Write},
+    path::PathBuf,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 use arrow::{
     array::{ArrayRef, Int64Array, StringArray},
@@ -11,7 +20,10 @@ use snafu::{ResultExt, Snafu};
 use super::repl_command::ReplCommand;
 
 use influxdb_iox_client::{
-    connection::Connection, flight::generated_types::ReadInfo, format::QueryOutputFormat,
+    connection::Connection,
+    flight::generated_types::{QueryType, ReadInfo},
+    format::QueryOutputFormat,
+    schema::generated_types::NamespaceSchema,
 };
 
 #[derive(Debug, Snafu)]
@@ -48,6 +60,36 @@ pub enum Error {
 
     #[snafu(display("Cannot create REPL: {}", source))]
     ReplCreation { source: ReadlineError },
+
+    #[snafu(display(
+        "Error: no database selected.\nHint: Run USE DATABASE <dbname> to select database"
+    ))]
+    NoDatabase,
+
+    #[snafu(display("Table {} not found in database {}", table, db_name))]
+    TableNotFound { table: String, db_name: String },
+
+    #[snafu(display("Error opening output file {}: {}", path, source))]
+    OpeningOutput {
+        path: String,
+        source: std::io::Error,
+    },
+
+    #[snafu(display("Error writing output file {}: {}", path, source))]
+    WritingOutput {
+        path: String,
+        source: std::io::Error,
+    },
+
+    #[snafu(display(
+        "Cannot set binary format {} while writing results to {}.\nHint: Run \\o to close it first",
+        format,
+        path
+    ))]
+    BinaryFormatWithOutput {
+        format: QueryOutputFormat,
+        path: String,
+    },
 }
 
 pub type Result<T, E = Error> = std::result::Result<T, E>;
@@ -63,6 +105,9 @@ enum QueryEngine {
 struct RustylineHelper {
     hinter: rustyline::hint::HistoryHinter,
     highlighter: rustyline::highlight::MatchingBracketHighlighter,
+
+    /// Table and column names of the current database, for tab completion
+    names: BTreeSet<String>,
 }
 
 impl Default for RustylineHelper {
@@ -70,6 +115,7 @@ impl Default for RustylineHelper {
         Self {
             hinter: rustyline::hint::HistoryHinter {},
             highlighter: rustyline::highlight::MatchingBracketHighlighter::default(),
+            names: BTreeSet::new(),
         }
     }
 }
@@ -83,7 +129,8 @@ impl rustyline::validate::Validator for RustylineHelper {
     ) -> rustyline::Result<rustyline::validate::ValidationResult> {
         let input = ctx.input();
 
-        if input.trim_end().ends_with(';') {
+        // meta-commands end at the end of the line
+        if input.trim_end().ends_with(';') || input.trim_start().starts_with('\\') {
             match ReplCommand::try_from(input) {
                 Ok(_) => Ok(rustyline::validate::ValidationResult::Valid(None)),
                 Err(err) => Ok(rustyline::validate::ValidationResult::Invalid(Some(err))),
@@ -149,6 +196,11 @@ impl rustyline::completion::Completer for RustylineHelper {
         pos: usize,
         ctx: &rustyline::Context<'_>,
     ) -> rustyline::Result<(usize, Vec<Self::Candidate>)> {
+        let (start, names) = complete_name(line, pos, &self.names);
+        if !names.is_empty() {
+            return Ok((start, names));
+        }
+
         // If there is a hint, use that as the auto-complete when user hits `tab`
         if let Some(hint) = self.hinter.hint(line, pos, ctx) {
             let start_pos = pos;
@@ -159,6 +211,31 @@ impl rustyline::completion::Completer for RustylineHelper {
     }
 }
 
+/// The table and column names in `names` that start with the word before
+/// `pos`, along with the position where that word starts
+fn complete_name(line: &str, pos: usize, names: &BTreeSet<String>) -> (usize, Vec<String>) {
+    let start = line[..pos]
+        .char_indices()
+        .rev()
+        .take_while(|(_, c)| c.is_alphanumeric() || *c == '_')
+        .last()
+        .map(|(idx, _)| idx)
+        .unwrap_or(pos);
+
+    let word = &line[start..pos];
+    if word.is_empty() {
+        return (pos, vec![]);
+    }
+
+    let names = names
+        .range(word.to_string()..)
+        .take_while(|name| name.starts_with(word))
+        .filter(|name| name.len() > word.len())
+        .cloned()
+        .collect();
+    (start, names)
+}
+
 /// Captures the state of the repl, gathers commands and executes them
 /// one by one
 pub struct Repl {
@@ -177,11 +254,23 @@ pub struct Repl {
     /// Client for running sql
     flight_client: influxdb_iox_client::flight::Client,
 
+    /// Client for fetching the tables and columns of a database
+    schema_client: influxdb_iox_client::schema::Client,
+
     /// database name against which SQL commands are run
     query_engine: Option<QueryEngine>,
 
     /// Formatter to use to format query results
     output_format: QueryOutputFormat,
+
+    /// Print how long each query took
+    timing: bool,
+
+    /// File query results are written to instead of stdout, set by `\o`.
+    ///
+    /// A binary format writes a complete file per result, so the file is
+    /// closed after the first result written in one.
+    output: Option<(String, BufWriter<File>)>,
 }
 
 impl Repl {
@@ -193,6 +282,7 @@ impl Repl {
     pub fn new(connection: Connection) -> Result<Self> {
         let namespace_client = influxdb_iox_client::namespace::Client::new(connection.clone());
         let flight_client = influxdb_iox_client::flight::Client::new(connection.clone());
+        let schema_client = influxdb_iox_client::schema::Client::new(connection.clone());
 
         let mut rl = Editor::new().context(ReplCreationSnafu)?;
         rl.set_helper(Some(RustylineHelper::default()));
@@ -212,8 +302,11 @@ impl Repl {
             connection,
             namespace_client,
             flight_client,
+            schema_client,
             query_engine: None,
             output_format,
+            timing: true,
+            output: None,
         })
     }
 
@@ -223,37 +316,68 @@ impl Repl {
     pub async fn run(&mut self) -> Result<()> {
         println!("Ready for commands. (Hint: try 'help;')");
         loop {
-            match self.next_command()? {
-                ReplCommand::Help => {
-                    self.print_help();
-                }
-                ReplCommand::Observer {} => {
-                    self.use_observer()
-                        .await
-                        .map_err(|e| println!("{}", e))
-                        .ok();
-                }
-                ReplCommand::ShowNamespaces => {
-                    self.list_namespaces()
-                        .await
-                        .map_err(|e| println!("{}", e))
-                        .ok();
-                }
-                ReplCommand::UseDatabase { db_name } => {
-                    self.use_database(db_name);
-                }
-                ReplCommand::SqlCommand { sql } => {
-                    self.run_sql(sql).await.map_err(|e| println!("{}", e)).ok();
-                }
-                ReplCommand::Exit => {
-                    info!("exiting at user request");
-                    return Ok(());
-                }
-                ReplCommand::SetFormat { format } => {
-                    self.set_output_format(format)?;
-                }
+            let command = self.next_command()?;
+            match self.execute(command).await {
+                Ok(true) => {}
+                Ok(false) => return Ok(()),
+                Err(e) => println!("{}", e),
+            }
+        }
+    }
+
+    /// Runs the commands of a script one after the other, stopping at the
+    /// first one that fails
+    pub async fn run_script(&mut self, script: &str) -> Result<()> {
+        for statement in split_script(script) {
+            let command = ReplCommand::try_from(statement.as_str())
+                .map_err(|message| Error::ParsingCommand { message })?;
+            if !self.execute(command).await? {
+                break;
             }
         }
+        Ok(())
+    }
+
+    /// Runs one command, returns false if the session should end
+    async fn execute(&mut self, command: ReplCommand) -> Result<bool> {
+        match command {
+            ReplCommand::Help => {
+                self.print_help();
+            }
+            ReplCommand::Observer {} => {
+                self.use_observer().await?;
+            }
+            ReplCommand::ShowNamespaces => {
+                self.list_namespaces().await?;
+            }
+            ReplCommand::ShowTables => {
+                self.show_tables().await?;
+            }
+            ReplCommand::Describe { table } => {
+                self.describe(&table).await?;
+            }
+            ReplCommand::UseDatabase { db_name } => {
+                self.use_database(db_name).await;
+            }
+            ReplCommand::SqlCommand { sql } => {
+                self.run_sql(sql).await?;
+            }
+            ReplCommand::Exit => {
+                info!("exiting at user request");
+                return Ok(false);
+            }
+            ReplCommand::SetFormat { format } => {
+                self.set_output_format(format)?;
+            }
+            ReplCommand::Timing { enabled } => {
+                self.timing = enabled.unwrap_or(!self.timing);
+                println!("Timing is {}", if self.timing { "on" } else { "off" });
+            }
+            ReplCommand::Output { path } => {
+                self.set_output(path)?;
+            }
+        }
+        Ok(true)
     }
 
     /// Parss the next command;
@@ -302,16 +426,100 @@ impl Repl {
         self.print_results(&[record_batch])
     }
 
+    // print the tables of the current database to the output
+    async fn show_tables(&mut self) -> Result<()> {
+        let db_name = match &self.query_engine {
+            None => return Err(Error::NoDatabase),
+            Some(QueryEngine::Remote(db_name)) => db_name.clone(),
+            // the observer knows its tables itself
+            Some(QueryEngine::Observer(_)) => return self.run_sql("SHOW TABLES".to_string()).await,
+        };
+
+        let schema = self.load_schema(&db_name).await?;
+        let mut table_names = schema.tables.keys().collect::<Vec<_>>();
+        table_names.sort();
+
+        let table_name: StringArray = table_names.into_iter().map(Some).collect();
+        let record_batch =
+            RecordBatch::try_from_iter(vec![("table_name", Arc::new(table_name) as ArrayRef)])
+                .expect("creating record batch successfully");
+
+        self.print_results(&[record_batch])
+    }
+
+    // print the columns of a table of the current database to the output
+    async fn describe(&mut self, table: &str) -> Result<()> {
+        let db_name = match &self.query_engine {
+            None => return Err(Error::NoDatabase),
+            Some(QueryEngine::Remote(db_name)) => db_name.clone(),
+            Some(QueryEngine::Observer(_)) => {
+                return self.run_sql(format!("SHOW COLUMNS FROM {}", table)).await
+            }
+        };
+
+        let schema = self.load_schema(&db_name).await?;
+        let table_schema = schema
+            .tables
+            .get(table)
+            .ok_or_else(|| Error::TableNotFound {
+                table: table.to_string(),
+                db_name,
+            })?;
+
+        let mut columns = table_schema.columns.iter().collect::<Vec<_>>();
+        columns.sort_by_key(|(name, _)| *name);
+
+        let column_name: StringArray = columns.iter().map(|(name, _)| Some(*name)).collect();
+        let column_type: StringArray = columns
+            .iter()
+            .map(|(_, column)| {
+                Some(
+                    data_types::ColumnType::try_from(column.column_type())
+                        .map(|column_type| column_type.to_string())
+                        .unwrap_or_else(|_| "unknown".to_string()),
+                )
+            })
+            .collect();
+
+        let record_batch = RecordBatch::try_from_iter(vec![
+            ("column_name", Arc::new(column_name) as ArrayRef),
+            ("column_type", Arc::new(column_type) as ArrayRef),
+        ])
+        .expect("creating record batch successfully");
+
+        self.print_results(&[record_batch])
+    }
+
+    /// Fetches the schema of `db_name` and refreshes the names used for
+    /// tab completion with it
+    async fn load_schema(&mut self, db_name: &str) -> Result<NamespaceSchema> {
+        let schema = self
+            .schema_client
+            .get_schema(db_name)
+            .await
+            .map_err(|e| Box::new(e) as _)
+            .context(LoadingRemoteStateSnafu)?;
+
+        if let Some(helper) = self.rl.helper_mut() {
+            helper.names = schema
+                .tables
+                .iter()
+                .flat_map(|(table_name, table)| {
+                    std::iter::once(table_name).chain(table.columns.keys())
+                })
+                .cloned()
+                .collect();
+        }
+
+        Ok(schema)
+    }
+
     // Run a command against the currently selected remote database
     async fn run_sql(&mut self, sql: String) -> Result<()> {
         let start = Instant::now();
 
         let batches = match &mut self.query_engine {
-            None => {
-                println!("Error: no database selected.");
-                println!("Hint: Run USE DATABASE <dbname> to select database");
-                return Ok(());
-            }
+            None => return Err(Error::NoDatabase),
             Some(QueryEngine::Remote(db_name)) => {
                 info!(%db_name, %sql, "Running sql on remote database");
 
@@ -327,16 +535,33 @@ impl Repl {
         };
 
         let end = Instant::now();
-        self.print_results(&batches)?;
 
-        println!(
-            "Returned {} in {:?}",
-            Self::row_summary(&batches),
-            end - start
-        );
+        // print plans as indented text rather than a table of multi-line cells
+        let explain = if self.output.is_none()
+            && self.output_format == QueryOutputFormat::Pretty
+            && is_explain(&sql)
+        {
+            format_explain(&batches)
+        } else {
+            None
+        };
+        match explain {
+            Some(explain) => print!("{}", explain),
+            None => self.print_results(&batches)?,
+        }
+
+        println!("{}", self.summary(&batches, end - start));
         Ok(())
     }
 
+    fn summary(&self, batches: &[RecordBatch], elapsed: Duration) -> String {
+        if self.timing {
+            format!("Returned {} in {:?}", Self::row_summary(batches), elapsed)
+        } else {
+            format!("Returned {}", Self::row_summary(batches))
+        }
+    }
+
     fn row_summary<'a>(batches: impl IntoIterator<Item = &'a RecordBatch>) -> String {
         let total_rows: usize = batches.into_iter().map(|b| b.num_rows()).sum();
 
@@ -349,9 +574,14 @@ impl Repl {
         }
     }
 
-    fn use_database(&mut self, db_name: String) {
+    async fn use_database(&mut self, db_name: String) {
         info!(%db_name, "setting current database");
         println!("You are now in remote mode, querying database {}", db_name);
+
+        // only tab completion needs the schema now
+        if let Err(e) = self.load_schema(&db_name).await {
+            debug!(%e, "error loading schema for completion");
+        }
         self.set_query_engine(QueryEngine::Remote(db_name));
     }
 
@@ -361,6 +591,9 @@ impl Repl {
             .await
             .context(RunningObserverQuerySnafu)?;
         println!("{}", observer.help());
+        if let Some(helper) = self.rl.helper_mut() {
+            helper.names.clear();
+        }
         self.set_query_engine(QueryEngine::Observer(observer));
         Ok(())
     }
@@ -379,20 +612,64 @@ impl Repl {
     pub fn set_output_format<S: AsRef<str>>(&mut self, requested_format: S) -> Result<()> {
         let requested_format = requested_format.as_ref();
 
-        self.output_format = requested_format
+        let output_format: QueryOutputFormat = requested_format
             .parse()
             .context(SettingFormatSnafu { requested_format })?;
+
+        // results already written to the file would precede the binary file
+        if let Some((path, _)) = &self.output {
+            if output_format.is_binary() {
+                return Err(Error::BinaryFormatWithOutput {
+                    format: output_format,
+                    path: path.clone(),
+                });
+            }
+        }
+
+        self.output_format = output_format;
         println!("Set output format format to {}", self.output_format);
         Ok(())
     }
 
-    /// Prints to the specified output format
-    fn print_results(&self, batches: &[RecordBatch]) -> Result<()> {
-        let formatted_results = self
-            .output_format
-            .format(batches)
-            .context(FormattingResultsSnafu)?;
-        println!("{}", formatted_results);
+    /// Redirects query results to the file at `path`, or back to stdout
+    pub fn set_output(&mut self, path: Option<String>) -> Result<()> {
+        self.output = match path {
+            Some(path) => {
+                let file = File::create(&path).context(OpeningOutputSnafu { path: &path })?;
+                println!("Writing results to {}", path);
+                Some((path, BufWriter::new(file)))
+            }
+            None => {
+                println!("Writing results to stdout");
+                None
+            }
+        };
+        Ok(())
+    }
+
+    /// Prints to the specified output format, or writes to the output file
+    fn print_results(&mut self, batches: &[RecordBatch]) -> Result<()> {
+        let output_format = self.output_format;
+        match &mut self.output {
+            Some((path, file)) if output_format.is_binary() => {
+                output_format
+                    .write(batches, &mut *file)
+                    .context(FormattingResultsSnafu)?;
+                file.flush().context(WritingOutputSnafu { path: &*path })?;
+
+                println!("Wrote results to {}, writing results to stdout", path);
+                self.output = None;
+            }
+            Some((_, file)) => output_format
+                .write(batches, file)
+                .context(FormattingResultsSnafu)?,
+            None => {
+                let formatted_results = output_format
+                    .format(batches)
+                    .context(FormattingResultsSnafu)?;
+                println!("{}", formatted_results);
+            }
+        }
         Ok(())
     }
 }
@@ -422,6 +699,95 @@ fn history_file() -> PathBuf {
     buf
 }
 
+/// Splits a script into commands: statements ending with a semicolon that is
+/// not quoted or in a comment, and meta-commands, which take a line of their
+/// own. Blank lines and `--` comments between commands are skipped.
+fn split_script(script: &str) -> Vec<String> {
+    let mut statements = vec![];
+    let mut statement = String::new();
+    // The quote character of the string or identifier being read, if any
+    let mut quote = None;
+
+    for line in script.lines() {
+        let trimmed = line.trim();
+        if statement.is_empty() {
+            if trimmed.starts_with('\\') {
+                statements.push(trimmed.to_string());
+                continue;
+            }
+        } else {
+            statement.push('\n');
+        }
+
+        let mut chars = line.chars().peekable();
+        while let Some(c) = chars.next() {
+            match quote {
+                Some(q) if c == q => quote = None,
+                Some(_) => {}
+                None if c == '\'' || c == '"' => quote = Some(c),
+                None if c == '-' && chars.peek() == Some(&'-') => {
+                    // the rest of the line is a comment, only kept inside
+                    // statements
+                    if !statement.is_empty() {
+                        statement.push(c);
+                        statement.extend(chars.by_ref());
+                    }
+                    break;
+                }
+                None if c == ';' => {
+                    statement.push(c);
+                    statements.push(std::mem::take(&mut statement));
+                    continue;
+                }
+                None => {}
+            }
+
+            if !(statement.is_empty() && c.is_whitespace()) {
+                statement.push(c);
+            }
+        }
+    }
+    if !statement.trim().is_empty() {
+        statements.push(statement);
+    }
+
+    statements
+}
+
+fn is_explain(sql: &str) -> bool {
+    sql.trim_start()
+        .get(..7)
+        .map_or(false, |keyword| keyword.eq_ignore_ascii_case("explain"))
+}
+
+/// The `plan_type` and `plan` columns of EXPLAIN results as text, `None` if
+/// the results have other columns
+fn format_explain(batches: &[RecordBatch]) -> Option<String> {
+    let mut explain = String::new();
+
+    for batch in batches {
+        let schema = batch.schema();
+        let column = |name: &str| {
+            batch
+                .column(schema.index_of(name).ok()?)
+                .as_any()
+                .downcast_ref::<StringArray>()
+        };
+        let plan_type = column("plan_type")?;
+        let plan = column("plan")?;
+
+        for row in 0..batch.num_rows() {
+            explain.push_str(&format!("{}:\n", plan_type.value(row)));
+            for line in plan.value(row).lines() {
+                explain.push_str(&format!("    {}\n", line));
+            }
+            explain.push('\n');
+        }
+    }
+
+    Some(explain)
+}
+
 /// Runs the specified `query` and returns the record batches of the result
 async fn scrape_query(
     client: &mut influxdb_iox_client::flight::Client,
@@ -432,6 +798,8 @@ async fn scrape_query(
         .perform_query(ReadInfo {
             namespace_name: db_name.to_string(),
             sql_query: query.to_string(),
+            params: vec![],
+            query_type: QueryType::Sql.into(),
         })
         .await
         .context(RunningRemoteQuerySnafu)?;
@@ -448,3 +816,90 @@ async fn scrape_query(
 
     Ok(batches)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_scripts_into_commands() {
+        let script = "-- setup\n\
+                      USE foo;\n\
+                      \n\
+                      \\timing off\n\
+                      SELECT *\n  FROM cpu;\n\
+                      \\o out.csv\n\
+                      SHOW TABLES";
+
+        assert_eq!(
+            split_script(script),
+            vec![
+                "USE foo;",
+                "\\timing off",
+                "SELECT *\n  FROM cpu;",
+                "\\o out.csv",
+                "SHOW TABLES",
+            ]
+        );
+    }
+
+    #[test]
+    fn splits_scripts_on_unquoted_semicolons() {
+        let script = "SELECT 1; SELECT 2; -- done\n\
+                      SELECT ';' AS \"a;b\"; SELECT 'it''s;\n\
+                      ok';\n\
+                      SELECT 3 -- not ; the end\n\
+                      ;";
+
+        assert_eq!(
+            split_script(script),
+            vec![
+                "SELECT 1;",
+                "SELECT 2;",
+                "SELECT ';' AS \"a;b\";",
+                "SELECT 'it''s;\nok';",
+                "SELECT 3 -- not ; the end\n;",
+            ]
+        );
+    }
+
+    #[test]
+    fn completes_names() {
+        let names = ["cpu", "cpu_usage", "host", "mem"]
+            .into_iter()
+            .map(String::from)
+            .collect();
+
+        assert_eq!(
+            complete_name("SELECT ho", 9, &names),
+            (7, vec!["host".to_string()])
+        );
+        assert_eq!(
+            complete_name("SELECT * FROM cp", 16, &names),
+            (14, vec!["cpu".to_string(), "cpu_usage".to_string()])
+        );
+        assert_eq!(
+            complete_name("SELECT * FROM cpu", 17, &names),
+            (14, vec!["cpu_usage".to_string()])
+        );
+        assert_eq!(complete_name("SELECT ", 7, &names), (7, vec![]));
+    }
+
+    #[test]
+    fn formats_explain() {
+        let plan_type = StringArray::from(vec!["Plan with Metrics"]);
+        let plan = StringArray::from(vec!["ProjectionExec: a\n  ParquetExec: b"]);
+        let batch = RecordBatch::try_from_iter(vec![
+            ("plan_type", Arc::new(plan_type) as ArrayRef),
+            ("plan", Arc::new(plan) as ArrayRef),
+        ])
+        .unwrap();
+
+        assert!(is_explain(" explain analyze select 1"));
+        assert!(!is_explain("select 1"));
+        assert_eq!(
+            format_explain(&[batch]).unwrap(),
+            "Plan with Metrics:\n    ProjectionExec: a\n      ParquetExec: b\n\n"
+        );
+    }
+}
@@ -5,10 +5,28 @@ use observability_deps::tracing::{debug, warn};
 pub enum ReplCommand {
     Help,
     ShowNamespaces,
+    ShowTables,
+    Describe {
+        table: String,
+    },
     Observer,
-    SetFormat { format: String },
-    UseDatabase { db_name: String },
-    SqlCommand { sql: String },
+    SetFormat {
+        format: String,
+    },
+    /// `\timing [on|off]`, toggles timing without an argument
+    Timing {
+        enabled: Option<bool>,
+    },
+    /// `\o [file]`, back to stdout without a file
+    Output {
+        path: Option<String>,
+    },
+    UseDatabase {
+        db_name: String,
+    },
+    SqlCommand {
+        sql: String,
+    },
     Exit,
 }
 
@@ -80,6 +98,23 @@ impl TryFrom<&str> for ReplCommand {
                 })
             }
             ["show", "namespaces"] => Ok(Self::ShowNamespaces),
+            ["show", "tables"] => Ok(Self::ShowTables),
+            ["describe"] => Err("table not specified. Usage: DESCRIBE <table>".to_string()),
+            ["describe", _table] => Ok(Self::Describe {
+                table: raw_commands[1].to_string(),
+            }),
+            ["\\timing"] => Ok(Self::Timing { enabled: None }),
+            ["\\timing", "on"] => Ok(Self::Timing {
+                enabled: Some(true),
+            }),
+            ["\\timing", "off"] => Ok(Self::Timing {
+                enabled: Some(false),
+            }),
+            ["\\timing", ..] => Err("Usage: \\timing [on|off]".to_string()),
+            ["\\o"] => Ok(Self::Output { path: None }),
+            ["\\o", ..] => Ok(Self::Output {
+                path: Some(raw_commands[1..].join(" ")),
+            }),
             ["set", "format", _format] => Ok(Self::SetFormat {
                 format: raw_commands[2].to_string(),
             }),
@@ -102,7 +137,18 @@ SHOW NAMESPACES: List databases available on the server
 
 USE [DATABASE|NAMESPACE] <name>: Set the current remote database to name
 
-SET FORMAT <format>: Set the output format to Pretty, csv or json
+SHOW TABLES: List the tables of the current database
+
+DESCRIBE <table>: List the columns of a table and their types
+
+SET FORMAT <format>: Set the output format to pretty, csv, json, lp,
+    or the binary parquet, arrow and arrow_stream (which need \o)
+
+\timing [on|off]: Show how long each query takes
+
+\o [file]: Write query results to file, or back to stdout without one.
+    With a binary format, only the next result is written to file
+    Meta-commands starting with \ don't need a trailing semicolon
 
 OBSERVER: Locally query unified queryable views of remote system tables
 
@@ -116,7 +162,10 @@ USE DATABASE foo;
 
 ;; Explore Schema:
 SHOW TABLES; ;; Show available tables
-SHOW COLUMNS FROM my_table; ;; Show columns in the table
+DESCRIBE my_table; ;; Show columns in the table
+
+;; Show the plan of a query and how long each part took
+EXPLAIN ANALYZE SELECT count(*) FROM my_table;
 
 ;; Show storage usage across partitions and tables
 SELECT
@@ -246,6 +295,65 @@ mod tests {
         assert_eq!("set format Hmm".try_into(), expected);
     }
 
+    #[test]
+    fn show_tables() {
+        let expected = Ok(ReplCommand::ShowTables);
+        assert_eq!("show tables".try_into(), expected);
+        assert_eq!("SHOW  Tables;".try_into(), expected);
+
+        assert_eq!(
+            "SHOW TABLES FROM foo".try_into(),
+            sql_cmd("SHOW TABLES FROM foo")
+        );
+    }
+
+    #[test]
+    fn describe() {
+        let expected = Ok(ReplCommand::Describe {
+            table: "Cpu".to_string(),
+        });
+        assert_eq!("describe Cpu".try_into(), expected);
+        assert_eq!("DESCRIBE  Cpu ;".try_into(), expected);
+
+        let expected: Result<ReplCommand, String> =
+            Err("table not specified. Usage: DESCRIBE <table>".to_string());
+        assert_eq!("describe;".try_into(), expected);
+    }
+
+    #[test]
+    fn timing() {
+        assert_eq!(
+            "\\timing".try_into(),
+            Ok(ReplCommand::Timing { enabled: None })
+        );
+        assert_eq!(
+            "\\timing on".try_into(),
+            Ok(ReplCommand::Timing {
+                enabled: Some(true)
+            })
+        );
+        assert_eq!(
+            "\\TIMING OFF;".try_into(),
+            Ok(ReplCommand::Timing {
+                enabled: Some(false)
+            })
+        );
+
+        let expected: Result<ReplCommand, String> = Err("Usage: \\timing [on|off]".to_string());
+        assert_eq!("\\timing maybe".try_into(), expected);
+    }
+
+    #[test]
+    fn output() {
+        assert_eq!("\\o".try_into(), Ok(ReplCommand::Output { path: None }));
+        assert_eq!(
+            "\\o Results.parquet".try_into(),
+            Ok(ReplCommand::Output {
+                path: Some("Results.parquet".to_string())
+            })
+        );
+    }
+
     #[test]
     fn sql_command() {
         let expected = sql_cmd("SELECT * from foo");
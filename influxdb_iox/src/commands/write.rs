@@ -1,9 +1,34 @@
-use influxdb_iox_client::{connection::Connection, write};
+use self::{csv::CsvBatches, lp::LpBatches, parquet::ParquetBatches};
+use backoff::{Backoff, BackoffConfig};
+use dml::{DmlMeta, DmlWrite};
+use flate2::read::GzDecoder;
+use futures::{stream, StreamExt};
+use hashbrown::HashMap;
+use influxdb_iox_client::{
+    connection::Connection,
+    error::Error as ClientError,
+    write::{self, generated_types::WriteRequest},
+};
 use iox_time::TimeProvider;
-use std::{fs::File, io::Read, path::PathBuf};
+use mutable_batch::MutableBatch;
+use observability_deps::tracing::{debug, info};
+use std::{
+    collections::BTreeMap,
+    ffi::{OsStr, OsString},
+    fs::File,
+    io::{BufRead, BufReader, Read, Seek, SeekFrom},
+    num::NonZeroUsize,
+    ops::ControlFlow,
+    path::{Path, PathBuf},
+    time::Duration,
+};
 use thiserror::Error;
 
-#[allow(clippy::enum_variant_names)]
+pub(crate) mod columns;
+mod csv;
+mod lp;
+mod parquet;
+
 #[derive(Debug, Error)]
 pub enum Error {
     #[error("Error reading file {:?}: {}", file_name, source)]
@@ -12,43 +37,566 @@ pub enum Error {
         source: std::io::Error,
     },
 
-    #[error("Client error: {0}")]
-    ClientError(#[from] influxdb_iox_client::error::Error),
+    #[error("Error reading input: {}", source)]
+    ReadingInput { source: std::io::Error },
+
+    #[error(
+        "Invalid line protocol in bytes {}..{} of the input: {}",
+        start,
+        end,
+        source
+    )]
+    InvalidLineProtocol {
+        start: u64,
+        end: u64,
+        source: mutable_batch_lp::Error,
+    },
+
+    #[error("Invalid CSV at line {}: {}", line, message)]
+    InvalidCsv { line: u64, message: String },
+
+    #[error("Error reading parquet file: {}", message)]
+    ReadingParquet { message: String },
+
+    #[error("Unable to write column {:?}: {}", column, message)]
+    UnsupportedColumn { column: String, message: String },
+
+    #[error(
+        "Error converting rows {}..{} of table {}: {}",
+        start,
+        end,
+        table,
+        source
+    )]
+    ConvertingRows {
+        table: String,
+        start: u64,
+        end: u64,
+        source: mutable_batch::writer::Error,
+    },
+
+    #[error(
+        "gzip compressed parquet files are not supported, parquet files are compressed internally"
+    )]
+    CompressedParquet,
+
+    #[error("Error reading checkpoint {:?}: {}", file_name, source)]
+    ReadingCheckpoint {
+        file_name: PathBuf,
+        source: std::io::Error,
+    },
+
+    #[error("Invalid checkpoint {:?}: {}", file_name, message)]
+    InvalidCheckpoint { file_name: PathBuf, message: String },
+
+    #[error("Error writing checkpoint {:?}: {}", file_name, source)]
+    WritingCheckpoint {
+        file_name: PathBuf,
+        source: std::io::Error,
+    },
+
+    #[error(
+        "Error writing {}..{} of the input: {}. \
+         Run again with --resume to continue after the last written batch",
+        start,
+        end,
+        source
+    )]
+    WritingBatch {
+        start: u64,
+        end: u64,
+        source: ClientError,
+    },
 }
 
 pub type Result<T, E = Error> = std::result::Result<T, E>;
 
 /// Write data into the specified database
+///
+/// The file is sent in batches, several at a time. The position up to which
+/// every batch was written (a byte offset, or a row number for parquet files)
+/// is recorded in a checkpoint file, so an interrupted write can continue
+/// where it stopped with `--resume`.
 #[derive(Debug, clap::Parser)]
 pub struct Config {
     /// The name of the database
     #[clap(action)]
     name: String,
 
-    /// File with data to load. Currently supported formats are .lp
+    /// File with data to load. Supported formats are line protocol (.lp),
+    /// CSV (.csv) and parquet (.parquet). Line protocol and CSV files may
+    /// be gzip compressed (.lp.gz, .csv.gz or just .gz for line protocol).
     #[clap(action)]
     file_name: PathBuf,
+
+    /// Format of the file, instead of the one its extension implies. Files
+    /// with other extensions are read as line protocol.
+    #[clap(long, arg_enum, action)]
+    format: Option<Format>,
+
+    /// Table the rows of CSV and parquet files are written to, unless a CSV
+    /// file has a measurement column. Defaults to the table a parquet file
+    /// was exported from, or else the file name without its extensions.
+    #[clap(long, action)]
+    measurement: Option<String>,
+
+    /// Comma separated columns of CSV and parquet files written as tags.
+    /// Other columns are written as fields. Ignored for CSV files with a
+    /// `#datatype` annotation and parquet files exported from IOx.
+    #[clap(long, use_value_delimiter = true, action)]
+    tag_columns: Vec<String>,
+
+    /// Column of CSV and parquet files holding the timestamp of each row,
+    /// as nanoseconds since the epoch or an RFC3339 date. Rows without one
+    /// are written at the current time.
+    #[clap(long, default_value = "time", action)]
+    timestamp_column: String,
+
+    /// Maximum size of one write request in bytes. A single line larger
+    /// than this is sent on its own.
+    #[clap(long, default_value = "1048576", action)]
+    max_batch_bytes: usize,
+
+    /// Number of write requests sent at the same time
+    #[clap(long, default_value = "4", action)]
+    concurrency: NonZeroUsize,
+
+    /// Number of times a batch is retried when the router is unavailable or
+    /// overloaded before the write gives up
+    #[clap(long, default_value = "10", action)]
+    max_retries: usize,
+
+    /// File recording how much of the input was written. Defaults to the
+    /// name of the input file with `.checkpoint` appended.
+    #[clap(long, action)]
+    checkpoint_file: Option<PathBuf>,
+
+    /// Continue an interrupted write after the last batch recorded in the
+    /// checkpoint file
+    #[clap(long, action)]
+    resume: bool,
+}
+
+/// Format of the file to write
+#[derive(Debug, Copy, Clone, PartialEq, Eq, clap::ArgEnum)]
+enum Format {
+    /// Line protocol
+    Lp,
+    Csv,
+    Parquet,
+}
+
+/// How the columns of CSV and parquet files are written
+#[derive(Debug)]
+struct TableOptions {
+    /// Table of rows without a measurement of their own
+    measurement: String,
+    tag_columns: Vec<String>,
+    timestamp_column: String,
+}
+
+/// How long to wait between retries of a batch
+fn backoff_config() -> BackoffConfig {
+    BackoffConfig {
+        init_backoff: Duration::from_millis(100),
+        max_backoff: Duration::from_secs(10),
+        base: 3.,
+    }
 }
 
 pub async fn command(connection: Connection, config: Config) -> Result<()> {
-    let mut client = write::Client::new(connection);
+    let checkpoint_file = config
+        .checkpoint_file
+        .clone()
+        .unwrap_or_else(|| default_checkpoint_file(&config.file_name));
+
+    let start = if config.resume {
+        read_checkpoint(&checkpoint_file)?
+    } else {
+        0
+    };
 
-    let mut file = File::open(&config.file_name).map_err(|e| Error::ReadingFile {
+    let (format, compressed) = detect_format(&config.file_name);
+    let format = config.format.unwrap_or(format);
+
+    let file = File::open(&config.file_name).map_err(|e| Error::ReadingFile {
         file_name: config.file_name.clone(),
         source: e,
     })?;
 
-    let mut lp_data = String::new();
-    file.read_to_string(&mut lp_data)
-        .map_err(|e| Error::ReadingFile {
-            file_name: config.file_name.clone(),
-            source: e,
-        })?;
+    // the offset of an uncompressed text file is a byte of the file
+    if !compressed && format != Format::Parquet {
+        let file_len = file
+            .metadata()
+            .map_err(|e| Error::ReadingFile {
+                file_name: config.file_name.clone(),
+                source: e,
+            })?
+            .len();
+        if start > file_len {
+            return Err(Error::InvalidCheckpoint {
+                file_name: checkpoint_file,
+                message: format!(
+                    "offset {} is past the end of {:?}, which has {} bytes",
+                    start, config.file_name, file_len
+                ),
+            });
+        }
+    }
+
+    if start > 0 {
+        let unit = match format {
+            Format::Parquet => "row",
+            Format::Lp | Format::Csv => "byte",
+        };
+        println!("Resuming at {} {} of {:?}", unit, start, config.file_name);
+    }
 
     let default_time = iox_time::SystemProvider::new().now().timestamp_nanos();
-    let lines_written = client.write_lp(config.name, lp_data, default_time).await?;
+    let batches = batches(&config, file, format, compressed, start, default_time)?;
+
+    // a resumed write must find a checkpoint even if no batch is written
+    write_checkpoint(&checkpoint_file, start)?;
+
+    let namespace = config.name.as_str();
+    let max_retries = config.max_retries;
+
+    let mut writes = stream::iter(batches)
+        .map(|batch| {
+            let client = write::Client::new(connection.clone());
+            async move { write_batch(client, namespace, batch?, max_retries).await }
+        })
+        .buffer_unordered(config.concurrency.get());
+
+    let mut progress = Progress::new(start);
+    let mut lines_written = 0;
+    while let Some(written) = writes.next().await {
+        let written = written?;
+        lines_written += written.lines;
+
+        if let Some(offset) = progress.written(written.start, written.end) {
+            write_checkpoint(&checkpoint_file, offset)?;
+        }
+    }
+
+    // the whole file is written, so there is nothing left to resume
+    if let Err(e) = std::fs::remove_file(&checkpoint_file) {
+        debug!(%e, ?checkpoint_file, "unable to remove checkpoint");
+    }
 
     println!("{} Lines OK", lines_written);
 
     Ok(())
 }
+
+/// The format a file name implies, and whether the file is gzip compressed.
+/// Files with unknown extensions are line protocol.
+fn detect_format(file_name: &Path) -> (Format, bool) {
+    let compressed = file_name.extension() == Some(OsStr::new("gz"));
+    let file_name = if compressed {
+        file_name.with_extension("")
+    } else {
+        file_name.to_path_buf()
+    };
+
+    let format = match file_name.extension().and_then(OsStr::to_str) {
+        Some(extension) if extension.eq_ignore_ascii_case("csv") => Format::Csv,
+        Some(extension) if extension.eq_ignore_ascii_case("parquet") => Format::Parquet,
+        _ => Format::Lp,
+    };
+    (format, compressed)
+}
+
+/// Reads `file` in batches, starting at `start`
+fn batches(
+    config: &Config,
+    file: File,
+    format: Format,
+    compressed: bool,
+    start: u64,
+    default_time: i64,
+) -> Result<Box<dyn Iterator<Item = Result<Batch>>>> {
+    let reading_file = |e| Error::ReadingFile {
+        file_name: config.file_name.clone(),
+        source: e,
+    };
+    let input = |file| -> Box<dyn BufRead> {
+        if compressed {
+            Box::new(BufReader::new(GzDecoder::new(file)))
+        } else {
+            Box::new(BufReader::new(file))
+        }
+    };
+
+    let options = TableOptions {
+        measurement: config
+            .measurement
+            .clone()
+            .unwrap_or_else(|| file_stem(&config.file_name)),
+        tag_columns: config.tag_columns.clone(),
+        timestamp_column: config.timestamp_column.clone(),
+    };
+
+    Ok(match format {
+        Format::Lp => {
+            let mut file = file;
+            if !compressed {
+                file.seek(SeekFrom::Start(start)).map_err(reading_file)?;
+            }
+            let mut reader = input(file);
+            // a gzip stream cannot seek, so the part already written is
+            // decompressed and dropped
+            if compressed {
+                std::io::copy(&mut reader.by_ref().take(start), &mut std::io::sink())
+                    .map_err(reading_file)?;
+            }
+            Box::new(LpBatches::new(
+                reader,
+                start,
+                config.max_batch_bytes,
+                default_time,
+            ))
+        }
+        Format::Csv => Box::new(CsvBatches::new(
+            input(file),
+            &options,
+            start,
+            config.max_batch_bytes,
+            default_time,
+        )?),
+        Format::Parquet if compressed => return Err(Error::CompressedParquet),
+        Format::Parquet => Box::new(ParquetBatches::new(
+            file,
+            &options,
+            config.measurement.as_deref(),
+            start,
+            config.max_batch_bytes,
+            default_time,
+        )?),
+    })
+}
+
+/// The name of the file without any extensions
+fn file_stem(file_name: &Path) -> String {
+    file_name
+        .file_name()
+        .map(|name| name.to_string_lossy())
+        .and_then(|name| name.split('.').next().map(str::to_string))
+        .unwrap_or_default()
+}
+
+/// Rows of the input sent as one write request
+#[derive(Debug)]
+struct Batch {
+    /// Position of the first row of the batch in the input
+    start: u64,
+
+    /// Position just past the last row of the batch in the input
+    end: u64,
+
+    /// Rows by table, empty for a batch without any
+    tables: HashMap<String, MutableBatch>,
+}
+
+/// A batch the router acknowledged
+#[derive(Debug)]
+struct Written {
+    start: u64,
+    end: u64,
+    lines: usize,
+}
+
+/// Writes one batch, retrying transient failures with a backoff
+async fn write_batch(
+    client: write::Client,
+    namespace: &str,
+    batch: Batch,
+    max_retries: usize,
+) -> Result<Written> {
+    let Batch { start, end, tables } = batch;
+
+    // only comments and blank lines
+    if tables.is_empty() {
+        return Ok(Written {
+            start,
+            end,
+            lines: 0,
+        });
+    }
+
+    match write_tables(&client, namespace, tables, max_retries).await {
+        Ok(lines) => {
+            info!(start, end, lines, "wrote batch");
+            Ok(Written { start, end, lines })
+        }
+        Err(source) => Err(Error::WritingBatch { start, end, source }),
+    }
+}
+
+/// Writes `tables` to `namespace` in one request, retrying transient
+/// failures up to `max_retries` times with a backoff. Returns the number of
+/// rows written.
+pub(crate) async fn write_tables(
+    client: &write::Client,
+    namespace: &str,
+    tables: HashMap<String, MutableBatch>,
+    max_retries: usize,
+) -> Result<usize, ClientError> {
+    let write = DmlWrite::new(namespace, tables, None, DmlMeta::unsequenced(None));
+    let rows = write.tables().map(|(_, table)| table.rows()).sum();
+    let request = WriteRequest {
+        database_batch: Some(mutable_batch_pb::encode::encode_write(namespace, &write)),
+    };
+    let request = &request;
+    let mut attempt = 0;
+
+    Backoff::new(&backoff_config())
+        .retry_with_backoff("write batch", || {
+            let mut client = client.clone();
+            attempt += 1;
+            let attempt = attempt;
+
+            async move {
+                match client.write_pb(request.clone()).await {
+                    Ok(_) => ControlFlow::Break(Ok(rows)),
+                    Err(e) if is_transient(&e) && attempt <= max_retries => {
+                        ControlFlow::Continue(e)
+                    }
+                    Err(e) => ControlFlow::Break(Err(e)),
+                }
+            }
+        })
+        .await
+        .expect("retry forever")
+}
+
+/// Errors a later attempt of the same write may not run into
+fn is_transient(error: &ClientError) -> bool {
+    matches!(
+        error,
+        ClientError::Unavailable(_)
+            | ClientError::ResourceExhausted(_)
+            | ClientError::DeadlineExceeded(_)
+            | ClientError::Aborted(_)
+    )
+}
+
+/// Tracks which batches were written. As batches are written concurrently
+/// they can finish in any order, the checkpoint only moves past a batch once
+/// every batch before it was written too.
+#[derive(Debug)]
+struct Progress {
+    /// Offset up to which the input was written
+    offset: u64,
+
+    /// Batches written past `offset`, as start to end offsets
+    written: BTreeMap<u64, u64>,
+}
+
+impl Progress {
+    fn new(offset: u64) -> Self {
+        Self {
+            offset,
+            written: BTreeMap::new(),
+        }
+    }
+
+    /// Records a written batch, returning the new offset up to which the
+    /// input was written if it moved
+    fn written(&mut self, start: u64, end: u64) -> Option<u64> {
+        self.written.insert(start, end);
+
+        let before = self.offset;
+        while let Some(end) = self.written.remove(&self.offset) {
+            self.offset = end;
+        }
+
+        if self.offset != before {
+            Some(self.offset)
+        } else {
+            None
+        }
+    }
+}
+
+fn default_checkpoint_file(file_name: &Path) -> PathBuf {
+    let mut checkpoint_file = OsString::from(file_name);
+    checkpoint_file.push(".checkpoint");
+    checkpoint_file.into()
+}
+
+fn read_checkpoint(file_name: &Path) -> Result<u64> {
+    let checkpoint = std::fs::read_to_string(file_name).map_err(|e| Error::ReadingCheckpoint {
+        file_name: file_name.to_path_buf(),
+        source: e,
+    })?;
+
+    checkpoint
+        .trim()
+        .parse()
+        .map_err(|e| Error::InvalidCheckpoint {
+            file_name: file_name.to_path_buf(),
+            message: e.to_string(),
+        })
+}
+
+/// Replaces the checkpoint, writing a new file first so an interrupted
+/// update leaves the previous checkpoint intact
+fn write_checkpoint(file_name: &Path, offset: u64) -> Result<()> {
+    let mut tmp_file_name = OsString::from(file_name);
+    tmp_file_name.push(".tmp");
+
+    std::fs::write(&tmp_file_name, format!("{}\n", offset))
+        .and_then(|_| std::fs::rename(&tmp_file_name, file_name))
+        .map_err(|e| Error::WritingCheckpoint {
+            file_name: file_name.to_path_buf(),
+            source: e,
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_formats_by_extension() {
+        let detect = |file_name: &str| detect_format(Path::new(file_name));
+
+        assert_eq!(detect("data.lp"), (Format::Lp, false));
+        assert_eq!(detect("data.lp.gz"), (Format::Lp, true));
+        assert_eq!(detect("data.gz"), (Format::Lp, true));
+        assert_eq!(detect("data.CSV"), (Format::Csv, false));
+        assert_eq!(detect("data.csv.gz"), (Format::Csv, true));
+        assert_eq!(detect("data.parquet"), (Format::Parquet, false));
+        assert_eq!(detect("data.txt"), (Format::Lp, false));
+
+        assert_eq!(file_stem(Path::new("/tmp/cpu.csv.gz")), "cpu");
+    }
+
+    #[test]
+    fn checkpoint_waits_for_earlier_batches() {
+        let mut progress = Progress::new(10);
+
+        assert_eq!(progress.written(20, 30), None);
+        assert_eq!(progress.written(30, 40), None);
+        assert_eq!(progress.written(10, 20), Some(40));
+        assert_eq!(progress.written(40, 50), Some(50));
+    }
+
+    #[test]
+    fn checkpoint_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let input = dir.path().join("data.lp");
+        let checkpoint = default_checkpoint_file(&input);
+        assert_eq!(checkpoint, dir.path().join("data.lp.checkpoint"));
+
+        write_checkpoint(&checkpoint, 42).unwrap();
+        assert_eq!(read_checkpoint(&checkpoint).unwrap(), 42);
+
+        std::fs::write(&checkpoint, "bananas").unwrap();
+        assert!(matches!(
+            read_checkpoint(&checkpoint),
+            Err(Error::InvalidCheckpoint { .. })
+        ));
+    }
+}
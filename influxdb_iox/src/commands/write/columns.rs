@@ -0,0 +1,216 @@
+//! Rows of CSV and parquet files gathered column by column and converted
+//! into [`MutableBatch`]es
+
+use mutable_batch::{
+    writer::{self, Writer},
+    MutableBatch,
+};
+use schema::{InfluxColumnType, InfluxFieldType, TIME_COLUMN_NAME};
+
+/// The values of one column of a table, `None` for nulls
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Values {
+    Tag(Vec<Option<String>>),
+    String(Vec<Option<String>>),
+    F64(Vec<Option<f64>>),
+    I64(Vec<Option<i64>>),
+    U64(Vec<Option<u64>>),
+    Bool(Vec<Option<bool>>),
+}
+
+impl Values {
+    /// A column of `column_type` holding `rows` nulls. Timestamps other than
+    /// the time column are stored as integer nanoseconds.
+    pub(crate) fn nulls(column_type: InfluxColumnType, rows: usize) -> Self {
+        match column_type {
+            InfluxColumnType::Tag => Self::Tag(vec![None; rows]),
+            InfluxColumnType::Field(InfluxFieldType::String) => Self::String(vec![None; rows]),
+            InfluxColumnType::Field(InfluxFieldType::Float) => Self::F64(vec![None; rows]),
+            InfluxColumnType::Field(InfluxFieldType::Integer) | InfluxColumnType::Timestamp => {
+                Self::I64(vec![None; rows])
+            }
+            InfluxColumnType::Field(InfluxFieldType::UInteger) => Self::U64(vec![None; rows]),
+            InfluxColumnType::Field(InfluxFieldType::Boolean) => Self::Bool(vec![None; rows]),
+        }
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        match self {
+            Self::Tag(values) | Self::String(values) => values.len(),
+            Self::F64(values) => values.len(),
+            Self::I64(values) => values.len(),
+            Self::U64(values) => values.len(),
+            Self::Bool(values) => values.len(),
+        }
+    }
+
+    pub(crate) fn push_null(&mut self) {
+        match self {
+            Self::Tag(values) | Self::String(values) => values.push(None),
+            Self::F64(values) => values.push(None),
+            Self::I64(values) => values.push(None),
+            Self::U64(values) => values.push(None),
+            Self::Bool(values) => values.push(None),
+        }
+    }
+
+    /// Parses and appends a value written as text, an empty string is a null
+    pub(crate) fn push_str(&mut self, value: &str) -> Result<(), String> {
+        if value.is_empty() {
+            self.push_null();
+            return Ok(());
+        }
+
+        match self {
+            Self::Tag(values) | Self::String(values) => values.push(Some(value.to_string())),
+            Self::F64(values) => values.push(Some(
+                value
+                    .parse()
+                    .map_err(|e| format!("invalid float {:?}: {}", value, e))?,
+            )),
+            Self::I64(values) => values.push(Some(
+                value
+                    .parse()
+                    .map_err(|e| format!("invalid integer {:?}: {}", value, e))?,
+            )),
+            Self::U64(values) => {
+                values.push(Some(value.parse().map_err(|e| {
+                    format!("invalid unsigned integer {:?}: {}", value, e)
+                })?))
+            }
+            Self::Bool(values) => values.push(Some(parse_bool(value)?)),
+        }
+        Ok(())
+    }
+
+    fn write(&self, writer: &mut Writer<'_>, name: &str) -> writer::Result<()> {
+        match self {
+            Self::Tag(values) => writer.write_tag(
+                name,
+                valid_mask(values).as_deref(),
+                values.iter().flatten().map(String::as_str),
+            ),
+            Self::String(values) => writer.write_string(
+                name,
+                valid_mask(values).as_deref(),
+                values.iter().flatten().map(String::as_str),
+            ),
+            Self::F64(values) => writer.write_f64(
+                name,
+                valid_mask(values).as_deref(),
+                values.iter().flatten().copied(),
+            ),
+            Self::I64(values) => writer.write_i64(
+                name,
+                valid_mask(values).as_deref(),
+                values.iter().flatten().copied(),
+            ),
+            Self::U64(values) => writer.write_u64(
+                name,
+                valid_mask(values).as_deref(),
+                values.iter().flatten().copied(),
+            ),
+            Self::Bool(values) => writer.write_bool(
+                name,
+                valid_mask(values).as_deref(),
+                values.iter().flatten().copied(),
+            ),
+        }
+    }
+}
+
+/// Converts the rows of one table into a [`MutableBatch`], `time` holds the
+/// timestamp of every row and each column a value for every row
+pub(crate) fn to_mutable_batch<'a>(
+    time: &[i64],
+    columns: impl IntoIterator<Item = (&'a str, &'a Values)>,
+) -> writer::Result<MutableBatch> {
+    let mut batch = MutableBatch::new();
+    let mut writer = Writer::new(&mut batch, time.len());
+
+    for (name, values) in columns {
+        assert_eq!(
+            values.len(),
+            time.len(),
+            "column {} has a value per row",
+            name
+        );
+        values.write(&mut writer, name)?;
+    }
+    writer.write_time(TIME_COLUMN_NAME, time.iter().copied())?;
+    writer.commit();
+
+    Ok(batch)
+}
+
+/// A bitmask with a set bit for each non-null value, `None` without nulls
+fn valid_mask<T>(values: &[Option<T>]) -> Option<Vec<u8>> {
+    if values.iter().all(Option::is_some) {
+        return None;
+    }
+
+    let mut mask = vec![0_u8; (values.len() + 7) / 8];
+    for (idx, value) in values.iter().enumerate() {
+        if value.is_some() {
+            mask[idx / 8] |= 1 << (idx % 8);
+        }
+    }
+    Some(mask)
+}
+
+fn parse_bool(value: &str) -> Result<bool, String> {
+    if value.eq_ignore_ascii_case("true") {
+        Ok(true)
+    } else if value.eq_ignore_ascii_case("false") {
+        Ok(false)
+    } else {
+        Err(format!("invalid boolean {:?}", value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow_util::assert_batches_eq;
+    use schema::selection::Selection;
+
+    #[test]
+    fn converts_columns_with_nulls() {
+        let mut host = Values::nulls(InfluxColumnType::Tag, 0);
+        let mut usage = Values::nulls(InfluxColumnType::Field(InfluxFieldType::Float), 0);
+        let mut up = Values::nulls(InfluxColumnType::Field(InfluxFieldType::Boolean), 0);
+        for (h, u, b) in [("a", "1.5", "true"), ("", "2", "FALSE"), ("b", "", "")] {
+            host.push_str(h).unwrap();
+            usage.push_str(u).unwrap();
+            up.push_str(b).unwrap();
+        }
+
+        let batch = to_mutable_batch(
+            &[1, 2, 3],
+            [("host", &host), ("usage", &usage), ("up", &up)],
+        )
+        .unwrap();
+
+        assert_batches_eq!(
+            &[
+                "+------+--------------------------------+-------+-------+",
+                "| host | time                           | up    | usage |",
+                "+------+--------------------------------+-------+-------+",
+                "| a    | 1970-01-01T00:00:00.000000001Z | true  | 1.5   |",
+                "|      | 1970-01-01T00:00:00.000000002Z | false | 2     |",
+                "| b    | 1970-01-01T00:00:00.000000003Z |       |       |",
+                "+------+--------------------------------+-------+-------+",
+            ],
+            &[batch.to_arrow(Selection::All).unwrap()]
+        );
+    }
+
+    #[test]
+    fn rejects_values_of_the_wrong_type() {
+        let mut usage = Values::nulls(InfluxColumnType::Field(InfluxFieldType::Integer), 2);
+
+        assert!(usage.push_str("12").is_ok());
+        assert!(usage.push_str("bananas").is_err());
+        assert_eq!(usage, Values::I64(vec![None, None, Some(12)]));
+    }
+}
@@ -0,0 +1,476 @@
+//! CSV input, with the type of each column given by a `#datatype`
+//! annotation as written by `influx query --raw` and read by `influx write`,
+//! or by the `--tag-columns` and `--timestamp-column` flags
+
+use super::{
+    columns::{to_mutable_batch, Values},
+    Batch, Error, Result, TableOptions,
+};
+use hashbrown::HashMap;
+use schema::{InfluxColumnType, InfluxFieldType};
+use std::{collections::BTreeMap, io::BufRead};
+
+/// What a column of the CSV file holds
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ColumnKind {
+    /// The table of the row
+    Measurement,
+
+    /// The timestamp of the row
+    Time(TimeFormat),
+
+    /// A tag or field, `None` for a field whose type is taken from its first
+    /// value
+    Column(Option<InfluxColumnType>),
+
+    Ignored,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TimeFormat {
+    /// Nanoseconds since the epoch
+    Number,
+    Rfc3339,
+
+    /// Either of the two
+    Any,
+}
+
+#[derive(Debug)]
+struct Column {
+    name: String,
+    kind: ColumnKind,
+}
+
+/// The rows of one table in a batch
+#[derive(Debug, Default)]
+struct Table {
+    time: Vec<i64>,
+
+    /// Values by index of their column. Columns with only nulls so far
+    /// have none.
+    columns: BTreeMap<usize, Values>,
+}
+
+/// Splits a CSV file into batches of whole records of about `max_bytes`
+/// each. Positions are byte offsets into the records following the header.
+pub(super) struct CsvBatches<R> {
+    reader: csv::Reader<R>,
+    columns: Vec<Column>,
+
+    /// Table of rows without a measurement column
+    measurement: String,
+
+    /// Timestamp of rows without one
+    default_time: i64,
+
+    max_bytes: usize,
+
+    /// Offset in the input of the next batch
+    offset: u64,
+
+    /// Number of annotation lines before the header, the CSV reader counts
+    /// lines from the header
+    annotation_lines: u64,
+
+    record: csv::StringRecord,
+}
+
+impl<R: BufRead> CsvBatches<R> {
+    /// Reads the annotations and header of the CSV file and skips the
+    /// records before `offset`
+    pub(super) fn new(
+        mut reader: R,
+        options: &TableOptions,
+        offset: u64,
+        max_bytes: usize,
+        default_time: i64,
+    ) -> Result<Self> {
+        let (datatypes, annotation_lines) = read_annotations(&mut reader)?;
+
+        let mut reader = csv::ReaderBuilder::new()
+            .comment(Some(b'#'))
+            .from_reader(reader);
+        let header = reader
+            .headers()
+            .map_err(|e| invalid_csv(e, annotation_lines))?
+            .clone();
+
+        let columns = match datatypes {
+            Some(datatypes) => {
+                if datatypes.len() != header.len() {
+                    return Err(Error::InvalidCsv {
+                        line: annotation_lines + 1,
+                        message: format!(
+                            "{} column types annotated for {} columns",
+                            datatypes.len(),
+                            header.len()
+                        ),
+                    });
+                }
+                header
+                    .iter()
+                    .zip(datatypes.iter())
+                    .map(|(name, datatype)| {
+                        Ok(Column {
+                            name: name.to_string(),
+                            kind: parse_datatype(datatype)?,
+                        })
+                    })
+                    .collect::<Result<Vec<_>>>()?
+            }
+            None => header
+                .iter()
+                .map(|name| Column {
+                    name: name.to_string(),
+                    kind: column_kind(options, name),
+                })
+                .collect(),
+        };
+
+        let mut batches = Self {
+            reader,
+            columns,
+            measurement: options.measurement.clone(),
+            default_time,
+            max_bytes,
+            offset,
+            annotation_lines,
+            record: csv::StringRecord::new(),
+        };
+
+        // a checkpoint is always the end of a record
+        while batches.reader.position().byte() < offset {
+            if !batches
+                .reader
+                .read_record(&mut batches.record)
+                .map_err(|e| invalid_csv(e, annotation_lines))?
+            {
+                break;
+            }
+        }
+
+        Ok(batches)
+    }
+
+    /// Adds the last record read to its table
+    fn add_record(&mut self, tables: &mut HashMap<String, Table>) -> Result<()> {
+        let line = self.record.position().map_or(0, |p| p.line()) + self.annotation_lines;
+        let invalid = |column: &Column, message: String| Error::InvalidCsv {
+            line,
+            message: format!("column {:?}: {}", column.name, message),
+        };
+
+        let measurement = self
+            .columns
+            .iter()
+            .zip(self.record.iter())
+            .find(|(column, _)| column.kind == ColumnKind::Measurement)
+            .map_or(self.measurement.as_str(), |(_, value)| value);
+        let table = tables.entry(measurement.to_string()).or_default();
+        let rows = table.time.len();
+
+        let mut time = self.default_time;
+        for (idx, (column, value)) in self.columns.iter_mut().zip(self.record.iter()).enumerate() {
+            match column.kind {
+                ColumnKind::Measurement | ColumnKind::Ignored => {}
+                ColumnKind::Time(format) => {
+                    if !value.is_empty() {
+                        time = parse_time(value, format).map_err(|e| invalid(&*column, e))?;
+                    }
+                }
+                ColumnKind::Column(column_type) => {
+                    if value.is_empty() {
+                        continue;
+                    }
+                    let column_type = match column_type {
+                        Some(column_type) => column_type,
+                        None => {
+                            let column_type = infer_type(value);
+                            column.kind = ColumnKind::Column(Some(column_type));
+                            column_type
+                        }
+                    };
+                    table
+                        .columns
+                        .entry(idx)
+                        .or_insert_with(|| Values::nulls(column_type, rows))
+                        .push_str(value)
+                        .map_err(|e| invalid(&*column, e))?;
+                }
+            }
+        }
+
+        table.time.push(time);
+        for values in table.columns.values_mut() {
+            if values.len() == rows {
+                values.push_null();
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<R: BufRead> Iterator for CsvBatches<R> {
+    type Item = Result<Batch>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let start = self.offset;
+        let mut tables = HashMap::new();
+        let mut rows = 0;
+
+        while rows == 0
+            || self.reader.position().byte().saturating_sub(start) < self.max_bytes as u64
+        {
+            match self.reader.read_record(&mut self.record) {
+                Ok(true) => {}
+                Ok(false) => break,
+                Err(e) => return Some(Err(invalid_csv(e, self.annotation_lines))),
+            }
+            if let Err(e) = self.add_record(&mut tables) {
+                return Some(Err(e));
+            }
+            rows += 1;
+        }
+
+        if rows == 0 {
+            return None;
+        }
+        self.offset = self.reader.position().byte();
+
+        let columns = &self.columns;
+        let tables = tables
+            .into_iter()
+            .map(|(name, table)| {
+                let values = table
+                    .columns
+                    .iter()
+                    .map(|(idx, values)| (columns[*idx].name.as_str(), values));
+                let batch = to_mutable_batch(&table.time, values).map_err(|source| {
+                    Error::ConvertingRows {
+                        table: name.clone(),
+                        start,
+                        end: self.offset,
+                        source,
+                    }
+                })?;
+                Ok((name, batch))
+            })
+            .collect::<Result<_>>();
+
+        Some(tables.map(|tables| Batch {
+            start,
+            end: self.offset,
+            tables,
+        }))
+    }
+}
+
+/// Reads the annotation lines at the start of the file, returning the
+/// column types of a `#datatype` annotation if there is one and the number
+/// of lines read
+fn read_annotations<R: BufRead>(reader: &mut R) -> Result<(Option<csv::StringRecord>, u64)> {
+    let mut datatypes = None;
+    let mut lines = 0;
+
+    loop {
+        let buf = reader
+            .fill_buf()
+            .map_err(|e| Error::ReadingInput { source: e })?;
+        if buf.first() != Some(&b'#') {
+            return Ok((datatypes, lines));
+        }
+        lines += 1;
+
+        let mut line = String::new();
+        reader
+            .read_line(&mut line)
+            .map_err(|e| Error::ReadingInput { source: e })?;
+
+        // both `#datatype tag,double` and `#datatype,tag,double` are used,
+        // the latter for a leading annotation column
+        if let Some(types) = line.strip_prefix("#datatype") {
+            let types = types.trim_start_matches(' ').trim_end();
+            let record = csv::ReaderBuilder::new()
+                .has_headers(false)
+                .from_reader(types.as_bytes())
+                .records()
+                .next()
+                .transpose()
+                .map_err(|e| invalid_csv(e, lines))?;
+            datatypes = record;
+        }
+    }
+}
+
+/// The kind of a column of an unannotated file
+fn column_kind(options: &TableOptions, name: &str) -> ColumnKind {
+    if name == options.timestamp_column {
+        ColumnKind::Time(TimeFormat::Any)
+    } else if options.tag_columns.iter().any(|tag| tag == name) {
+        ColumnKind::Column(Some(InfluxColumnType::Tag))
+    } else {
+        ColumnKind::Column(None)
+    }
+}
+
+fn parse_datatype(datatype: &str) -> Result<ColumnKind> {
+    Ok(match datatype {
+        "measurement" => ColumnKind::Measurement,
+        "tag" => ColumnKind::Column(Some(InfluxColumnType::Tag)),
+        "field" => ColumnKind::Column(None),
+        "double" => ColumnKind::Column(Some(InfluxColumnType::Field(InfluxFieldType::Float))),
+        "long" => ColumnKind::Column(Some(InfluxColumnType::Field(InfluxFieldType::Integer))),
+        "unsignedLong" => {
+            ColumnKind::Column(Some(InfluxColumnType::Field(InfluxFieldType::UInteger)))
+        }
+        "boolean" => ColumnKind::Column(Some(InfluxColumnType::Field(InfluxFieldType::Boolean))),
+        "string" => ColumnKind::Column(Some(InfluxColumnType::Field(InfluxFieldType::String))),
+        "dateTime" => ColumnKind::Time(TimeFormat::Any),
+        "dateTime:RFC3339" => ColumnKind::Time(TimeFormat::Rfc3339),
+        "dateTime:number" => ColumnKind::Time(TimeFormat::Number),
+        "ignored" | "" => ColumnKind::Ignored,
+        other => {
+            return Err(Error::InvalidCsv {
+                line: 0,
+                message: format!("unsupported datatype annotation {:?}", other),
+            })
+        }
+    })
+}
+
+/// The type of a field of an unannotated file, from its first value
+fn infer_type(value: &str) -> InfluxColumnType {
+    let field_type = if value.eq_ignore_ascii_case("true") || value.eq_ignore_ascii_case("false") {
+        InfluxFieldType::Boolean
+    } else if value.parse::<f64>().is_ok() {
+        InfluxFieldType::Float
+    } else {
+        InfluxFieldType::String
+    };
+    InfluxColumnType::Field(field_type)
+}
+
+fn parse_time(value: &str, format: TimeFormat) -> Result<i64, String> {
+    let number = || {
+        value
+            .parse::<i64>()
+            .map_err(|e| format!("invalid timestamp {:?}: {}", value, e))
+    };
+    let rfc3339 = || {
+        iox_time::Time::from_rfc3339(value)
+            .map(|t| t.timestamp_nanos())
+            .map_err(|e| format!("invalid timestamp {:?}: {}", value, e))
+    };
+
+    match format {
+        TimeFormat::Number => number(),
+        TimeFormat::Rfc3339 => rfc3339(),
+        TimeFormat::Any => number().or_else(|_| rfc3339()),
+    }
+}
+
+fn invalid_csv(error: csv::Error, annotation_lines: u64) -> Error {
+    Error::InvalidCsv {
+        line: error.position().map_or(0, |p| p.line()) + annotation_lines,
+        message: error.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow_util::{assert_batches_eq, assert_batches_sorted_eq};
+    use schema::selection::Selection;
+
+    fn options(tag_columns: &[&str]) -> TableOptions {
+        TableOptions {
+            measurement: "cpu".to_string(),
+            tag_columns: tag_columns.iter().map(|c| c.to_string()).collect(),
+            timestamp_column: "time".to_string(),
+        }
+    }
+
+    fn batches(csv: &str, options: &TableOptions, offset: u64, max_bytes: usize) -> Vec<Batch> {
+        CsvBatches::new(csv.as_bytes(), options, offset, max_bytes, 42)
+            .unwrap()
+            .collect::<Result<_>>()
+            .unwrap()
+    }
+
+    #[test]
+    fn reads_columns_named_by_flags() {
+        let csv = "host,usage,up,time\na,1.5,true,1\nb,,false,\n";
+        let batches = batches(csv, &options(&["host"]), 0, 1024);
+
+        assert_eq!(batches.len(), 1);
+        assert_batches_eq!(
+            &[
+                "+------+--------------------------------+-------+-------+",
+                "| host | time                           | up    | usage |",
+                "+------+--------------------------------+-------+-------+",
+                "| a    | 1970-01-01T00:00:00.000000001Z | true  | 1.5   |",
+                "| b    | 1970-01-01T00:00:00.000000042Z | false |       |",
+                "+------+--------------------------------+-------+-------+",
+            ],
+            &[batches[0].tables["cpu"].to_arrow(Selection::All).unwrap()]
+        );
+    }
+
+    #[test]
+    fn reads_annotated_columns() {
+        let csv = "#datatype measurement,tag,long,ignored,dateTime:RFC3339\n\
+                   m,host,free,note,time\n\
+                   mem,a,10,x,1970-01-01T00:00:00.000000002Z\n\
+                   swap,b,20,y,1970-01-01T00:00:00.000000003Z\n";
+        let batches = batches(csv, &options(&[]), 0, 1024);
+
+        assert_batches_sorted_eq!(
+            &[
+                "+------+------+--------------------------------+",
+                "| free | host | time                           |",
+                "+------+------+--------------------------------+",
+                "| 10   | a    | 1970-01-01T00:00:00.000000002Z |",
+                "+------+------+--------------------------------+",
+            ],
+            &[batches[0].tables["mem"].to_arrow(Selection::All).unwrap()]
+        );
+        assert_eq!(batches[0].tables["swap"].rows(), 1);
+        assert!(!batches[0].tables.contains_key("cpu"));
+    }
+
+    #[test]
+    fn splits_records_into_batches_and_resumes() {
+        let csv = "usage,time\n1,1\n2,2\n3,3\n";
+
+        let all = batches(csv, &options(&[]), 0, 8);
+        let rows = all
+            .iter()
+            .map(|batch| (batch.start, batch.end, batch.tables["cpu"].rows()))
+            .collect::<Vec<_>>();
+        // the first batch starts at the header
+        assert_eq!(rows, vec![(0, 15, 1), (15, 23, 2)]);
+
+        let resumed = batches(csv, &options(&[]), 15, 8);
+        assert_eq!(resumed.len(), 1);
+        assert_eq!(resumed[0].start, 15);
+        assert_eq!(resumed[0].tables["cpu"].rows(), 2);
+    }
+
+    #[test]
+    fn reports_the_line_of_invalid_values() {
+        let csv = "#datatype long,dateTime\nusage,time\n1,1\nbananas,2\n";
+        let error = CsvBatches::new(csv.as_bytes(), &options(&[]), 0, 1024, 0)
+            .unwrap()
+            .next()
+            .unwrap()
+            .unwrap_err();
+
+        assert!(
+            matches!(&error, Error::InvalidCsv { line: 4, message } if message.contains("bananas")),
+            "{}",
+            error
+        );
+    }
+}
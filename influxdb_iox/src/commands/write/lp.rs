@@ -0,0 +1,206 @@
+//! Line protocol input, split into batches of whole lines
+
+use super::{Batch, Error, Result};
+use hashbrown::HashMap;
+use std::io::BufRead;
+
+/// Splits line protocol into batches of whole lines of at most `max_bytes`
+/// each, reading the input only as the batches are needed. Positions are
+/// byte offsets into the (decompressed) input.
+pub(super) struct LpBatches<R> {
+    reader: R,
+
+    /// Offset in the input of the next batch
+    offset: u64,
+
+    max_bytes: usize,
+
+    /// Timestamp of lines without one
+    default_time: i64,
+
+    /// A line read that did not fit into the previous batch
+    carry: Vec<u8>,
+}
+
+impl<R: BufRead> LpBatches<R> {
+    pub(super) fn new(reader: R, offset: u64, max_bytes: usize, default_time: i64) -> Self {
+        Self {
+            reader,
+            offset,
+            max_bytes,
+            default_time,
+            carry: vec![],
+        }
+    }
+
+    /// Reads the next run of whole lines
+    fn next_lines(&mut self) -> Option<std::io::Result<Lines>> {
+        let mut batch = std::mem::take(&mut self.carry);
+
+        loop {
+            let mut line = vec![];
+            match self.reader.read_until(b'\n', &mut line) {
+                Ok(0) => break,
+                Ok(_) => {}
+                Err(e) => return Some(Err(e)),
+            }
+
+            if !batch.is_empty() && batch.len() + line.len() > self.max_bytes {
+                self.carry = line;
+                break;
+            }
+            batch.extend_from_slice(&line);
+        }
+
+        if batch.is_empty() {
+            return None;
+        }
+
+        let start = self.offset;
+        self.offset += batch.len() as u64;
+
+        Some(
+            String::from_utf8(batch)
+                .map(|lp_data| Lines {
+                    start,
+                    end: self.offset,
+                    lp_data,
+                })
+                .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e)),
+        )
+    }
+}
+
+impl<R: BufRead> Iterator for LpBatches<R> {
+    type Item = Result<Batch>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let lines = match self.next_lines()? {
+            Ok(lines) => lines,
+            Err(e) => return Some(Err(Error::ReadingInput { source: e })),
+        };
+
+        let tables = match mutable_batch_lp::lines_to_batches(&lines.lp_data, self.default_time) {
+            Ok(tables) => tables,
+            // a batch of only comments and blank lines
+            Err(mutable_batch_lp::Error::EmptyPayload) => HashMap::new(),
+            Err(source) => {
+                return Some(Err(Error::InvalidLineProtocol {
+                    start: lines.start,
+                    end: lines.end,
+                    source,
+                }))
+            }
+        };
+
+        Some(Ok(Batch {
+            start: lines.start,
+            end: lines.end,
+            tables,
+        }))
+    }
+}
+
+/// A run of whole lines of the input
+#[derive(Debug, PartialEq, Eq)]
+struct Lines {
+    /// Offset of the first byte of the lines in the input
+    start: u64,
+
+    /// Offset just past the last byte of the lines in the input
+    end: u64,
+
+    lp_data: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(lp: &str, offset: u64, max_bytes: usize) -> Vec<Lines> {
+        let mut batches = LpBatches::new(lp.as_bytes(), offset, max_bytes, 0);
+        std::iter::from_fn(|| batches.next_lines())
+            .collect::<std::io::Result<_>>()
+            .unwrap()
+    }
+
+    fn batch(start: u64, lp_data: &str) -> Lines {
+        Lines {
+            start,
+            end: start + lp_data.len() as u64,
+            lp_data: lp_data.to_string(),
+        }
+    }
+
+    #[test]
+    fn splits_input_into_batches_of_whole_lines() {
+        let lp = "cpu usage=1 1\ncpu usage=2 2\ncpu usage=3 3\n";
+
+        assert_eq!(lines(lp, 0, 1024), vec![batch(0, lp)]);
+        assert_eq!(
+            lines(lp, 0, 28),
+            vec![
+                batch(0, "cpu usage=1 1\ncpu usage=2 2\n"),
+                batch(28, "cpu usage=3 3\n"),
+            ]
+        );
+    }
+
+    #[test]
+    fn sends_long_lines_on_their_own() {
+        let lp = "cpu usage=1 1\ncpu,host=a_very_long_host_name usage=2 2\ncpu usage=3 3";
+
+        assert_eq!(
+            lines(lp, 100, 20),
+            vec![
+                batch(100, "cpu usage=1 1\n"),
+                batch(114, "cpu,host=a_very_long_host_name usage=2 2\n"),
+                batch(155, "cpu usage=3 3"),
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_utf8() {
+        let mut batches = LpBatches::new(&b"cpu usage=1 1\n\xff\n"[..], 0, 10, 0);
+
+        assert!(batches.next().unwrap().is_ok());
+        assert!(matches!(
+            batches.next().unwrap(),
+            Err(Error::ReadingInput { .. })
+        ));
+    }
+
+    #[test]
+    fn converts_lines_to_tables() {
+        let lp = "cpu usage=1 1\n# a comment\n\nmem free=2i 2\n";
+        let mut batches = LpBatches::new(lp.as_bytes(), 0, 14, 0);
+
+        let batch = batches.next().unwrap().unwrap();
+        assert_eq!(batch.tables["cpu"].rows(), 1);
+
+        let batch = batches.next().unwrap().unwrap();
+        assert!(batch.tables.is_empty());
+
+        let batch = batches.next().unwrap().unwrap();
+        assert_eq!(batch.tables["mem"].rows(), 1);
+        assert_eq!(batch.end, lp.len() as u64);
+
+        assert!(batches.next().is_none());
+    }
+
+    #[test]
+    fn reports_invalid_line_protocol_with_its_batch() {
+        let mut batches = LpBatches::new(&b"cpu usage=1 1\ncpu usage=\n"[..], 0, 10, 0);
+
+        assert!(batches.next().unwrap().is_ok());
+        assert!(matches!(
+            batches.next().unwrap(),
+            Err(Error::InvalidLineProtocol {
+                start: 14,
+                end: 25,
+                ..
+            })
+        ));
+    }
+}
@@ -0,0 +1,377 @@
+//! Parquet input. Files exported from IOx carry the type of each column
+//! and the measurement in their schema metadata, for other files the
+//! `--tag-columns` and `--timestamp-column` flags say which column is which.
+
+use super::{
+    columns::{to_mutable_batch, Values},
+    Batch, Error, Result, TableOptions,
+};
+use arrow::{
+    array::{as_boolean_array, as_primitive_array, as_string_array, ArrayRef},
+    compute::cast,
+    datatypes::{
+        DataType, Float64Type, Int64Type, SchemaRef as ArrowSchemaRef, TimeUnit, UInt64Type,
+    },
+    error::ArrowError,
+    record_batch::RecordBatch,
+};
+use parquet::{
+    arrow::{arrow_reader::ParquetRecordBatchReader, ArrowReader, ParquetFileArrowReader},
+    file::reader::{FileReader, SerializedFileReader},
+};
+use schema::{InfluxColumnType, InfluxFieldType, Schema};
+use std::{fs::File, sync::Arc};
+
+/// Reads a parquet file in batches of about `max_bytes` of parquet data.
+/// Positions are row numbers.
+pub(super) struct ParquetBatches {
+    reader: ParquetRecordBatchReader,
+
+    measurement: String,
+
+    /// Type of each column of the file, `None` for the time column
+    column_types: Vec<Option<InfluxColumnType>>,
+
+    /// Timestamp of rows without one
+    default_time: i64,
+
+    /// Rows still to skip to get to the offset the write resumes at
+    skip: usize,
+
+    /// Row number of the next batch
+    offset: u64,
+}
+
+impl ParquetBatches {
+    pub(super) fn new(
+        file: File,
+        options: &TableOptions,
+        measurement: Option<&str>,
+        offset: u64,
+        max_bytes: usize,
+        default_time: i64,
+    ) -> Result<Self> {
+        let file_reader = SerializedFileReader::new(file).map_err(reading_parquet)?;
+
+        // size record batches so they hold about `max_bytes` of parquet data
+        let metadata = file_reader.metadata();
+        let rows = metadata.file_metadata().num_rows().max(1) as usize;
+        let bytes = metadata
+            .row_groups()
+            .iter()
+            .map(|row_group| row_group.total_byte_size())
+            .sum::<i64>()
+            .max(1) as usize;
+        let batch_size = (max_bytes / (bytes / rows).max(1)).max(1);
+
+        let mut arrow_reader = ParquetFileArrowReader::new(Arc::new(file_reader));
+        let arrow_schema = Arc::new(arrow_reader.get_schema().map_err(reading_parquet)?);
+        let (iox_measurement, column_types) = column_types(&arrow_schema, options)?;
+        let reader = arrow_reader
+            .get_record_reader(batch_size)
+            .map_err(reading_parquet)?;
+
+        let measurement = measurement
+            .map(str::to_string)
+            .or(iox_measurement)
+            .unwrap_or_else(|| options.measurement.clone());
+
+        Ok(Self {
+            reader,
+            measurement,
+            column_types,
+            default_time,
+            skip: offset as usize,
+            offset,
+        })
+    }
+
+    fn to_batch(&self, record_batch: &RecordBatch) -> Result<Batch> {
+        let start = self.offset;
+        let end = start + record_batch.num_rows() as u64;
+        let unsupported = |column: &str, e: ArrowError| Error::UnsupportedColumn {
+            column: column.to_string(),
+            message: e.to_string(),
+        };
+
+        let mut time = vec![self.default_time; record_batch.num_rows()];
+        let mut columns = vec![];
+        for (idx, column_type) in self.column_types.iter().enumerate() {
+            let name = record_batch.schema().field(idx).name().clone();
+            let array = record_batch.column(idx);
+            match column_type {
+                Some(column_type) => {
+                    let values =
+                        to_values(*column_type, array).map_err(|e| unsupported(&name, e))?;
+                    columns.push((name, values));
+                }
+                None => {
+                    time = to_time(array, self.default_time).map_err(|e| unsupported(&name, e))?
+                }
+            }
+        }
+
+        let batch = to_mutable_batch(
+            &time,
+            columns.iter().map(|(name, values)| (name.as_str(), values)),
+        )
+        .map_err(|source| Error::ConvertingRows {
+            table: self.measurement.clone(),
+            start,
+            end,
+            source,
+        })?;
+
+        Ok(Batch {
+            start,
+            end,
+            tables: std::iter::once((self.measurement.clone(), batch)).collect(),
+        })
+    }
+}
+
+impl Iterator for ParquetBatches {
+    type Item = Result<Batch>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let record_batch = match self.reader.next()? {
+                Ok(record_batch) => record_batch,
+                Err(e) => {
+                    return Some(Err(Error::ReadingParquet {
+                        message: e.to_string(),
+                    }))
+                }
+            };
+
+            // skip the rows written before the write was interrupted
+            let record_batch = if self.skip >= record_batch.num_rows() {
+                self.skip -= record_batch.num_rows();
+                continue;
+            } else if self.skip > 0 {
+                let skip = std::mem::take(&mut self.skip);
+                record_batch.slice(skip, record_batch.num_rows() - skip)
+            } else {
+                record_batch
+            };
+
+            let batch = self.to_batch(&record_batch);
+            self.offset += record_batch.num_rows() as u64;
+            return Some(batch);
+        }
+    }
+}
+
+/// The measurement and column types stored in the schema of a parquet file
+/// written by IOx, or the column types given by `options` for other files
+fn column_types(
+    arrow_schema: &ArrowSchemaRef,
+    options: &TableOptions,
+) -> Result<(Option<String>, Vec<Option<InfluxColumnType>>)> {
+    if let Ok(schema) = Schema::try_from(Arc::clone(arrow_schema)) {
+        let column_types = (0..schema.len())
+            .map(|idx| schema.field(idx).0)
+            .collect::<Option<Vec<_>>>();
+        if let Some(column_types) = column_types {
+            let column_types = column_types
+                .into_iter()
+                .map(|column_type| match column_type {
+                    InfluxColumnType::Timestamp => None,
+                    column_type => Some(column_type),
+                })
+                .collect();
+            return Ok((schema.measurement().cloned(), column_types));
+        }
+    }
+
+    let column_types = arrow_schema
+        .fields()
+        .iter()
+        .map(|field| {
+            let name = field.name();
+            if *name == options.timestamp_column {
+                return Ok(None);
+            }
+            if options.tag_columns.contains(name) {
+                return Ok(Some(InfluxColumnType::Tag));
+            }
+            field_type(field.data_type())
+                .map(|field_type| Some(InfluxColumnType::Field(field_type)))
+                .ok_or_else(|| Error::UnsupportedColumn {
+                    column: name.clone(),
+                    message: format!("type {} cannot be written to IOx", field.data_type()),
+                })
+        })
+        .collect::<Result<_>>()?;
+
+    Ok((None, column_types))
+}
+
+/// The field type a column of `data_type` is written as
+fn field_type(data_type: &DataType) -> Option<InfluxFieldType> {
+    match data_type {
+        DataType::Float16 | DataType::Float32 | DataType::Float64 => Some(InfluxFieldType::Float),
+        DataType::Int8
+        | DataType::Int16
+        | DataType::Int32
+        | DataType::Int64
+        | DataType::Timestamp(_, _) => Some(InfluxFieldType::Integer),
+        DataType::UInt8 | DataType::UInt16 | DataType::UInt32 | DataType::UInt64 => {
+            Some(InfluxFieldType::UInteger)
+        }
+        DataType::Boolean => Some(InfluxFieldType::Boolean),
+        DataType::Utf8 | DataType::LargeUtf8 => Some(InfluxFieldType::String),
+        DataType::Dictionary(_, value_type) => field_type(value_type),
+        _ => None,
+    }
+}
+
+fn to_values(column_type: InfluxColumnType, array: &ArrayRef) -> Result<Values, ArrowError> {
+    Ok(match column_type {
+        InfluxColumnType::Tag => {
+            let array = cast(array, &DataType::Utf8)?;
+            Values::Tag(strings(&array))
+        }
+        InfluxColumnType::Field(InfluxFieldType::String) => {
+            let array = cast(array, &DataType::Utf8)?;
+            Values::String(strings(&array))
+        }
+        InfluxColumnType::Field(InfluxFieldType::Float) => {
+            let array = cast(array, &DataType::Float64)?;
+            Values::F64(as_primitive_array::<Float64Type>(&array).iter().collect())
+        }
+        InfluxColumnType::Field(InfluxFieldType::Integer) | InfluxColumnType::Timestamp => {
+            let array = cast(&nanoseconds(array)?, &DataType::Int64)?;
+            Values::I64(as_primitive_array::<Int64Type>(&array).iter().collect())
+        }
+        InfluxColumnType::Field(InfluxFieldType::UInteger) => {
+            let array = cast(array, &DataType::UInt64)?;
+            Values::U64(as_primitive_array::<UInt64Type>(&array).iter().collect())
+        }
+        InfluxColumnType::Field(InfluxFieldType::Boolean) => {
+            let array = cast(array, &DataType::Boolean)?;
+            Values::Bool(as_boolean_array(&array).iter().collect())
+        }
+    })
+}
+
+/// Timestamps of the time column, `default_time` for nulls
+fn to_time(array: &ArrayRef, default_time: i64) -> Result<Vec<i64>, ArrowError> {
+    let array = cast(&nanoseconds(array)?, &DataType::Int64)?;
+    Ok(as_primitive_array::<Int64Type>(&array)
+        .iter()
+        .map(|time| time.unwrap_or(default_time))
+        .collect())
+}
+
+/// Converts timestamps of any unit to nanoseconds
+fn nanoseconds(array: &ArrayRef) -> Result<ArrayRef, ArrowError> {
+    match array.data_type() {
+        DataType::Timestamp(TimeUnit::Nanosecond, _) => Ok(Arc::clone(array)),
+        DataType::Timestamp(_, _) => cast(array, &DataType::Timestamp(TimeUnit::Nanosecond, None)),
+        _ => Ok(Arc::clone(array)),
+    }
+}
+
+fn strings(array: &ArrayRef) -> Vec<Option<String>> {
+    as_string_array(array)
+        .iter()
+        .map(|value| value.map(str::to_string))
+        .collect()
+}
+
+fn reading_parquet(e: parquet::errors::ParquetError) -> Error {
+    Error::ReadingParquet {
+        message: e.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::{
+        array::{Float64Array, StringArray, TimestampMillisecondArray},
+        datatypes::{Field, Schema as ArrowSchema},
+    };
+    use arrow_util::assert_batches_eq;
+    use parquet::arrow::ArrowWriter;
+    use schema::selection::Selection;
+
+    fn write_parquet(dir: &tempfile::TempDir, batch: RecordBatch) -> File {
+        let path = dir.path().join("cpu.parquet");
+        let mut writer =
+            ArrowWriter::try_new(File::create(&path).unwrap(), batch.schema(), None).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+        File::open(path).unwrap()
+    }
+
+    fn options() -> TableOptions {
+        TableOptions {
+            measurement: "cpu".to_string(),
+            tag_columns: vec!["host".to_string()],
+            timestamp_column: "ts".to_string(),
+        }
+    }
+
+    #[test]
+    fn reads_columns_named_by_flags() {
+        let dir = tempfile::tempdir().unwrap();
+        let batch = RecordBatch::try_from_iter(vec![
+            (
+                "host",
+                Arc::new(StringArray::from(vec![Some("a"), None, Some("b")])) as ArrayRef,
+            ),
+            (
+                "usage",
+                Arc::new(Float64Array::from(vec![Some(1.5), Some(2.0), None])) as ArrayRef,
+            ),
+            (
+                "ts",
+                Arc::new(TimestampMillisecondArray::from(vec![
+                    Some(1),
+                    None,
+                    Some(3),
+                ])) as ArrayRef,
+            ),
+        ])
+        .unwrap();
+        let file = write_parquet(&dir, batch);
+
+        let batches = ParquetBatches::new(file, &options(), None, 1, 1024, 42)
+            .unwrap()
+            .collect::<Result<Vec<_>>>()
+            .unwrap();
+
+        assert_eq!(batches.len(), 1);
+        assert_eq!((batches[0].start, batches[0].end), (1, 3));
+        assert_batches_eq!(
+            &[
+                "+------+--------------------------------+-------+",
+                "| host | time                           | usage |",
+                "+------+--------------------------------+-------+",
+                "|      | 1970-01-01T00:00:00.000000042Z | 2     |",
+                "| b    | 1970-01-01T00:00:00.003Z       |       |",
+                "+------+--------------------------------+-------+",
+            ],
+            &[batches[0].tables["cpu"].to_arrow(Selection::All).unwrap()]
+        );
+    }
+
+    #[test]
+    fn rejects_unsupported_columns() {
+        let dir = tempfile::tempdir().unwrap();
+        let schema = Arc::new(ArrowSchema::new(vec![Field::new(
+            "raw",
+            DataType::Binary,
+            true,
+        )]));
+        let batch = RecordBatch::new_empty(schema);
+        let file = write_parquet(&dir, batch);
+
+        assert!(matches!(
+            ParquetBatches::new(file, &options(), None, 0, 1024, 0),
+            Err(Error::UnsupportedColumn { column, .. }) if column == "raw"
+        ));
+    }
+}
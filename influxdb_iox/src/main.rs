@@ -29,6 +29,8 @@ use tokio::runtime::Runtime;
 mod commands {
     pub mod catalog;
     pub mod debug;
+    pub mod import;
+    pub mod namespace;
     pub mod query;
     pub mod query_ingester;
     pub mod remote;
@@ -160,12 +162,18 @@ enum Command {
     /// Interrogate internal database data
     Debug(commands::debug::Config),
 
+    /// Manage IOx namespaces
+    Namespace(commands::namespace::Config),
+
     /// Initiate a read request to the gRPC storage service.
     Storage(commands::storage::Config),
 
     /// Write data into the specified database
     Write(commands::write::Config),
 
+    /// Import data from other databases
+    Import(commands::import::Config),
+
     /// Query the data with SQL
     Query(commands::query::Config),
 
@@ -281,6 +289,21 @@ fn main() -> Result<(), std::io::Error> {
                     std::process::exit(ReturnCode::Failure as _)
                 }
             }
+            Some(Command::Namespace(config)) => {
+                let _tracing_guard = handle_init_logs(init_simple_logs(log_verbose_count));
+                let connection = connection().await;
+                if let Err(e) = commands::namespace::command(connection, config).await {
+                    eprintln!("{}", e);
+                    std::process::exit(ReturnCode::Failure as _)
+                }
+            }
+            Some(Command::Import(config)) => {
+                let _tracing_guard = handle_init_logs(init_simple_logs(log_verbose_count));
+                if let Err(e) = commands::import::command(connection, config).await {
+                    eprintln!("{}", e);
+                    std::process::exit(ReturnCode::Failure as _)
+                }
+            }
             Some(Command::Write(config)) => {
                 let _tracing_guard = handle_init_logs(init_simple_logs(log_verbose_count));
                 let connection = connection().await;
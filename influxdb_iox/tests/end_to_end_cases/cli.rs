@@ -283,6 +283,180 @@ async fn namespaces_cli() {
     .await
 }
 
+/// Test the namespace lifecycle cli commands
+#[tokio::test]
+async fn namespace_lifecycle_cli() {
+    test_helpers::maybe_start_logging();
+    let database_url = maybe_skip_integration!();
+
+    let mut cluster = MiniCluster::create_shared(database_url).await;
+
+    StepTest::new(
+        &mut cluster,
+        vec![Step::Custom(Box::new(|state: &mut StepTestState| {
+            async {
+                let router_addr = state.cluster().router().router_grpc_base().to_string();
+                let namespace = "namespace_lifecycle_cli";
+
+                let run = |args: &[&str]| {
+                    Command::cargo_bin("influxdb_iox")
+                        .unwrap()
+                        .arg("-h")
+                        .arg(&router_addr)
+                        .arg("namespace")
+                        .args(args)
+                        .assert()
+                };
+
+                run(&["create", namespace, "--retention", "30d"])
+                    .success()
+                    .stdout(predicate::str::contains(r#""retentionDuration": "30d""#));
+
+                run(&["create", namespace])
+                    .failure()
+                    .stderr(predicate::str::contains("already exists"));
+
+                run(&["retention", namespace, "1y"])
+                    .success()
+                    .stdout(predicate::str::contains(r#""retentionDuration": "1y""#));
+
+                run(&["limits", namespace, "--max-tables", "42"])
+                    .success()
+                    .stdout(predicate::str::contains(r#""maxTables": 42"#));
+
+                run(&["delete", namespace])
+                    .success()
+                    .stdout(predicate::str::contains("deletedAt"));
+
+                run(&["list"])
+                    .success()
+                    .stdout(predicate::str::contains(namespace).not());
+
+                run(&["restore", namespace]).success();
+
+                run(&["list"])
+                    .success()
+                    .stdout(predicate::str::contains(namespace));
+            }
+            .boxed()
+        }))],
+    )
+    .run()
+    .await
+}
+
+/// Test dropping tables and columns with the namespace CLI command
+#[tokio::test]
+async fn drop_table_and_column_cli() {
+    test_helpers::maybe_start_logging();
+    let database_url = maybe_skip_integration!();
+
+    let mut cluster = MiniCluster::create_shared(database_url).await;
+
+    StepTest::new(
+        &mut cluster,
+        vec![
+            Step::WriteLineProtocol(String::from(
+                "drop_cli_table1,tag1=A val=42i 123456\ndrop_cli_table2,tag1=A val=42i 123456",
+            )),
+            Step::Custom(Box::new(|state: &mut StepTestState| {
+                async {
+                    let router_addr = state.cluster().router().router_grpc_base().to_string();
+                    let namespace = state.cluster().namespace().to_string();
+
+                    let run = |args: &[&str]| {
+                        Command::cargo_bin("influxdb_iox")
+                            .unwrap()
+                            .arg("-h")
+                            .arg(&router_addr)
+                            .arg("namespace")
+                            .args(args)
+                            .assert()
+                    };
+
+                    run(&["drop-column", &namespace, "drop_cli_table1", "tag1"])
+                        .success()
+                        .stdout(predicate::str::contains("columnId"));
+
+                    run(&["drop-column", &namespace, "drop_cli_table1", "time"])
+                        .failure()
+                        .stderr(predicate::str::contains("cannot be dropped"));
+
+                    run(&["drop-table", &namespace, "drop_cli_table2"])
+                        .success()
+                        .stdout(predicate::str::contains("tableId"));
+
+                    run(&["drop-table", &namespace, "drop_cli_table2"])
+                        .failure()
+                        .stderr(predicate::str::contains("not found"));
+                }
+                .boxed()
+            })),
+        ],
+    )
+    .run()
+    .await
+}
+
+/// Test setting partition templates with the namespace CLI command
+#[tokio::test]
+async fn partition_template_cli() {
+    test_helpers::maybe_start_logging();
+    let database_url = maybe_skip_integration!();
+
+    let mut cluster = MiniCluster::create_shared(database_url).await;
+
+    StepTest::new(
+        &mut cluster,
+        vec![Step::Custom(Box::new(|state: &mut StepTestState| {
+            async {
+                let router_addr = state.cluster().router().router_grpc_base().to_string();
+                let namespace = "partition_template_cli";
+
+                let run = |args: &[&str]| {
+                    Command::cargo_bin("influxdb_iox")
+                        .unwrap()
+                        .arg("-h")
+                        .arg(&router_addr)
+                        .arg("namespace")
+                        .args(args)
+                        .assert()
+                };
+
+                run(&[
+                    "create",
+                    namespace,
+                    "--partition-template",
+                    r#"{"parts": [{"timeFormat": "%Y-%m-%d %H"}]}"#,
+                ])
+                .success()
+                .stdout(predicate::str::contains(r#""timeFormat": "%Y-%m-%d %H""#));
+
+                run(&[
+                    "create",
+                    "partition_template_cli_invalid",
+                    "--partition-template",
+                    r#"{"parts": []}"#,
+                ])
+                .failure()
+                .stderr(predicate::str::contains("at least one part"));
+
+                run(&[
+                    "table-partition-template",
+                    namespace,
+                    "cpu",
+                    r#"{"parts": [{"table": {}}, {"column": "region"}]}"#,
+                ])
+                .success()
+                .stdout(predicate::str::contains(r#""column": "region""#));
+            }
+            .boxed()
+        }))],
+    )
+    .run()
+    .await
+}
+
 /// Test the query_ingester CLI command
 #[tokio::test]
 async fn query_ingester() {
@@ -85,7 +85,7 @@ pub enum Error {
 ///     connection::Builder,
 ///     flight::{
 ///         Client,
-///         generated_types::ReadInfo,
+///         generated_types::{query_param::Value, QueryParam, QueryType, ReadInfo},
 ///     },
 /// };
 ///
@@ -99,7 +99,12 @@ pub enum Error {
 /// let mut query_results = client
 ///     .perform_query(ReadInfo {
 ///         namespace_name: "my_database".to_string(),
-///         sql_query: "select * from cpu_load".to_string(),
+///         sql_query: "select * from cpu_load where host = $host".to_string(),
+///         params: vec![QueryParam::named(
+///             "host",
+///             Some(Value::StringValue("server01".to_string())),
+///         )],
+///         query_type: QueryType::Sql.into(),
 ///     })
 ///     .await
 ///     .expect("query request should work");
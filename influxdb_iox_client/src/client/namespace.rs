@@ -1,13 +1,16 @@
 use self::generated_types::{namespace_service_client::NamespaceServiceClient, *};
+use ::generated_types::google::OptionalField;
+
 use crate::connection::Connection;
 use crate::error::Error;
 
 /// Re-export generated_types
 pub mod generated_types {
     pub use generated_types::influxdata::iox::namespace::v1::*;
+    pub use generated_types::influxdata::iox::partition_template::v1::*;
 }
 
-/// A basic client for fetching the Schema for a Namespace.
+/// A basic client for managing namespaces.
 #[derive(Debug, Clone)]
 pub struct Client {
     inner: NamespaceServiceClient<Connection>,
@@ -27,4 +30,141 @@ impl Client {
 
         Ok(response.into_inner().namespaces)
     }
+
+    /// Create a namespace, keeping data for `retention_duration` (empty or `inf` for forever)
+    /// and partitioning it with `partition_template` (`None` for the default template)
+    pub async fn create_namespace(
+        &mut self,
+        name: &str,
+        retention_duration: &str,
+        partition_template: Option<PartitionTemplate>,
+    ) -> Result<Namespace, Error> {
+        let response = self
+            .inner
+            .create_namespace(CreateNamespaceRequest {
+                name: name.to_string(),
+                retention_duration: retention_duration.to_string(),
+                partition_template,
+            })
+            .await?;
+
+        Ok(response.into_inner().namespace.unwrap_field("namespace")?)
+    }
+
+    /// Update the retention duration of a namespace
+    pub async fn update_namespace_retention(
+        &mut self,
+        name: &str,
+        retention_duration: &str,
+    ) -> Result<Namespace, Error> {
+        let response = self
+            .inner
+            .update_namespace_retention(UpdateNamespaceRetentionRequest {
+                name: name.to_string(),
+                retention_duration: retention_duration.to_string(),
+            })
+            .await?;
+
+        Ok(response.into_inner().namespace.unwrap_field("namespace")?)
+    }
+
+    /// Update the table and/or column limits of a namespace, leaving limits that are `None`
+    /// unchanged
+    pub async fn update_namespace_limits(
+        &mut self,
+        name: &str,
+        max_tables: Option<i32>,
+        max_columns_per_table: Option<i32>,
+    ) -> Result<Namespace, Error> {
+        let response = self
+            .inner
+            .update_namespace_limits(UpdateNamespaceLimitsRequest {
+                name: name.to_string(),
+                max_tables,
+                max_columns_per_table,
+            })
+            .await?;
+
+        Ok(response.into_inner().namespace.unwrap_field("namespace")?)
+    }
+
+    /// Soft delete a namespace
+    pub async fn delete_namespace(&mut self, name: &str) -> Result<Namespace, Error> {
+        let response = self
+            .inner
+            .delete_namespace(DeleteNamespaceRequest {
+                name: name.to_string(),
+            })
+            .await?;
+
+        Ok(response.into_inner().namespace.unwrap_field("namespace")?)
+    }
+
+    /// Restore a soft deleted namespace
+    pub async fn restore_namespace(&mut self, name: &str) -> Result<Namespace, Error> {
+        let response = self
+            .inner
+            .restore_namespace(RestoreNamespaceRequest {
+                name: name.to_string(),
+            })
+            .await?;
+
+        Ok(response.into_inner().namespace.unwrap_field("namespace")?)
+    }
+
+    /// Drop a table, flagging its parquet files for deletion
+    pub async fn drop_table(
+        &mut self,
+        namespace: &str,
+        table: &str,
+    ) -> Result<DropTableResponse, Error> {
+        let response = self
+            .inner
+            .drop_table(DropTableRequest {
+                namespace: namespace.to_string(),
+                table: table.to_string(),
+            })
+            .await?;
+
+        Ok(response.into_inner())
+    }
+
+    /// Drop a column of a table
+    pub async fn drop_column(
+        &mut self,
+        namespace: &str,
+        table: &str,
+        column: &str,
+    ) -> Result<DropColumnResponse, Error> {
+        let response = self
+            .inner
+            .drop_column(DropColumnRequest {
+                namespace: namespace.to_string(),
+                table: table.to_string(),
+                column: column.to_string(),
+            })
+            .await?;
+
+        Ok(response.into_inner())
+    }
+
+    /// Set the partition template of a table, creating the table if needed. A `None` template
+    /// makes the table use the template of its namespace.
+    pub async fn update_table_partition_template(
+        &mut self,
+        namespace: &str,
+        table: &str,
+        partition_template: Option<PartitionTemplate>,
+    ) -> Result<UpdateTablePartitionTemplateResponse, Error> {
+        let response = self
+            .inner
+            .update_table_partition_template(UpdateTablePartitionTemplateRequest {
+                namespace: namespace.to_string(),
+                table: table.to_string(),
+                partition_template,
+            })
+            .await?;
+
+        Ok(response.into_inner())
+    }
 }
@@ -1,18 +1,37 @@
 //! Output formatting utilities for Arrow record batches
 
-use std::{fmt::Display, str::FromStr};
+use std::{fmt::Display, io::Write, str::FromStr};
 
 use thiserror::Error;
 
 use arrow::{
-    self, csv::WriterBuilder, error::ArrowError, json::ArrayWriter, record_batch::RecordBatch,
+    self,
+    array::{as_boolean_array, as_primitive_array, as_string_array, Array, ArrayRef},
+    csv::WriterBuilder,
+    datatypes::{DataType, Float64Type, Int64Type, TimeUnit, TimestampNanosecondType, UInt64Type},
+    error::ArrowError,
+    ipc::writer::{FileWriter, StreamWriter},
+    json::ArrayWriter,
+    record_batch::RecordBatch,
+    util::display::array_value_to_string,
 };
+use bytes::BufMut;
+use influxdb_line_protocol::{
+    builder::{AfterField, AfterMeasurement},
+    LineProtocolBuilder,
+};
+use parquet::{arrow::ArrowWriter, errors::ParquetError};
+use schema::{InfluxColumnType, Schema, TIME_COLUMN_NAME};
 
 /// Error type for results formatting
 #[derive(Debug, Error)]
 pub enum Error {
     /// Unknown formatting type
-    #[error("Unknown format type: {}. Expected one of 'pretty', 'csv' or 'json'", .0)]
+    #[error(
+        "Unknown format type: {}. Expected one of 'pretty', 'csv', 'json', 'parquet', 'arrow', \
+         'arrow_stream' or 'lp'",
+        .0
+    )]
     Invalid(String),
 
     /// Error pretty printing
@@ -34,6 +53,50 @@ pub enum Error {
     /// Error converting JSON output to utf-8
     #[error("Error converting JSON output to UTF-8: {}", .0)]
     JsonUtf8(std::string::FromUtf8Error),
+
+    /// A binary format was requested as a string
+    #[error("Format {} is binary and can only be written to a file or stream", .0)]
+    Binary(QueryOutputFormat),
+
+    /// Parquet and Arrow files need the schema of at least one batch
+    #[error("Cannot write {} output without any record batches", .0)]
+    NoRecordBatches(QueryOutputFormat),
+
+    /// Error during Parquet encoding
+    #[error("Parquet writing error: {}", .0)]
+    Parquet(ParquetError),
+
+    /// Error during Arrow IPC encoding
+    #[error("Arrow IPC writing error: {}", .0)]
+    ArrowIpc(ArrowError),
+
+    /// Error writing the formatted output
+    #[error("Error writing output: {}", .0)]
+    Io(std::io::Error),
+
+    /// The IOx schema metadata of the results is invalid
+    #[error("Invalid IOx schema for line protocol output: {}", .0)]
+    LpSchema(schema::Error),
+
+    /// Rows of line protocol need a measurement
+    #[error(
+        "Line protocol output needs a measurement name, which the schema metadata of the results \
+         doesn't have"
+    )]
+    LpMeasurement,
+
+    /// A column can't be written as a tag, field or timestamp
+    #[error(
+        "Column {} of type {} can't be written as line protocol",
+        column,
+        data_type
+    )]
+    LpColumn {
+        /// The column name
+        column: String,
+        /// The arrow type of the column
+        data_type: DataType,
+    },
 }
 type Result<T, E = Error> = std::result::Result<T, E>;
 
@@ -46,6 +109,15 @@ pub enum QueryOutputFormat {
     Csv,
     /// Arrow JSON format
     Json,
+    /// Parquet file (binary)
+    Parquet,
+    /// Arrow IPC file format (binary)
+    ArrowIpc,
+    /// Arrow IPC streaming format (binary)
+    ArrowIpcStream,
+    /// InfluxDB line protocol, with tags, fields and timestamps taken from
+    /// the IOx schema metadata of the results
+    Lp,
 }
 
 impl Display for QueryOutputFormat {
@@ -54,6 +126,10 @@ impl Display for QueryOutputFormat {
             QueryOutputFormat::Pretty => write!(f, "pretty"),
             QueryOutputFormat::Csv => write!(f, "csv"),
             QueryOutputFormat::Json => write!(f, "json"),
+            QueryOutputFormat::Parquet => write!(f, "parquet"),
+            QueryOutputFormat::ArrowIpc => write!(f, "arrow"),
+            QueryOutputFormat::ArrowIpcStream => write!(f, "arrow_stream"),
+            QueryOutputFormat::Lp => write!(f, "lp"),
         }
     }
 }
@@ -72,6 +148,10 @@ impl FromStr for QueryOutputFormat {
             "pretty" => Ok(Self::Pretty),
             "csv" => Ok(Self::Csv),
             "json" => Ok(Self::Json),
+            "parquet" => Ok(Self::Parquet),
+            "arrow" => Ok(Self::ArrowIpc),
+            "arrow_stream" => Ok(Self::ArrowIpcStream),
+            "lp" | "line_protocol" => Ok(Self::Lp),
             _ => Err(Error::Invalid(s.to_string())),
         }
     }
@@ -84,8 +164,18 @@ impl QueryOutputFormat {
             Self::Pretty => "text/plain",
             Self::Csv => "text/csv",
             Self::Json => "application/json",
+            Self::Parquet => "application/vnd.apache.parquet",
+            Self::ArrowIpc => "application/vnd.apache.arrow.file",
+            Self::ArrowIpcStream => "application/vnd.apache.arrow.stream",
+            Self::Lp => "text/plain; charset=utf-8",
         }
     }
+
+    /// Return true if this format is binary and so can't be returned by
+    /// [`format`](Self::format) or printed to a terminal
+    pub fn is_binary(&self) -> bool {
+        matches!(self, Self::Parquet | Self::ArrowIpc | Self::ArrowIpcStream)
+    }
 }
 
 impl QueryOutputFormat {
@@ -116,12 +206,43 @@ impl QueryOutputFormat {
     ///  {"location":"Boston","state":"MA","surface_degrees":50.2,"time":1568756160}
     /// ]
     /// ```
+    ///
+    /// Line protocol:
+    /// ```text
+    /// h2o,location=santa_monica,state=CA bottom_degrees=50.4,surface_degrees=65.2 1568756160
+    /// ```
+    ///
+    /// Line protocol output needs a measurement in the IOx schema metadata of
+    /// the batches, use [`format_lp`] to set one.
+    ///
+    /// The binary formats return [`Error::Binary`], use
+    /// [`write`](Self::write) instead.
     pub fn format(&self, batches: &[RecordBatch]) -> Result<String> {
         match self {
             Self::Pretty => batches_to_pretty(batches),
             Self::Csv => batches_to_csv(batches),
             Self::Json => batches_to_json(batches),
+            Self::Lp => format_lp(batches, None),
+            Self::Parquet | Self::ArrowIpc | Self::ArrowIpcStream => Err(Error::Binary(*self)),
+        }
+    }
+
+    /// Write the [`RecordBatch`]es to `writer` in any of the formats,
+    /// including the binary ones
+    pub fn write<W: Write>(&self, batches: &[RecordBatch], mut writer: W) -> Result<()> {
+        match self {
+            Self::Parquet => batches_to_parquet(batches, &mut writer)?,
+            Self::ArrowIpc => batches_to_arrow_ipc(batches, &mut writer)?,
+            Self::ArrowIpcStream => batches_to_arrow_ipc_stream(batches, &mut writer)?,
+            Self::Pretty | Self::Csv | Self::Json | Self::Lp => {
+                let formatted = self.format(batches)?;
+                writer.write_all(formatted.as_bytes()).map_err(Error::Io)?;
+                if !formatted.ends_with('\n') {
+                    writer.write_all(b"\n").map_err(Error::Io)?;
+                }
+            }
         }
+        writer.flush().map_err(Error::Io)
     }
 }
 
@@ -158,6 +279,213 @@ fn batches_to_json(batches: &[RecordBatch]) -> Result<String> {
     Ok(json)
 }
 
+/// The schema shared by `batches`, needed up front by the binary formats
+fn schema_of(
+    batches: &[RecordBatch],
+    format: QueryOutputFormat,
+) -> Result<arrow::datatypes::SchemaRef> {
+    batches
+        .first()
+        .map(|batch| batch.schema())
+        .ok_or(Error::NoRecordBatches(format))
+}
+
+fn batches_to_parquet<W: Write>(batches: &[RecordBatch], writer: W) -> Result<()> {
+    let schema = schema_of(batches, QueryOutputFormat::Parquet)?;
+    let mut writer = ArrowWriter::try_new(writer, schema, None).map_err(Error::Parquet)?;
+
+    for batch in batches {
+        writer.write(batch).map_err(Error::Parquet)?;
+    }
+    writer.close().map_err(Error::Parquet)?;
+
+    Ok(())
+}
+
+fn batches_to_arrow_ipc<W: Write>(batches: &[RecordBatch], writer: W) -> Result<()> {
+    let schema = schema_of(batches, QueryOutputFormat::ArrowIpc)?;
+    let mut writer = FileWriter::try_new(writer, &schema).map_err(Error::ArrowIpc)?;
+
+    for batch in batches {
+        writer.write(batch).map_err(Error::ArrowIpc)?;
+    }
+    writer.finish().map_err(Error::ArrowIpc)
+}
+
+fn batches_to_arrow_ipc_stream<W: Write>(batches: &[RecordBatch], writer: W) -> Result<()> {
+    let schema = schema_of(batches, QueryOutputFormat::ArrowIpcStream)?;
+    let mut writer = StreamWriter::try_new(writer, &schema).map_err(Error::ArrowIpc)?;
+
+    for batch in batches {
+        writer.write(batch).map_err(Error::ArrowIpc)?;
+    }
+    writer.finish().map_err(Error::ArrowIpc)
+}
+
+/// Format the [`RecordBatch`]es as line protocol, with `measurement` as the
+/// measurement of every line, or else the measurement in the IOx schema
+/// metadata of the batches.
+///
+/// Results of SQL queries usually have no such metadata, so a measurement is
+/// needed to write them as line protocol.
+pub fn format_lp(batches: &[RecordBatch], measurement: Option<&str>) -> Result<String> {
+    let mut lp = vec![];
+
+    for batch in batches {
+        batch_to_lp(batch, measurement, &mut lp)?;
+    }
+
+    Ok(String::from_utf8(lp).expect("line protocol built from strings is UTF-8"))
+}
+
+/// Appends a line for each row of `batch` with at least one non-null field.
+///
+/// Columns are tags, fields or the timestamp according to the IOx schema
+/// metadata. Columns without metadata are the timestamp if named `time`, tags
+/// if dictionary encoded and fields otherwise.
+fn batch_to_lp(batch: &RecordBatch, measurement: Option<&str>, lp: &mut Vec<u8>) -> Result<()> {
+    let schema = Schema::try_from(batch.schema()).map_err(Error::LpSchema)?;
+
+    let mut time = None;
+    let mut tags = vec![];
+    let mut fields = vec![];
+    for (idx, (column_type, field)) in schema.iter().enumerate() {
+        let column = batch.column(idx);
+        let name = field.name().as_str();
+
+        match column_type {
+            Some(InfluxColumnType::Tag) => tags.push((name, column)),
+            Some(InfluxColumnType::Field(_)) => fields.push((name, column)),
+            Some(InfluxColumnType::Timestamp) => time = Some(column),
+            None if name == TIME_COLUMN_NAME => time = Some(column),
+            None if matches!(field.data_type(), DataType::Dictionary(_, _)) => {
+                tags.push((name, column))
+            }
+            None => fields.push((name, column)),
+        }
+    }
+
+    let measurement = measurement
+        .or_else(|| schema.measurement().map(String::as_str))
+        .ok_or(Error::LpMeasurement)?;
+
+    for row in 0..batch.num_rows() {
+        let mut row_fields = Vec::with_capacity(fields.len());
+        for (name, column) in &fields {
+            if let Some(value) = LpField::try_new(name, column, row)? {
+                row_fields.push((*name, value));
+            }
+        }
+        // a line needs at least one field
+        let mut row_fields = row_fields.into_iter();
+        let (first_name, first_value) = match row_fields.next() {
+            Some(field) => field,
+            None => continue,
+        };
+
+        let mut line = LineProtocolBuilder::new_with(&mut *lp).measurement(measurement);
+        for (name, column) in &tags {
+            if column.is_valid(row) {
+                let value = array_value_to_string(column, row)
+                    .map_err(|_| lp_column_error(name, column))?;
+                line = line.tag(name, &value);
+            }
+        }
+
+        let mut line = first_value.write_first(line, first_name);
+        for (name, value) in row_fields {
+            line = value.write_next(line, name);
+        }
+
+        match time {
+            Some(column) if column.is_valid(row) => {
+                line.timestamp(timestamp(column, row)?).close_line();
+            }
+            _ => {
+                line.close_line();
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn lp_column_error(name: &str, column: &ArrayRef) -> Error {
+    Error::LpColumn {
+        column: name.to_string(),
+        data_type: column.data_type().clone(),
+    }
+}
+
+fn timestamp(column: &ArrayRef, row: usize) -> Result<i64> {
+    match column.data_type() {
+        DataType::Timestamp(TimeUnit::Nanosecond, _) => {
+            Ok(as_primitive_array::<TimestampNanosecondType>(column).value(row))
+        }
+        DataType::Int64 => Ok(as_primitive_array::<Int64Type>(column).value(row)),
+        _ => Err(lp_column_error(TIME_COLUMN_NAME, column)),
+    }
+}
+
+/// The value of a field in one row
+enum LpField {
+    F64(f64),
+    I64(i64),
+    U64(u64),
+    Bool(bool),
+    String(String),
+}
+
+impl LpField {
+    /// The value of `column` at `row`, `None` if it is null
+    fn try_new(name: &str, column: &ArrayRef, row: usize) -> Result<Option<Self>> {
+        if column.is_null(row) {
+            return Ok(None);
+        }
+
+        let value = match column.data_type() {
+            DataType::Float64 => Self::F64(as_primitive_array::<Float64Type>(column).value(row)),
+            DataType::Int64 => Self::I64(as_primitive_array::<Int64Type>(column).value(row)),
+            DataType::UInt64 => Self::U64(as_primitive_array::<UInt64Type>(column).value(row)),
+            DataType::Boolean => Self::Bool(as_boolean_array(column).value(row)),
+            DataType::Utf8 => Self::String(as_string_array(column).value(row).to_string()),
+            DataType::Dictionary(_, _) => Self::String(
+                array_value_to_string(column, row).map_err(|_| lp_column_error(name, column))?,
+            ),
+            _ => return Err(lp_column_error(name, column)),
+        };
+        Ok(Some(value))
+    }
+
+    fn write_first<B: BufMut>(
+        self,
+        line: LineProtocolBuilder<B, AfterMeasurement>,
+        name: &str,
+    ) -> LineProtocolBuilder<B, AfterField> {
+        match self {
+            Self::F64(value) => line.field(name, value),
+            Self::I64(value) => line.field(name, value),
+            Self::U64(value) => line.field(name, value),
+            Self::Bool(value) => line.field(name, value),
+            Self::String(value) => line.field(name, value.as_str()),
+        }
+    }
+
+    fn write_next<B: BufMut>(
+        self,
+        line: LineProtocolBuilder<B, AfterField>,
+        name: &str,
+    ) -> LineProtocolBuilder<B, AfterField> {
+        match self {
+            Self::F64(value) => line.field(name, value),
+            Self::I64(value) => line.field(name, value),
+            Self::U64(value) => line.field(name, value),
+            Self::Bool(value) => line.field(name, value),
+            Self::String(value) => line.field(name, value.as_str()),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -193,7 +521,17 @@ mod tests {
 
         assert_eq!(
             QueryOutputFormat::from_str("un").unwrap_err().to_string(),
-            "Unknown format type: un. Expected one of 'pretty', 'csv' or 'json'"
+            "Unknown format type: un. Expected one of 'pretty', 'csv', 'json', 'parquet', \
+             'arrow', 'arrow_stream' or 'lp'"
+        );
+
+        assert_eq!(
+            QueryOutputFormat::from_str("Parquet").unwrap(),
+            QueryOutputFormat::Parquet
+        );
+        assert_eq!(
+            QueryOutputFormat::from_str("line_protocol").unwrap(),
+            QueryOutputFormat::Lp
         );
     }
 
@@ -213,5 +551,126 @@ mod tests {
             QueryOutputFormat::from_str(&QueryOutputFormat::Json.to_string()).unwrap(),
             QueryOutputFormat::Json
         );
+
+        for format in [
+            QueryOutputFormat::Parquet,
+            QueryOutputFormat::ArrowIpc,
+            QueryOutputFormat::ArrowIpcStream,
+            QueryOutputFormat::Lp,
+        ] {
+            assert_eq!(
+                QueryOutputFormat::from_str(&format.to_string()).unwrap(),
+                format
+            );
+        }
+    }
+
+    fn h2o_batch() -> RecordBatch {
+        use arrow::array::{DictionaryArray, Float64Array, Int64Array, TimestampNanosecondArray};
+        use arrow::datatypes::Int32Type;
+        use schema::{builder::SchemaBuilder, InfluxFieldType};
+        use std::sync::Arc;
+
+        let schema = SchemaBuilder::new()
+            .measurement("h2o")
+            .tag("location")
+            .influx_field("temp", InfluxFieldType::Float)
+            .influx_field("count", InfluxFieldType::Integer)
+            .timestamp()
+            .build()
+            .unwrap();
+
+        let location: DictionaryArray<Int32Type> = vec![Some("santa monica"), Some("boston"), None]
+            .into_iter()
+            .collect();
+
+        RecordBatch::try_new(
+            schema.as_arrow(),
+            vec![
+                Arc::new(location),
+                Arc::new(Float64Array::from(vec![Some(1.5), None, None])),
+                Arc::new(Int64Array::from(vec![Some(1), Some(2), None])),
+                Arc::new(TimestampNanosecondArray::from(vec![10, 20, 30])),
+            ],
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_lp() {
+        let lp = QueryOutputFormat::Lp.format(&[h2o_batch()]).unwrap();
+
+        assert_eq!(
+            lp,
+            "h2o,location=santa\\ monica temp=1.5,count=1i 10\n\
+             h2o,location=boston count=2i 20\n"
+        );
+    }
+
+    #[test]
+    fn test_lp_needs_measurement() {
+        let batch = RecordBatch::try_from_iter(vec![(
+            "temp",
+            std::sync::Arc::new(arrow::array::Float64Array::from(vec![1.5])) as ArrayRef,
+        )])
+        .unwrap();
+
+        assert!(matches!(
+            QueryOutputFormat::Lp.format(&[batch.clone()]),
+            Err(Error::LpMeasurement)
+        ));
+        assert_eq!(format_lp(&[batch], Some("cpu")).unwrap(), "cpu temp=1.5\n");
+
+        // an explicit measurement replaces the one of the schema
+        assert_eq!(
+            format_lp(&[h2o_batch()], Some("water")).unwrap(),
+            "water,location=santa\\ monica temp=1.5,count=1i 10\n\
+             water,location=boston count=2i 20\n"
+        );
+    }
+
+    #[test]
+    fn test_binary() {
+        let batches = [h2o_batch()];
+
+        for format in [
+            QueryOutputFormat::Parquet,
+            QueryOutputFormat::ArrowIpc,
+            QueryOutputFormat::ArrowIpcStream,
+        ] {
+            assert!(format.is_binary());
+            assert!(matches!(format.format(&batches), Err(Error::Binary(_))));
+            assert!(matches!(
+                format.write(&[], vec![]),
+                Err(Error::NoRecordBatches(_))
+            ));
+        }
+
+        let mut parquet = vec![];
+        QueryOutputFormat::Parquet
+            .write(&batches, &mut parquet)
+            .unwrap();
+        assert!(parquet.starts_with(b"PAR1") && parquet.ends_with(b"PAR1"));
+
+        let mut arrow = vec![];
+        QueryOutputFormat::ArrowIpc
+            .write(&batches, &mut arrow)
+            .unwrap();
+        assert!(arrow.starts_with(b"ARROW1") && arrow.ends_with(b"ARROW1"));
+
+        let mut stream = vec![];
+        QueryOutputFormat::ArrowIpcStream
+            .write(&batches, &mut stream)
+            .unwrap();
+        // continuation marker of the schema message
+        assert!(stream.starts_with(&[0xff; 4]));
+
+        let mut lp = vec![];
+        QueryOutputFormat::Lp.write(&batches, &mut lp).unwrap();
+        assert!(!QueryOutputFormat::Lp.is_binary());
+        assert_eq!(
+            lp,
+            QueryOutputFormat::Lp.format(&batches).unwrap().as_bytes()
+        );
     }
 }
@@ -167,6 +167,111 @@ fn parse_tsm_key_internal(key: &[u8]) -> Result<ParsedTsmKey, DataError> {
     })
 }
 
+/// parses the measurement, field key and tag set from a tsm index key
+/// written by InfluxDB 1.x, which has no org and bucket ids and starts with
+/// the measurement instead of a `\x00` tag.
+///
+/// The format looks roughly like:
+///
+/// <measurement>,<tag_keys_str>#!~#<field_key_str>
+///
+/// For example:
+/// cpu,host=a,region=west#!~#usage_idle
+///
+///    measurement = "cpu"
+///    tags = [("host", "a"), ("region", "west")]
+///    field = "usage_idle"
+///
+/// The org and bucket ids of the parsed key are zero.
+pub fn parse_v1_tsm_key(key: &[u8]) -> Result<ParsedTsmKey, Error> {
+    parse_v1_tsm_key_internal(key).context(ParsingTsmKeySnafu {
+        key: String::from_utf8_lossy(key),
+    })
+}
+
+/// Whether `key` was written by InfluxDB 1.x, as only 2.x keys hold the
+/// measurement as the value of a `\x00` tag
+pub fn is_v1_tsm_key(key: &[u8]) -> bool {
+    !key.windows(2).any(|w| w == b"\x00=")
+}
+
+fn parse_v1_tsm_key_internal(key: &[u8]) -> Result<ParsedTsmKey, DataError> {
+    let delimiter = key
+        .windows(FIELD_KEY_DELIMITER.len())
+        .position(|w| w == FIELD_KEY_DELIMITER)
+        .context(NoFieldKeySnafu)?;
+    let series_key = &key[..delimiter];
+    let field_key = String::from_utf8(key[delimiter + FIELD_KEY_DELIMITER.len()..].to_vec())
+        .map_err(|e| DataError::ParsingFieldKey {
+            details: e.to_string(),
+        })?;
+
+    let mut parts = split_unescaped(series_key, b',').into_iter();
+    let measurement = unescape(parts.next().unwrap_or_default())?;
+    if measurement.is_empty() {
+        return NoMeasurementSnafu.fail();
+    }
+
+    let tagset = parts
+        .map(|tag| match split_unescaped(tag, b'=').as_slice() {
+            [tag_key, tag_value] => Ok((unescape(tag_key)?, unescape(tag_value)?)),
+            _ => ParsingTsmTagKeySnafu {
+                description: format!("invalid tag '{}'", String::from_utf8_lossy(tag)),
+            }
+            .fail(),
+        })
+        .collect::<Result<_, _>>()?;
+
+    Ok(ParsedTsmKey {
+        org_id: InfluxId(0),
+        bucket_id: InfluxId(0),
+        measurement,
+        tagset,
+        field_key,
+    })
+}
+
+/// Separates the series key from the field key in tsm index keys
+const FIELD_KEY_DELIMITER: &[u8] = b"#!~#";
+
+/// Splits `bytes` at each `delimiter` not escaped by a backslash
+fn split_unescaped(bytes: &[u8], delimiter: u8) -> Vec<&[u8]> {
+    let mut parts = vec![];
+    let mut start = 0;
+    let mut escaped = false;
+
+    for (i, &byte) in bytes.iter().enumerate() {
+        if escaped {
+            escaped = false;
+        } else if byte == b'\\' {
+            escaped = true;
+        } else if byte == delimiter {
+            parts.push(&bytes[start..i]);
+            start = i + 1;
+        }
+    }
+    parts.push(&bytes[start..]);
+
+    parts
+}
+
+/// Removes the backslashes escaping ',', '=' and ' ' in series keys
+fn unescape(bytes: &[u8]) -> Result<String, DataError> {
+    let mut unescaped = Vec::with_capacity(bytes.len());
+    let mut bytes = bytes.iter().copied().peekable();
+
+    while let Some(byte) = bytes.next() {
+        if byte == b'\\' && matches!(bytes.peek(), Some(b',' | b'=' | b' ')) {
+            continue;
+        }
+        unescaped.push(byte);
+    }
+
+    String::from_utf8(unescaped).map_err(|e| DataError::ParsingTsmTagKey {
+        description: e.to_string(),
+    })
+}
+
 // Parses an influx id from the byte sequence. IDs are generally just 8 bytes, but we escape
 // certain characters ('\', ' ' and '='), so we unescape them as part of this process.
 // The iterator will consume all bytes that are part of the id.
@@ -829,6 +934,51 @@ mod tests {
         assert_eq!(parsed_key.field_key, String::from("sum"));
     }
 
+    #[test]
+    fn parse_v1_tsm_key_good() {
+        let key = b"cpu,host=a,region=west#!~#usage_idle";
+        assert!(is_v1_tsm_key(key));
+
+        let parsed_key = super::parse_v1_tsm_key(key).unwrap();
+        assert_eq!(parsed_key.org_id, InfluxId(0));
+        assert_eq!(parsed_key.measurement, String::from("cpu"));
+        let exp_tagset = vec![
+            (String::from("host"), String::from("a")),
+            (String::from("region"), String::from("west")),
+        ];
+        assert_eq!(parsed_key.tagset, exp_tagset);
+        assert_eq!(parsed_key.field_key, String::from("usage_idle"));
+
+        let key = add_field_key(make_tsm_key_prefix("m", "tag1=val1"), "f");
+        assert!(!is_v1_tsm_key(&key));
+    }
+
+    #[test]
+    fn parse_v1_tsm_key_escaped() {
+        let key = br"disk\ io,path=C:\,mount\=x\ y#!~#read bytes";
+
+        let parsed_key = super::parse_v1_tsm_key(key).unwrap();
+        assert_eq!(parsed_key.measurement, String::from("disk io"));
+        assert_eq!(
+            parsed_key.tagset,
+            vec![(String::from("path"), String::from("C:,mount=x y"))]
+        );
+        assert_eq!(parsed_key.field_key, String::from("read bytes"));
+    }
+
+    #[test]
+    fn parse_v1_tsm_key_errors() {
+        let err_str = parse_v1_tsm_key(b"cpu,host=a")
+            .expect_err("expect parsing error")
+            .to_string();
+        assert!(err_str.contains("No field key"), "{}", err_str);
+
+        let err_str = parse_v1_tsm_key(b"cpu,host#!~#usage")
+            .expect_err("expect parsing error")
+            .to_string();
+        assert!(err_str.contains("invalid tag 'host'"), "{}", err_str);
+    }
+
     #[test]
     fn parse_tsm_key_escaped() {
         //<org_id bucket_id>,\x00=query_log,env=prod01-eu-central-1,error=memory\
@@ -204,26 +204,38 @@ impl MeasurementTable {
     // `process` expects a closure to process each section.
     pub fn process<F>(
         &mut self,
-        mut block_reader: impl BlockDecoder,
+        block_reader: impl BlockDecoder,
         mut apply_fn: F,
     ) -> Result<(), TsmError>
     where
         F: FnMut(TableSection) -> Result<(), TsmError>,
     {
-        for (i, (tag_set_pair, blocks)) in self.tag_set_fields_blocks().iter_mut().enumerate() {
-            let (ts, field_cols) = map_field_columns(&mut block_reader, blocks)?;
-
-            let col_set = TableSection {
-                i,
-                ts,
-                field_cols,
-                tag_cols: tag_set_pair.clone(),
-            };
-            apply_fn(col_set)?;
+        for section in self.sections(block_reader) {
+            apply_fn(section?)?;
         }
         Ok(())
     }
 
+    // Like `process`, but returns the sections one at a time, decoding the
+    // blocks of each section only when it is requested.
+    pub fn sections<'a>(
+        &'a mut self,
+        mut block_reader: impl BlockDecoder + 'a,
+    ) -> impl Iterator<Item = Result<TableSection, TsmError>> + 'a {
+        self.tag_set_fields_blocks().iter_mut().enumerate().map(
+            move |(i, (tag_set_pair, blocks))| {
+                let (ts, field_cols) = map_field_columns(&mut block_reader, blocks)?;
+
+                Ok(TableSection {
+                    i,
+                    ts,
+                    field_cols,
+                    tag_cols: tag_set_pair.clone(),
+                })
+            },
+        )
+    }
+
     /// Merge another `MeasurementTable` into this one.
     ///
     /// `other` must be associated with the same measurement, otherwise an error
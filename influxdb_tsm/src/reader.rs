@@ -220,8 +220,15 @@ impl IndexEntry {
         InfluxId::from_be_bytes(buf)
     }
 
+    /// Parses the key of the entry, in the format of InfluxDB 2.x or 1.x.
+    /// The org and bucket ids of 1.x keys are zero.
     pub fn parse_key(&self) -> Result<ParsedTsmKey, TsmError> {
-        key::parse_tsm_key(&self.key).map_err(|e| TsmError {
+        let parsed = if key::is_v1_tsm_key(&self.key) {
+            key::parse_v1_tsm_key(&self.key)
+        } else {
+            key::parse_tsm_key(&self.key)
+        };
+        parsed.map_err(|e| TsmError {
             description: e.to_string(),
         })
     }
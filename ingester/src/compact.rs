@@ -210,6 +210,7 @@ mod tests {
                 table_id: TableId::new(table_id),
                 partition_key: partition_key.into(),
                 sort_key: vec![],
+                partition_template: None,
             },
         };
 
@@ -280,6 +281,7 @@ mod tests {
                 table_id: TableId::new(table_id),
                 partition_key: partition_key.into(),
                 sort_key: vec![],
+                partition_template: None,
             },
         };
 
@@ -374,6 +376,7 @@ mod tests {
                 partition_key: partition_key.into(),
                 // NO SORT KEY from the catalog here, first persisting batch
                 sort_key: vec![],
+                partition_template: None,
             },
         };
 
@@ -471,6 +474,7 @@ mod tests {
                 // SPECIFY A SORT KEY HERE to simulate a sort key being stored in the catalog
                 // this is NOT what the computed sort key would be based on this data's cardinality
                 sort_key: vec!["tag3".to_string(), "tag1".to_string(), "time".to_string()],
+                partition_template: None,
             },
         };
 
@@ -569,6 +573,7 @@ mod tests {
                 // this is NOT what the computed sort key would be based on this data's cardinality
                 // The new column, tag1, should get added just before the time column
                 sort_key: vec!["tag3".to_string(), "time".to_string()],
+                partition_template: None,
             },
         };
 
@@ -675,6 +680,7 @@ mod tests {
                     "tag4".to_string(),
                     "time".to_string(),
                 ],
+                partition_template: None,
             },
         };
 
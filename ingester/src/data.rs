@@ -9,16 +9,16 @@ use async_trait::async_trait;
 use backoff::{Backoff, BackoffConfig};
 use data_types::{
     DeletePredicate, KafkaPartition, NamespaceId, PartitionId, PartitionInfo, PartitionKey,
-    SequenceNumber, SequencerId, TableId, Timestamp, Tombstone,
+    PartitionTemplate, SequenceNumber, SequencerId, TableId, Timestamp, Tombstone,
 };
 use datafusion::physical_plan::SendableRecordBatchStream;
 use dml::DmlOperation;
 use futures::{Stream, StreamExt};
-use iox_catalog::interface::{get_table_schema_by_id, Catalog};
+use iox_catalog::interface::{get_partition_template_by_table_id, get_table_schema_by_id, Catalog};
 use iox_query::exec::Executor;
 use iox_time::SystemProvider;
 use metric::U64Counter;
-use mutable_batch::MutableBatch;
+use mutable_batch::{MutableBatch, PartitionWrite};
 use object_store::DynObjectStore;
 use observability_deps::tracing::{debug, warn};
 use parking_lot::RwLock;
@@ -239,12 +239,7 @@ impl Persister for IngesterData {
         let table_schema = Backoff::new(&self.backoff_config)
             .retry_all_errors("get table schema", || async {
                 let mut repos = self.catalog.repositories().await;
-                let table = repos
-                    .tables()
-                    .get_by_namespace_and_name(namespace.namespace_id, &partition_info.table_name)
-                    .await?
-                    .expect("table not found in catalog");
-                get_table_schema_by_id(table.id, repos.as_mut()).await
+                get_table_schema_by_id(partition_info.partition.table_id, repos.as_mut()).await
             })
             .await
             .expect("retry forever");
@@ -619,6 +614,7 @@ impl NamespaceData {
                         let mut table_data = table_data.write().await;
                         let should_pause = table_data
                             .buffer_table_write(
+                                &t,
                                 sequence_number,
                                 b,
                                 partition_key.clone(),
@@ -874,6 +870,7 @@ impl TableData {
     // ingest should be paused.
     async fn buffer_table_write(
         &mut self,
+        table_name: &str,
         sequence_number: SequenceNumber,
         batch: MutableBatch,
         partition_key: PartitionKey,
@@ -884,8 +881,14 @@ impl TableData {
         let partition_data = match self.partition_data.get_mut(&partition_key) {
             Some(p) => p,
             None => {
-                self.insert_partition(partition_key.clone(), sequencer_id, catalog)
-                    .await?;
+                self.insert_partition(
+                    table_name,
+                    &batch,
+                    partition_key.clone(),
+                    sequencer_id,
+                    catalog,
+                )
+                .await?;
                 self.partition_data.get_mut(&partition_key).unwrap()
             }
         };
@@ -897,6 +900,28 @@ impl TableData {
             }
         }
 
+        // The router derived the partition key with the template it had cached, which is not
+        // necessarily the one recorded for the partition. Forget the recorded template as soon
+        // as it does not reproduce the key, so the key is never interpreted with the wrong one.
+        if let Some(template) = &partition_data.partition_template {
+            if !is_partitioned_into(table_name, &batch, template, &partition_key) {
+                warn!(
+                    partition_id=%partition_data.id,
+                    %partition_key,
+                    %table_name,
+                    "write was partitioned with a different template, clearing partition template"
+                );
+                catalog
+                    .repositories()
+                    .await
+                    .partitions()
+                    .clear_partition_template(partition_data.id)
+                    .await
+                    .context(CatalogSnafu)?;
+                partition_data.partition_template = None;
+            }
+        }
+
         let should_pause = lifecycle_handle.log_write(
             partition_data.id,
             sequencer_id,
@@ -965,14 +990,28 @@ impl TableData {
 
     async fn insert_partition(
         &mut self,
+        table_name: &str,
+        batch: &MutableBatch,
         partition_key: PartitionKey,
         sequencer_id: SequencerId,
         catalog: &dyn Catalog,
     ) -> Result<()> {
         let mut repos = catalog.repositories().await;
+
+        // Record the current template of the table for a new partition, but only if it derives
+        // the key of the write that creates the partition.
+        let partition_template = get_partition_template_by_table_id(self.table_id, repos.as_mut())
+            .await
+            .context(CatalogSnafu)?
+            .filter(|template| is_partitioned_into(table_name, batch, template, &partition_key));
         let partition = repos
             .partitions()
-            .create_or_get(partition_key, sequencer_id, self.table_id)
+            .create_or_get_with_template(
+                partition_key,
+                sequencer_id,
+                self.table_id,
+                partition_template.as_ref(),
+            )
             .await
             .context(CatalogSnafu)?;
 
@@ -988,6 +1027,7 @@ impl TableData {
 
         let mut data = PartitionData::new(partition.id);
         data.data.max_persisted_sequence_number = max_persisted_sequence_number;
+        data.partition_template = partition.partition_template;
 
         self.partition_data.insert(partition.partition_key, data);
 
@@ -1010,6 +1050,18 @@ impl TableData {
     }
 }
 
+/// Returns true if partitioning `batch` with `template` puts all of its rows into `partition_key`.
+fn is_partitioned_into(
+    table_name: &str,
+    batch: &MutableBatch,
+    template: &PartitionTemplate,
+    partition_key: &PartitionKey,
+) -> bool {
+    PartitionWrite::partition(table_name, batch, template)
+        .keys()
+        .all(|key| key == partition_key)
+}
+
 /// Read only copy of the unpersisted data for a partition in the ingester for a specific partition.
 #[derive(Debug)]
 pub(crate) struct UnpersistedPartitionData {
@@ -1024,6 +1076,8 @@ pub(crate) struct UnpersistedPartitionData {
 pub(crate) struct PartitionData {
     id: PartitionId,
     data: DataBuffer,
+    /// The template the catalog records for the partition key, if any
+    partition_template: Option<PartitionTemplate>,
 }
 
 impl PartitionData {
@@ -1032,6 +1086,7 @@ impl PartitionData {
         Self {
             id,
             data: Default::default(),
+            partition_template: None,
         }
     }
 
@@ -1656,7 +1711,7 @@ mod tests {
     use assert_matches::assert_matches;
     use data_types::{
         ColumnId, ColumnSet, CompactionLevel, NamespaceSchema, NonEmptyString, ParquetFileParams,
-        Sequence, TimestampRange,
+        Sequence, TemplatePart, TimestampRange,
     };
     use datafusion::physical_plan::RecordBatchStream;
     use dml::{DmlDelete, DmlMeta, DmlWrite};
@@ -1712,6 +1767,7 @@ mod tests {
         let mut partition_data = PartitionData {
             id: PartitionId::new(1),
             data: Default::default(),
+            partition_template: None,
         };
 
         let seq_num1 = SequenceNumber::new(1);
@@ -1780,7 +1836,7 @@ mod tests {
             BackoffConfig::default(),
         ));
 
-        let schema = NamespaceSchema::new(namespace.id, kafka_topic.id, query_pool.id);
+        let schema = NamespaceSchema::new(namespace.id, kafka_topic.id, query_pool.id, None);
 
         let ignored_ts = Time::from_timestamp_millis(42);
 
@@ -1873,7 +1929,7 @@ mod tests {
             BackoffConfig::default(),
         ));
 
-        let schema = NamespaceSchema::new(namespace.id, kafka_topic.id, query_pool.id);
+        let schema = NamespaceSchema::new(namespace.id, kafka_topic.id, query_pool.id, None);
 
         let ignored_ts = Time::from_timestamp_millis(42);
 
@@ -2067,7 +2123,7 @@ mod tests {
             BackoffConfig::default(),
         ));
 
-        let schema = NamespaceSchema::new(namespace.id, kafka_topic.id, query_pool.id);
+        let schema = NamespaceSchema::new(namespace.id, kafka_topic.id, query_pool.id, None);
 
         let ignored_ts = Time::from_timestamp_millis(42);
 
@@ -2428,7 +2484,7 @@ mod tests {
             .await
             .unwrap();
 
-        let schema = NamespaceSchema::new(namespace.id, kafka_topic.id, query_pool.id);
+        let schema = NamespaceSchema::new(namespace.id, kafka_topic.id, query_pool.id, None);
 
         let ignored_ts = Time::from_timestamp_millis(42);
 
@@ -2570,6 +2626,104 @@ mod tests {
         });
     }
 
+    #[tokio::test]
+    async fn buffer_operation_records_partition_template() {
+        let metrics = Arc::new(metric::Registry::new());
+        let catalog: Arc<dyn Catalog> = Arc::new(MemCatalog::new(Arc::clone(&metrics)));
+        let mut repos = catalog.repositories().await;
+        let kafka_topic = repos.kafka_topics().create_or_get("whatevs").await.unwrap();
+        let query_pool = repos.query_pools().create_or_get("whatevs").await.unwrap();
+        let kafka_partition = KafkaPartition::new(0);
+        let namespace = repos
+            .namespaces()
+            .create("foo", "inf", kafka_topic.id, query_pool.id)
+            .await
+            .unwrap();
+        let template = PartitionTemplate {
+            parts: vec![TemplatePart::Column("region".to_string())],
+        };
+        repos
+            .namespaces()
+            .update_partition_template("foo", Some(&template))
+            .await
+            .unwrap();
+        let sequencer = repos
+            .sequencers()
+            .create_or_get(&kafka_topic, kafka_partition)
+            .await
+            .unwrap();
+
+        let schema = NamespaceSchema::new(namespace.id, kafka_topic.id, query_pool.id, None);
+
+        let ignored_ts = Time::from_timestamp_millis(42);
+        let write = |lp: &str, key: &str, sequence_number: i64| {
+            DmlWrite::new(
+                "foo",
+                lines_to_batches(lp, 0).unwrap(),
+                Some(key.into()),
+                DmlMeta::sequenced(
+                    Sequence::new(1, SequenceNumber::new(sequence_number)),
+                    ignored_ts,
+                    None,
+                    50,
+                ),
+            )
+        };
+
+        // the key is derived with the namespace template
+        let w1 = write("mem,region=west foo=1 10", "region_west", 1);
+        // a router with a stale template put this into the same partition
+        let w2 = write("mem,region=east foo=1 20", "region_west", 2);
+        // a router with a stale template created this partition
+        let w3 = write("mem,region=west foo=1 30", "1970-01-01", 3);
+
+        let _ = validate_or_insert_schema(w1.tables(), &schema, repos.deref_mut())
+            .await
+            .unwrap()
+            .unwrap();
+        std::mem::drop(repos);
+
+        let manager = LifecycleManager::new(
+            LifecycleConfig::new(1, 0, 0, Duration::from_secs(1), Duration::from_secs(1)),
+            Arc::clone(&metrics),
+            Arc::new(SystemProvider::new()),
+        );
+        let exec = Executor::new(1);
+
+        let data = NamespaceData::new(namespace.id, &*metrics);
+
+        for (w, key, want) in [
+            (w1, "region_west", Some(template)),
+            (w2, "region_west", None),
+            (w3, "1970-01-01", None),
+        ] {
+            data.buffer_operation(
+                DmlOperation::Write(w),
+                sequencer.id,
+                catalog.as_ref(),
+                &manager.handle(),
+                &exec,
+            )
+            .await
+            .unwrap();
+
+            let table_data = data.table_data("mem").unwrap();
+            let table = table_data.read().await;
+            let p = table.partition_data.get(&key.into()).unwrap();
+            assert_eq!(p.partition_template, want);
+
+            let partition = catalog
+                .repositories()
+                .await
+                .partitions()
+                .get_by_id(p.id)
+                .await
+                .unwrap()
+                .unwrap();
+            assert_eq!(partition.partition_template, want);
+        }
+    }
+
     #[tokio::test]
     async fn buffer_deletes_updates_tombstone_watermark() {
         let metrics = Arc::new(metric::Registry::new());
@@ -2606,7 +2760,7 @@ mod tests {
             BackoffConfig::default(),
         ));
 
-        let schema = NamespaceSchema::new(namespace.id, kafka_topic.id, query_pool.id);
+        let schema = NamespaceSchema::new(namespace.id, kafka_topic.id, query_pool.id, None);
 
         let ignored_ts = Time::from_timestamp_millis(42);
 
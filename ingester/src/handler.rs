@@ -411,6 +411,7 @@ mod tests {
             ingester.namespace.id,
             ingester.kafka_topic.id,
             ingester.query_pool.id,
+            None,
         );
         let mut txn = ingester.catalog.start_transaction().await.unwrap();
         let ingest_ts1 = Time::from_timestamp_millis(42);
@@ -659,7 +660,7 @@ mod tests {
         let write_buffer_state =
             MockBufferSharedState::empty_with_n_sequencers(NonZeroU32::try_from(1).unwrap());
 
-        let schema = NamespaceSchema::new(namespace.id, kafka_topic.id, query_pool.id);
+        let schema = NamespaceSchema::new(namespace.id, kafka_topic.id, query_pool.id, None);
         for write_operation in write_operations {
             validate_or_insert_schema(write_operation.tables(), &schema, txn.deref_mut())
                 .await
@@ -0,0 +1,5 @@
+-- Soft deletion marker of a namespace, in nanoseconds since the epoch. A namespace with a
+-- deleted_at value is hidden from queries and writes until it is restored.
+ALTER TABLE
+  IF EXISTS namespace
+  ADD COLUMN deleted_at BIGINT DEFAULT NULL;
@@ -0,0 +1,10 @@
+-- Mark dropped tables and columns. They are hidden from the namespace schema
+-- but kept in the catalog, so that data written before the drop can still be
+-- resolved, and their names cannot be reused.
+ALTER TABLE
+  IF EXISTS table_name
+  ADD COLUMN deleted_at BIGINT DEFAULT NULL;
+
+ALTER TABLE
+  IF EXISTS column_name
+  ADD COLUMN deleted_at BIGINT DEFAULT NULL;
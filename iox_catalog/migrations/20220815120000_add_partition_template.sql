@@ -0,0 +1,10 @@
+-- Partition templates of namespaces and tables, as JSON. A table template
+-- overrides the template of its namespace, and NULL falls back to the default
+-- template of the router.
+ALTER TABLE
+  IF EXISTS namespace
+  ADD COLUMN partition_template JSONB DEFAULT NULL;
+
+ALTER TABLE
+  IF EXISTS table_name
+  ADD COLUMN partition_template JSONB DEFAULT NULL;
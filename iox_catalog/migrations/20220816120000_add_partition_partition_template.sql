@@ -0,0 +1,5 @@
+-- The partition template the key of a partition was derived with. NULL means
+-- the template is unknown, and the key cannot be interpreted.
+ALTER TABLE
+  IF EXISTS partition
+  ADD COLUMN partition_template JSONB DEFAULT NULL;
@@ -0,0 +1,18 @@
+-- Only tables and columns that have not been dropped need unique names, so
+-- that a dropped table or column can be created again under the same name, as
+-- a new table or column with a new ID.
+ALTER TABLE
+  IF EXISTS table_name
+  DROP CONSTRAINT IF EXISTS table_name_unique;
+
+CREATE UNIQUE INDEX IF NOT EXISTS table_name_unique
+  ON table_name (namespace_id, name)
+  WHERE deleted_at IS NULL;
+
+ALTER TABLE
+  IF EXISTS column_name
+  DROP CONSTRAINT IF EXISTS column_name_unique;
+
+CREATE UNIQUE INDEX IF NOT EXISTS column_name_unique
+  ON column_name (table_id, name)
+  WHERE deleted_at IS NULL;
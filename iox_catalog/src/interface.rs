@@ -2,11 +2,11 @@
 
 use async_trait::async_trait;
 use data_types::{
-    Column, ColumnSchema, ColumnType, KafkaPartition, KafkaTopic, KafkaTopicId, Namespace,
-    NamespaceId, NamespaceSchema, ParquetFile, ParquetFileId, ParquetFileParams, Partition,
-    PartitionId, PartitionInfo, PartitionKey, PartitionParam, ProcessedTombstone, QueryPool,
-    QueryPoolId, SequenceNumber, Sequencer, SequencerId, Table, TableId, TablePartition,
-    TableSchema, Timestamp, Tombstone, TombstoneId,
+    Column, ColumnId, ColumnSchema, ColumnType, KafkaPartition, KafkaTopic, KafkaTopicId,
+    Namespace, NamespaceId, NamespaceSchema, ParquetFile, ParquetFileId, ParquetFileParams,
+    Partition, PartitionId, PartitionInfo, PartitionKey, PartitionParam, PartitionTemplate,
+    ProcessedTombstone, QueryPool, QueryPoolId, SequenceNumber, Sequencer, SequencerId, Table,
+    TableId, TablePartition, TableSchema, Timestamp, Tombstone, TombstoneId,
 };
 use iox_time::TimeProvider;
 use snafu::{OptionExt, Snafu};
@@ -53,6 +53,9 @@ pub enum Error {
     #[snafu(display("table {} not found", id))]
     TableNotFound { id: TableId },
 
+    #[snafu(display("column {} not found", id))]
+    ColumnNotFound { id: ColumnId },
+
     #[snafu(display("partition {} not found", id))]
     PartitionNotFound { id: PartitionId },
 
@@ -270,13 +273,13 @@ pub trait NamespaceRepo: Send + Sync {
         query_pool_id: QueryPoolId,
     ) -> Result<Namespace>;
 
-    /// List all namespaces.
+    /// List all namespaces that are not soft deleted.
     async fn list(&mut self) -> Result<Vec<Namespace>>;
 
-    /// Gets the namespace by its ID.
+    /// Gets the namespace by its ID, including a soft deleted namespace.
     async fn get_by_id(&mut self, id: NamespaceId) -> Result<Option<Namespace>>;
 
-    /// Gets the namespace by its unique name.
+    /// Gets the namespace by its unique name, unless it is soft deleted.
     async fn get_by_name(&mut self, name: &str) -> Result<Option<Namespace>>;
 
     /// Update the limit on the number of tables that can exist per namespace.
@@ -284,18 +287,46 @@ pub trait NamespaceRepo: Send + Sync {
 
     /// Update the limit on the number of columns that can exist per table in a given namespace.
     async fn update_column_limit(&mut self, name: &str, new_max: i32) -> Result<Namespace>;
+
+    /// Update the retention duration of the namespace. `inf` keeps data forever.
+    async fn update_retention_duration(
+        &mut self,
+        name: &str,
+        retention_duration: &str,
+    ) -> Result<Namespace>;
+
+    /// Soft delete the namespace, hiding it from queries and writes until it is
+    /// [restored](Self::restore). Its name cannot be reused in the meantime.
+    async fn soft_delete(&mut self, name: &str) -> Result<Namespace>;
+
+    /// Restore a soft deleted namespace.
+    async fn restore(&mut self, name: &str) -> Result<Namespace>;
+
+    /// Update the partition template of the namespace, or reset it to the default template
+    /// with `None`. Existing partitions keep the keys they were created with.
+    async fn update_partition_template(
+        &mut self,
+        name: &str,
+        partition_template: Option<&PartitionTemplate>,
+    ) -> Result<Namespace>;
 }
 
 /// Functions for working with tables in the catalog
+///
+/// Unless noted otherwise, dropped tables are returned like any other table, with
+/// [`Table::deleted_at`] set. Only tables that have not been dropped have unique names: once a
+/// table is dropped, its name can be used by a new table.
 #[async_trait]
 pub trait TableRepo: Send + Sync {
-    /// Creates the table in the catalog or get the existing record by name.
+    /// Creates the table in the catalog or get the existing record by name. Dropped tables are
+    /// ignored, so a new table is created in place of a dropped one.
     async fn create_or_get(&mut self, name: &str, namespace_id: NamespaceId) -> Result<Table>;
 
     /// get table by ID
     async fn get_by_id(&mut self, table_id: TableId) -> Result<Option<Table>>;
 
-    /// get table by namespace ID and name
+    /// get table by namespace ID and name, preferring the table that has not been dropped over
+    /// dropped tables of the same name, and the most recently created dropped table otherwise
     async fn get_by_namespace_and_name(
         &mut self,
         namespace_id: NamespaceId,
@@ -308,13 +339,27 @@ pub trait TableRepo: Send + Sync {
     /// List all tables.
     async fn list(&mut self) -> Result<Vec<Table>>;
 
-    /// Gets the table persistence info for the given sequencer
+    /// Gets the table persistence info for the given sequencer, for the table returned by
+    /// [`get_by_namespace_and_name`](Self::get_by_namespace_and_name)
     async fn get_table_persist_info(
         &mut self,
         sequencer_id: SequencerId,
         namespace_id: NamespaceId,
         table_name: &str,
     ) -> Result<Option<TablePersistInfo>>;
+
+    /// Drop the table, hiding it from the namespace schema. Dropped tables do not count against
+    /// the table limit of their namespace.
+    async fn soft_delete(&mut self, table_id: TableId) -> Result<Table>;
+
+    /// Update the partition template of the table, overriding the template of its namespace,
+    /// or fall back to the namespace template with `None`. Existing partitions keep the keys
+    /// they were created with.
+    async fn update_partition_template(
+        &mut self,
+        table_id: TableId,
+        partition_template: Option<&PartitionTemplate>,
+    ) -> Result<Table>;
 }
 
 /// Information for a table's persistence information for a specific sequencer from the catalog
@@ -341,11 +386,16 @@ pub struct ColumnUpsertRequest<'a> {
 }
 
 /// Functions for working with columns in the catalog
+///
+/// Unless noted otherwise, dropped columns are returned like any other column, with
+/// [`Column::deleted_at`] set. Only columns that have not been dropped have unique names: once a
+/// column is dropped, its name can be used by a new column of any type.
 #[async_trait]
 pub trait ColumnRepo: Send + Sync {
     /// Creates the column in the catalog or returns the existing column. Will return a
     /// `Error::ColumnTypeMismatch` if the existing column type doesn't match the type
-    /// the caller is attempting to create.
+    /// the caller is attempting to create. Dropped columns are ignored, so a new column is
+    /// created in place of a dropped one.
     async fn create_or_get(
         &mut self,
         name: &str,
@@ -371,6 +421,10 @@ pub trait ColumnRepo: Send + Sync {
 
     /// List all columns.
     async fn list(&mut self) -> Result<Vec<Column>>;
+
+    /// Drop the column, hiding it from the table schema. Dropped columns do not count against
+    /// the column limit of their namespace.
+    async fn soft_delete(&mut self, column_id: ColumnId) -> Result<Column>;
 }
 
 /// Functions for working with sequencers in the catalog
@@ -416,6 +470,17 @@ pub trait PartitionRepo: Send + Sync {
         table_id: TableId,
     ) -> Result<Partition>;
 
+    /// create or get a partition record for the given partition key, sequencer and table,
+    /// recording `partition_template` as the template the key was derived with if the partition
+    /// is created by this call. The template of an existing partition is left untouched.
+    async fn create_or_get_with_template(
+        &mut self,
+        key: PartitionKey,
+        sequencer_id: SequencerId,
+        table_id: TableId,
+        partition_template: Option<&PartitionTemplate>,
+    ) -> Result<Partition>;
+
     /// get partition by ID
     async fn get_by_id(&mut self, partition_id: PartitionId) -> Result<Option<Partition>>;
 
@@ -441,6 +506,10 @@ pub trait PartitionRepo: Send + Sync {
         partition_id: PartitionId,
         sort_key: &[&str],
     ) -> Result<Partition>;
+
+    /// Forget the partition template of the partition because data that was not partitioned
+    /// with it has been written to the partition. There is no way to set the template again.
+    async fn clear_partition_template(&mut self, partition_id: PartitionId) -> Result<Partition>;
 }
 
 /// Functions for working with tombstones in the catalog
@@ -635,7 +704,7 @@ pub trait ProcessedTombstoneRepo: Send + Sync {
     async fn count_by_tombstone_id(&mut self, tombstone_id: TombstoneId) -> Result<i64>;
 }
 
-/// Gets the namespace schema including all tables and columns.
+/// Gets the namespace schema including all tables and columns that have not been dropped.
 pub async fn get_schema_by_id<R>(id: NamespaceId, repos: &mut R) -> Result<NamespaceSchema>
 where
     R: RepoCollection + ?Sized,
@@ -649,7 +718,7 @@ where
     get_schema_internal(namespace, repos).await
 }
 
-/// Gets the namespace schema including all tables and columns.
+/// Gets the namespace schema including all tables and columns that have not been dropped.
 pub async fn get_schema_by_name<R>(name: &str, repos: &mut R) -> Result<NamespaceSchema>
 where
     R: RepoCollection + ?Sized,
@@ -671,19 +740,26 @@ where
     let columns = repos.columns().list_by_namespace_id(namespace.id).await?;
     let tables = repos.tables().list_by_namespace_id(namespace.id).await?;
 
-    let mut namespace = NamespaceSchema::new(
+    let mut schema = NamespaceSchema::new(
         namespace.id,
         namespace.kafka_topic_id,
         namespace.query_pool_id,
+        namespace.retention_period_ns(),
     );
+    schema.partition_template = namespace.partition_template;
 
     let mut table_id_to_schema = BTreeMap::new();
-    for t in tables {
-        table_id_to_schema.insert(t.id, (t.name, TableSchema::new(t.id)));
+    for t in tables.into_iter().filter(|t| t.deleted_at.is_none()) {
+        let table_schema = TableSchema::new_for_table(&t);
+        table_id_to_schema.insert(t.id, (t.name, table_schema));
     }
 
-    for c in columns {
-        let (_, t) = table_id_to_schema.get_mut(&c.table_id).unwrap();
+    for c in columns.into_iter().filter(|c| c.deleted_at.is_none()) {
+        // columns of dropped tables are skipped along with their table
+        let (_, t) = match table_id_to_schema.get_mut(&c.table_id) {
+            Some(v) => v,
+            None => continue,
+        };
         match ColumnType::try_from(c.column_type) {
             Ok(column_type) => {
                 t.columns.insert(
@@ -703,14 +779,18 @@ where
         }
     }
 
-    for (_, (table_name, schema)) in table_id_to_schema {
-        namespace.tables.insert(table_name, schema);
+    for (_, (table_name, table_schema)) in table_id_to_schema {
+        schema.tables.insert(table_name, table_schema);
     }
 
-    Ok(namespace)
+    Ok(schema)
 }
 
 /// Gets the table schema including all columns.
+///
+/// Unlike [`get_schema_by_id`], this includes dropped columns, as data written to them before
+/// they were dropped may still need to be persisted. Columns that have not been dropped take
+/// precedence over dropped columns of the same name.
 pub async fn get_table_schema_by_id<R>(id: TableId, repos: &mut R) -> Result<TableSchema>
 where
     R: RepoCollection + ?Sized,
@@ -719,6 +799,10 @@ where
     let mut schema = TableSchema::new(id);
 
     for c in columns {
+        if c.deleted_at.is_some() && schema.columns.contains_key(&c.name) {
+            continue;
+        }
+
         match ColumnType::try_from(c.column_type) {
             Ok(column_type) => {
                 schema.columns.insert(
@@ -741,6 +825,37 @@ where
     Ok(schema)
 }
 
+/// Gets the partition template in effect for the table: the template of the table, falling back
+/// to the template of its namespace.
+///
+/// Returns `None` if neither has a template, in which case the router partitions the table with
+/// its default template.
+pub async fn get_partition_template_by_table_id<R>(
+    id: TableId,
+    repos: &mut R,
+) -> Result<Option<PartitionTemplate>>
+where
+    R: RepoCollection + ?Sized,
+{
+    let table = repos
+        .tables()
+        .get_by_id(id)
+        .await?
+        .ok_or(Error::TableNotFound { id })?;
+    if table.partition_template.is_some() {
+        return Ok(table.partition_template);
+    }
+
+    let namespace = repos
+        .namespaces()
+        .get_by_id(table.namespace_id)
+        .await?
+        .ok_or(Error::NamespaceNotFoundById {
+            id: table.namespace_id,
+        })?;
+    Ok(namespace.partition_template)
+}
+
 /// Fetch all [`NamespaceSchema`] in the catalog.
 ///
 /// This method performs the minimal number of queries needed to build the
@@ -762,8 +877,14 @@ pub async fn list_schemas(
     // column snapshot was taken).
 
     // First fetch all the columns - this is the state snapshot of the catalog
-    // schemas.
-    let columns = repos.columns().list().await?;
+    // schemas. Dropped columns are not part of any schema.
+    let columns = repos
+        .columns()
+        .list()
+        .await?
+        .into_iter()
+        .filter(|c| c.deleted_at.is_none())
+        .collect::<Vec<_>>();
 
     // Construct the set of table IDs these columns belong to.
     let retain_table_ids = columns.iter().map(|c| c.table_id).collect::<HashSet<_>>();
@@ -771,15 +892,16 @@ pub async fn list_schemas(
     // Fetch all tables, and filter for those that are needed to construct
     // schemas for "columns" only.
     //
-    // Discard any tables that have no columns or have been created since
-    // the "columns" snapshot was retrieved, and construct a map of ID->Table.
+    // Discard any tables that have no columns, have been created since the
+    // "columns" snapshot was retrieved, or have been dropped, and construct a
+    // map of ID->Table.
     let tables = repos
         .tables()
         .list()
         .await?
         .into_iter()
         .filter_map(|t| {
-            if !retain_table_ids.contains(&t.id) {
+            if !retain_table_ids.contains(&t.id) || t.deleted_at.is_some() {
                 return None;
             }
 
@@ -799,8 +921,12 @@ pub async fn list_schemas(
 
     let mut joined = HashMap::<NamespaceId, NamespaceTables>::default();
     for column in columns {
-        // Resolve the table this column references
-        let table = tables.get(&column.table_id).expect("no table for column");
+        // Resolve the table this column references, skipping columns of dropped
+        // tables.
+        let table = match tables.get(&column.table_id) {
+            Some(t) => t,
+            None => continue,
+        };
 
         let table_schema = joined
             // Find or create a record in the joined <NamespaceId, Tables> map
@@ -809,7 +935,7 @@ pub async fn list_schemas(
             .or_default()
             // Fetch the schema record for this table, or create an empty one.
             .entry(table.name.clone())
-            .or_insert_with(|| TableSchema::new(column.table_id));
+            .or_insert_with(|| TableSchema::new_for_table(table));
 
         table_schema.add_column(&column);
     }
@@ -826,7 +952,13 @@ pub async fn list_schemas(
         // was created, or have no tables/columns (and therefore have no entry
         // in "joined").
         .filter_map(move |v| {
-            let mut ns = NamespaceSchema::new(v.id, v.kafka_topic_id, v.query_pool_id);
+            let mut ns = NamespaceSchema::new(
+                v.id,
+                v.kafka_topic_id,
+                v.query_pool_id,
+                v.retention_period_ns(),
+            );
+            ns.partition_template = v.partition_template.clone();
             ns.tables = joined.remove(&v.id)?;
             Some((v, ns))
         });
@@ -840,7 +972,7 @@ pub(crate) mod test_helpers {
 
     use super::*;
     use ::test_helpers::{assert_contains, tracing::TracingCapture};
-    use data_types::{ColumnId, ColumnSet, CompactionLevel};
+    use data_types::{ColumnId, ColumnSet, CompactionLevel, TemplatePart};
     use metric::{Attributes, DurationHistogram, Metric};
     use std::{
         ops::{Add, DerefMut},
@@ -975,7 +1107,7 @@ pub(crate) mod test_helpers {
             .unwrap();
         let mut namespaces = repos.namespaces().list().await.unwrap();
         namespaces.sort_by_key(|ns| ns.name.clone());
-        assert_eq!(namespaces, vec![namespace, namespace2]);
+        assert_eq!(namespaces, vec![namespace, namespace2.clone()]);
 
         const NEW_TABLE_LIMIT: i32 = 15000;
         let modified = repos
@@ -992,6 +1124,103 @@ pub(crate) mod test_helpers {
             .await
             .expect("namespace should be updateable");
         assert_eq!(NEW_COLUMN_LIMIT, modified.max_columns_per_table);
+
+        let modified = repos
+            .namespaces()
+            .update_retention_duration(namespace_name, "30d")
+            .await
+            .expect("namespace should be updateable");
+        assert_eq!(modified.retention_duration.as_deref(), Some("30d"));
+
+        let err = repos
+            .namespaces()
+            .update_retention_duration("does_not_exist", "30d")
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::NamespaceNotFoundByName { .. }));
+
+        let template = PartitionTemplate {
+            parts: vec![TemplatePart::TimeFormat("%Y-%m-%d %H".to_string())],
+        };
+        let modified = repos
+            .namespaces()
+            .update_partition_template(namespace_name, Some(&template))
+            .await
+            .expect("namespace should be updateable");
+        assert_eq!(modified.partition_template.as_ref(), Some(&template));
+        let schema = get_schema_by_name(namespace_name, repos.as_mut())
+            .await
+            .unwrap();
+        assert_eq!(schema.partition_template, Some(template));
+
+        // soft deleted namespaces are hidden from lookups by name and listing
+        let deleted = repos
+            .namespaces()
+            .soft_delete(namespace2_name)
+            .await
+            .expect("namespace should be deletable");
+        assert!(deleted.deleted_at.is_some());
+        assert!(repos
+            .namespaces()
+            .get_by_name(namespace2_name)
+            .await
+            .unwrap()
+            .is_none());
+        let namespaces = repos.namespaces().list().await.unwrap();
+        assert_eq!(namespaces, vec![modified.clone()]);
+
+        // but not from lookups by ID
+        let found = repos
+            .namespaces()
+            .get_by_id(namespace2.id)
+            .await
+            .unwrap()
+            .expect("namespace should be there");
+        assert_eq!(found, deleted);
+
+        // a soft deleted namespace can neither be deleted again, nor updated
+        let err = repos
+            .namespaces()
+            .soft_delete(namespace2_name)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::NamespaceNotFoundByName { .. }));
+        let err = repos
+            .namespaces()
+            .update_table_limit(namespace2_name, NEW_TABLE_LIMIT)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::NamespaceNotFoundByName { .. }));
+
+        // its name is still taken
+        let err = repos
+            .namespaces()
+            .create(namespace2_name, "inf", kafka.id, pool.id)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::NameExists { .. }));
+
+        // only soft deleted namespaces can be restored
+        let err = repos
+            .namespaces()
+            .restore(namespace_name)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::NamespaceNotFoundByName { .. }));
+
+        let restored = repos
+            .namespaces()
+            .restore(namespace2_name)
+            .await
+            .expect("namespace should be restorable");
+        assert_eq!(restored, namespace2);
+        let found = repos
+            .namespaces()
+            .get_by_name(namespace2_name)
+            .await
+            .unwrap()
+            .expect("namespace should be there");
+        assert_eq!(found, namespace2);
     }
 
     async fn test_table(catalog: Arc<dyn Catalog>) {
@@ -1169,6 +1398,95 @@ pub(crate) mod test_helpers {
                 namespace_id: _
             }
         ));
+
+        // test overriding the namespace partition template for a table
+        let template = PartitionTemplate {
+            parts: vec![TemplatePart::Column("region".to_string())],
+        };
+        let updated = repos
+            .tables()
+            .update_partition_template(t.id, Some(&template))
+            .await
+            .expect("table should be updateable");
+        assert_eq!(updated.partition_template.as_ref(), Some(&template));
+        let schema = get_schema_by_name("namespace_table_test", repos.as_mut())
+            .await
+            .unwrap();
+        assert_eq!(schema.partition_template_for("test_table"), Some(&template));
+        assert_eq!(schema.partition_template_for("other_table"), None);
+
+        // test dropping a table
+        let dropped = repos
+            .tables()
+            .soft_delete(t.id)
+            .await
+            .expect("table should be dropped");
+        assert_eq!(dropped.id, t.id);
+        assert!(dropped.deleted_at.is_some());
+        assert_eq!(
+            repos.tables().get_by_id(t.id).await.unwrap(),
+            Some(dropped.clone())
+        );
+        let err = repos
+            .tables()
+            .soft_delete(t.id)
+            .await
+            .expect_err("dropping a table twice should fail");
+        assert!(matches!(err, Error::TableNotFound { id } if id == t.id));
+
+        // the name of a dropped table can be used by a new table, which doesn't count the dropped
+        // table towards the table limit
+        let recreated = repos
+            .tables()
+            .create_or_get("test_table", namespace.id)
+            .await
+            .expect("dropped tables should not count towards the limit");
+        assert_ne!(recreated.id, t.id);
+        assert!(recreated.deleted_at.is_none());
+        assert_eq!(
+            repos
+                .tables()
+                .get_by_namespace_and_name(namespace.id, "test_table")
+                .await
+                .unwrap(),
+            Some(recreated.clone())
+        );
+        let ti = repos
+            .tables()
+            .get_table_persist_info(seq.id, namespace.id, "test_table")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(ti.table_id, recreated.id);
+        let schema = get_schema_by_name("namespace_table_test", repos.as_mut())
+            .await
+            .unwrap();
+        assert_eq!(schema.tables["test_table"].id, recreated.id);
+
+        // dropped tables are hidden from the schema, the most recently dropped table is returned
+        // by name
+        let recreated = repos
+            .tables()
+            .soft_delete(recreated.id)
+            .await
+            .expect("table should be dropped");
+        assert_eq!(
+            repos
+                .tables()
+                .get_by_namespace_and_name(namespace.id, "test_table")
+                .await
+                .unwrap(),
+            Some(recreated)
+        );
+        let schema = get_schema_by_name("namespace_table_test", repos.as_mut())
+            .await
+            .unwrap();
+        assert!(schema.tables.is_empty());
+        repos
+            .tables()
+            .create_or_get("definitely_unique", latest.id)
+            .await
+            .expect("dropped tables should not count towards the limit");
     }
 
     async fn test_column(catalog: Arc<dyn Catalog>) {
@@ -1283,6 +1601,69 @@ pub(crate) mod test_helpers {
                 table_id: _,
             }
         ));
+
+        // test dropping a column
+        let dropped = repos
+            .columns()
+            .soft_delete(want2[0].id)
+            .await
+            .expect("column should be dropped");
+        assert_eq!(dropped.id, want2[0].id);
+        assert!(dropped.deleted_at.is_some());
+        let err = repos
+            .columns()
+            .soft_delete(want2[0].id)
+            .await
+            .expect_err("dropping a column twice should fail");
+        assert!(matches!(err, Error::ColumnNotFound { id } if id == want2[0].id));
+
+        // dropped columns are hidden from the schema
+        repos
+            .columns()
+            .soft_delete(want2[1].id)
+            .await
+            .expect("column should be dropped");
+        let schema = get_schema_by_name("namespace_column_test", repos.as_mut())
+            .await
+            .unwrap();
+        assert!(schema.tables["test_table"].columns.is_empty());
+        assert_eq!(schema.tables["test_table_2"].columns.len(), 2);
+
+        // the name of a dropped column can be used by a new column of another type, which doesn't
+        // count the dropped columns towards the column limit
+        let recreated = repos
+            .columns()
+            .create_or_get("column_test", table.id, ColumnType::U64)
+            .await
+            .expect("dropped columns should not count towards the limit");
+        assert_ne!(recreated.id, want2[0].id);
+        assert!(recreated.deleted_at.is_none());
+        let got = repos
+            .columns()
+            .create_or_get_many(&[ColumnUpsertRequest {
+                name: "column_test",
+                table_id: table.id,
+                column_type: ColumnType::U64,
+            }])
+            .await
+            .unwrap();
+        assert_eq!(got, vec![recreated.clone()]);
+
+        let schema = get_schema_by_name("namespace_column_test", repos.as_mut())
+            .await
+            .unwrap();
+        let columns = &schema.tables["test_table"].columns;
+        assert_eq!(columns.len(), 1);
+        assert_eq!(columns["column_test"].id, recreated.id);
+        assert_eq!(columns["column_test"].column_type, ColumnType::U64);
+
+        // the table schema still has the dropped columns, but the new column takes precedence
+        let table_schema = get_table_schema_by_id(table.id, repos.as_mut())
+            .await
+            .unwrap();
+        assert_eq!(table_schema.columns.len(), 2);
+        assert_eq!(table_schema.columns["column_test"].id, recreated.id);
+        assert_eq!(table_schema.columns["a"].id, want2[1].id);
     }
 
     async fn test_sequencer(catalog: Arc<dyn Catalog>) {
@@ -1521,6 +1902,66 @@ pub(crate) mod test_helpers {
             updated_other_partition.sort_key,
             vec!["tag2", "tag1", "tag3 , with comma", "time"]
         );
+
+        // the partition template is unknown unless given on creation
+        assert_eq!(other_partition.partition_template, None);
+        let template = PartitionTemplate {
+            parts: vec![TemplatePart::Column("tag1".to_owned())],
+        };
+        let templated = repos
+            .partitions()
+            .create_or_get_with_template("tag1_a".into(), sequencer.id, table.id, Some(&template))
+            .await
+            .unwrap();
+        assert_eq!(templated.partition_template.as_ref(), Some(&template));
+
+        // the template of an existing partition is not replaced
+        let other_template = PartitionTemplate {
+            parts: vec![TemplatePart::Column("tag2".to_owned())],
+        };
+        let existing = repos
+            .partitions()
+            .create_or_get_with_template(
+                "tag1_a".into(),
+                sequencer.id,
+                table.id,
+                Some(&other_template),
+            )
+            .await
+            .unwrap();
+        assert_eq!(existing.id, templated.id);
+        assert_eq!(existing.partition_template.as_ref(), Some(&template));
+
+        // once cleared, the template is gone for good
+        let cleared = repos
+            .partitions()
+            .clear_partition_template(templated.id)
+            .await
+            .unwrap();
+        assert_eq!(cleared.partition_template, None);
+        let existing = repos
+            .partitions()
+            .create_or_get_with_template("tag1_a".into(), sequencer.id, table.id, Some(&template))
+            .await
+            .unwrap();
+        assert_eq!(existing.partition_template, None);
+        assert_eq!(
+            repos
+                .partitions()
+                .get_by_id(templated.id)
+                .await
+                .unwrap()
+                .unwrap()
+                .partition_template,
+            None
+        );
+
+        let err = repos
+            .partitions()
+            .clear_partition_template(PartitionId::new(i64::MAX))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::PartitionNotFound { .. }));
     }
 
     async fn test_tombstone(catalog: Arc<dyn Catalog>) {
@@ -3632,7 +4073,7 @@ pub(crate) mod test_helpers {
 
         let batches = mutable_batch_lp::lines_to_batches(lines, 42).unwrap();
         let batches = batches.iter().map(|(table, batch)| (table.as_str(), batch));
-        let ns = NamespaceSchema::new(namespace.id, kafka.id, pool.id);
+        let ns = NamespaceSchema::new(namespace.id, kafka.id, pool.id, None);
 
         let schema = validate_or_insert_schema(batches, &ns, repos)
             .await
@@ -90,7 +90,7 @@ where
                 .tables()
                 .create_or_get(table_name, schema.id)
                 .await
-                .map(|t| TableSchema::new(t.id))?;
+                .map(|t| TableSchema::new_for_table(&t))?;
 
             // Always add a time column to all new tables.
             let time_col = repos
@@ -241,6 +241,7 @@ mod tests {
                         namespace.id,
                         namespace.kafka_topic_id,
                         namespace.query_pool_id,
+                        namespace.retention_period_ns(),
                     );
 
                     // Apply all the lp literals as individual writes, feeding
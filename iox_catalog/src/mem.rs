@@ -14,9 +14,9 @@ use async_trait::async_trait;
 use data_types::{
     Column, ColumnId, ColumnType, CompactionLevel, KafkaPartition, KafkaTopic, KafkaTopicId,
     Namespace, NamespaceId, ParquetFile, ParquetFileId, ParquetFileParams, Partition, PartitionId,
-    PartitionInfo, PartitionKey, PartitionParam, ProcessedTombstone, QueryPool, QueryPoolId,
-    SequenceNumber, Sequencer, SequencerId, Table, TableId, TablePartition, Timestamp, Tombstone,
-    TombstoneId,
+    PartitionInfo, PartitionKey, PartitionParam, PartitionTemplate, ProcessedTombstone, QueryPool,
+    QueryPoolId, SequenceNumber, Sequencer, SequencerId, Table, TableId, TablePartition, Timestamp,
+    Tombstone, TombstoneId,
 };
 use iox_time::{SystemProvider, TimeProvider};
 use observability_deps::tracing::warn;
@@ -303,6 +303,8 @@ impl NamespaceRepo for MemTxn {
             retention_duration: Some(retention_duration.to_string()),
             max_tables: 10000,
             max_columns_per_table: 1000,
+            deleted_at: None,
+            partition_template: None,
         };
         stage.namespaces.push(namespace);
         Ok(stage.namespaces.last().unwrap().clone())
@@ -311,7 +313,12 @@ impl NamespaceRepo for MemTxn {
     async fn list(&mut self) -> Result<Vec<Namespace>> {
         let stage = self.stage();
 
-        Ok(stage.namespaces.clone())
+        Ok(stage
+            .namespaces
+            .iter()
+            .filter(|n| n.deleted_at.is_none())
+            .cloned()
+            .collect())
     }
 
     async fn get_by_id(&mut self, id: NamespaceId) -> Result<Option<Namespace>> {
@@ -323,12 +330,20 @@ impl NamespaceRepo for MemTxn {
     async fn get_by_name(&mut self, name: &str) -> Result<Option<Namespace>> {
         let stage = self.stage();
 
-        Ok(stage.namespaces.iter().find(|n| n.name == name).cloned())
+        Ok(stage
+            .namespaces
+            .iter()
+            .find(|n| n.name == name && n.deleted_at.is_none())
+            .cloned())
     }
 
     async fn update_table_limit(&mut self, name: &str, new_max: i32) -> Result<Namespace> {
         let stage = self.stage();
-        match stage.namespaces.iter_mut().find(|n| n.name == name) {
+        match stage
+            .namespaces
+            .iter_mut()
+            .find(|n| n.name == name && n.deleted_at.is_none())
+        {
             Some(n) => {
                 n.max_tables = new_max;
                 Ok(n.clone())
@@ -341,7 +356,11 @@ impl NamespaceRepo for MemTxn {
 
     async fn update_column_limit(&mut self, name: &str, new_max: i32) -> Result<Namespace> {
         let stage = self.stage();
-        match stage.namespaces.iter_mut().find(|n| n.name == name) {
+        match stage
+            .namespaces
+            .iter_mut()
+            .find(|n| n.name == name && n.deleted_at.is_none())
+        {
             Some(n) => {
                 n.max_columns_per_table = new_max;
                 Ok(n.clone())
@@ -351,6 +370,83 @@ impl NamespaceRepo for MemTxn {
             }),
         }
     }
+
+    async fn update_retention_duration(
+        &mut self,
+        name: &str,
+        retention_duration: &str,
+    ) -> Result<Namespace> {
+        let stage = self.stage();
+        match stage
+            .namespaces
+            .iter_mut()
+            .find(|n| n.name == name && n.deleted_at.is_none())
+        {
+            Some(n) => {
+                n.retention_duration = Some(retention_duration.to_string());
+                Ok(n.clone())
+            }
+            None => Err(Error::NamespaceNotFoundByName {
+                name: name.to_string(),
+            }),
+        }
+    }
+
+    async fn soft_delete(&mut self, name: &str) -> Result<Namespace> {
+        let deleted_at = Timestamp::new(self.time_provider.now().timestamp_nanos());
+        let stage = self.stage();
+        match stage
+            .namespaces
+            .iter_mut()
+            .find(|n| n.name == name && n.deleted_at.is_none())
+        {
+            Some(n) => {
+                n.deleted_at = Some(deleted_at);
+                Ok(n.clone())
+            }
+            None => Err(Error::NamespaceNotFoundByName {
+                name: name.to_string(),
+            }),
+        }
+    }
+
+    async fn restore(&mut self, name: &str) -> Result<Namespace> {
+        let stage = self.stage();
+        match stage
+            .namespaces
+            .iter_mut()
+            .find(|n| n.name == name && n.deleted_at.is_some())
+        {
+            Some(n) => {
+                n.deleted_at = None;
+                Ok(n.clone())
+            }
+            None => Err(Error::NamespaceNotFoundByName {
+                name: name.to_string(),
+            }),
+        }
+    }
+
+    async fn update_partition_template(
+        &mut self,
+        name: &str,
+        partition_template: Option<&PartitionTemplate>,
+    ) -> Result<Namespace> {
+        let stage = self.stage();
+        match stage
+            .namespaces
+            .iter_mut()
+            .find(|n| n.name == name && n.deleted_at.is_none())
+        {
+            Some(n) => {
+                n.partition_template = partition_template.cloned();
+                Ok(n.clone())
+            }
+            None => Err(Error::NamespaceNotFoundByName {
+                name: name.to_string(),
+            }),
+        }
+    }
 }
 
 #[async_trait]
@@ -375,7 +471,7 @@ impl TableRepo for MemTxn {
                 let tables_count = stage
                     .tables
                     .iter()
-                    .filter(|t| t.namespace_id == namespace_id)
+                    .filter(|t| t.namespace_id == namespace_id && t.deleted_at.is_none())
                     .count();
                 if tables_count >= max_tables.try_into().unwrap() {
                     return Err(Error::TableCreateLimitError {
@@ -386,22 +482,24 @@ impl TableRepo for MemTxn {
                 Ok(())
             })?;
 
-        let table = match stage
-            .tables
-            .iter()
-            .find(|t| t.name == name && t.namespace_id == namespace_id)
-        {
-            Some(t) => t,
-            None => {
-                let table = Table {
-                    id: TableId::new(stage.tables.len() as i64 + 1),
-                    namespace_id,
-                    name: name.to_string(),
-                };
-                stage.tables.push(table);
-                stage.tables.last().unwrap()
-            }
-        };
+        // a dropped table doesn't keep its name from being used again
+        let table =
+            match stage.tables.iter().find(|t| {
+                t.name == name && t.namespace_id == namespace_id && t.deleted_at.is_none()
+            }) {
+                Some(t) => t,
+                None => {
+                    let table = Table {
+                        id: TableId::new(stage.tables.len() as i64 + 1),
+                        namespace_id,
+                        name: name.to_string(),
+                        deleted_at: None,
+                        partition_template: None,
+                    };
+                    stage.tables.push(table);
+                    stage.tables.last().unwrap()
+                }
+            };
 
         Ok(table.clone())
     }
@@ -422,7 +520,8 @@ impl TableRepo for MemTxn {
         Ok(stage
             .tables
             .iter()
-            .find(|t| t.namespace_id == namespace_id && t.name == name)
+            .filter(|t| t.namespace_id == namespace_id && t.name == name)
+            .max_by_key(|t| (t.deleted_at.is_none(), t.id))
             .cloned())
     }
 
@@ -454,7 +553,8 @@ impl TableRepo for MemTxn {
         if let Some(table) = stage
             .tables
             .iter()
-            .find(|t| t.name == table_name && t.namespace_id == namespace_id)
+            .filter(|t| t.name == table_name && t.namespace_id == namespace_id)
+            .max_by_key(|t| (t.deleted_at.is_none(), t.id))
         {
             let tombstone_max_sequence_number = stage
                 .tombstones
@@ -472,6 +572,41 @@ impl TableRepo for MemTxn {
 
         Ok(None)
     }
+
+    async fn soft_delete(&mut self, table_id: TableId) -> Result<Table> {
+        let deleted_at = Timestamp::new(self.time_provider.now().timestamp_nanos());
+        let stage = self.stage();
+        match stage
+            .tables
+            .iter_mut()
+            .find(|t| t.id == table_id && t.deleted_at.is_none())
+        {
+            Some(t) => {
+                t.deleted_at = Some(deleted_at);
+                Ok(t.clone())
+            }
+            None => Err(Error::TableNotFound { id: table_id }),
+        }
+    }
+
+    async fn update_partition_template(
+        &mut self,
+        table_id: TableId,
+        partition_template: Option<&PartitionTemplate>,
+    ) -> Result<Table> {
+        let stage = self.stage();
+        match stage
+            .tables
+            .iter_mut()
+            .find(|t| t.id == table_id && t.deleted_at.is_none())
+        {
+            Some(t) => {
+                t.partition_template = partition_template.cloned();
+                Ok(t.clone())
+            }
+            None => Err(Error::TableNotFound { id: table_id }),
+        }
+    }
 }
 
 #[async_trait]
@@ -508,7 +643,7 @@ impl ColumnRepo for MemTxn {
                         let columns_count = stage
                             .columns
                             .iter()
-                            .filter(|t| t.table_id == table_id)
+                            .filter(|t| t.table_id == table_id && t.deleted_at.is_none())
                             .count();
                         if columns_count >= max_columns_per_table.try_into().unwrap() {
                             return Err(Error::ColumnCreateLimitError {
@@ -521,10 +656,11 @@ impl ColumnRepo for MemTxn {
                 Ok(())
             })?;
 
+        // a dropped column doesn't keep its name from being used again
         let column = match stage
             .columns
             .iter()
-            .find(|t| t.name == name && t.table_id == table_id)
+            .find(|t| t.name == name && t.table_id == table_id && t.deleted_at.is_none())
         {
             Some(c) => {
                 if column_type as i16 != c.column_type {
@@ -543,6 +679,7 @@ impl ColumnRepo for MemTxn {
                     table_id,
                     name: name.to_string(),
                     column_type: column_type as i16,
+                    deleted_at: None,
                 };
                 stage.columns.push(column);
                 stage.columns.last().unwrap()
@@ -601,6 +738,22 @@ impl ColumnRepo for MemTxn {
         let stage = self.stage();
         Ok(stage.columns.clone())
     }
+
+    async fn soft_delete(&mut self, column_id: ColumnId) -> Result<Column> {
+        let deleted_at = Timestamp::new(self.time_provider.now().timestamp_nanos());
+        let stage = self.stage();
+        match stage
+            .columns
+            .iter_mut()
+            .find(|c| c.id == column_id && c.deleted_at.is_none())
+        {
+            Some(c) => {
+                c.deleted_at = Some(deleted_at);
+                Ok(c.clone())
+            }
+            None => Err(Error::ColumnNotFound { id: column_id }),
+        }
+    }
 }
 
 #[async_trait]
@@ -688,6 +841,17 @@ impl PartitionRepo for MemTxn {
         key: PartitionKey,
         sequencer_id: SequencerId,
         table_id: TableId,
+    ) -> Result<Partition> {
+        self.create_or_get_with_template(key, sequencer_id, table_id, None)
+            .await
+    }
+
+    async fn create_or_get_with_template(
+        &mut self,
+        key: PartitionKey,
+        sequencer_id: SequencerId,
+        table_id: TableId,
+        partition_template: Option<&PartitionTemplate>,
     ) -> Result<Partition> {
         let stage = self.stage();
 
@@ -702,6 +866,7 @@ impl PartitionRepo for MemTxn {
                     table_id,
                     partition_key: key,
                     sort_key: vec![],
+                    partition_template: partition_template.cloned(),
                 };
                 stage.partitions.push(p);
                 stage.partitions.last().unwrap()
@@ -813,6 +978,17 @@ impl PartitionRepo for MemTxn {
             None => Err(Error::PartitionNotFound { id: partition_id }),
         }
     }
+
+    async fn clear_partition_template(&mut self, partition_id: PartitionId) -> Result<Partition> {
+        let stage = self.stage();
+        match stage.partitions.iter_mut().find(|p| p.id == partition_id) {
+            Some(p) => {
+                p.partition_template = None;
+                Ok(p.clone())
+            }
+            None => Err(Error::PartitionNotFound { id: partition_id }),
+        }
+    }
 }
 
 #[async_trait]
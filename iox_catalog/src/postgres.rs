@@ -11,11 +11,11 @@ use crate::{
 };
 use async_trait::async_trait;
 use data_types::{
-    Column, ColumnType, CompactionLevel, KafkaPartition, KafkaTopic, KafkaTopicId, Namespace,
-    NamespaceId, ParquetFile, ParquetFileId, ParquetFileParams, Partition, PartitionId,
-    PartitionInfo, PartitionKey, PartitionParam, ProcessedTombstone, QueryPool, QueryPoolId,
-    SequenceNumber, Sequencer, SequencerId, Table, TableId, TablePartition, Timestamp, Tombstone,
-    TombstoneId,
+    Column, ColumnId, ColumnType, CompactionLevel, KafkaPartition, KafkaTopic, KafkaTopicId,
+    Namespace, NamespaceId, ParquetFile, ParquetFileId, ParquetFileParams, Partition, PartitionId,
+    PartitionInfo, PartitionKey, PartitionParam, PartitionTemplate, ProcessedTombstone, QueryPool,
+    QueryPoolId, SequenceNumber, Sequencer, SequencerId, Table, TableId, TablePartition, Timestamp,
+    Tombstone, TombstoneId,
 };
 use iox_time::{SystemProvider, TimeProvider};
 use observability_deps::tracing::{debug, info, warn};
@@ -606,7 +606,8 @@ RETURNING *;
         let rec = sqlx::query_as::<_, Namespace>(
             r#"
 SELECT *
-FROM namespace;
+FROM namespace
+WHERE deleted_at IS NULL;
             "#,
         )
         .fetch_all(&mut self.inner)
@@ -642,7 +643,7 @@ WHERE id = $1;
             r#"
 SELECT *
 FROM namespace
-WHERE name = $1;
+WHERE name = $1 AND deleted_at IS NULL;
         "#,
         )
         .bind(&name) // $1
@@ -663,7 +664,7 @@ WHERE name = $1;
             r#"
 UPDATE namespace
 SET max_tables = $1
-WHERE name = $2
+WHERE name = $2 AND deleted_at IS NULL
 RETURNING *;
         "#,
         )
@@ -687,7 +688,7 @@ RETURNING *;
             r#"
 UPDATE namespace
 SET max_columns_per_table = $1
-WHERE name = $2
+WHERE name = $2 AND deleted_at IS NULL
 RETURNING *;
         "#,
         )
@@ -705,6 +706,111 @@ RETURNING *;
 
         Ok(namespace)
     }
+
+    async fn update_retention_duration(
+        &mut self,
+        name: &str,
+        retention_duration: &str,
+    ) -> Result<Namespace> {
+        let rec = sqlx::query_as::<_, Namespace>(
+            r#"
+UPDATE namespace
+SET retention_duration = $1
+WHERE name = $2 AND deleted_at IS NULL
+RETURNING *;
+        "#,
+        )
+        .bind(&retention_duration)
+        .bind(&name)
+        .fetch_one(&mut self.inner)
+        .await;
+
+        let namespace = rec.map_err(|e| match e {
+            sqlx::Error::RowNotFound => Error::NamespaceNotFoundByName {
+                name: name.to_string(),
+            },
+            _ => Error::SqlxError { source: e },
+        })?;
+
+        Ok(namespace)
+    }
+
+    async fn soft_delete(&mut self, name: &str) -> Result<Namespace> {
+        let deleted_at = Timestamp::new(self.time_provider.now().timestamp_nanos());
+
+        let rec = sqlx::query_as::<_, Namespace>(
+            r#"
+UPDATE namespace
+SET deleted_at = $1
+WHERE name = $2 AND deleted_at IS NULL
+RETURNING *;
+        "#,
+        )
+        .bind(&deleted_at)
+        .bind(&name)
+        .fetch_one(&mut self.inner)
+        .await;
+
+        let namespace = rec.map_err(|e| match e {
+            sqlx::Error::RowNotFound => Error::NamespaceNotFoundByName {
+                name: name.to_string(),
+            },
+            _ => Error::SqlxError { source: e },
+        })?;
+
+        Ok(namespace)
+    }
+
+    async fn restore(&mut self, name: &str) -> Result<Namespace> {
+        let rec = sqlx::query_as::<_, Namespace>(
+            r#"
+UPDATE namespace
+SET deleted_at = NULL
+WHERE name = $1 AND deleted_at IS NOT NULL
+RETURNING *;
+        "#,
+        )
+        .bind(&name)
+        .fetch_one(&mut self.inner)
+        .await;
+
+        let namespace = rec.map_err(|e| match e {
+            sqlx::Error::RowNotFound => Error::NamespaceNotFoundByName {
+                name: name.to_string(),
+            },
+            _ => Error::SqlxError { source: e },
+        })?;
+
+        Ok(namespace)
+    }
+
+    async fn update_partition_template(
+        &mut self,
+        name: &str,
+        partition_template: Option<&PartitionTemplate>,
+    ) -> Result<Namespace> {
+        let rec = sqlx::query_as::<_, Namespace>(
+            r#"
+UPDATE namespace
+SET partition_template = $1
+WHERE name = $2 AND deleted_at IS NULL
+RETURNING *;
+        "#,
+        )
+        .bind(&partition_template) // $1
+        .bind(&name) // $2
+        .fetch_one(&mut self.inner)
+        .await;
+
+        let namespace = rec.map_err(|e| match e {
+            sqlx::Error::RowNotFound => Error::NamespaceNotFoundByName {
+                name: name.to_string(),
+            },
+            _ => Error::SqlxError { source: e },
+        })?;
+
+        Ok(namespace)
+    }
 }
 
 #[async_trait]
@@ -724,11 +830,12 @@ impl TableRepo for PostgresTxn {
 INSERT INTO table_name ( name, namespace_id )
 SELECT $1, id FROM (
     SELECT namespace.id AS id, max_tables, COUNT(table_name.*) AS count
-    FROM namespace LEFT JOIN table_name ON namespace.id = table_name.namespace_id
+    FROM namespace LEFT JOIN table_name
+        ON namespace.id = table_name.namespace_id AND table_name.deleted_at IS NULL
     WHERE namespace.id = $2
     GROUP BY namespace.max_tables, table_name.namespace_id, namespace.id
 ) AS get_count WHERE count < max_tables
-ON CONFLICT ON CONSTRAINT table_name_unique
+ON CONFLICT (namespace_id, name) WHERE deleted_at IS NULL
 DO UPDATE SET name = table_name.name
 RETURNING *;
         "#,
@@ -784,7 +891,9 @@ WHERE id = $1;
             r#"
 SELECT *
 FROM table_name
-WHERE namespace_id = $1 AND name = $2;
+WHERE namespace_id = $1 AND name = $2
+ORDER BY deleted_at IS NOT NULL, id DESC
+LIMIT 1;
             "#,
         )
         .bind(&namespace_id) // $1
@@ -834,7 +943,11 @@ WHERE namespace_id = $1;
     ) -> Result<Option<TablePersistInfo>> {
         let rec = sqlx::query_as::<_, TablePersistInfo>(
             r#"
-WITH tid as (SELECT id FROM table_name WHERE name = $2 AND namespace_id = $3)
+WITH tid as (
+  SELECT id FROM table_name WHERE name = $2 AND namespace_id = $3
+  ORDER BY deleted_at IS NOT NULL, id DESC
+  LIMIT 1
+)
 SELECT $1 as sequencer_id, id as table_id,
        tombstone.sequence_number as tombstone_max_sequence_number
 FROM tid
@@ -861,6 +974,53 @@ LEFT JOIN (
 
         Ok(Some(info))
     }
+
+    async fn soft_delete(&mut self, table_id: TableId) -> Result<Table> {
+        let deleted_at = Timestamp::new(self.time_provider.now().timestamp_nanos());
+        let rec = sqlx::query_as::<_, Table>(
+            r#"
+UPDATE table_name
+SET deleted_at = $1
+WHERE id = $2 AND deleted_at IS NULL
+RETURNING *;
+        "#,
+        )
+        .bind(&deleted_at) // $1
+        .bind(&table_id) // $2
+        .fetch_one(&mut self.inner)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::RowNotFound => Error::TableNotFound { id: table_id },
+            _ => Error::SqlxError { source: e },
+        })?;
+
+        Ok(rec)
+    }
+
+    async fn update_partition_template(
+        &mut self,
+        table_id: TableId,
+        partition_template: Option<&PartitionTemplate>,
+    ) -> Result<Table> {
+        let rec = sqlx::query_as::<_, Table>(
+            r#"
+UPDATE table_name
+SET partition_template = $1
+WHERE id = $2 AND deleted_at IS NULL
+RETURNING *;
+        "#,
+        )
+        .bind(&partition_template) // $1
+        .bind(&table_id) // $2
+        .fetch_one(&mut self.inner)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::RowNotFound => Error::TableNotFound { id: table_id },
+            _ => Error::SqlxError { source: e },
+        })?;
+
+        Ok(rec)
+    }
 }
 
 #[async_trait]
@@ -878,11 +1038,12 @@ INSERT INTO column_name ( name, table_id, column_type )
 SELECT $1, table_id, $3 FROM (
     SELECT max_columns_per_table, namespace.id, table_name.id as table_id, COUNT(column_name.*) AS count
     FROM namespace LEFT JOIN table_name ON namespace.id = table_name.namespace_id
-                   LEFT JOIN column_name ON table_name.id = column_name.table_id
+                   LEFT JOIN column_name
+                       ON table_name.id = column_name.table_id AND column_name.deleted_at IS NULL
     WHERE table_name.id = $2
     GROUP BY namespace.max_columns_per_table, namespace.id, table_name.id
 ) AS get_count WHERE count < max_columns_per_table
-ON CONFLICT ON CONSTRAINT column_name_unique
+ON CONFLICT (table_id, name) WHERE deleted_at IS NULL
 DO UPDATE SET name = column_name.name
 RETURNING *;
         "#,
@@ -973,7 +1134,7 @@ WHERE table_id = $1;
             r#"
 INSERT INTO column_name ( name, table_id, column_type )
 SELECT name, table_id, column_type FROM UNNEST($1, $2, $3) as a(name, table_id, column_type)
-ON CONFLICT ON CONSTRAINT column_name_unique
+ON CONFLICT (table_id, name) WHERE deleted_at IS NULL
 DO UPDATE SET name = column_name.name
 RETURNING *;
             "#,
@@ -1009,6 +1170,28 @@ RETURNING *;
             })
             .collect()
     }
+
+    async fn soft_delete(&mut self, column_id: ColumnId) -> Result<Column> {
+        let deleted_at = Timestamp::new(self.time_provider.now().timestamp_nanos());
+        let rec = sqlx::query_as::<_, Column>(
+            r#"
+UPDATE column_name
+SET deleted_at = $1
+WHERE id = $2 AND deleted_at IS NULL
+RETURNING *;
+        "#,
+        )
+        .bind(&deleted_at) // $1
+        .bind(&column_id) // $2
+        .fetch_one(&mut self.inner)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::RowNotFound => Error::ColumnNotFound { id: column_id },
+            _ => Error::SqlxError { source: e },
+        })?;
+
+        Ok(rec)
+    }
 }
 
 #[async_trait]
@@ -1109,6 +1292,17 @@ impl PartitionRepo for PostgresTxn {
         key: PartitionKey,
         sequencer_id: SequencerId,
         table_id: TableId,
+    ) -> Result<Partition> {
+        self.create_or_get_with_template(key, sequencer_id, table_id, None)
+            .await
+    }
+
+    async fn create_or_get_with_template(
+        &mut self,
+        key: PartitionKey,
+        sequencer_id: SequencerId,
+        table_id: TableId,
+        partition_template: Option<&PartitionTemplate>,
     ) -> Result<Partition> {
         // Note: since sort_key is now an array, we must explicitly insert '{}' which is an empty array
         // rather than NULL which sqlx will throw `UnexpectedNullError` while is is doing `ColumnDecode`
@@ -1116,9 +1310,9 @@ impl PartitionRepo for PostgresTxn {
         let v = sqlx::query_as::<_, Partition>(
             r#"
 INSERT INTO partition
-    ( partition_key, sequencer_id, table_id, sort_key)
+    ( partition_key, sequencer_id, table_id, sort_key, partition_template)
 VALUES
-    ( $1, $2, $3, '{}')
+    ( $1, $2, $3, '{}', $4)
 ON CONFLICT ON CONSTRAINT partition_key_unique
 DO UPDATE SET partition_key = partition.partition_key
 RETURNING *;
@@ -1127,6 +1321,7 @@ RETURNING *;
         .bind(key) // $1
         .bind(&sequencer_id) // $2
         .bind(&table_id) // $3
+        .bind(&partition_template) // $4
         .fetch_one(&mut self.inner)
         .await
         .map_err(|e| {
@@ -1227,6 +1422,7 @@ WHERE partition.id = $1;
             table_id: info.get("table_id"),
             partition_key: info.get("partition_key"),
             sort_key: info.get("sort_key"),
+            partition_template: info.get("partition_template"),
         };
 
         Ok(Some(PartitionInfo {
@@ -1263,6 +1459,25 @@ RETURNING *;
 
         Ok(partition)
     }
+
+    async fn clear_partition_template(&mut self, partition_id: PartitionId) -> Result<Partition> {
+        let rec = sqlx::query_as::<_, Partition>(
+            r#"
+UPDATE partition
+SET partition_template = NULL
+WHERE id = $1
+RETURNING *;
+        "#,
+        )
+        .bind(&partition_id) // $1
+        .fetch_one(&mut self.inner)
+        .await;
+
+        rec.map_err(|e| match e {
+            sqlx::Error::RowNotFound => Error::PartitionNotFound { id: partition_id },
+            _ => Error::SqlxError { source: e },
+        })
+    }
 }
 
 #[async_trait]
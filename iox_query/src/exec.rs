@@ -4,6 +4,7 @@
 pub(crate) mod context;
 pub mod field;
 pub mod fieldlist;
+pub(crate) mod gapfill;
 mod non_null_checker;
 mod query_tracing;
 mod schema_pivot;
@@ -29,7 +30,11 @@ use datafusion::{
 pub use context::{IOxSessionConfig, IOxSessionContext, SessionContextIOxExt};
 use schema_pivot::SchemaPivotNode;
 
-use self::{non_null_checker::NonNullCheckerNode, split::StreamSplitNode};
+use self::{
+    gapfill::{GapFill, GapFillNode},
+    non_null_checker::NonNullCheckerNode,
+    split::StreamSplitNode,
+};
 
 /// Configuration for an Executor
 #[derive(Debug, Clone)]
@@ -256,6 +261,33 @@ pub fn make_stream_split(input: LogicalPlan, split_exprs: Vec<Expr>) -> LogicalP
     LogicalPlan::Extension(Extension { node })
 }
 
+/// Create a GapFill node which takes an input of aggregated windows, sorted
+/// by the `series` columns and then time, and adds a row for each window of
+/// a series without data, with values chosen by the [`FillStrategy`] of
+/// `params`.
+///
+/// For windows of 10 that span `[0, 30)` and `FillStrategy::Null`, this input:
+/// ```text
+///  series | time | value
+/// --------+------+-------
+///   a     | 10   | 1
+/// ```
+///
+/// Would produce this output:
+/// ```text
+///  series | time | value
+/// --------+------+-------
+///   a     | 0    | NULL
+///   a     | 10   | 1
+///   a     | 20   | NULL
+/// ```
+///
+/// [`FillStrategy`]: gapfill::FillStrategy
+pub(crate) fn make_gap_fill(input: LogicalPlan, params: GapFill) -> LogicalPlan {
+    let node = Arc::new(GapFillNode::new(input, params));
+    LogicalPlan::Extension(Extension { node })
+}
+
 /// A type that can provide `IOxSessionContext` for query
 pub trait ExecutionContextProvider {
     /// Returns a new execution context suitable for running queries
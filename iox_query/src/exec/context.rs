@@ -32,6 +32,7 @@ use trace::{
 
 use crate::exec::{
     fieldlist::{FieldList, IntoFieldList},
+    gapfill::GapFillExec,
     non_null_checker::NonNullCheckerExec,
     query_tracing::TracedStream,
     schema_pivot::{SchemaPivotExec, SchemaPivotNode},
@@ -53,7 +54,8 @@ use crate::plan::{
 pub use datafusion::error::{DataFusionError as Error, Result};
 
 use super::{
-    non_null_checker::NonNullCheckerNode, seriesset::series::Either, split::StreamSplitNode,
+    gapfill::GapFillNode, non_null_checker::NonNullCheckerNode, seriesset::series::Either,
+    split::StreamSplitNode,
 };
 
 // The default catalog name - this impacts what SQL queries use if not specified
@@ -113,6 +115,12 @@ impl ExtensionPlanner for IOxExtensionPlanner {
                 non_null_checker.schema().as_ref().clone().into(),
                 non_null_checker.value(),
             )) as Arc<dyn ExecutionPlan>)
+        } else if let Some(gap_fill) = any.downcast_ref::<GapFillNode>() {
+            assert_eq!(physical_inputs.len(), 1, "Inconsistent number of inputs");
+            Some(Arc::new(GapFillExec::new(
+                Arc::clone(&physical_inputs[0]),
+                gap_fill.params().clone(),
+            )) as Arc<dyn ExecutionPlan>)
         } else if let Some(stream_split) = any.downcast_ref::<StreamSplitNode>() {
             assert_eq!(
                 logical_inputs.len(),
@@ -0,0 +1,666 @@
+//! This module contains code for the "GapFill" DataFusion extension
+//! plan node
+//!
+//! A GapFill node takes an input of aggregated windows, sorted by the
+//! series columns and then time, and adds a row for each window of a
+//! series that has no data.
+//!
+//! For windows of 10 that span `[0, 40)`, this input:
+//!
+//!  series | time | value
+//! --------+------+-------
+//!   a     | 10   | 1
+//!   b     | 0    | 2
+//!   b     | 30   | 3
+//!
+//! With [`FillStrategy::Previous`] becomes:
+//!
+//!  series | time | value
+//! --------+------+-------
+//!   a     | 0    | NULL
+//!   a     | 10   | 1
+//!   a     | 20   | 1
+//!   a     | 30   | 1
+//!   b     | 0    | 2
+//!   b     | 10   | 2
+//!   b     | 20   | 2
+//!   b     | 30   | 3
+//!
+//! This operation is used to implement the `fill()` options of InfluxQL
+//! `GROUP BY time()` queries.
+
+use std::{
+    any::Any,
+    fmt::{self, Debug},
+    sync::Arc,
+};
+
+use arrow::{
+    array::{
+        as_primitive_array, as_string_array, Array, ArrayRef, BooleanArray, Int64Array, UInt32Array,
+    },
+    compute::{cast, kernels::zip::zip, take},
+    datatypes::{DataType, SchemaRef, TimeUnit, TimestampNanosecondType},
+    error::{ArrowError, Result as ArrowResult},
+    record_batch::RecordBatch,
+};
+use datafusion::{
+    error::{DataFusionError as Error, Result},
+    execution::context::TaskContext,
+    logical_plan::{DFSchemaRef, Expr, LogicalPlan, UserDefinedLogicalNode},
+    physical_plan::{
+        expressions::PhysicalSortExpr,
+        metrics::{BaselineMetrics, ExecutionPlanMetricsSet, MetricsSet},
+        DisplayFormatType, Distribution, ExecutionPlan, Partitioning, SendableRecordBatchStream,
+        Statistics,
+    },
+    scalar::ScalarValue,
+};
+
+use datafusion_util::{watch::WatchedTask, AdapterStream};
+use observability_deps::tracing::debug;
+use tokio::sync::mpsc;
+use tokio_stream::StreamExt;
+
+/// The most rows a GapFill node returns, so that a long time range with a
+/// short interval can't exhaust the memory of the querier
+pub const MAX_GAP_FILL_ROWS: usize = 1_000_000;
+
+/// What a GapFill node puts in the value columns of the windows it adds
+#[derive(Debug, Clone, PartialEq)]
+pub enum FillStrategy {
+    /// NULL
+    Null,
+    /// The value, cast to the type of each numeric column. Other columns
+    /// are NULL.
+    Value(ScalarValue),
+    /// The values of the previous window of the series with data, NULL
+    /// before the first one
+    Previous,
+}
+
+/// Parameters of a GapFill node
+#[derive(Debug, Clone, PartialEq)]
+pub struct GapFill {
+    /// The Utf8 columns that identify a series
+    pub series: Vec<String>,
+    /// The nanosecond timestamp column with the start of each window
+    pub time: String,
+    /// The length of the windows, in nanoseconds
+    pub interval: i64,
+    /// The offset of the windows from the epoch, in nanoseconds
+    pub offset: i64,
+    /// The windows start with the one containing this time, or with the
+    /// first window of each series with data
+    pub start: Option<i64>,
+    /// The windows end with the one containing the time before this one,
+    /// or with the last window of each series with data
+    pub end: Option<i64>,
+    /// True if the input is sorted by descending time
+    pub descending: bool,
+    /// The values of the windows without data
+    pub fill: FillStrategy,
+}
+
+impl GapFill {
+    /// The start of the window containing `time`
+    fn window(&self, time: i64) -> i64 {
+        let offset = self.offset.rem_euclid(self.interval);
+        time.saturating_sub(time.wrapping_sub(offset).rem_euclid(self.interval))
+    }
+}
+
+/// Implements the GapFill operation as described in this module's documentation
+pub struct GapFillNode {
+    input: LogicalPlan,
+    /// these expressions represent what columns are "used" by this
+    /// node (in this case all of them) -- columns that are not used
+    /// are optimzied away by datafusion.
+    exprs: Vec<Expr>,
+    params: GapFill,
+}
+
+impl GapFillNode {
+    pub fn new(input: LogicalPlan, params: GapFill) -> Self {
+        // Form exprs that refer to all of our input columns (so that
+        // datafusion knows not to opimize them away)
+        let exprs = input
+            .schema()
+            .fields()
+            .iter()
+            .map(|field| Expr::Column(field.qualified_column()))
+            .collect::<Vec<_>>();
+
+        Self {
+            input,
+            exprs,
+            params,
+        }
+    }
+
+    /// Return the parameters of this node
+    pub fn params(&self) -> &GapFill {
+        &self.params
+    }
+}
+
+impl Debug for GapFillNode {
+    /// Use explain format for the Debug format.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fmt_for_explain(f)
+    }
+}
+
+impl UserDefinedLogicalNode for GapFillNode {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn inputs(&self) -> Vec<&LogicalPlan> {
+        vec![&self.input]
+    }
+
+    /// Schema for GapFill is the same as its input
+    fn schema(&self) -> &DFSchemaRef {
+        self.input.schema()
+    }
+
+    fn expressions(&self) -> Vec<Expr> {
+        self.exprs.clone()
+    }
+
+    /// For example: `GapFill(time, interval=60000000000, fill=Null)`
+    fn fmt_for_explain(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "GapFill({}, interval={}, fill={:?})",
+            self.params.time, self.params.interval, self.params.fill
+        )
+    }
+
+    fn from_template(
+        &self,
+        exprs: &[Expr],
+        inputs: &[LogicalPlan],
+    ) -> Arc<dyn UserDefinedLogicalNode> {
+        assert_eq!(inputs.len(), 1, "GapFill: input sizes inconistent");
+        assert_eq!(
+            exprs.len(),
+            self.exprs.len(),
+            "GapFill: expression sizes inconistent"
+        );
+        Arc::new(Self::new(inputs[0].clone(), self.params.clone()))
+    }
+}
+
+// ------ The implementation of GapFill code follows -----
+
+/// Physical operator that implements the GapFill operation
+pub struct GapFillExec {
+    input: Arc<dyn ExecutionPlan>,
+    params: GapFill,
+    /// Execution metrics
+    metrics: ExecutionPlanMetricsSet,
+}
+
+impl GapFillExec {
+    pub fn new(input: Arc<dyn ExecutionPlan>, params: GapFill) -> Self {
+        Self {
+            input,
+            params,
+            metrics: ExecutionPlanMetricsSet::new(),
+        }
+    }
+}
+
+impl Debug for GapFillExec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "GapFillExec")
+    }
+}
+
+impl ExecutionPlan for GapFillExec {
+    fn as_any(&self) -> &(dyn std::any::Any + 'static) {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.input.schema()
+    }
+
+    fn output_partitioning(&self) -> Partitioning {
+        Partitioning::UnknownPartitioning(1)
+    }
+
+    fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
+        self.input.output_ordering()
+    }
+
+    /// The windows of a series are filled in one pass over its sorted rows
+    fn required_child_distribution(&self) -> Distribution {
+        Distribution::SinglePartition
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        vec![Arc::clone(&self.input)]
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        match children.len() {
+            1 => Ok(Arc::new(Self::new(
+                Arc::clone(&children[0]),
+                self.params.clone(),
+            ))),
+            _ => Err(Error::Internal(
+                "GapFillExec wrong number of children".to_string(),
+            )),
+        }
+    }
+
+    /// Execute one partition and return an iterator over RecordBatch
+    fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> Result<SendableRecordBatchStream> {
+        debug!(partition, "Start GapFillExec::execute");
+        if self.output_partitioning().partition_count() <= partition {
+            return Err(Error::Internal(format!(
+                "GapFillExec invalid partition {}",
+                partition
+            )));
+        }
+
+        let baseline_metrics = BaselineMetrics::new(&self.metrics, partition);
+        let input_stream = self.input.execute(partition, context)?;
+
+        let (tx, rx) = mpsc::channel(1);
+
+        let fut = gap_fill(
+            input_stream,
+            self.schema(),
+            self.params.clone(),
+            baseline_metrics,
+            tx.clone(),
+        );
+
+        // A second task watches the output of the worker task and
+        // reports errors
+        let handle = WatchedTask::new(fut, vec![tx], "gap_fill");
+
+        debug!(partition, "End GapFillExec::execute");
+        Ok(AdapterStream::adapt(self.schema(), rx, handle))
+    }
+
+    fn fmt_as(&self, t: DisplayFormatType, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match t {
+            DisplayFormatType::Default => {
+                write!(
+                    f,
+                    "GapFillExec: interval={}, fill={:?}",
+                    self.params.interval, self.params.fill
+                )
+            }
+        }
+    }
+
+    fn metrics(&self) -> Option<MetricsSet> {
+        Some(self.metrics.clone_inner())
+    }
+
+    fn statistics(&self) -> Statistics {
+        // don't know anything about the statistics
+        Statistics::default()
+    }
+}
+
+async fn gap_fill(
+    mut input_stream: SendableRecordBatchStream,
+    schema: SchemaRef,
+    params: GapFill,
+    baseline_metrics: BaselineMetrics,
+    tx: mpsc::Sender<ArrowResult<RecordBatch>>,
+) -> ArrowResult<()> {
+    // the windows of a series may span several input batches
+    let mut batches = vec![];
+    while let Some(input_batch) = input_stream.next().await.transpose()? {
+        batches.push(input_batch);
+    }
+
+    let timer = baseline_metrics.elapsed_compute().timer();
+    let input = RecordBatch::concat(&schema, &batches)?;
+    if input.num_rows() == 0 {
+        return Ok(());
+    }
+    let output_batch = fill_batch(&input, &params)?;
+    baseline_metrics.record_output(output_batch.num_rows());
+    std::mem::drop(timer);
+
+    // ignore errors on sending (means receiver hung up)
+    tx.send(Ok(output_batch)).await.ok();
+    Ok(())
+}
+
+/// The rows of the output of a GapFill node
+#[derive(Debug, Default)]
+struct Rows {
+    /// An input row of the series of each output row
+    series: Vec<u32>,
+    /// The time of each output row
+    times: Vec<i64>,
+    /// The input row with the values of each output row
+    values: Vec<Option<u32>>,
+    /// True for the output rows of windows without data
+    gaps: Vec<bool>,
+}
+
+impl Rows {
+    fn push(&mut self, series: usize, time: i64, values: Option<usize>, gap: bool) {
+        self.series.push(series as u32);
+        self.times.push(time);
+        self.values.push(values.map(|row| row as u32));
+        self.gaps.push(gap);
+    }
+}
+
+/// Adds the windows without data to `input`
+fn fill_batch(input: &RecordBatch, params: &GapFill) -> ArrowResult<RecordBatch> {
+    let schema = input.schema();
+    let series_idx = params
+        .series
+        .iter()
+        .map(|name| schema.index_of(name))
+        .collect::<ArrowResult<Vec<_>>>()?;
+    let time_idx = schema.index_of(&params.time)?;
+
+    let time_column = input.column(time_idx);
+    if time_column.data_type() != &DataType::Timestamp(TimeUnit::Nanosecond, None) {
+        return Err(ArrowError::InvalidArgumentError(format!(
+            "GapFill requires a nanosecond timestamp column, {} is {:?}",
+            params.time,
+            time_column.data_type()
+        )));
+    }
+    let times = as_primitive_array::<TimestampNanosecondType>(time_column);
+    let series: Vec<_> = series_idx
+        .iter()
+        .map(|idx| as_string_array(input.column(*idx)))
+        .collect();
+    let same_series = |a: usize, b: usize| {
+        series
+            .iter()
+            .all(|array| array.is_valid(a) == array.is_valid(b) && array.value(a) == array.value(b))
+    };
+
+    let mut rows = Rows::default();
+    let mut start = 0;
+    while start < input.num_rows() {
+        let mut end = start + 1;
+        while end < input.num_rows() && same_series(start, end) {
+            end += 1;
+        }
+        fill_series(start, end, times.values(), params, &mut rows)?;
+        start = end;
+    }
+
+    let times: ArrayRef = Arc::new(Int64Array::from(rows.times));
+    let series_rows = UInt32Array::from(rows.series);
+    let value_rows = UInt32Array::from(rows.values);
+    let gaps = BooleanArray::from(rows.gaps);
+
+    let columns = input
+        .columns()
+        .iter()
+        .enumerate()
+        .map(|(idx, column)| {
+            if idx == time_idx {
+                return cast(&times, column.data_type());
+            }
+            if series_idx.contains(&idx) {
+                return take(column.as_ref(), &series_rows, None);
+            }
+
+            let values = take(column.as_ref(), &value_rows, None)?;
+            match &params.fill {
+                FillStrategy::Value(value) if is_numeric(column.data_type()) => {
+                    let fill = cast(&value.to_array_of_size(gaps.len()), column.data_type())?;
+                    zip(&gaps, fill.as_ref(), values.as_ref())
+                }
+                _ => Ok(values),
+            }
+        })
+        .collect::<ArrowResult<Vec<_>>>()?;
+
+    RecordBatch::try_new(schema, columns)
+}
+
+/// Adds the rows of the input rows `start..end` of a series, and of its
+/// windows without data, to `rows`
+fn fill_series(
+    start: usize,
+    end: usize,
+    times: &[i64],
+    params: &GapFill,
+    rows: &mut Rows,
+) -> ArrowResult<()> {
+    let (first, last) = if params.descending {
+        (times[end - 1], times[start])
+    } else {
+        (times[start], times[end - 1])
+    };
+    let first = params.window(params.start.unwrap_or(first));
+    let last = params.window(params.end.map(|end| end.saturating_sub(1)).unwrap_or(last));
+
+    let windows = (i128::from(last) - i128::from(first)) / i128::from(params.interval) + 1;
+    if windows.max(0) as u128 + rows.times.len() as u128 > MAX_GAP_FILL_ROWS as u128 {
+        return Err(ArrowError::ComputeError(format!(
+            "fill() would return more than {} rows, narrow the time range or use a \
+             longer GROUP BY time() interval",
+            MAX_GAP_FILL_ROWS
+        )));
+    }
+
+    let before = |a: i64, b: i64| if params.descending { a > b } else { a < b };
+    let mut row = start;
+    let mut previous = None;
+    let push_row = |row: usize, previous: &mut Option<usize>, rows: &mut Rows| {
+        rows.push(start, times[row], Some(row), false);
+        *previous = Some(row);
+    };
+
+    for window in 0..windows.max(0) as i64 {
+        let time = if params.descending {
+            last - window * params.interval
+        } else {
+            first + window * params.interval
+        };
+
+        // rows that are not at the start of a window are kept as they are
+        while row < end && before(times[row], time) {
+            push_row(row, &mut previous, rows);
+            row += 1;
+        }
+
+        if row < end && times[row] == time {
+            push_row(row, &mut previous, rows);
+            row += 1;
+        } else {
+            let values = match params.fill {
+                FillStrategy::Previous => previous,
+                FillStrategy::Null | FillStrategy::Value(_) => None,
+            };
+            rows.push(start, time, values, true);
+        }
+    }
+    while row < end {
+        push_row(row, &mut previous, rows);
+        row += 1;
+    }
+
+    Ok(())
+}
+
+fn is_numeric(data_type: &DataType) -> bool {
+    matches!(
+        data_type,
+        DataType::Int64 | DataType::UInt64 | DataType::Float64
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{Float64Array, StringArray, TimestampNanosecondArray};
+    use arrow_util::assert_batches_eq;
+    use datafusion::{
+        physical_plan::{collect, memory::MemoryExec},
+        prelude::SessionContext,
+    };
+    use datafusion_util::test_collect;
+
+    fn input() -> RecordBatch {
+        let series = StringArray::from(vec!["a", "b", "b"]);
+        let time = TimestampNanosecondArray::from(vec![10, 0, 30]);
+        let value = Float64Array::from(vec![1.0, 2.0, 3.0]);
+        RecordBatch::try_from_iter(vec![
+            ("series", Arc::new(series) as ArrayRef),
+            ("time", Arc::new(time) as ArrayRef),
+            ("value", Arc::new(value) as ArrayRef),
+        ])
+        .unwrap()
+    }
+
+    fn params(fill: FillStrategy) -> GapFill {
+        GapFill {
+            series: vec!["series".to_string()],
+            time: "time".to_string(),
+            interval: 10,
+            offset: 0,
+            start: Some(0),
+            end: Some(40),
+            descending: false,
+            fill,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fill_null() {
+        let results = fill(vec![input()], params(FillStrategy::Null)).await;
+
+        let expected = vec![
+            "+--------+--------------------------------+-------+",
+            "| series | time                           | value |",
+            "+--------+--------------------------------+-------+",
+            "| a      | 1970-01-01T00:00:00Z           |       |",
+            "| a      | 1970-01-01T00:00:00.000000010Z | 1     |",
+            "| a      | 1970-01-01T00:00:00.000000020Z |       |",
+            "| a      | 1970-01-01T00:00:00.000000030Z |       |",
+            "| b      | 1970-01-01T00:00:00Z           | 2     |",
+            "| b      | 1970-01-01T00:00:00.000000010Z |       |",
+            "| b      | 1970-01-01T00:00:00.000000020Z |       |",
+            "| b      | 1970-01-01T00:00:00.000000030Z | 3     |",
+            "+--------+--------------------------------+-------+",
+        ];
+        assert_batches_eq!(&expected, &results);
+    }
+
+    #[tokio::test]
+    async fn test_fill_value() {
+        let fill_value = FillStrategy::Value(ScalarValue::Int64(Some(-1)));
+        let results = fill(vec![input()], params(fill_value)).await;
+
+        let expected = vec![
+            "+--------+--------------------------------+-------+",
+            "| series | time                           | value |",
+            "+--------+--------------------------------+-------+",
+            "| a      | 1970-01-01T00:00:00Z           | -1    |",
+            "| a      | 1970-01-01T00:00:00.000000010Z | 1     |",
+            "| a      | 1970-01-01T00:00:00.000000020Z | -1    |",
+            "| a      | 1970-01-01T00:00:00.000000030Z | -1    |",
+            "| b      | 1970-01-01T00:00:00Z           | 2     |",
+            "| b      | 1970-01-01T00:00:00.000000010Z | -1    |",
+            "| b      | 1970-01-01T00:00:00.000000020Z | -1    |",
+            "| b      | 1970-01-01T00:00:00.000000030Z | 3     |",
+            "+--------+--------------------------------+-------+",
+        ];
+        assert_batches_eq!(&expected, &results);
+    }
+
+    #[tokio::test]
+    async fn test_fill_previous_across_batches() {
+        let input = input();
+        let batches = vec![input.slice(0, 2), input.slice(2, 1)];
+        let results = fill(batches, params(FillStrategy::Previous)).await;
+
+        let expected = vec![
+            "+--------+--------------------------------+-------+",
+            "| series | time                           | value |",
+            "+--------+--------------------------------+-------+",
+            "| a      | 1970-01-01T00:00:00Z           |       |",
+            "| a      | 1970-01-01T00:00:00.000000010Z | 1     |",
+            "| a      | 1970-01-01T00:00:00.000000020Z | 1     |",
+            "| a      | 1970-01-01T00:00:00.000000030Z | 1     |",
+            "| b      | 1970-01-01T00:00:00Z           | 2     |",
+            "| b      | 1970-01-01T00:00:00.000000010Z | 2     |",
+            "| b      | 1970-01-01T00:00:00.000000020Z | 2     |",
+            "| b      | 1970-01-01T00:00:00.000000030Z | 3     |",
+            "+--------+--------------------------------+-------+",
+        ];
+        assert_batches_eq!(&expected, &results);
+    }
+
+    #[tokio::test]
+    async fn test_unbounded_range() {
+        let mut params = params(FillStrategy::Null);
+        params.start = None;
+        params.end = None;
+        let results = fill(vec![input()], params).await;
+
+        let expected = vec![
+            "+--------+--------------------------------+-------+",
+            "| series | time                           | value |",
+            "+--------+--------------------------------+-------+",
+            "| a      | 1970-01-01T00:00:00.000000010Z | 1     |",
+            "| b      | 1970-01-01T00:00:00Z           | 2     |",
+            "| b      | 1970-01-01T00:00:00.000000010Z |       |",
+            "| b      | 1970-01-01T00:00:00.000000020Z |       |",
+            "| b      | 1970-01-01T00:00:00.000000030Z | 3     |",
+            "+--------+--------------------------------+-------+",
+        ];
+        assert_batches_eq!(&expected, &results);
+    }
+
+    #[tokio::test]
+    async fn test_too_many_rows() {
+        let mut params = params(FillStrategy::Null);
+        params.interval = 1;
+        params.end = Some(MAX_GAP_FILL_ROWS as i64);
+
+        let schema = input().schema();
+        let input = Arc::new(MemoryExec::try_new(&[vec![input()]], schema, None).unwrap());
+        let exec = Arc::new(GapFillExec::new(input, params));
+        let ctx = Arc::new(TaskContext::from(&SessionContext::new()));
+        let err = collect(exec, ctx).await.unwrap_err();
+        assert!(
+            err.to_string().contains("fill() would return more than"),
+            "{}",
+            err
+        );
+    }
+
+    /// Run the input through a GapFill node and return results
+    async fn fill(input: Vec<RecordBatch>, params: GapFill) -> Vec<RecordBatch> {
+        test_helpers::maybe_start_logging();
+
+        // Setup in memory stream
+        let schema = input[0].schema();
+        let projection = None;
+        let input = Arc::new(MemoryExec::try_new(&[input], schema, projection).unwrap());
+
+        let exec = Arc::new(GapFillExec::new(input, params));
+        test_collect(exec as Arc<dyn ExecutionPlan>).await
+    }
+}
@@ -0,0 +1,1632 @@
+//! Query frontend for InfluxQL, the query language of InfluxDB 1.x
+
+pub mod ast;
+mod parser;
+
+use std::{
+    collections::{BTreeSet, HashMap},
+    sync::Arc,
+};
+
+use arrow::{
+    array::{ArrayRef, StringArray},
+    datatypes::{DataType, TimeUnit},
+    error::ArrowError,
+    record_batch::RecordBatch,
+};
+use datafusion::{
+    error::DataFusionError,
+    logical_expr::binary_expr,
+    logical_plan::{
+        lit, lit_timestamp_nano, Column, Expr, ExprSchemable, LogicalPlan, LogicalPlanBuilder,
+        Operator,
+    },
+    scalar::ScalarValue,
+};
+use datafusion_util::AsExpr;
+use iox_time::{SystemProvider, Time, TimeProvider};
+use observability_deps::tracing::debug;
+use predicate::Predicate;
+use query_functions::{
+    group_by::Aggregate,
+    regex_match_expr, regex_not_match_expr,
+    selectors::{selector_first, selector_last, SelectorOutput},
+};
+use schema::{InfluxColumnType, InfluxFieldType, Schema, TIME_COLUMN_NAME};
+use snafu::{OptionExt, ResultExt, Snafu};
+
+use self::ast::{
+    BinaryOp, Dimension, Fill, Literal, SelectStatement, ShowFieldKeysStatement,
+    ShowMeasurementsStatement, ShowTagKeysStatement, ShowTagValuesStatement, Source, Statement,
+};
+use crate::{
+    exec::{
+        gapfill::{FillStrategy, GapFill},
+        make_gap_fill, IOxSessionContext,
+    },
+    frontend::common::ScanPlanBuilder,
+    util::make_scan_plan,
+    QueryDatabase,
+};
+
+pub use self::parser::ParseError;
+pub use schema::MEASUREMENT_COLUMN_NAME;
+
+#[derive(Debug, Snafu)]
+pub enum Error {
+    #[snafu(display("{}", source))]
+    Parsing { source: ParseError },
+
+    #[snafu(display("InfluxQL {} is not supported", feature))]
+    NotSupported { feature: String },
+
+    #[snafu(display("Invalid InfluxQL statement: {}", message))]
+    InvalidStatement { message: String },
+
+    #[snafu(display("Invalid regex /{}/: {}", pattern, source))]
+    InvalidRegex {
+        pattern: String,
+        source: regex::Error,
+    },
+
+    #[snafu(display(
+        "InfluxQL planner got error fetching chunks for table '{}': {}",
+        table_name,
+        source
+    ))]
+    GettingChunks {
+        table_name: String,
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+
+    #[snafu(display("InfluxQL planner got error building plan: {}", source))]
+    BuildingPlan { source: DataFusionError },
+
+    #[snafu(display("InfluxQL planner got error creating scan: {}", source))]
+    CreatingScan { source: super::common::Error },
+
+    #[snafu(display("Error creating aggregate expression: {}", source))]
+    CreatingAggregates {
+        source: query_functions::group_by::Error,
+    },
+
+    #[snafu(display("InfluxQL planner got error creating results: {}", source))]
+    CreatingBatch { source: ArrowError },
+
+    #[snafu(display("Table was removed while planning query: {}", table_name))]
+    TableRemoved { table_name: String },
+}
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+impl From<super::common::Error> for Error {
+    fn from(source: super::common::Error) -> Self {
+        Self::CreatingScan { source }
+    }
+}
+
+impl From<DataFusionError> for Error {
+    fn from(source: DataFusionError) -> Self {
+        Self::BuildingPlan { source }
+    }
+}
+
+fn not_supported<T>(feature: impl Into<String>) -> Result<T> {
+    NotSupportedSnafu {
+        feature: feature.into(),
+    }
+    .fail()
+}
+
+fn invalid<T>(message: impl Into<String>) -> Result<T> {
+    InvalidStatementSnafu {
+        message: message.into(),
+    }
+    .fail()
+}
+
+/// The plan of an InfluxQL statement.
+///
+/// Rows of the results are sorted by [`MEASUREMENT_COLUMN_NAME`], the
+/// `group_by` tag columns and then time. Each run of rows with the same
+/// measurement and tag values is a series of the InfluxDB 1.x results.
+#[derive(Debug)]
+pub struct InfluxQLPlan {
+    pub plan: LogicalPlan,
+
+    /// The tags of the `GROUP BY` clause
+    pub group_by: Vec<String>,
+}
+
+/// Plans InfluxQL statements against a [`QueryDatabase`].
+///
+/// The results of a statement are a single table, with the name of the
+/// measurement of each row in the [`MEASUREMENT_COLUMN_NAME`] column:
+///
+/// * `SELECT`: the measurement, `time`, the `GROUP BY` tags and then the
+///   selected fields. Tags are Utf8 and fields of measurements that don't
+///   have them are NULL.
+/// * `SHOW MEASUREMENTS`: `name`, with `measurements` as the measurement
+/// * `SHOW TAG KEYS`: `tagKey`
+/// * `SHOW FIELD KEYS`: `fieldKey` and `fieldType`
+/// * `SHOW TAG VALUES`: `key` and `value`
+///
+/// `GROUP BY time()` returns the windows without data as `fill()` asks,
+/// NULL by default. The windows span the time range of the `WHERE` clause;
+/// without a lower or upper bound, a series starts or ends with its first or
+/// last window with data. `fill(linear)` is not supported.
+#[derive(Debug)]
+pub struct InfluxQLPlanner {
+    ctx: IOxSessionContext,
+    time_provider: Arc<dyn TimeProvider>,
+}
+
+impl InfluxQLPlanner {
+    /// Create a new instance of the InfluxQL planner
+    pub fn new(ctx: IOxSessionContext) -> Self {
+        Self {
+            ctx,
+            time_provider: Arc::new(SystemProvider::new()),
+        }
+    }
+
+    /// Use `time_provider` for `now()`
+    pub fn with_time_provider(mut self, time_provider: Arc<dyn TimeProvider>) -> Self {
+        self.time_provider = time_provider;
+        self
+    }
+
+    /// Parses the `;` separated statements of `query`
+    pub fn parse(query: &str) -> Result<Vec<Statement>> {
+        parser::parse_statements(query).context(ParsingSnafu)
+    }
+
+    /// Returns a plan of the results of `statement`
+    pub async fn plan(
+        &self,
+        database: &dyn QueryDatabase,
+        statement: &Statement,
+    ) -> Result<InfluxQLPlan> {
+        debug!(?statement, "planning InfluxQL statement");
+
+        match statement {
+            Statement::Select(select) => self.select(database, select).await,
+            Statement::ShowMeasurements(show) => show_measurements(database, show),
+            Statement::ShowTagKeys(show) => show_tag_keys(database, show),
+            Statement::ShowFieldKeys(show) => show_field_keys(database, show),
+            Statement::ShowTagValues(show) => self.show_tag_values(database, show).await,
+        }
+    }
+
+    async fn select(
+        &self,
+        database: &dyn QueryDatabase,
+        select: &SelectStatement,
+    ) -> Result<InfluxQLPlan> {
+        let ctx = self.ctx.child_ctx("influxql select planning");
+
+        let tables = tables(database, &select.sources)?;
+        let (range, condition) = split_condition(select.condition.as_ref(), self.now())?;
+
+        let mut window = None;
+        let mut group_by = BTreeSet::new();
+        let mut group_by_wildcard = false;
+        for dimension in &select.dimensions {
+            match dimension {
+                Dimension::Time { interval, offset } => {
+                    if window.replace((*interval, *offset)).is_some() {
+                        return invalid("multiple GROUP BY time() dimensions");
+                    }
+                }
+                Dimension::Tag(tag) => {
+                    group_by.insert(tag.clone());
+                }
+                Dimension::Wildcard => group_by_wildcard = true,
+            }
+        }
+        if group_by_wildcard {
+            for (_, schema) in &tables {
+                group_by.extend(schema.tags_iter().map(|field| field.name().clone()));
+            }
+        }
+        let group_by: Vec<_> = group_by.into_iter().collect();
+
+        let is_aggregate = select.fields.iter().any(|field| field.expr.has_call());
+        if window.is_some() && !is_aggregate {
+            return invalid("GROUP BY time() requires an aggregate function");
+        }
+        // Like InfluxDB 1.x, windows without data are NULL unless the query
+        // asks otherwise
+        let fill = match &select.fill {
+            Some(Fill::Null) | None => Some(FillStrategy::Null),
+            Some(Fill::Number(v)) => Some(FillStrategy::Value(ScalarValue::Float64(Some(*v)))),
+            Some(Fill::Previous) => Some(FillStrategy::Previous),
+            Some(Fill::Linear) => return not_supported("fill(linear)"),
+            Some(Fill::None) => None,
+        };
+
+        let predicate = range.predicate();
+        let mut measurements = vec![];
+        for (table_name, schema) in tables {
+            let chunks = database
+                .chunks(&table_name, &predicate, ctx.child_ctx("table chunks"))
+                .await
+                .context(GettingChunksSnafu {
+                    table_name: &table_name,
+                })?;
+            if chunks.is_empty() {
+                continue;
+            }
+
+            let scan = ScanPlanBuilder::new(
+                Arc::clone(&schema),
+                ctx.child_ctx("scan_and_filter planning"),
+            )
+            .with_chunks(chunks)
+            .with_predicate(&predicate)
+            .build()?;
+
+            let mut plan_builder = scan.plan_builder;
+            if let Some(condition) = &condition {
+                plan_builder = plan_builder.filter(condition_expr(condition, &schema)?)?;
+            }
+
+            let measurement = if is_aggregate {
+                aggregate_measurement(
+                    table_name,
+                    &schema,
+                    plan_builder,
+                    select,
+                    &group_by,
+                    window,
+                    range.start.unwrap_or(0),
+                )?
+            } else {
+                raw_measurement(table_name, &schema, plan_builder, select, &group_by)?
+            };
+
+            if let Some(measurement) = measurement {
+                measurements.push(measurement);
+            }
+        }
+
+        let mut plan = union_measurements(measurements, select, &group_by)?;
+        if let (Some((interval, offset)), Some(fill)) = (window, fill) {
+            let series = std::iter::once(MEASUREMENT_COLUMN_NAME.to_string())
+                .chain(group_by.iter().cloned())
+                .collect();
+            plan = make_gap_fill(
+                plan,
+                GapFill {
+                    series,
+                    time: TIME_COLUMN_NAME.to_string(),
+                    interval,
+                    offset,
+                    start: range.start,
+                    end: range.end,
+                    descending: select.order_desc,
+                    fill,
+                },
+            );
+        }
+        Ok(InfluxQLPlan { plan, group_by })
+    }
+
+    async fn show_tag_values(
+        &self,
+        database: &dyn QueryDatabase,
+        show: &ShowTagValuesStatement,
+    ) -> Result<InfluxQLPlan> {
+        let ctx = self.ctx.child_ctx("influxql show tag values planning");
+
+        let (range, condition) = split_condition(show.condition.as_ref(), self.now())?;
+        let predicate = range.predicate();
+
+        let mut plans = vec![];
+        for (table_name, schema) in tables(database, &show.sources)? {
+            let mut keys = vec![];
+            for field in schema.tags_iter() {
+                if show
+                    .with_key
+                    .matches(field.name())
+                    .map_err(|source| Error::InvalidRegex {
+                        pattern: format!("{:?}", show.with_key),
+                        source,
+                    })?
+                {
+                    keys.push(field.name().clone());
+                }
+            }
+            if keys.is_empty() {
+                continue;
+            }
+
+            let chunks = database
+                .chunks(&table_name, &predicate, ctx.child_ctx("table chunks"))
+                .await
+                .context(GettingChunksSnafu {
+                    table_name: &table_name,
+                })?;
+            if chunks.is_empty() {
+                continue;
+            }
+
+            let scan = ScanPlanBuilder::new(
+                Arc::clone(&schema),
+                ctx.child_ctx("scan_and_filter planning"),
+            )
+            .with_chunks(chunks)
+            .with_predicate(&predicate)
+            .build()?;
+
+            let mut plan_builder = scan.plan_builder;
+            if let Some(condition) = &condition {
+                plan_builder = plan_builder.filter(condition_expr(condition, &schema)?)?;
+            }
+            let input = plan_builder.build()?;
+
+            // Plan for each key looks like:
+            //
+            //   Projection(measurement, key, value)
+            //     Aggregate(GROUP BY value)
+            //       Filter(key IS NOT NULL)
+            //         Filter(condition)
+            //           Scan
+            for key in keys {
+                let plan = LogicalPlanBuilder::from(input.clone())
+                    .filter(column(&key).is_not_null())?
+                    .aggregate(vec![tag_expr(&key)], Vec::<Expr>::new())?
+                    .project(vec![
+                        lit(table_name.as_str()).alias(MEASUREMENT_COLUMN_NAME),
+                        lit(key.as_str()).alias("key"),
+                        column(&key).alias("value"),
+                    ])?
+                    .build()?;
+                plans.push(plan);
+            }
+        }
+
+        let plan = match union(plans)? {
+            Some(plan_builder) => plan_builder
+                .sort(vec![
+                    MEASUREMENT_COLUMN_NAME.as_sort_expr(),
+                    "key".as_sort_expr(),
+                    "value".as_sort_expr(),
+                ])?
+                .build()?,
+            None => empty_plan(&[MEASUREMENT_COLUMN_NAME, "key", "value"])?,
+        };
+
+        Ok(InfluxQLPlan {
+            plan,
+            group_by: vec![],
+        })
+    }
+
+    fn now(&self) -> i64 {
+        self.time_provider.now().timestamp_nanos()
+    }
+}
+
+/// The names and schemas of the tables selected by `sources`, all tables if
+/// there are no sources, sorted by name
+fn tables(database: &dyn QueryDatabase, sources: &[Source]) -> Result<Vec<(String, Arc<Schema>)>> {
+    let mut table_names = database.table_names();
+    table_names.sort();
+
+    let mut tables = vec![];
+    for table_name in table_names {
+        if !sources.is_empty() && !matches_any(sources, &table_name)? {
+            continue;
+        }
+
+        let schema = database
+            .table_schema(&table_name)
+            .context(TableRemovedSnafu {
+                table_name: &table_name,
+            })?;
+        tables.push((table_name, schema));
+    }
+    Ok(tables)
+}
+
+fn matches_any(sources: &[Source], name: &str) -> Result<bool> {
+    for source in sources {
+        let matches = source.matches(name).map_err(|source_error| {
+            let pattern = match source {
+                Source::Name(name) | Source::Regex(name) => name.clone(),
+            };
+            Error::InvalidRegex {
+                pattern,
+                source: source_error,
+            }
+        })?;
+        if matches {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+/// Bounds on time of a `WHERE` clause
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+struct TimeRange {
+    /// Inclusive
+    start: Option<i64>,
+    /// Exclusive
+    end: Option<i64>,
+}
+
+impl TimeRange {
+    fn predicate(&self) -> Predicate {
+        match (self.start, self.end) {
+            (None, None) => Predicate::new(),
+            (start, end) => {
+                Predicate::new().with_range(start.unwrap_or(i64::MIN), end.unwrap_or(i64::MAX))
+            }
+        }
+    }
+
+    fn restrict(&mut self, op: BinaryOp, time: i64) -> Result<()> {
+        let (start, end) = match op {
+            BinaryOp::Gt => (time.checked_add(1), None),
+            BinaryOp::GtEq => (Some(time), None),
+            BinaryOp::Lt => (None, Some(time)),
+            BinaryOp::LtEq => (None, time.checked_add(1)),
+            BinaryOp::Eq => (Some(time), time.checked_add(1)),
+            _ => return not_supported(format!("{:?} comparisons of time", op)),
+        };
+
+        if let Some(start) = start {
+            self.start = Some(self.start.map_or(start, |s| s.max(start)));
+        }
+        if let Some(end) = end {
+            self.end = Some(self.end.map_or(end, |e| e.min(end)));
+        }
+        Ok(())
+    }
+}
+
+/// Splits the time range out of the top level `AND`s of `condition`,
+/// returning the range and the rest of the condition
+fn split_condition(
+    condition: Option<&ast::Expr>,
+    now: i64,
+) -> Result<(TimeRange, Option<ast::Expr>)> {
+    let mut conjuncts = vec![];
+    if let Some(condition) = condition {
+        flatten_and(condition, &mut conjuncts);
+    }
+
+    let mut range = TimeRange::default();
+    let mut rest: Option<ast::Expr> = None;
+    for expr in conjuncts {
+        if let ast::Expr::Binary { lhs, op, rhs } = expr {
+            match (lhs.as_ref(), rhs.as_ref()) {
+                (ast::Expr::VarRef(name), other) if is_time(name) => {
+                    range.restrict(*op, time_value(other, now)?)?;
+                    continue;
+                }
+                (other, ast::Expr::VarRef(name)) if is_time(name) => {
+                    range.restrict(flip(*op), time_value(other, now)?)?;
+                    continue;
+                }
+                _ => {}
+            }
+        }
+
+        if references_time(expr) {
+            return not_supported("ORing time conditions");
+        }
+        rest = Some(match rest {
+            Some(rest) => ast::Expr::binary(rest, BinaryOp::And, expr.clone()),
+            None => expr.clone(),
+        });
+    }
+
+    Ok((range, rest))
+}
+
+fn flatten_and<'a>(expr: &'a ast::Expr, conjuncts: &mut Vec<&'a ast::Expr>) {
+    match expr {
+        ast::Expr::Binary {
+            lhs,
+            op: BinaryOp::And,
+            rhs,
+        } => {
+            flatten_and(lhs, conjuncts);
+            flatten_and(rhs, conjuncts);
+        }
+        expr => conjuncts.push(expr),
+    }
+}
+
+fn is_time(name: &str) -> bool {
+    name.eq_ignore_ascii_case(TIME_COLUMN_NAME)
+}
+
+fn references_time(expr: &ast::Expr) -> bool {
+    match expr {
+        ast::Expr::VarRef(name) => is_time(name),
+        ast::Expr::Call { args, .. } => args.iter().any(references_time),
+        ast::Expr::Binary { lhs, rhs, .. } => references_time(lhs) || references_time(rhs),
+        ast::Expr::Wildcard | ast::Expr::Literal(_) => false,
+    }
+}
+
+/// The operator of `a <op> b` written as `b <op> a`
+fn flip(op: BinaryOp) -> BinaryOp {
+    match op {
+        BinaryOp::Lt => BinaryOp::Gt,
+        BinaryOp::LtEq => BinaryOp::GtEq,
+        BinaryOp::Gt => BinaryOp::Lt,
+        BinaryOp::GtEq => BinaryOp::LtEq,
+        op => op,
+    }
+}
+
+/// The timestamp in nanoseconds of a time expression such as `now() - 1h`,
+/// `'2022-01-01T00:00:00Z'` or `1640995200000000000`
+fn time_value(expr: &ast::Expr, now: i64) -> Result<i64> {
+    match expr {
+        ast::Expr::Call { name, args } if name == "now" && args.is_empty() => Ok(now),
+        ast::Expr::Literal(Literal::Integer(v)) => Ok(*v),
+        ast::Expr::Literal(Literal::String(s)) => Time::from_rfc3339(s)
+            .map(|time| time.timestamp_nanos())
+            .or_else(|_| invalid(format!("invalid RFC3339 timestamp '{}'", s))),
+        ast::Expr::Binary { lhs, op, rhs } if matches!(op, BinaryOp::Add | BinaryOp::Sub) => {
+            let lhs = time_value(lhs, now)?;
+            let rhs = match rhs.as_ref() {
+                ast::Expr::Literal(Literal::Duration(v) | Literal::Integer(v)) => *v,
+                _ => return invalid("time expressions can only add or subtract durations"),
+            };
+            let value = match op {
+                BinaryOp::Add => lhs.checked_add(rhs),
+                _ => lhs.checked_sub(rhs),
+            };
+            value.map_or_else(|| invalid("time out of range"), Ok)
+        }
+        _ => invalid(format!("invalid time expression {:?}", expr)),
+    }
+}
+
+/// A reference to the column `name`, which may contain characters that
+/// [`col`](datafusion::logical_plan::col) would parse
+fn column(name: &str) -> Expr {
+    Expr::Column(Column::from_name(name))
+}
+
+/// The value of tag `name` as Utf8, aliased as the tag
+fn tag_expr(name: &str) -> Expr {
+    Expr::Cast {
+        expr: Box::new(column(name)),
+        data_type: DataType::Utf8,
+    }
+    .alias(name)
+}
+
+fn null_of(data_type: &DataType) -> Result<Expr> {
+    Ok(lit(ScalarValue::try_from(data_type)?))
+}
+
+fn literal_expr(literal: &Literal) -> Result<Expr> {
+    Ok(match literal {
+        Literal::Integer(v) | Literal::Duration(v) => lit(*v),
+        Literal::Float(v) => lit(*v),
+        Literal::String(v) => lit(v.as_str()),
+        Literal::Boolean(v) => lit(*v),
+        Literal::Regex(_) => return invalid("regexes can only be matched with =~ and !~"),
+    })
+}
+
+fn operator(op: BinaryOp) -> Operator {
+    match op {
+        BinaryOp::Add => Operator::Plus,
+        BinaryOp::Sub => Operator::Minus,
+        BinaryOp::Mul => Operator::Multiply,
+        BinaryOp::Div => Operator::Divide,
+        BinaryOp::Eq => Operator::Eq,
+        BinaryOp::NotEq => Operator::NotEq,
+        BinaryOp::Lt => Operator::Lt,
+        BinaryOp::LtEq => Operator::LtEq,
+        BinaryOp::Gt => Operator::Gt,
+        BinaryOp::GtEq => Operator::GtEq,
+        BinaryOp::And => Operator::And,
+        BinaryOp::Or => Operator::Or,
+        BinaryOp::RegexMatch | BinaryOp::RegexNotMatch => {
+            unreachable!("regex matches are planned as functions")
+        }
+    }
+}
+
+/// The DataFusion expression of the `WHERE` condition `expr` of a table.
+/// Comparisons with columns the table doesn't have are false, or true for
+/// `!=` and `!~`.
+fn condition_expr(expr: &ast::Expr, schema: &Schema) -> Result<Expr> {
+    let (lhs, op, rhs) = match expr {
+        ast::Expr::Binary { lhs, op, rhs } => (lhs.as_ref(), *op, rhs.as_ref()),
+        ast::Expr::VarRef(name) => {
+            return Ok(match schema.find_index_of(name) {
+                Some(_) => column(name),
+                None => lit(false),
+            })
+        }
+        ast::Expr::Literal(Literal::Boolean(v)) => return Ok(lit(*v)),
+        _ => return invalid(format!("invalid condition {:?}", expr)),
+    };
+
+    match op {
+        BinaryOp::And => Ok(condition_expr(lhs, schema)?.and(condition_expr(rhs, schema)?)),
+        BinaryOp::Or => Ok(condition_expr(lhs, schema)?.or(condition_expr(rhs, schema)?)),
+        BinaryOp::RegexMatch | BinaryOp::RegexNotMatch => {
+            let pattern = match rhs {
+                ast::Expr::Literal(Literal::Regex(pattern)) => pattern.clone(),
+                _ => return invalid("=~ and !~ must be followed by a regex"),
+            };
+            Ok(match operand_expr(lhs, schema)? {
+                Some(expr) if op == BinaryOp::RegexMatch => regex_match_expr(expr, pattern),
+                Some(expr) => regex_not_match_expr(expr, pattern),
+                None => lit(op == BinaryOp::RegexNotMatch),
+            })
+        }
+        op if op.is_comparison() => {
+            match (operand_expr(lhs, schema)?, operand_expr(rhs, schema)?) {
+                (Some(lhs), Some(rhs)) => Ok(binary_expr(lhs, operator(op), rhs)),
+                _ => Ok(lit(op == BinaryOp::NotEq)),
+            }
+        }
+        _ => invalid(format!("invalid condition {:?}", expr)),
+    }
+}
+
+/// An operand of a comparison, `None` if it references a column the table
+/// doesn't have
+fn operand_expr(expr: &ast::Expr, schema: &Schema) -> Result<Option<Expr>> {
+    Ok(match expr {
+        ast::Expr::VarRef(name) => schema.find_index_of(name).map(|_| column(name)),
+        ast::Expr::Literal(literal) => Some(literal_expr(literal)?),
+        ast::Expr::Binary { lhs, op, rhs }
+            if matches!(
+                op,
+                BinaryOp::Add | BinaryOp::Sub | BinaryOp::Mul | BinaryOp::Div
+            ) =>
+        {
+            match (operand_expr(lhs, schema)?, operand_expr(rhs, schema)?) {
+                (Some(lhs), Some(rhs)) => Some(binary_expr(lhs, operator(*op), rhs)),
+                _ => None,
+            }
+        }
+        ast::Expr::Call { name, .. } => {
+            return not_supported(format!("function {}() in WHERE", name))
+        }
+        _ => return invalid(format!("invalid operand {:?}", expr)),
+    })
+}
+
+/// The name of a select field without an alias: the names of its calls and
+/// columns, joined by `_`
+fn field_name(expr: &ast::Expr) -> String {
+    fn names(expr: &ast::Expr, out: &mut Vec<String>) {
+        match expr {
+            ast::Expr::VarRef(name) => out.push(name.clone()),
+            ast::Expr::Call { name, .. } => out.push(name.clone()),
+            ast::Expr::Binary { lhs, rhs, .. } => {
+                names(lhs, out);
+                names(rhs, out);
+            }
+            ast::Expr::Wildcard | ast::Expr::Literal(_) => {}
+        }
+    }
+
+    let mut out = vec![];
+    names(expr, &mut out);
+    out.join("_")
+}
+
+/// The plan of one measurement of a `SELECT`, and the DataFusion
+/// expressions of the select fields the measurement has
+struct MeasurementPlan {
+    plan_builder: LogicalPlanBuilder,
+    /// Expression of the time column
+    time: Expr,
+    /// `(select field index, output name, expression)`
+    fields: Vec<(usize, String, Expr)>,
+}
+
+/// Plans a `SELECT` without aggregates of one measurement, `None` if the
+/// measurement has none of the selected fields
+fn raw_measurement(
+    table_name: String,
+    schema: &Schema,
+    plan_builder: LogicalPlanBuilder,
+    select: &SelectStatement,
+    group_by: &[String],
+) -> Result<Option<(String, MeasurementPlan)>> {
+    let mut fields = vec![];
+    let mut field_columns = BTreeSet::new();
+
+    for (idx, field) in select.fields.iter().enumerate() {
+        match &field.expr {
+            ast::Expr::Wildcard => {
+                for (column_type, arrow_field) in schema.iter() {
+                    let name = arrow_field.name();
+                    match column_type {
+                        Some(InfluxColumnType::Tag) if !group_by.contains(name) => {
+                            fields.push((idx, name.clone(), tag_expr(name)));
+                        }
+                        Some(InfluxColumnType::Field(_)) => {
+                            field_columns.insert(name.clone());
+                            fields.push((idx, name.clone(), column(name)));
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            ast::Expr::VarRef(name) if is_time(name) => {}
+            expr if expr.has_wildcard() => return not_supported("wildcards in expressions"),
+            expr => {
+                if let Some(df_expr) = raw_expr(expr, schema, &mut field_columns)? {
+                    let name = field.alias.clone().unwrap_or_else(|| field_name(expr));
+                    fields.push((idx, name, df_expr));
+                }
+            }
+        }
+    }
+
+    // Like InfluxDB 1.x, only return rows with a value for at least one of
+    // the selected fields
+    let has_field = match field_columns
+        .iter()
+        .map(|name| column(name).is_not_null())
+        .reduce(Expr::or)
+    {
+        Some(has_field) => has_field,
+        None => return Ok(None),
+    };
+
+    let plan_builder = plan_builder.filter(has_field)?.project(
+        std::iter::once(column(TIME_COLUMN_NAME).alias(TIME_COLUMN_NAME))
+            .chain(group_by.iter().map(|tag| {
+                if schema.find_index_of(tag).is_some() {
+                    tag_expr(tag)
+                } else {
+                    lit(ScalarValue::Utf8(None)).alias(tag)
+                }
+            }))
+            .chain(
+                fields
+                    .iter()
+                    .enumerate()
+                    .map(|(i, (_, _, expr))| expr.clone().alias(&format!("__field{}", i))),
+            ),
+    )?;
+
+    let fields = fields
+        .into_iter()
+        .enumerate()
+        .map(|(i, (idx, name, _))| (idx, name, column(&format!("__field{}", i))))
+        .collect();
+
+    Ok(Some((
+        table_name,
+        MeasurementPlan {
+            plan_builder,
+            time: column(TIME_COLUMN_NAME),
+            fields,
+        },
+    )))
+}
+
+/// The expression of a select field without aggregates, `None` if it
+/// references a column the table doesn't have
+fn raw_expr(
+    expr: &ast::Expr,
+    schema: &Schema,
+    field_columns: &mut BTreeSet<String>,
+) -> Result<Option<Expr>> {
+    Ok(match expr {
+        ast::Expr::VarRef(name) => match schema.find_index_of(name).map(|idx| schema.field(idx)) {
+            Some((Some(InfluxColumnType::Tag), _)) => Some(tag_expr(name)),
+            Some((Some(InfluxColumnType::Field(_)), _)) => {
+                field_columns.insert(name.clone());
+                Some(column(name))
+            }
+            _ => None,
+        },
+        ast::Expr::Literal(literal) => Some(literal_expr(literal)?),
+        ast::Expr::Binary { lhs, op, rhs }
+            if matches!(
+                op,
+                BinaryOp::Add | BinaryOp::Sub | BinaryOp::Mul | BinaryOp::Div
+            ) =>
+        {
+            match (
+                raw_expr(lhs, schema, field_columns)?,
+                raw_expr(rhs, schema, field_columns)?,
+            ) {
+                (Some(lhs), Some(rhs)) => Some(binary_expr(lhs, operator(*op), rhs)),
+                _ => None,
+            }
+        }
+        _ => return invalid(format!("invalid select field {:?}", expr)),
+    })
+}
+
+/// Plans a `SELECT` with aggregates of one measurement, `None` if the
+/// measurement has none of the aggregated fields
+fn aggregate_measurement(
+    table_name: String,
+    schema: &Schema,
+    plan_builder: LogicalPlanBuilder,
+    select: &SelectStatement,
+    group_by: &[String],
+    window: Option<(i64, i64)>,
+    range_start: i64,
+) -> Result<Option<(String, MeasurementPlan)>> {
+    let mut aggregates = vec![];
+    let mut fields = vec![];
+
+    for (idx, field) in select.fields.iter().enumerate() {
+        match &field.expr {
+            ast::Expr::Call { name, args } if args == &[ast::Expr::Wildcard] => {
+                // like InfluxDB 1.x, skip fields the function doesn't support
+                for (column_type, arrow_field) in schema.iter() {
+                    match column_type {
+                        Some(InfluxColumnType::Field(field_type)) if supports(name, field_type) => {
+                        }
+                        _ => continue,
+                    }
+
+                    let arg = ast::Expr::VarRef(arrow_field.name().clone());
+                    if let Some(expr) = aggregate(name, &arg, schema, &mut aggregates)? {
+                        fields.push((idx, format!("{}_{}", name, arrow_field.name()), expr));
+                    }
+                }
+            }
+            expr if expr.has_wildcard() => return not_supported("wildcards in expressions"),
+            expr => {
+                if let Some(df_expr) = aggregate_expr(expr, schema, &mut aggregates)? {
+                    let name = field.alias.clone().unwrap_or_else(|| field_name(expr));
+                    fields.push((idx, name, df_expr));
+                }
+            }
+        }
+    }
+
+    if aggregates.is_empty() {
+        return Ok(None);
+    }
+
+    let mut group_exprs: Vec<_> = group_by
+        .iter()
+        .map(|tag| {
+            if schema.find_index_of(tag).is_some() {
+                tag_expr(tag)
+            } else {
+                lit(ScalarValue::Utf8(None)).alias(tag)
+            }
+        })
+        .collect();
+
+    let time = match window {
+        Some((interval, offset)) => {
+            group_exprs.push(window_start(interval, offset).alias(TIME_COLUMN_NAME));
+            column(TIME_COLUMN_NAME)
+        }
+        // Like InfluxDB 1.x, aggregates over the whole range are at its start
+        None => lit_timestamp_nano(range_start),
+    };
+
+    let plan_builder = plan_builder.aggregate(group_exprs, aggregates)?;
+
+    Ok(Some((
+        table_name,
+        MeasurementPlan {
+            plan_builder,
+            time,
+            fields,
+        },
+    )))
+}
+
+/// The start of the `GROUP BY time(interval, offset)` window of each row:
+///
+/// ```text
+/// time - ((time - offset) % interval + interval) % interval
+/// ```
+fn window_start(interval: i64, offset: i64) -> Expr {
+    let time = Expr::Cast {
+        expr: Box::new(column(TIME_COLUMN_NAME)),
+        data_type: DataType::Int64,
+    };
+    let offset = lit(offset.rem_euclid(interval));
+    let interval = lit(interval);
+
+    let remainder = binary_expr(
+        binary_expr(
+            binary_expr(
+                binary_expr(time.clone(), Operator::Minus, offset),
+                Operator::Modulo,
+                interval.clone(),
+            ),
+            Operator::Plus,
+            interval.clone(),
+        ),
+        Operator::Modulo,
+        interval,
+    );
+
+    Expr::Cast {
+        expr: Box::new(binary_expr(time, Operator::Minus, remainder)),
+        data_type: DataType::Timestamp(TimeUnit::Nanosecond, None),
+    }
+}
+
+/// The expression of a select field with aggregates, whose aggregates are
+/// added to `aggregates`. `None` if it aggregates a field the table doesn't
+/// have.
+fn aggregate_expr(
+    expr: &ast::Expr,
+    schema: &Schema,
+    aggregates: &mut Vec<Expr>,
+) -> Result<Option<Expr>> {
+    Ok(match expr {
+        ast::Expr::Call { name, args } => match args.as_slice() {
+            [arg] => aggregate(name, arg, schema, aggregates)?,
+            _ => return invalid(format!("{}() takes exactly one argument", name)),
+        },
+        ast::Expr::Literal(literal) => Some(literal_expr(literal)?),
+        ast::Expr::Binary { lhs, op, rhs }
+            if matches!(
+                op,
+                BinaryOp::Add | BinaryOp::Sub | BinaryOp::Mul | BinaryOp::Div
+            ) =>
+        {
+            match (
+                aggregate_expr(lhs, schema, aggregates)?,
+                aggregate_expr(rhs, schema, aggregates)?,
+            ) {
+                (Some(lhs), Some(rhs)) => Some(binary_expr(lhs, operator(*op), rhs)),
+                _ => None,
+            }
+        }
+        ast::Expr::VarRef(_) => return not_supported("mixing aggregate and non-aggregate fields"),
+        _ => return invalid(format!("invalid select field {:?}", expr)),
+    })
+}
+
+/// Adds the aggregates of `function(arg)` to `aggregates`, returning the
+/// expression of its value
+fn aggregate(
+    function: &str,
+    arg: &ast::Expr,
+    schema: &Schema,
+    aggregates: &mut Vec<Expr>,
+) -> Result<Option<Expr>> {
+    let field_name = match arg {
+        ast::Expr::VarRef(name) => name,
+        _ => return not_supported(format!("{}() of expressions", function)),
+    };
+    let field_type = match schema
+        .find_index_of(field_name)
+        .map(|idx| schema.field(idx))
+    {
+        Some((Some(InfluxColumnType::Field(field_type)), _)) => field_type,
+        Some(_) => {
+            return invalid(format!(
+                "{}() can only aggregate fields, {} is not a field",
+                function, field_name
+            ))
+        }
+        None => return Ok(None),
+    };
+    if !supports(function, field_type) {
+        return match function {
+            "first" | "last" => not_supported(format!("{}() of unsigned fields", function)),
+            _ => invalid(format!(
+                "{}() requires a numeric field, {} is {:?}",
+                function, field_name, field_type
+            )),
+        };
+    }
+    let field = column(field_name);
+
+    let mut add = |expr: Expr| {
+        let name = format!("__agg{}", aggregates.len());
+        aggregates.push(expr.alias(&name));
+        column(&name)
+    };
+
+    let expr = match function {
+        "count" => add(to_datafusion_expr(Aggregate::Count, field)?),
+        "sum" => add(to_datafusion_expr(Aggregate::Sum, field)?),
+        "mean" => add(to_datafusion_expr(Aggregate::Mean, field)?),
+        "min" => add(to_datafusion_expr(Aggregate::Min, field)?),
+        "max" => add(to_datafusion_expr(Aggregate::Max, field)?),
+        "spread" => {
+            let max = add(to_datafusion_expr(Aggregate::Max, field.clone())?);
+            let min = add(to_datafusion_expr(Aggregate::Min, field)?);
+            binary_expr(max, Operator::Minus, min)
+        }
+        "first" | "last" => {
+            let data_type = match field_type {
+                InfluxFieldType::Float => DataType::Float64,
+                InfluxFieldType::Integer => DataType::Int64,
+                InfluxFieldType::String => DataType::Utf8,
+                InfluxFieldType::Boolean => DataType::Boolean,
+                InfluxFieldType::UInteger => unreachable!("checked by supports()"),
+            };
+            let selector = if function == "first" {
+                selector_first(&data_type, SelectorOutput::Value)
+            } else {
+                selector_last(&data_type, SelectorOutput::Value)
+            };
+            add(selector.call(vec![field, column(TIME_COLUMN_NAME)]))
+        }
+        _ => return not_supported(format!("function {}()", function)),
+    };
+
+    Ok(Some(expr))
+}
+
+/// Returns true if `function` can aggregate fields of `field_type`
+fn supports(function: &str, field_type: InfluxFieldType) -> bool {
+    match function {
+        "sum" | "mean" | "spread" => matches!(
+            field_type,
+            InfluxFieldType::Float | InfluxFieldType::Integer | InfluxFieldType::UInteger
+        ),
+        // the selectors have no unsigned implementation
+        "first" | "last" => field_type != InfluxFieldType::UInteger,
+        _ => true,
+    }
+}
+
+fn to_datafusion_expr(aggregate: Aggregate, input: Expr) -> Result<Expr> {
+    aggregate
+        .to_datafusion_expr(input)
+        .context(CreatingAggregatesSnafu)
+}
+
+/// Unions the plans of the measurements of a `SELECT`, giving every
+/// measurement the same columns
+fn union_measurements(
+    measurements: Vec<(String, MeasurementPlan)>,
+    select: &SelectStatement,
+    group_by: &[String],
+) -> Result<LogicalPlan> {
+    // Output columns for each select field, in order, with the type of the
+    // first measurement that has them
+    let mut columns: Vec<(usize, String, DataType)> = vec![];
+    for idx in 0..select.fields.len() {
+        let mut names = BTreeSet::new();
+        let mut types = HashMap::new();
+        for (_, measurement) in &measurements {
+            for (field_idx, name, expr) in &measurement.fields {
+                if *field_idx == idx && names.insert(name.clone()) {
+                    let data_type = expr.get_type(measurement.plan_builder.schema())?;
+                    types.insert(name.clone(), data_type);
+                }
+            }
+        }
+        for name in names {
+            let data_type = types.remove(&name).expect("type of each name");
+            columns.push((idx, name, data_type));
+        }
+    }
+
+    // Duplicate names get a suffix like in InfluxDB 1.x
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    let output_names: Vec<_> = columns
+        .iter()
+        .map(|(_, name, _)| {
+            let count = counts.entry(name.clone()).or_default();
+            let output_name = match *count {
+                0 => name.clone(),
+                n => format!("{}_{}", name, n),
+            };
+            *count += 1;
+            output_name
+        })
+        .collect();
+
+    let mut plans = vec![];
+    for (table_name, measurement) in measurements {
+        let schema = measurement.plan_builder.schema().clone();
+
+        let mut exprs = vec![
+            lit(table_name.as_str()).alias(MEASUREMENT_COLUMN_NAME),
+            measurement.time.alias(TIME_COLUMN_NAME),
+        ];
+        exprs.extend(group_by.iter().map(|tag| column(tag).alias(tag)));
+
+        for ((idx, name, data_type), output_name) in columns.iter().zip(&output_names) {
+            let expr = measurement
+                .fields
+                .iter()
+                .find(|(field_idx, field_name, _)| field_idx == idx && field_name == name)
+                .map(|(_, _, expr)| expr.clone());
+
+            let expr = match expr {
+                Some(expr) => expr.cast_to(data_type, &schema)?,
+                None => null_of(data_type)?,
+            };
+            exprs.push(expr.alias(output_name));
+        }
+
+        plans.push(measurement.plan_builder.project(exprs)?.build()?);
+    }
+
+    let plan_builder = match union(plans)? {
+        Some(plan_builder) => plan_builder,
+        None => {
+            let mut names = vec![MEASUREMENT_COLUMN_NAME, TIME_COLUMN_NAME];
+            names.extend(group_by.iter().map(|tag| tag.as_str()));
+            return empty_plan(&names);
+        }
+    };
+
+    let time_sort = Expr::Sort {
+        expr: Box::new(column(TIME_COLUMN_NAME)),
+        asc: !select.order_desc,
+        nulls_first: true,
+    };
+    let sort_exprs = std::iter::once(MEASUREMENT_COLUMN_NAME.as_sort_expr())
+        .chain(group_by.iter().map(|tag| column(tag).as_sort_expr()))
+        .chain(std::iter::once(time_sort))
+        .collect::<Vec<_>>();
+
+    Ok(plan_builder.sort(sort_exprs)?.build()?)
+}
+
+/// The union of `plans`, `None` if there are none
+fn union(plans: Vec<LogicalPlan>) -> Result<Option<LogicalPlanBuilder>> {
+    let mut plans = plans.into_iter();
+    let first = match plans.next() {
+        Some(first) => first,
+        None => return Ok(None),
+    };
+
+    let mut plan_builder = LogicalPlanBuilder::from(first);
+    for plan in plans {
+        plan_builder = plan_builder.union(plan)?;
+    }
+    Ok(Some(plan_builder))
+}
+
+/// A plan of no rows with the Utf8 columns `names`
+fn empty_plan(names: &[&str]) -> Result<LogicalPlan> {
+    string_columns_plan(names.iter().map(|name| (*name, vec![])).collect())
+}
+
+/// A plan of the Utf8 `columns`
+fn string_columns_plan(columns: Vec<(&str, Vec<String>)>) -> Result<LogicalPlan> {
+    let batch = RecordBatch::try_from_iter(columns.into_iter().map(|(name, values)| {
+        (
+            name,
+            Arc::new(values.into_iter().map(Some).collect::<StringArray>()) as ArrayRef,
+        )
+    }))
+    .context(CreatingBatchSnafu)?;
+
+    Ok(make_scan_plan(batch)?)
+}
+
+/// Skips `offset` and takes `limit` of `values`
+fn limit_offset<T>(values: Vec<T>, limit: Option<usize>, offset: Option<usize>) -> Vec<T> {
+    values
+        .into_iter()
+        .skip(offset.unwrap_or(0))
+        .take(limit.unwrap_or(usize::MAX))
+        .collect()
+}
+
+fn show_measurements(
+    database: &dyn QueryDatabase,
+    show: &ShowMeasurementsStatement,
+) -> Result<InfluxQLPlan> {
+    let sources: Vec<_> = show.with_measurement.iter().cloned().collect();
+    let names: Vec<_> = tables(database, &sources)?
+        .into_iter()
+        .map(|(name, _)| name)
+        .collect();
+    let names = limit_offset(names, show.limit, show.offset);
+
+    let plan = string_columns_plan(vec![
+        (
+            MEASUREMENT_COLUMN_NAME,
+            vec!["measurements".to_string(); names.len()],
+        ),
+        ("name", names),
+    ])?;
+
+    Ok(InfluxQLPlan {
+        plan,
+        group_by: vec![],
+    })
+}
+
+fn show_tag_keys(
+    database: &dyn QueryDatabase,
+    show: &ShowTagKeysStatement,
+) -> Result<InfluxQLPlan> {
+    let mut measurements = vec![];
+    let mut keys = vec![];
+    for (table_name, schema) in tables(database, &show.sources)? {
+        let mut tag_keys: Vec<_> = schema
+            .tags_iter()
+            .map(|field| field.name().clone())
+            .collect();
+        tag_keys.sort();
+
+        for key in limit_offset(tag_keys, show.limit, show.offset) {
+            measurements.push(table_name.clone());
+            keys.push(key);
+        }
+    }
+
+    let plan = string_columns_plan(vec![
+        (MEASUREMENT_COLUMN_NAME, measurements),
+        ("tagKey", keys),
+    ])?;
+
+    Ok(InfluxQLPlan {
+        plan,
+        group_by: vec![],
+    })
+}
+
+fn show_field_keys(
+    database: &dyn QueryDatabase,
+    show: &ShowFieldKeysStatement,
+) -> Result<InfluxQLPlan> {
+    let mut measurements = vec![];
+    let mut keys = vec![];
+    let mut types = vec![];
+    for (table_name, schema) in tables(database, &show.sources)? {
+        let mut field_keys: Vec<_> = schema
+            .iter()
+            .filter_map(|(column_type, field)| match column_type {
+                Some(InfluxColumnType::Field(field_type)) => {
+                    Some((field.name().clone(), field_type))
+                }
+                _ => None,
+            })
+            .collect();
+        field_keys.sort_by(|a, b| a.0.cmp(&b.0));
+
+        for (key, field_type) in limit_offset(field_keys, show.limit, show.offset) {
+            let field_type = match field_type {
+                InfluxFieldType::Float => "float",
+                InfluxFieldType::Integer => "integer",
+                InfluxFieldType::UInteger => "unsigned",
+                InfluxFieldType::String => "string",
+                InfluxFieldType::Boolean => "boolean",
+            };
+            measurements.push(table_name.clone());
+            keys.push(key);
+            types.push(field_type.to_string());
+        }
+    }
+
+    let plan = string_columns_plan(vec![
+        (MEASUREMENT_COLUMN_NAME, measurements),
+        ("fieldKey", keys),
+        ("fieldType", types),
+    ])?;
+
+    Ok(InfluxQLPlan {
+        plan,
+        group_by: vec![],
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow_util::assert_batches_eq;
+    use iox_time::MockProvider;
+
+    use super::*;
+    use crate::{
+        exec::{Executor, ExecutorType},
+        test::{TestChunk, TestDatabase},
+    };
+
+    fn test_database(executor: Arc<Executor>) -> TestDatabase {
+        // +------+------+-----------+-----------------------------+
+        // | tag1 | tag2 | field_int | time                        |
+        // +------+------+-----------+-----------------------------+
+        // | WA   | SC   | 1000      | 1970-01-01 00:00:00.000008  |
+        // | VT   | NC   | 10        | 1970-01-01 00:00:00.000010  |
+        // | UT   | RI   | 70        | 1970-01-01 00:00:00.000020  |
+        // +------+------+-----------+-----------------------------+
+        let h2o = TestChunk::new("h2o")
+            .with_id(1)
+            .with_time_column()
+            .with_tag_column("tag1")
+            .with_tag_column("tag2")
+            .with_i64_field_column("field_int")
+            .with_three_rows_of_data();
+
+        let o2 = TestChunk::new("o2")
+            .with_id(2)
+            .with_time_column()
+            .with_tag_column("tag1")
+            .with_i64_field_column("field_int")
+            .with_string_field_column_with_stats("field_str", None, None)
+            .with_one_row_of_data();
+
+        TestDatabase::new(executor)
+            .with_chunk("p1", Arc::new(h2o))
+            .with_chunk("p1", Arc::new(o2))
+    }
+
+    async fn run(query: &str) -> Vec<RecordBatch> {
+        let executor = Arc::new(Executor::new(1));
+        let database = test_database(Arc::clone(&executor));
+        let ctx = executor.new_context(ExecutorType::Query);
+
+        let time_provider = Arc::new(MockProvider::new(Time::from_timestamp_nanos(30_000)));
+        let planner =
+            InfluxQLPlanner::new(ctx.child_ctx("influxql")).with_time_provider(time_provider);
+
+        let mut statements = InfluxQLPlanner::parse(query).unwrap();
+        assert_eq!(statements.len(), 1);
+        let plan = planner
+            .plan(&database, &statements.remove(0))
+            .await
+            .unwrap();
+        ctx.run_logical_plan(plan.plan).await.unwrap()
+    }
+
+    async fn plan_error(query: &str) -> String {
+        let executor = Arc::new(Executor::new(1));
+        let database = test_database(Arc::clone(&executor));
+        let planner = InfluxQLPlanner::new(executor.new_context(ExecutorType::Query));
+
+        let statements = InfluxQLPlanner::parse(query).unwrap();
+        planner
+            .plan(&database, &statements[0])
+            .await
+            .unwrap_err()
+            .to_string()
+    }
+
+    #[tokio::test]
+    async fn select_raw() {
+        let batches =
+            run("SELECT field_int, tag2 FROM h2o WHERE tag1 = 'WA' OR tag1 =~ /^U/").await;
+        assert_batches_eq!(
+            &[
+                "+------------------+-----------------------------+-----------+------+",
+                "| iox::measurement | time                        | field_int | tag2 |",
+                "+------------------+-----------------------------+-----------+------+",
+                "| h2o              | 1970-01-01T00:00:00.000008Z | 1000      | SC   |",
+                "| h2o              | 1970-01-01T00:00:00.000020Z | 70        | RI   |",
+                "+------------------+-----------------------------+-----------+------+",
+            ],
+            &batches
+        );
+    }
+
+    #[tokio::test]
+    async fn select_wildcard_from_regex() {
+        let batches =
+            run("SELECT * FROM /o/ WHERE time >= now() - 29us AND tag1 != 'WA' ORDER BY time DESC")
+                .await;
+        assert_batches_eq!(
+            &[
+                "+------------------+-----------------------------+-----------+-----------+------+------+",
+                "| iox::measurement | time                        | field_int | field_str | tag1 | tag2 |",
+                "+------------------+-----------------------------+-----------+-----------+------+------+",
+                "| h2o              | 1970-01-01T00:00:00.000020Z | 70        |           | UT   | RI   |",
+                "| h2o              | 1970-01-01T00:00:00.000010Z | 10        |           | VT   | NC   |",
+                "| o2               | 1970-01-01T00:00:00.000001Z | 1000      | MA        | MA   |      |",
+                "+------------------+-----------------------------+-----------+-----------+------+------+",
+            ],
+            &batches
+        );
+    }
+
+    #[tokio::test]
+    async fn select_aggregates() {
+        let batches = run(
+            "SELECT count(field_int), spread(field_int) AS s, last(field_int) FROM h2o \
+             WHERE time >= 0 AND time < 30000 GROUP BY time(10us), tag1 fill(none)",
+        )
+        .await;
+        assert_batches_eq!(
+            &[
+                "+------------------+-----------------------------+------+-------+---+------+",
+                "| iox::measurement | time                        | tag1 | count | s | last |",
+                "+------------------+-----------------------------+------+-------+---+------+",
+                "| h2o              | 1970-01-01T00:00:00.000020Z | UT   | 1     | 0 | 70   |",
+                "| h2o              | 1970-01-01T00:00:00.000010Z | VT   | 1     | 0 | 10   |",
+                "| h2o              | 1970-01-01T00:00:00Z        | WA   | 1     | 0 | 1000 |",
+                "+------------------+-----------------------------+------+-------+---+------+",
+            ],
+            &batches
+        );
+
+        let batches = run("SELECT max(field_int), mean(field_int) + 0.5 FROM h2o, o2").await;
+        assert_batches_eq!(
+            &[
+                "+------------------+----------------------+------+--------+",
+                "| iox::measurement | time                 | max  | mean   |",
+                "+------------------+----------------------+------+--------+",
+                "| h2o              | 1970-01-01T00:00:00Z | 1000 | 360.5  |",
+                "| o2               | 1970-01-01T00:00:00Z | 1000 | 1000.5 |",
+                "+------------------+----------------------+------+--------+",
+            ],
+            &batches
+        );
+    }
+
+    #[tokio::test]
+    async fn select_fill() {
+        let batches = run(
+            "SELECT sum(field_int) FROM h2o WHERE time >= 0 AND time < 30000 GROUP BY time(5us)",
+        )
+        .await;
+        assert_batches_eq!(
+            &[
+                "+------------------+-----------------------------+------+",
+                "| iox::measurement | time                        | sum  |",
+                "+------------------+-----------------------------+------+",
+                "| h2o              | 1970-01-01T00:00:00Z        |      |",
+                "| h2o              | 1970-01-01T00:00:00.000005Z | 1000 |",
+                "| h2o              | 1970-01-01T00:00:00.000010Z | 10   |",
+                "| h2o              | 1970-01-01T00:00:00.000015Z |      |",
+                "| h2o              | 1970-01-01T00:00:00.000020Z | 70   |",
+                "| h2o              | 1970-01-01T00:00:00.000025Z |      |",
+                "+------------------+-----------------------------+------+",
+            ],
+            &batches
+        );
+
+        let batches = run(
+            "SELECT sum(field_int) FROM h2o WHERE time >= 0 AND time < 30000 \
+             GROUP BY time(5us) fill(previous) ORDER BY time DESC",
+        )
+        .await;
+        assert_batches_eq!(
+            &[
+                "+------------------+-----------------------------+------+",
+                "| iox::measurement | time                        | sum  |",
+                "+------------------+-----------------------------+------+",
+                "| h2o              | 1970-01-01T00:00:00.000025Z |      |",
+                "| h2o              | 1970-01-01T00:00:00.000020Z | 70   |",
+                "| h2o              | 1970-01-01T00:00:00.000015Z | 70   |",
+                "| h2o              | 1970-01-01T00:00:00.000010Z | 10   |",
+                "| h2o              | 1970-01-01T00:00:00.000005Z | 1000 |",
+                "| h2o              | 1970-01-01T00:00:00Z        | 1000 |",
+                "+------------------+-----------------------------+------+",
+            ],
+            &batches
+        );
+
+        let batches = run(
+            "SELECT sum(field_int) FROM h2o WHERE time >= 0 AND time < 30000 \
+             GROUP BY time(10us), tag1 fill(-1)",
+        )
+        .await;
+        assert_batches_eq!(
+            &[
+                "+------------------+-----------------------------+------+------+",
+                "| iox::measurement | time                        | tag1 | sum  |",
+                "+------------------+-----------------------------+------+------+",
+                "| h2o              | 1970-01-01T00:00:00Z        | UT   | -1   |",
+                "| h2o              | 1970-01-01T00:00:00.000010Z | UT   | -1   |",
+                "| h2o              | 1970-01-01T00:00:00.000020Z | UT   | 70   |",
+                "| h2o              | 1970-01-01T00:00:00Z        | VT   | -1   |",
+                "| h2o              | 1970-01-01T00:00:00.000010Z | VT   | 10   |",
+                "| h2o              | 1970-01-01T00:00:00.000020Z | VT   | -1   |",
+                "| h2o              | 1970-01-01T00:00:00Z        | WA   | 1000 |",
+                "| h2o              | 1970-01-01T00:00:00.000010Z | WA   | -1   |",
+                "| h2o              | 1970-01-01T00:00:00.000020Z | WA   | -1   |",
+                "+------------------+-----------------------------+------+------+",
+            ],
+            &batches
+        );
+    }
+
+    #[tokio::test]
+    async fn show() {
+        let batches = run("SHOW MEASUREMENTS WITH MEASUREMENT =~ /2/").await;
+        assert_batches_eq!(
+            &[
+                "+------------------+------+",
+                "| iox::measurement | name |",
+                "+------------------+------+",
+                "| measurements     | h2o  |",
+                "| measurements     | o2   |",
+                "+------------------+------+",
+            ],
+            &batches
+        );
+
+        let batches = run("SHOW TAG KEYS FROM h2o").await;
+        assert_batches_eq!(
+            &[
+                "+------------------+--------+",
+                "| iox::measurement | tagKey |",
+                "+------------------+--------+",
+                "| h2o              | tag1   |",
+                "| h2o              | tag2   |",
+                "+------------------+--------+",
+            ],
+            &batches
+        );
+
+        let batches = run("SHOW FIELD KEYS FROM o2").await;
+        assert_batches_eq!(
+            &[
+                "+------------------+-----------+-----------+",
+                "| iox::measurement | fieldKey  | fieldType |",
+                "+------------------+-----------+-----------+",
+                "| o2               | field_int | integer   |",
+                "| o2               | field_str | string    |",
+                "+------------------+-----------+-----------+",
+            ],
+            &batches
+        );
+
+        let batches = run("SHOW TAG VALUES WITH KEY = tag1 WHERE field_int > 50").await;
+        assert_batches_eq!(
+            &[
+                "+------------------+------+-------+",
+                "| iox::measurement | key  | value |",
+                "+------------------+------+-------+",
+                "| h2o              | tag1 | UT    |",
+                "| h2o              | tag1 | WA    |",
+                "| o2               | tag1 | MA    |",
+                "+------------------+------+-------+",
+            ],
+            &batches
+        );
+    }
+
+    #[tokio::test]
+    async fn unknown_measurements_and_fields() {
+        let batches = run("SELECT field_int FROM nope").await;
+        assert!(batches.iter().all(|batch| batch.num_rows() == 0));
+
+        let batches = run("SELECT nope FROM h2o").await;
+        assert!(batches.iter().all(|batch| batch.num_rows() == 0));
+    }
+
+    #[tokio::test]
+    async fn errors() {
+        let cases = [
+            (
+                "SELECT field_int, count(field_int) FROM h2o",
+                "mixing aggregate and non-aggregate fields is not supported",
+            ),
+            (
+                "SELECT field_int FROM h2o GROUP BY time(1m)",
+                "GROUP BY time() requires an aggregate function",
+            ),
+            (
+                "SELECT median(field_int) FROM h2o",
+                "function median() is not supported",
+            ),
+            (
+                "SELECT mean(field_str) FROM o2",
+                "mean() requires a numeric field",
+            ),
+            (
+                "SELECT count(field_int) FROM h2o GROUP BY time(1m) fill(linear)",
+                "fill(linear) is not supported",
+            ),
+            (
+                "SELECT field_int FROM h2o WHERE time > 0 OR tag1 = 'a'",
+                "InfluxQL ORing time conditions is not supported",
+            ),
+        ];
+
+        for (query, expected) in cases {
+            let error = plan_error(query).await;
+            assert!(error.contains(expected), "{}: {}", query, error);
+        }
+    }
+}
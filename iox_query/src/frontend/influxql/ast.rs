@@ -0,0 +1,219 @@
+//! Syntax tree of the subset of InfluxQL IOx supports
+
+/// A parsed InfluxQL statement
+#[derive(Debug, Clone, PartialEq)]
+pub enum Statement {
+    Select(SelectStatement),
+    ShowMeasurements(ShowMeasurementsStatement),
+    ShowTagKeys(ShowTagKeysStatement),
+    ShowTagValues(ShowTagValuesStatement),
+    ShowFieldKeys(ShowFieldKeysStatement),
+}
+
+/// `SELECT <fields> FROM <sources> [WHERE <condition>] [GROUP BY <dimensions>]
+/// [fill(<fill>)] [ORDER BY time [ASC|DESC]]`
+#[derive(Debug, Clone, PartialEq)]
+pub struct SelectStatement {
+    pub fields: Vec<Field>,
+    pub sources: Vec<Source>,
+    pub condition: Option<Expr>,
+    pub dimensions: Vec<Dimension>,
+    pub fill: Option<Fill>,
+    /// True for `ORDER BY time DESC`
+    pub order_desc: bool,
+}
+
+/// An expression of the select list, with an optional alias
+#[derive(Debug, Clone, PartialEq)]
+pub struct Field {
+    pub expr: Expr,
+    pub alias: Option<String>,
+}
+
+/// A measurement to read from
+#[derive(Debug, Clone, PartialEq)]
+pub enum Source {
+    /// The measurement with this name
+    Name(String),
+    /// All measurements whose name matches this regex
+    Regex(String),
+}
+
+impl Source {
+    /// Returns true if `measurement` is selected by this source
+    pub fn matches(&self, measurement: &str) -> Result<bool, regex::Error> {
+        Ok(match self {
+            Self::Name(name) => name == measurement,
+            Self::Regex(pattern) => regex::Regex::new(pattern)?.is_match(measurement),
+        })
+    }
+}
+
+/// An expression of the `GROUP BY` clause
+#[derive(Debug, Clone, PartialEq)]
+pub enum Dimension {
+    /// `time(<interval>[, <offset>])`, in nanoseconds
+    Time { interval: i64, offset: i64 },
+    /// A tag
+    Tag(String),
+    /// `*`, all tags
+    Wildcard,
+}
+
+/// The value of `fill()`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Fill {
+    Null,
+    None,
+    Number(f64),
+    Previous,
+    Linear,
+}
+
+/// `SHOW MEASUREMENTS [WITH MEASUREMENT <=|=~> <name|regex>]`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShowMeasurementsStatement {
+    pub with_measurement: Option<Source>,
+    pub limit: Option<usize>,
+    pub offset: Option<usize>,
+}
+
+/// `SHOW TAG KEYS [FROM <sources>]`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShowTagKeysStatement {
+    pub sources: Vec<Source>,
+    pub limit: Option<usize>,
+    pub offset: Option<usize>,
+}
+
+/// `SHOW FIELD KEYS [FROM <sources>]`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShowFieldKeysStatement {
+    pub sources: Vec<Source>,
+    pub limit: Option<usize>,
+    pub offset: Option<usize>,
+}
+
+/// `SHOW TAG VALUES [FROM <sources>] WITH KEY <condition> [WHERE <condition>]`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShowTagValuesStatement {
+    pub sources: Vec<Source>,
+    pub with_key: TagKeys,
+    pub condition: Option<Expr>,
+}
+
+/// The tag keys of `SHOW TAG VALUES ... WITH KEY`
+#[derive(Debug, Clone, PartialEq)]
+pub enum TagKeys {
+    /// `= <key>`
+    Eq(String),
+    /// `!= <key>`
+    NotEq(String),
+    /// `=~ /<regex>/`
+    Regex(String),
+    /// `!~ /<regex>/`
+    NotRegex(String),
+    /// `IN (<key>, ...)`
+    In(Vec<String>),
+}
+
+impl TagKeys {
+    /// Returns true if `key` is selected
+    pub fn matches(&self, key: &str) -> Result<bool, regex::Error> {
+        Ok(match self {
+            Self::Eq(name) => name == key,
+            Self::NotEq(name) => name != key,
+            Self::Regex(pattern) => regex::Regex::new(pattern)?.is_match(key),
+            Self::NotRegex(pattern) => !regex::Regex::new(pattern)?.is_match(key),
+            Self::In(names) => names.iter().any(|name| name == key),
+        })
+    }
+}
+
+/// An InfluxQL expression
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    /// `*`
+    Wildcard,
+    /// A reference to a field, tag or `time`
+    VarRef(String),
+    /// A function call such as `mean(usage)` or `now()`
+    Call {
+        name: String,
+        args: Vec<Expr>,
+    },
+    Literal(Literal),
+    Binary {
+        lhs: Box<Expr>,
+        op: BinaryOp,
+        rhs: Box<Expr>,
+    },
+}
+
+impl Expr {
+    pub fn binary(lhs: Self, op: BinaryOp, rhs: Self) -> Self {
+        Self::Binary {
+            lhs: Box::new(lhs),
+            op,
+            rhs: Box::new(rhs),
+        }
+    }
+
+    /// Returns true if this expression contains a function call
+    pub fn has_call(&self) -> bool {
+        match self {
+            Self::Call { .. } => true,
+            Self::Binary { lhs, rhs, .. } => lhs.has_call() || rhs.has_call(),
+            Self::Wildcard | Self::VarRef(_) | Self::Literal(_) => false,
+        }
+    }
+
+    /// Returns true if this expression contains a `*`
+    pub fn has_wildcard(&self) -> bool {
+        match self {
+            Self::Wildcard => true,
+            Self::Call { args, .. } => args.iter().any(Self::has_wildcard),
+            Self::Binary { lhs, rhs, .. } => lhs.has_wildcard() || rhs.has_wildcard(),
+            Self::VarRef(_) | Self::Literal(_) => false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Literal {
+    Integer(i64),
+    Float(f64),
+    String(String),
+    Boolean(bool),
+    /// A duration such as `5m`, in nanoseconds
+    Duration(i64),
+    Regex(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BinaryOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Eq,
+    NotEq,
+    Lt,
+    LtEq,
+    Gt,
+    GtEq,
+    RegexMatch,
+    RegexNotMatch,
+    And,
+    Or,
+}
+
+impl BinaryOp {
+    /// Returns true for `=`, `!=`, `<`, `<=`, `>` and `>=`
+    pub fn is_comparison(&self) -> bool {
+        matches!(
+            self,
+            Self::Eq | Self::NotEq | Self::Lt | Self::LtEq | Self::Gt | Self::GtEq
+        )
+    }
+}
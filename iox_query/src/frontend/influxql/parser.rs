@@ -0,0 +1,1077 @@
+//! Hand-written recursive descent parser for the InfluxQL statements in
+//! [`ast`](super::ast)
+
+use std::fmt;
+
+use super::ast::{
+    BinaryOp, Dimension, Expr, Field, Fill, Literal, SelectStatement, ShowFieldKeysStatement,
+    ShowMeasurementsStatement, ShowTagKeysStatement, ShowTagValuesStatement, Source, Statement,
+    TagKeys,
+};
+
+/// Keywords that can't be used as unquoted identifiers
+const RESERVED: &[&str] = &[
+    "AND", "AS", "BY", "FILL", "FROM", "GROUP", "LIMIT", "OFFSET", "ON", "OR", "ORDER", "SELECT",
+    "SHOW", "SLIMIT", "SOFFSET", "WHERE", "WITH",
+];
+
+/// An error parsing an InfluxQL query, at the character offset `pos`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    pub message: String,
+    pub pos: usize,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "error parsing query: {} at char {}",
+            self.message,
+            self.pos + 1
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+type Result<T, E = ParseError> = std::result::Result<T, E>;
+
+/// Parses the `;` separated statements of `query`
+pub fn parse_statements(query: &str) -> Result<Vec<Statement>> {
+    let mut parser = Parser::new(query);
+    let mut statements = vec![];
+
+    loop {
+        while parser.consume_op(";")? {}
+        if parser.peek()? == Token::Eof {
+            break;
+        }
+
+        statements.push(parser.statement()?);
+
+        match parser.peek()? {
+            Token::Eof => break,
+            Token::Op(";") => {}
+            _ => return Err(parser.unexpected("end of statement")),
+        }
+    }
+
+    if statements.is_empty() {
+        return Err(parser.error("empty query"));
+    }
+
+    Ok(statements)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    /// An unquoted identifier or keyword
+    Ident(String),
+    /// A double quoted identifier
+    QuotedIdent(String),
+    String(String),
+    Integer(i64),
+    Float(f64),
+    /// A duration, in nanoseconds
+    Duration(i64),
+    Op(&'static str),
+    Eof,
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Ident(s) => write!(f, "{}", s),
+            Self::QuotedIdent(s) => write!(f, "\"{}\"", s),
+            Self::String(s) => write!(f, "'{}'", s),
+            Self::Integer(v) => write!(f, "{}", v),
+            Self::Float(v) => write!(f, "{}", v),
+            Self::Duration(v) => write!(f, "{}ns", v),
+            Self::Op(op) => write!(f, "{}", op),
+            Self::Eof => write!(f, "EOF"),
+        }
+    }
+}
+
+/// Operators, longest first so that `<=` isn't lexed as `<`
+const OPS: &[&str] = &[
+    "::", "!=", "<>", "<=", ">=", "=~", "!~", "=", "<", ">", "+", "-", "*", "/", "(", ")", ",",
+    ";", ".",
+];
+
+struct Parser<'a> {
+    input: &'a str,
+    /// Byte offset of the next token
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn new(input: &'a str) -> Self {
+        Self { input, pos: 0 }
+    }
+
+    fn error(&self, message: impl Into<String>) -> ParseError {
+        ParseError {
+            message: message.into(),
+            pos: self.input[..self.pos].chars().count(),
+        }
+    }
+
+    fn unexpected(&self, expected: &str) -> ParseError {
+        let token = match self.peek() {
+            Ok(token) => token,
+            Err(e) => return e,
+        };
+        ParseError {
+            message: format!("found {}, expected {}", token, expected),
+            pos: self.input[..self.skip_whitespace()].chars().count(),
+        }
+    }
+
+    /// Offset of the next character that isn't whitespace or in a comment
+    fn skip_whitespace(&self) -> usize {
+        let mut pos = self.pos;
+        loop {
+            let rest = &self.input[pos..];
+            let trimmed = rest.trim_start();
+            pos += rest.len() - trimmed.len();
+
+            if trimmed.starts_with("--") {
+                pos += trimmed.find('\n').unwrap_or(trimmed.len());
+            } else if trimmed.starts_with("/*") {
+                pos += trimmed.find("*/").map_or(trimmed.len(), |end| end + 2);
+            } else {
+                return pos;
+            }
+        }
+    }
+
+    /// The next token and the offset after it
+    fn lex(&self) -> Result<(Token, usize)> {
+        let start = self.skip_whitespace();
+        let rest = &self.input[start..];
+        let error = |message: String| ParseError {
+            message,
+            pos: self.input[..start].chars().count(),
+        };
+
+        let c = match rest.chars().next() {
+            Some(c) => c,
+            None => return Ok((Token::Eof, start)),
+        };
+
+        if c.is_ascii_alphabetic() || c == '_' {
+            let len = rest
+                .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                .unwrap_or(rest.len());
+            return Ok((Token::Ident(rest[..len].to_string()), start + len));
+        }
+
+        if c == '"' || c == '\'' {
+            let (value, len) = quoted(rest).ok_or_else(|| error("unterminated quote".into()))?;
+            let token = if c == '"' {
+                Token::QuotedIdent(value)
+            } else {
+                Token::String(value)
+            };
+            return Ok((token, start + len));
+        }
+
+        if c.is_ascii_digit() || (c == '.' && rest[1..].starts_with(|c: char| c.is_ascii_digit())) {
+            let (token, len) = number(rest).map_err(error)?;
+            return Ok((token, start + len));
+        }
+
+        OPS.iter()
+            .find(|op| rest.starts_with(**op))
+            .map(|op| (Token::Op(op), start + op.len()))
+            .ok_or_else(|| error(format!("unexpected character {:?}", c)))
+    }
+
+    fn peek(&self) -> Result<Token> {
+        self.lex().map(|(token, _)| token)
+    }
+
+    fn next(&mut self) -> Result<Token> {
+        let (token, pos) = self.lex()?;
+        self.pos = pos;
+        Ok(token)
+    }
+
+    fn peek_keyword(&self, keyword: &str) -> Result<bool> {
+        Ok(matches!(self.peek()?, Token::Ident(ident) if ident.eq_ignore_ascii_case(keyword)))
+    }
+
+    fn consume_keyword(&mut self, keyword: &str) -> Result<bool> {
+        let found = self.peek_keyword(keyword)?;
+        if found {
+            self.next()?;
+        }
+        Ok(found)
+    }
+
+    fn expect_keyword(&mut self, keyword: &str) -> Result<()> {
+        if self.consume_keyword(keyword)? {
+            Ok(())
+        } else {
+            Err(self.unexpected(keyword))
+        }
+    }
+
+    fn consume_op(&mut self, op: &str) -> Result<bool> {
+        let found = matches!(self.peek()?, Token::Op(found) if found == op);
+        if found {
+            self.next()?;
+        }
+        Ok(found)
+    }
+
+    fn expect_op(&mut self, op: &str) -> Result<()> {
+        if self.consume_op(op)? {
+            Ok(())
+        } else {
+            Err(self.unexpected(op))
+        }
+    }
+
+    /// Consumes a `/regex/` if it is next
+    fn regex(&mut self) -> Result<Option<String>> {
+        let start = self.skip_whitespace();
+        let rest = &self.input[start..];
+        if !rest.starts_with('/') {
+            return Ok(None);
+        }
+
+        let mut pattern = String::new();
+        let mut chars = rest.char_indices().skip(1);
+        while let Some((idx, c)) = chars.next() {
+            match c {
+                '/' => {
+                    self.pos = start + idx + 1;
+                    return Ok(Some(pattern));
+                }
+                '\\' => match chars.next() {
+                    Some((_, '/')) => pattern.push('/'),
+                    Some((_, c)) => {
+                        pattern.push('\\');
+                        pattern.push(c);
+                    }
+                    None => break,
+                },
+                c => pattern.push(c),
+            }
+        }
+
+        self.pos = start;
+        Err(self.error("unterminated regex"))
+    }
+
+    fn expect_regex(&mut self) -> Result<String> {
+        match self.regex()? {
+            Some(pattern) => Ok(pattern),
+            None => Err(self.unexpected("regex")),
+        }
+    }
+
+    fn identifier(&mut self) -> Result<String> {
+        match self.peek()? {
+            Token::Ident(ident) if !is_reserved(&ident) => {
+                self.next()?;
+                Ok(ident)
+            }
+            Token::QuotedIdent(ident) => {
+                self.next()?;
+                Ok(ident)
+            }
+            _ => Err(self.unexpected("identifier")),
+        }
+    }
+
+    fn unsigned(&mut self) -> Result<usize> {
+        match self.peek()? {
+            Token::Integer(v) if v >= 0 => {
+                self.next()?;
+                Ok(v as usize)
+            }
+            _ => Err(self.unexpected("unsigned integer")),
+        }
+    }
+
+    fn statement(&mut self) -> Result<Statement> {
+        if self.consume_keyword("SELECT")? {
+            return self.select().map(Statement::Select);
+        }
+        if self.consume_keyword("SHOW")? {
+            return self.show();
+        }
+        Err(self.unexpected("SELECT or SHOW"))
+    }
+
+    fn select(&mut self) -> Result<SelectStatement> {
+        let mut fields = vec![self.field()?];
+        while self.consume_op(",")? {
+            fields.push(self.field()?);
+        }
+
+        if self.peek_keyword("INTO")? {
+            return Err(self.error("SELECT INTO is not supported"));
+        }
+        self.expect_keyword("FROM")?;
+        let sources = self.sources()?;
+
+        let condition = if self.consume_keyword("WHERE")? {
+            Some(self.expr()?)
+        } else {
+            None
+        };
+
+        let mut dimensions = vec![];
+        if self.consume_keyword("GROUP")? {
+            self.expect_keyword("BY")?;
+            dimensions.push(self.dimension()?);
+            while self.consume_op(",")? {
+                dimensions.push(self.dimension()?);
+            }
+        }
+
+        let fill = if self.consume_keyword("FILL")? {
+            Some(self.fill()?)
+        } else {
+            None
+        };
+
+        let mut order_desc = false;
+        if self.consume_keyword("ORDER")? {
+            self.expect_keyword("BY")?;
+            if self.identifier()? != "time" {
+                return Err(self.error("only ORDER BY time is supported"));
+            }
+            if self.consume_keyword("DESC")? {
+                order_desc = true;
+            } else {
+                self.consume_keyword("ASC")?;
+            }
+        }
+
+        for keyword in ["LIMIT", "OFFSET", "SLIMIT", "SOFFSET", "TZ"] {
+            if self.peek_keyword(keyword)? {
+                return Err(self.error(format!("{} is not supported in SELECT", keyword)));
+            }
+        }
+
+        Ok(SelectStatement {
+            fields,
+            sources,
+            condition,
+            dimensions,
+            fill,
+            order_desc,
+        })
+    }
+
+    fn field(&mut self) -> Result<Field> {
+        let expr = self.expr()?;
+        let alias = if self.consume_keyword("AS")? {
+            Some(self.identifier()?)
+        } else {
+            None
+        };
+        Ok(Field { expr, alias })
+    }
+
+    fn sources(&mut self) -> Result<Vec<Source>> {
+        let mut sources = vec![self.source()?];
+        while self.consume_op(",")? {
+            sources.push(self.source()?);
+        }
+        Ok(sources)
+    }
+
+    /// A measurement name or regex. The database and retention policy of
+    /// `"db"."rp"."measurement"` are ignored, the request selects the
+    /// namespace.
+    fn source(&mut self) -> Result<Source> {
+        if let Some(pattern) = self.regex()? {
+            return Ok(Source::Regex(pattern));
+        }
+
+        let mut name = self.identifier()?;
+        while self.consume_op(".")? {
+            if let Some(pattern) = self.regex()? {
+                return Ok(Source::Regex(pattern));
+            }
+            name = self.identifier()?;
+        }
+        Ok(Source::Name(name))
+    }
+
+    fn dimension(&mut self) -> Result<Dimension> {
+        if self.consume_op("*")? {
+            return Ok(Dimension::Wildcard);
+        }
+        if self.regex()?.is_some() {
+            return Err(self.error("GROUP BY regex is not supported"));
+        }
+
+        let name = self.identifier()?;
+        if name.eq_ignore_ascii_case("time") && self.consume_op("(")? {
+            let interval = self.duration()?;
+            let offset = if self.consume_op(",")? {
+                self.duration()?
+            } else {
+                0
+            };
+            self.expect_op(")")?;
+
+            if interval <= 0 {
+                return Err(self.error("GROUP BY time interval must be positive"));
+            }
+            return Ok(Dimension::Time { interval, offset });
+        }
+
+        Ok(Dimension::Tag(name))
+    }
+
+    fn duration(&mut self) -> Result<i64> {
+        match self.unary()? {
+            Expr::Literal(Literal::Duration(v)) => Ok(v),
+            _ => Err(self.error("expected duration")),
+        }
+    }
+
+    fn fill(&mut self) -> Result<Fill> {
+        self.expect_op("(")?;
+        let fill = match self.next()? {
+            Token::Ident(ident) if ident.eq_ignore_ascii_case("null") => Fill::Null,
+            Token::Ident(ident) if ident.eq_ignore_ascii_case("none") => Fill::None,
+            Token::Ident(ident) if ident.eq_ignore_ascii_case("previous") => Fill::Previous,
+            Token::Ident(ident) if ident.eq_ignore_ascii_case("linear") => Fill::Linear,
+            Token::Integer(v) => Fill::Number(v as f64),
+            Token::Float(v) => Fill::Number(v),
+            Token::Op("-") => match self.next()? {
+                Token::Integer(v) => Fill::Number(-v as f64),
+                Token::Float(v) => Fill::Number(-v),
+                _ => return Err(self.error("expected number")),
+            },
+            token => {
+                return Err(self.error(format!(
+                    "found {}, expected null, none, previous, linear or a number",
+                    token
+                )))
+            }
+        };
+        self.expect_op(")")?;
+        Ok(fill)
+    }
+
+    fn show(&mut self) -> Result<Statement> {
+        if self.consume_keyword("MEASUREMENTS")? {
+            self.on_database()?;
+
+            let with_measurement = if self.consume_keyword("WITH")? {
+                self.expect_keyword("MEASUREMENT")?;
+                if self.consume_op("=~")? {
+                    Some(Source::Regex(self.expect_regex()?))
+                } else {
+                    self.expect_op("=")?;
+                    Some(self.source()?)
+                }
+            } else {
+                None
+            };
+            self.no_where("SHOW MEASUREMENTS")?;
+            let (limit, offset) = self.limit_offset()?;
+
+            return Ok(Statement::ShowMeasurements(ShowMeasurementsStatement {
+                with_measurement,
+                limit,
+                offset,
+            }));
+        }
+
+        if self.consume_keyword("FIELD")? {
+            self.expect_keyword("KEYS")?;
+            self.on_database()?;
+            let sources = self.from()?;
+            let (limit, offset) = self.limit_offset()?;
+
+            return Ok(Statement::ShowFieldKeys(ShowFieldKeysStatement {
+                sources,
+                limit,
+                offset,
+            }));
+        }
+
+        if self.consume_keyword("TAG")? {
+            if self.consume_keyword("KEYS")? {
+                self.on_database()?;
+                let sources = self.from()?;
+                self.no_where("SHOW TAG KEYS")?;
+                let (limit, offset) = self.limit_offset()?;
+
+                return Ok(Statement::ShowTagKeys(ShowTagKeysStatement {
+                    sources,
+                    limit,
+                    offset,
+                }));
+            }
+
+            self.expect_keyword("VALUES")?;
+            self.on_database()?;
+            let sources = self.from()?;
+            self.expect_keyword("WITH")?;
+            self.expect_keyword("KEY")?;
+            let with_key = self.tag_keys()?;
+            let condition = if self.consume_keyword("WHERE")? {
+                Some(self.expr()?)
+            } else {
+                None
+            };
+            for keyword in ["LIMIT", "OFFSET"] {
+                if self.peek_keyword(keyword)? {
+                    return Err(
+                        self.error(format!("{} is not supported in SHOW TAG VALUES", keyword))
+                    );
+                }
+            }
+
+            return Ok(Statement::ShowTagValues(ShowTagValuesStatement {
+                sources,
+                with_key,
+                condition,
+            }));
+        }
+
+        Err(self.unexpected("MEASUREMENTS, TAG KEYS, TAG VALUES or FIELD KEYS"))
+    }
+
+    /// Skips `ON <database>`, the request selects the namespace
+    fn on_database(&mut self) -> Result<()> {
+        if self.consume_keyword("ON")? {
+            self.identifier()?;
+        }
+        Ok(())
+    }
+
+    fn from(&mut self) -> Result<Vec<Source>> {
+        if self.consume_keyword("FROM")? {
+            self.sources()
+        } else {
+            Ok(vec![])
+        }
+    }
+
+    fn no_where(&self, statement: &str) -> Result<()> {
+        if self.peek_keyword("WHERE")? {
+            return Err(self.error(format!("WHERE is not supported in {}", statement)));
+        }
+        Ok(())
+    }
+
+    fn limit_offset(&mut self) -> Result<(Option<usize>, Option<usize>)> {
+        let limit = if self.consume_keyword("LIMIT")? {
+            Some(self.unsigned()?)
+        } else {
+            None
+        };
+        let offset = if self.consume_keyword("OFFSET")? {
+            Some(self.unsigned()?)
+        } else {
+            None
+        };
+        Ok((limit, offset))
+    }
+
+    fn tag_keys(&mut self) -> Result<TagKeys> {
+        if self.consume_op("=~")? {
+            return Ok(TagKeys::Regex(self.expect_regex()?));
+        }
+        if self.consume_op("!~")? {
+            return Ok(TagKeys::NotRegex(self.expect_regex()?));
+        }
+        if self.consume_op("=")? {
+            return Ok(TagKeys::Eq(self.identifier()?));
+        }
+        if self.consume_op("!=")? || self.consume_op("<>")? {
+            return Ok(TagKeys::NotEq(self.identifier()?));
+        }
+        if self.consume_keyword("IN")? {
+            self.expect_op("(")?;
+            let mut keys = vec![self.identifier()?];
+            while self.consume_op(",")? {
+                keys.push(self.identifier()?);
+            }
+            self.expect_op(")")?;
+            return Ok(TagKeys::In(keys));
+        }
+        Err(self.unexpected("=, !=, =~, !~ or IN"))
+    }
+
+    fn expr(&mut self) -> Result<Expr> {
+        let mut expr = self.and()?;
+        while self.consume_keyword("OR")? {
+            expr = Expr::binary(expr, BinaryOp::Or, self.and()?);
+        }
+        Ok(expr)
+    }
+
+    fn and(&mut self) -> Result<Expr> {
+        let mut expr = self.comparison()?;
+        while self.consume_keyword("AND")? {
+            expr = Expr::binary(expr, BinaryOp::And, self.comparison()?);
+        }
+        Ok(expr)
+    }
+
+    fn comparison(&mut self) -> Result<Expr> {
+        let lhs = self.additive()?;
+
+        let op = match self.peek()? {
+            Token::Op("=~") => BinaryOp::RegexMatch,
+            Token::Op("!~") => BinaryOp::RegexNotMatch,
+            Token::Op("=") => BinaryOp::Eq,
+            Token::Op("!=") | Token::Op("<>") => BinaryOp::NotEq,
+            Token::Op("<") => BinaryOp::Lt,
+            Token::Op("<=") => BinaryOp::LtEq,
+            Token::Op(">") => BinaryOp::Gt,
+            Token::Op(">=") => BinaryOp::GtEq,
+            _ => return Ok(lhs),
+        };
+        self.next()?;
+
+        let rhs = match op {
+            BinaryOp::RegexMatch | BinaryOp::RegexNotMatch => {
+                Expr::Literal(Literal::Regex(self.expect_regex()?))
+            }
+            _ => self.additive()?,
+        };
+        Ok(Expr::binary(lhs, op, rhs))
+    }
+
+    fn additive(&mut self) -> Result<Expr> {
+        let mut expr = self.multiplicative()?;
+        loop {
+            let op = match self.peek()? {
+                Token::Op("+") => BinaryOp::Add,
+                Token::Op("-") => BinaryOp::Sub,
+                _ => return Ok(expr),
+            };
+            self.next()?;
+            expr = Expr::binary(expr, op, self.multiplicative()?);
+        }
+    }
+
+    fn multiplicative(&mut self) -> Result<Expr> {
+        let mut expr = self.unary()?;
+        loop {
+            let op = match self.peek()? {
+                Token::Op("*") => BinaryOp::Mul,
+                Token::Op("/") => BinaryOp::Div,
+                _ => return Ok(expr),
+            };
+            self.next()?;
+            expr = Expr::binary(expr, op, self.unary()?);
+        }
+    }
+
+    fn unary(&mut self) -> Result<Expr> {
+        if !self.consume_op("-")? {
+            return self.primary();
+        }
+
+        Ok(match self.unary()? {
+            Expr::Literal(Literal::Integer(v)) => Expr::Literal(Literal::Integer(-v)),
+            Expr::Literal(Literal::Float(v)) => Expr::Literal(Literal::Float(-v)),
+            Expr::Literal(Literal::Duration(v)) => Expr::Literal(Literal::Duration(-v)),
+            expr => Expr::binary(Expr::Literal(Literal::Integer(0)), BinaryOp::Sub, expr),
+        })
+    }
+
+    fn primary(&mut self) -> Result<Expr> {
+        let token = self.peek()?;
+        let quoted = matches!(token, Token::QuotedIdent(_));
+        let expr = match token {
+            Token::Op("(") => {
+                self.next()?;
+                let expr = self.expr()?;
+                self.expect_op(")")?;
+                return Ok(expr);
+            }
+            Token::Op("*") => {
+                self.next()?;
+                return Ok(Expr::Wildcard);
+            }
+            Token::Integer(v) => Expr::Literal(Literal::Integer(v)),
+            Token::Float(v) => Expr::Literal(Literal::Float(v)),
+            Token::Duration(v) => Expr::Literal(Literal::Duration(v)),
+            Token::String(s) => Expr::Literal(Literal::String(s)),
+            Token::Ident(ident) if ident.eq_ignore_ascii_case("true") => {
+                Expr::Literal(Literal::Boolean(true))
+            }
+            Token::Ident(ident) if ident.eq_ignore_ascii_case("false") => {
+                Expr::Literal(Literal::Boolean(false))
+            }
+            Token::Ident(_) | Token::QuotedIdent(_) => {
+                let name = self.identifier()?;
+
+                if !quoted && self.consume_op("(")? {
+                    let mut args = vec![];
+                    if !self.consume_op(")")? {
+                        args.push(self.expr()?);
+                        while self.consume_op(",")? {
+                            args.push(self.expr()?);
+                        }
+                        self.expect_op(")")?;
+                    }
+                    return Ok(Expr::Call {
+                        name: name.to_ascii_lowercase(),
+                        args,
+                    });
+                }
+
+                // type hints such as `usage::field` don't change the result
+                if self.consume_op("::")? {
+                    self.identifier()?;
+                }
+                return Ok(Expr::VarRef(name));
+            }
+            _ => return Err(self.unexpected("expression")),
+        };
+
+        self.next()?;
+        Ok(expr)
+    }
+}
+
+fn is_reserved(ident: &str) -> bool {
+    RESERVED.iter().any(|kw| kw.eq_ignore_ascii_case(ident))
+}
+
+/// The unescaped content of the quoted string at the start of `s`, and its
+/// length including the quotes
+fn quoted(s: &str) -> Option<(String, usize)> {
+    let mut chars = s.char_indices();
+    let (_, quote) = chars.next()?;
+
+    let mut value = String::new();
+    while let Some((idx, c)) = chars.next() {
+        match c {
+            '\\' => {
+                let (_, c) = chars.next()?;
+                match c {
+                    'n' => value.push('\n'),
+                    '\\' | '\'' | '"' => value.push(c),
+                    c => {
+                        value.push('\\');
+                        value.push(c);
+                    }
+                }
+            }
+            c if c == quote => return Some((value, idx + 1)),
+            c => value.push(c),
+        }
+    }
+    None
+}
+
+/// Nanoseconds per duration unit, longest unit names first
+const DURATION_UNITS: &[(&str, i64)] = &[
+    ("ns", 1),
+    ("ms", 1_000_000),
+    ("u", 1_000),
+    ("µ", 1_000),
+    ("s", 1_000_000_000),
+    ("m", 60 * 1_000_000_000),
+    ("h", 60 * 60 * 1_000_000_000),
+    ("d", 24 * 60 * 60 * 1_000_000_000),
+    ("w", 7 * 24 * 60 * 60 * 1_000_000_000),
+];
+
+/// The number or duration such as `1h30m` at the start of `s`, and its
+/// length
+fn number(s: &str) -> Result<(Token, usize), String> {
+    let digits = |s: &str| s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    // the text up to the end of the word at `len`, for errors
+    let word = |len: usize| {
+        let rest = &s[len..];
+        &s[..len
+            + rest
+                .find(|c: char| !c.is_alphanumeric())
+                .unwrap_or(rest.len())]
+    };
+
+    let mut len = digits(s);
+    let mut is_float = false;
+    if s[len..].starts_with('.') {
+        is_float = true;
+        len += 1 + digits(&s[len + 1..]);
+    }
+    if s[len..].starts_with(|c| c == 'e' || c == 'E') {
+        let exponent = &s[len + 1..];
+        let sign = usize::from(exponent.starts_with(|c| c == '+' || c == '-'));
+        let exponent_len = digits(&exponent[sign..]);
+        if exponent_len > 0 {
+            is_float = true;
+            len += 1 + sign + exponent_len;
+        }
+    }
+
+    let text = &s[..len];
+    if is_float {
+        return text
+            .parse()
+            .map(|v| (Token::Float(v), len))
+            .map_err(|_| format!("invalid number {}", text));
+    }
+
+    let value: i64 = text
+        .parse()
+        .map_err(|_| format!("integer {} out of range", text))?;
+
+    if !s[len..].starts_with(|c: char| c.is_alphabetic()) {
+        return Ok((Token::Integer(value), len));
+    }
+
+    // a duration is a sequence of integers with units, such as `1h30m`
+    let mut total: i64 = 0;
+    let mut value = value;
+    loop {
+        let (unit, nanos) = DURATION_UNITS
+            .iter()
+            .find(|(unit, _)| s[len..].starts_with(unit))
+            .ok_or_else(|| format!("invalid duration {}", word(len)))?;
+        len += unit.len();
+
+        total = value
+            .checked_mul(*nanos)
+            .and_then(|v| total.checked_add(v))
+            .ok_or_else(|| format!("duration {} out of range", &s[..len]))?;
+
+        let next_len = digits(&s[len..]);
+        if next_len == 0 {
+            break;
+        }
+        value = s[len..len + next_len]
+            .parse()
+            .map_err(|_| format!("duration {} out of range", &s[..len + next_len]))?;
+        len += next_len;
+    }
+
+    if s[len..].starts_with(|c: char| c.is_alphanumeric() || c == '_') {
+        return Err(format!("invalid duration {}", word(len)));
+    }
+    Ok((Token::Duration(total), len))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_one(query: &str) -> Statement {
+        let mut statements = parse_statements(query).unwrap();
+        assert_eq!(statements.len(), 1);
+        statements.remove(0)
+    }
+
+    fn var(name: &str) -> Expr {
+        Expr::VarRef(name.to_string())
+    }
+
+    fn string(s: &str) -> Expr {
+        Expr::Literal(Literal::String(s.to_string()))
+    }
+
+    #[test]
+    fn select() {
+        let statement = parse_one(
+            "SELECT mean(\"usage\") AS m, max(usage) * 2 FROM \"db\".\"autogen\".cpu, /^disk/ \
+             WHERE host = 'a' AND time > now() - 1h GROUP BY time(5m, -1m), host fill(0) \
+             ORDER BY time DESC;",
+        );
+
+        let expected = SelectStatement {
+            fields: vec![
+                Field {
+                    expr: Expr::Call {
+                        name: "mean".into(),
+                        args: vec![var("usage")],
+                    },
+                    alias: Some("m".into()),
+                },
+                Field {
+                    expr: Expr::binary(
+                        Expr::Call {
+                            name: "max".into(),
+                            args: vec![var("usage")],
+                        },
+                        BinaryOp::Mul,
+                        Expr::Literal(Literal::Integer(2)),
+                    ),
+                    alias: None,
+                },
+            ],
+            sources: vec![Source::Name("cpu".into()), Source::Regex("^disk".into())],
+            condition: Some(Expr::binary(
+                Expr::binary(var("host"), BinaryOp::Eq, string("a")),
+                BinaryOp::And,
+                Expr::binary(
+                    var("time"),
+                    BinaryOp::Gt,
+                    Expr::binary(
+                        Expr::Call {
+                            name: "now".into(),
+                            args: vec![],
+                        },
+                        BinaryOp::Sub,
+                        Expr::Literal(Literal::Duration(3_600_000_000_000)),
+                    ),
+                ),
+            )),
+            dimensions: vec![
+                Dimension::Time {
+                    interval: 300_000_000_000,
+                    offset: -60_000_000_000,
+                },
+                Dimension::Tag("host".into()),
+            ],
+            fill: Some(Fill::Number(0.0)),
+            order_desc: true,
+        };
+        assert_eq!(statement, Statement::Select(expected));
+    }
+
+    #[test]
+    fn select_wildcard_and_regex_condition() {
+        let statement = parse_one("select * from cpu where host =~ /a\\/b/ or host !~ /c/");
+        let expected = SelectStatement {
+            fields: vec![Field {
+                expr: Expr::Wildcard,
+                alias: None,
+            }],
+            sources: vec![Source::Name("cpu".into())],
+            condition: Some(Expr::binary(
+                Expr::binary(
+                    var("host"),
+                    BinaryOp::RegexMatch,
+                    Expr::Literal(Literal::Regex("a/b".into())),
+                ),
+                BinaryOp::Or,
+                Expr::binary(
+                    var("host"),
+                    BinaryOp::RegexNotMatch,
+                    Expr::Literal(Literal::Regex("c".into())),
+                ),
+            )),
+            dimensions: vec![],
+            fill: None,
+            order_desc: false,
+        };
+        assert_eq!(statement, Statement::Select(expected));
+    }
+
+    #[test]
+    fn show() {
+        assert_eq!(
+            parse_one("SHOW MEASUREMENTS ON db WITH MEASUREMENT =~ /cpu/ LIMIT 10 OFFSET 2"),
+            Statement::ShowMeasurements(ShowMeasurementsStatement {
+                with_measurement: Some(Source::Regex("cpu".into())),
+                limit: Some(10),
+                offset: Some(2),
+            })
+        );
+        assert_eq!(
+            parse_one("SHOW TAG KEYS FROM cpu"),
+            Statement::ShowTagKeys(ShowTagKeysStatement {
+                sources: vec![Source::Name("cpu".into())],
+                limit: None,
+                offset: None,
+            })
+        );
+        assert_eq!(
+            parse_one("SHOW FIELD KEYS"),
+            Statement::ShowFieldKeys(ShowFieldKeysStatement {
+                sources: vec![],
+                limit: None,
+                offset: None,
+            })
+        );
+        assert_eq!(
+            parse_one("SHOW TAG VALUES FROM cpu WITH KEY IN (host, \"region\") WHERE host != 'b'"),
+            Statement::ShowTagValues(ShowTagValuesStatement {
+                sources: vec![Source::Name("cpu".into())],
+                with_key: TagKeys::In(vec!["host".into(), "region".into()]),
+                condition: Some(Expr::binary(var("host"), BinaryOp::NotEq, string("b"))),
+            })
+        );
+    }
+
+    #[test]
+    fn multiple_statements() {
+        let statements = parse_statements("SHOW MEASUREMENTS; SELECT a FROM b;").unwrap();
+        assert_eq!(statements.len(), 2);
+    }
+
+    #[test]
+    fn literals() {
+        let statement = parse_one("SELECT a FROM b WHERE c = -1.5e3 OR d = 1h30m OR e = 'it\\'s'");
+        let condition = match statement {
+            Statement::Select(select) => select.condition.unwrap(),
+            _ => panic!("not a select"),
+        };
+        assert_eq!(
+            condition,
+            Expr::binary(
+                Expr::binary(
+                    Expr::binary(
+                        var("c"),
+                        BinaryOp::Eq,
+                        Expr::Literal(Literal::Float(-1500.0))
+                    ),
+                    BinaryOp::Or,
+                    Expr::binary(
+                        var("d"),
+                        BinaryOp::Eq,
+                        Expr::Literal(Literal::Duration(5_400_000_000_000))
+                    ),
+                ),
+                BinaryOp::Or,
+                Expr::binary(var("e"), BinaryOp::Eq, string("it's")),
+            )
+        );
+    }
+
+    #[test]
+    fn errors() {
+        let cases = [
+            ("", "empty query at char 1"),
+            ("SELECT", "found EOF, expected expression at char 7"),
+            ("SELECT a", "found EOF, expected FROM at char 9"),
+            ("SELECT a FROM", "found EOF, expected identifier at char 14"),
+            (
+                "SELECT a FROM b LIMIT 1",
+                "LIMIT is not supported in SELECT",
+            ),
+            ("SELECT a FROM b GROUP BY time(5x)", "invalid duration 5x"),
+            (
+                "SELECT a FROM b WHERE c =~ 'd'",
+                "found 'd', expected regex",
+            ),
+            ("SELECT a FROM b c", "found c, expected end of statement"),
+            (
+                "DROP MEASUREMENT cpu",
+                "found DROP, expected SELECT or SHOW",
+            ),
+            (
+                "SHOW TAG KEYS WHERE a = 'b'",
+                "WHERE is not supported in SHOW TAG KEYS",
+            ),
+            ("SELECT 'a", "unterminated quote"),
+        ];
+
+        for (query, expected) in cases {
+            let error = parse_statements(query).unwrap_err().to_string();
+            assert!(error.contains(expected), "{}: {}", query, error);
+        }
+    }
+}
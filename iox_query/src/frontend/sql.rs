@@ -3,6 +3,10 @@ use std::sync::Arc;
 use crate::exec::context::IOxSessionContext;
 use datafusion::{error::Result, physical_plan::ExecutionPlan};
 
+mod params;
+
+pub use params::QueryParams;
+
 /// This struct can create plans for running SQL queries against databases
 #[derive(Debug, Default)]
 pub struct SqlQueryPlanner {}
@@ -21,4 +25,16 @@ impl SqlQueryPlanner {
     ) -> Result<Arc<dyn ExecutionPlan>> {
         ctx.prepare_sql(query).await
     }
+
+    /// Like [`query`](Self::query), with `params` bound to the placeholders
+    /// of the query.
+    pub async fn query_with_params(
+        &self,
+        query: &str,
+        params: &QueryParams,
+        ctx: &IOxSessionContext,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        let query = params.bind(query)?;
+        self.query(&query, ctx).await
+    }
 }
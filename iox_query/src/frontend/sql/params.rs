@@ -0,0 +1,289 @@
+//! Parameters bound to the placeholders of SQL queries
+
+use std::{collections::HashMap, iter::Peekable, str::CharIndices};
+
+use datafusion::{
+    error::{DataFusionError, Result},
+    scalar::ScalarValue,
+};
+
+/// Values for the `$name` and `$1`, `$2`, ... placeholders of a SQL query.
+///
+/// The version of DataFusion IOx uses can't plan placeholders, so the values
+/// are bound by replacing each placeholder with a SQL literal of its value
+/// before planning. The query text is tokenized well enough that
+/// placeholders in string literals, quoted identifiers and comments are left
+/// alone, and string values are always quoted, so values can't change the
+/// structure of the query.
+///
+/// Binding doesn't produce a reusable plan: the bound query is planned like
+/// any other query every time it runs.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct QueryParams {
+    named: HashMap<String, ScalarValue>,
+    positional: Vec<ScalarValue>,
+}
+
+impl QueryParams {
+    /// Create empty parameters
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Return true if there are no parameters
+    pub fn is_empty(&self) -> bool {
+        self.named.is_empty() && self.positional.is_empty()
+    }
+
+    /// Bind `value` to the `$name` placeholders
+    pub fn with_named(mut self, name: impl Into<String>, value: ScalarValue) -> Self {
+        self.named.insert(name.into(), value);
+        self
+    }
+
+    /// Bind `value` to the next positional placeholder, the first value to
+    /// `$1`
+    pub fn with_positional(mut self, value: ScalarValue) -> Self {
+        self.positional.push(value);
+        self
+    }
+
+    /// Return `sql` with each placeholder replaced by its value. Parameters
+    /// without a placeholder are ignored.
+    pub fn bind(&self, sql: &str) -> Result<String> {
+        if self.is_empty() {
+            return Ok(sql.to_string());
+        }
+
+        let mut bound = String::with_capacity(sql.len());
+        let mut chars = sql.char_indices().peekable();
+
+        while let Some((idx, c)) = chars.next() {
+            match c {
+                '\'' | '"' => {
+                    bound.push(c);
+                    copy_quoted(&mut chars, c, &mut bound);
+                }
+                '-' if next_is(&mut chars, '-') => {
+                    bound.push(c);
+                    copy_until(&mut chars, "\n", sql, idx + 2, &mut bound);
+                }
+                '/' if next_is(&mut chars, '*') => {
+                    bound.push(c);
+                    copy_block_comment(&mut chars, &mut bound);
+                }
+                '$' => {
+                    let start = idx + 1;
+                    let mut end = start;
+                    while let Some((idx, c)) = chars.peek() {
+                        if !(c.is_alphanumeric() || *c == '_') {
+                            break;
+                        }
+                        end = idx + c.len_utf8();
+                        chars.next();
+                    }
+
+                    let name = &sql[start..end];
+                    if name.is_empty() {
+                        bound.push(c);
+                    } else {
+                        bound.push_str(&literal(name, self.value(name)?)?);
+                    }
+                }
+                _ => bound.push(c),
+            }
+        }
+
+        Ok(bound)
+    }
+
+    fn value(&self, name: &str) -> Result<&ScalarValue> {
+        let value = match name.parse::<usize>() {
+            Ok(position) => position
+                .checked_sub(1)
+                .and_then(|idx| self.positional.get(idx)),
+            Err(_) => self.named.get(name),
+        };
+
+        value.ok_or_else(|| DataFusionError::Plan(format!("No value for parameter ${}", name)))
+    }
+}
+
+fn next_is(chars: &mut Peekable<CharIndices<'_>>, expected: char) -> bool {
+    matches!(chars.peek(), Some((_, c)) if *c == expected)
+}
+
+/// Copies a string literal or quoted identifier up to and including its
+/// closing `quote`, a doubled quote being an escaped one
+fn copy_quoted(chars: &mut Peekable<CharIndices<'_>>, quote: char, bound: &mut String) {
+    while let Some((_, c)) = chars.next() {
+        bound.push(c);
+        if c == quote {
+            if next_is(chars, quote) {
+                chars.next();
+                bound.push(quote);
+            } else {
+                return;
+            }
+        }
+    }
+}
+
+/// Copies a line comment whose text starts at `start` up to and including
+/// the `end` of the comment, or the rest of `sql`
+fn copy_until(
+    chars: &mut Peekable<CharIndices<'_>>,
+    end: &str,
+    sql: &str,
+    start: usize,
+    bound: &mut String,
+) {
+    for (idx, c) in chars.by_ref() {
+        bound.push(c);
+        if sql[start..idx + c.len_utf8()].ends_with(end) {
+            return;
+        }
+    }
+}
+
+/// Copies a block comment up to and including its closing `*/`, or the rest
+/// of the query. Like sqlparser, block comments nest.
+fn copy_block_comment(chars: &mut Peekable<CharIndices<'_>>, bound: &mut String) {
+    // the `*` of the opening `/*`
+    if let Some((_, c)) = chars.next() {
+        bound.push(c);
+    }
+
+    let mut depth = 1;
+    while let Some((_, c)) = chars.next() {
+        bound.push(c);
+        match c {
+            '/' if next_is(chars, '*') => {
+                chars.next();
+                bound.push('*');
+                depth += 1;
+            }
+            '*' if next_is(chars, '/') => {
+                chars.next();
+                bound.push('/');
+                depth -= 1;
+                if depth == 0 {
+                    return;
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// The SQL literal of `value`
+fn literal(name: &str, value: &ScalarValue) -> Result<String> {
+    if value.is_null() {
+        return Ok("NULL".to_string());
+    }
+
+    Ok(match value {
+        ScalarValue::Boolean(Some(true)) => "TRUE".to_string(),
+        ScalarValue::Boolean(Some(false)) => "FALSE".to_string(),
+        // parenthesized so that `x - $1` stays a subtraction
+        ScalarValue::Int64(Some(v)) if *v < 0 => format!("({})", v),
+        ScalarValue::Int64(Some(v)) => v.to_string(),
+        ScalarValue::UInt64(Some(v)) => v.to_string(),
+        // casting text also covers NaN, infinity and exponents
+        ScalarValue::Float64(Some(v)) => format!("CAST('{:?}' AS DOUBLE)", v),
+        ScalarValue::Utf8(Some(v)) => format!("'{}'", v.replace('\'', "''")),
+        _ => {
+            return Err(DataFusionError::Plan(format!(
+                "Unsupported type {} of parameter ${}",
+                value.get_datatype(),
+                name
+            )))
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn binds_named_and_positional_parameters() {
+        let params = QueryParams::new()
+            .with_named("host", ScalarValue::Utf8(Some("server01".to_string())))
+            .with_named("min", ScalarValue::Float64(Some(0.5)))
+            .with_positional(ScalarValue::Int64(Some(-10)))
+            .with_positional(ScalarValue::Boolean(Some(true)))
+            .with_positional(ScalarValue::Utf8(None));
+
+        let sql = "SELECT * FROM cpu WHERE host = $host AND usage > $min \
+                   AND x - $1 > 0 AND $2 OR y IS $3";
+        assert_eq!(
+            params.bind(sql).unwrap(),
+            "SELECT * FROM cpu WHERE host = 'server01' AND usage > CAST('0.5' AS DOUBLE) \
+             AND x - (-10) > 0 AND TRUE OR y IS NULL"
+        );
+    }
+
+    #[test]
+    fn leaves_literals_identifiers_and_comments_alone() {
+        let params = QueryParams::new().with_named("a", ScalarValue::UInt64(Some(1)));
+
+        let sql = "SELECT '$a', 'it''s $a', \"$a\" -- $a\n, $a /* $a */ FROM t WHERE c = $a";
+        assert_eq!(
+            params.bind(sql).unwrap(),
+            "SELECT '$a', 'it''s $a', \"$a\" -- $a\n, 1 /* $a */ FROM t WHERE c = 1"
+        );
+
+        // block comments nest
+        assert_eq!(
+            params
+                .bind("SELECT /* /* $a */ $a */ $a, /*/ $a */ $a")
+                .unwrap(),
+            "SELECT /* /* $a */ $a */ 1, /*/ $a */ 1"
+        );
+
+        // a lone dollar sign is not a placeholder
+        assert_eq!(params.bind("SELECT '$' || $").unwrap(), "SELECT '$' || $");
+    }
+
+    #[test]
+    fn quotes_string_values() {
+        let params = QueryParams::new().with_named(
+            "host",
+            ScalarValue::Utf8(Some("a'; DROP TABLE cpu; --".to_string())),
+        );
+
+        assert_eq!(
+            params.bind("SELECT * FROM cpu WHERE host = $host").unwrap(),
+            "SELECT * FROM cpu WHERE host = 'a''; DROP TABLE cpu; --'"
+        );
+    }
+
+    #[test]
+    fn reports_missing_and_unsupported_parameters() {
+        let params = QueryParams::new()
+            .with_positional(ScalarValue::Int32(Some(1)))
+            .with_named("a", ScalarValue::Int64(Some(1)));
+
+        let error = params.bind("SELECT $b").unwrap_err().to_string();
+        assert!(error.contains("No value for parameter $b"), "{}", error);
+
+        let error = params.bind("SELECT $2").unwrap_err().to_string();
+        assert!(error.contains("No value for parameter $2"), "{}", error);
+
+        let error = params.bind("SELECT $0").unwrap_err().to_string();
+        assert!(error.contains("No value for parameter $0"), "{}", error);
+
+        let error = params.bind("SELECT $1").unwrap_err().to_string();
+        assert!(
+            error.contains("Unsupported type Int32 of parameter $1"),
+            "{}",
+            error
+        );
+    }
+
+    #[test]
+    fn empty_parameters_leave_the_query_alone() {
+        assert_eq!(QueryParams::new().bind("SELECT $1").unwrap(), "SELECT $1");
+    }
+}
@@ -6,9 +6,9 @@ use arrow::{
 };
 use data_types::{
     Column, ColumnSet, ColumnType, CompactionLevel, KafkaPartition, KafkaTopic, Namespace,
-    NamespaceSchema, ParquetFile, ParquetFileParams, Partition, PartitionId, QueryPool,
-    SequenceNumber, Sequencer, SequencerId, Table, TableId, TableSchema, Timestamp, Tombstone,
-    TombstoneId,
+    NamespaceSchema, ParquetFile, ParquetFileParams, Partition, PartitionId, PartitionTemplate,
+    QueryPool, SequenceNumber, Sequencer, SequencerId, Table, TableId, TableSchema, Timestamp,
+    Tombstone, TombstoneId,
 };
 use datafusion::physical_plan::metrics::Count;
 use iox_catalog::{
@@ -389,6 +389,34 @@ impl TestTableBoundSequencer {
         })
     }
 
+    /// Create a partition for the table whose key was derived with `partition_template`
+    pub async fn create_partition_with_template(
+        self: &Arc<Self>,
+        key: &str,
+        partition_template: &PartitionTemplate,
+    ) -> Arc<TestPartition> {
+        let mut repos = self.catalog.catalog.repositories().await;
+
+        let partition = repos
+            .partitions()
+            .create_or_get_with_template(
+                key.into(),
+                self.sequencer.sequencer.id,
+                self.table.table.id,
+                Some(partition_template),
+            )
+            .await
+            .unwrap();
+
+        Arc::new(TestPartition {
+            catalog: Arc::clone(&self.catalog),
+            namespace: Arc::clone(&self.namespace),
+            table: Arc::clone(&self.table),
+            sequencer: Arc::clone(&self.sequencer),
+            partition,
+        })
+    }
+
     /// Creat a partition with a specified sort key for the table
     pub async fn create_partition_with_sort_key(
         self: &Arc<Self>,
@@ -0,0 +1,1135 @@
+//! HTTP API of the querier, so that scripts and lightweight integrations can
+//! run SQL and InfluxQL queries without a Flight client.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    io::Write,
+    sync::{Arc, Mutex},
+};
+
+use arrow::{
+    array::{
+        as_boolean_array, as_primitive_array, as_string_array, Array, ArrayRef,
+        TimestampNanosecondArray,
+    },
+    datatypes::{DataType, Float64Type, Int64Type, Schema, SchemaRef, TimeUnit, UInt64Type},
+    error::ArrowError,
+    ipc::writer::StreamWriter,
+    record_batch::RecordBatch,
+    util::display::array_value_to_string,
+};
+use arrow_util::optimize::{optimize_record_batch, optimize_schema};
+use bytes::Bytes;
+use chrono::SecondsFormat;
+use data_types::{db_and_rp_to_database, DbRpMappingError};
+use datafusion::{error::DataFusionError, physical_plan::SendableRecordBatchStream};
+use futures::{Stream, StreamExt};
+use hyper::{header::CONTENT_TYPE, Body, Method, Request, Response, StatusCode};
+use iox_query::{
+    exec::{ExecutionContextProvider, IOxSessionContext},
+    frontend::influxql::{ast::Statement, InfluxQLPlanner, MEASUREMENT_COLUMN_NAME},
+    QueryCompletedToken, QueryDatabase,
+};
+use iox_time::Time;
+use ioxd_common::http::{
+    error::{HttpApiError, HttpApiErrorCode, HttpApiErrorSource},
+    utils::{parse_body, ParseBodyError},
+};
+use observability_deps::tracing::info;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use service_common::{planner::Planner, QueryDatabaseProvider};
+use thiserror::Error;
+use trace::{ctx::SpanContext, span::SpanExt};
+use tracker::InstrumentedAsyncOwnedSemaphorePermit;
+
+/// Path of the SQL query endpoint
+pub const QUERY_SQL_PATH: &str = "/api/v3/query_sql";
+
+/// Path of the InfluxQL query endpoint, compatible with InfluxDB 1.x
+pub const QUERY_INFLUXQL_PATH: &str = "/query";
+
+/// Largest accepted body of a query request
+const MAX_REQUEST_SIZE: usize = 1024 * 1024;
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Method {0} is not allowed, use GET or POST")]
+    MethodNotAllowed(Method),
+
+    #[error("Invalid query parameters: {0}")]
+    InvalidParams(#[from] serde_urlencoded::de::Error),
+
+    #[error("Invalid JSON request body: {0}")]
+    InvalidJson(#[from] serde_json::Error),
+
+    #[error("Cannot read request body: {0}")]
+    ReadingBody(#[from] ParseBodyError),
+
+    #[error("Invalid database: {0}")]
+    InvalidDbRp(#[from] DbRpMappingError),
+
+    #[error("Namespace {0} not found")]
+    NamespaceNotFound(String),
+
+    #[error("Error while planning query: {0}")]
+    Planning(DataFusionError),
+
+    #[error("Error while executing query: {0}")]
+    Execution(DataFusionError),
+
+    #[error("Error encoding query results: {0}")]
+    Encoding(#[from] ArrowError),
+}
+
+impl HttpApiErrorSource for Error {
+    fn to_http_api_error(&self) -> HttpApiError {
+        let code = match self {
+            Self::MethodNotAllowed(_) => HttpApiErrorCode::MethodNotAllowed,
+            Self::InvalidParams(_) | Self::InvalidJson(_) | Self::InvalidDbRp(_) => {
+                HttpApiErrorCode::Invalid
+            }
+            Self::ReadingBody(e) => return e.to_http_api_error(),
+            Self::NamespaceNotFound(_) => HttpApiErrorCode::NotFound,
+            Self::Planning(DataFusionError::External(_)) => HttpApiErrorCode::InternalError,
+            Self::Planning(_) => HttpApiErrorCode::Invalid,
+            Self::Execution(_) | Self::Encoding(_) => HttpApiErrorCode::InternalError,
+        };
+        HttpApiError::new(code, self.to_string())
+    }
+}
+
+/// Output format of query results
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum QueryFormat {
+    /// Comma separated values with a header line
+    Csv,
+    /// A JSON object per row, one per line
+    Json,
+    /// The Arrow IPC streaming format
+    Arrow,
+}
+
+impl Default for QueryFormat {
+    fn default() -> Self {
+        Self::Json
+    }
+}
+
+impl QueryFormat {
+    fn content_type(&self) -> &'static str {
+        match self {
+            Self::Csv => "text/csv",
+            Self::Json => "application/jsonl",
+            Self::Arrow => "application/vnd.apache.arrow.stream",
+        }
+    }
+}
+
+/// Parameters of a query request, from the query string of a `GET` or the
+/// form or JSON body of a `POST`
+#[derive(Debug, PartialEq, Deserialize)]
+struct QueryParams {
+    /// The namespace to query
+    db: String,
+
+    /// The SQL query
+    q: String,
+
+    #[serde(default)]
+    format: QueryFormat,
+}
+
+impl QueryParams {
+    async fn from_request(req: Request<Body>) -> Result<Self, Error> {
+        let query = req.uri().query().unwrap_or_default().to_string();
+
+        let method = req.method().clone();
+        match method {
+            Method::GET => Ok(serde_urlencoded::from_str(&query)?),
+            Method::POST => {
+                let is_json = req
+                    .headers()
+                    .get(CONTENT_TYPE)
+                    .and_then(|v| v.to_str().ok())
+                    .map_or(false, |v| v.starts_with("application/json"));
+
+                let body = parse_body(req, MAX_REQUEST_SIZE).await?;
+                if body.is_empty() {
+                    Ok(serde_urlencoded::from_str(&query)?)
+                } else if is_json {
+                    Ok(serde_json::from_slice(&body)?)
+                } else {
+                    Ok(serde_urlencoded::from_bytes(&body)?)
+                }
+            }
+            method => Err(Error::MethodNotAllowed(method)),
+        }
+    }
+}
+
+/// Runs the SQL query of `req` against a namespace of `server`, streaming
+/// the results in chunks as they are produced.
+pub async fn query_sql<S>(server: &S, req: Request<Body>) -> Result<Response<Body>, Error>
+where
+    S: QueryDatabaseProvider,
+{
+    let span_ctx: Option<SpanContext> = req.extensions().get().cloned();
+    let params = QueryParams::from_request(req).await?;
+
+    let permit = server
+        .acquire_semaphore(span_ctx.child_span("query rate limit semaphore"))
+        .await;
+    info!(db_name=%params.db, sql_query=%params.q, "http query_sql");
+
+    let db = server
+        .db(&params.db, span_ctx.child_span("get namespace"))
+        .await
+        .ok_or_else(|| Error::NamespaceNotFound(params.db.clone()))?;
+
+    let ctx = db.new_query_context(span_ctx);
+    let query_completed_token = db.record_query(&ctx, "sql", Box::new(params.q.clone()));
+
+    let physical_plan = Planner::new(&ctx)
+        .sql(&params.q)
+        .await
+        .map_err(Error::Planning)?;
+    let batches = ctx
+        .execute_stream(physical_plan)
+        .await
+        .map_err(Error::Execution)?;
+    // tags are sent as plain strings rather than dictionaries, as over Flight
+    let schema = Arc::new(optimize_schema(&batches.schema()));
+    let encoder = Encoder::try_new(params.format, &schema)?;
+
+    let body = encode(batches, schema, encoder, query_completed_token, permit);
+
+    Ok(Response::builder()
+        .header(CONTENT_TYPE, params.format.content_type())
+        .body(Body::wrap_stream(body))
+        .expect("valid response"))
+}
+
+/// Parameters of an InfluxDB 1.x query request, from the query string and
+/// the form body of a `POST`
+#[derive(Debug, PartialEq, Deserialize)]
+struct InfluxQLParams {
+    /// The database to query
+    db: String,
+
+    /// The retention policy of the database
+    #[serde(default)]
+    rp: String,
+
+    /// The `;` separated InfluxQL statements
+    q: String,
+
+    /// Unit of integer timestamps, RFC 3339 strings if not set
+    epoch: Option<Epoch>,
+
+    /// Return the results as a JSON object per line, each with a chunk of
+    /// the rows of a series
+    #[serde(default)]
+    chunked: bool,
+
+    /// The most rows of a chunk, [`DEFAULT_CHUNK_SIZE`] if not set
+    chunk_size: Option<usize>,
+}
+
+/// Rows per chunk of chunked InfluxQL results, as in InfluxDB 1.x
+const DEFAULT_CHUNK_SIZE: usize = 10_000;
+
+impl InfluxQLParams {
+    async fn from_request(req: Request<Body>) -> Result<Self, Error> {
+        let query = req.uri().query().unwrap_or_default().to_string();
+        let mut params: HashMap<String, String> = serde_urlencoded::from_str(&query)?;
+
+        let method = req.method().clone();
+        match method {
+            Method::GET => {}
+            Method::POST => {
+                // parameters of the form take precedence over the query string
+                let body = parse_body(req, MAX_REQUEST_SIZE).await?;
+                let form: Vec<(String, String)> = serde_urlencoded::from_bytes(&body)?;
+                params.extend(form);
+            }
+            method => return Err(Error::MethodNotAllowed(method)),
+        }
+
+        let params = serde_urlencoded::to_string(params).expect("strings are encodable");
+        Ok(serde_urlencoded::from_str(&params)?)
+    }
+
+    /// The namespace of the database and retention policy
+    fn namespace(&self) -> Result<String, Error> {
+        Ok(db_and_rp_to_database(&self.db, &self.rp)?.into())
+    }
+}
+
+/// Unit of integer timestamps in InfluxQL results
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+enum Epoch {
+    #[serde(rename = "ns", alias = "n")]
+    Nanoseconds,
+    #[serde(rename = "u", alias = "µ")]
+    Microseconds,
+    #[serde(rename = "ms")]
+    Milliseconds,
+    #[serde(rename = "s")]
+    Seconds,
+    #[serde(rename = "m")]
+    Minutes,
+    #[serde(rename = "h")]
+    Hours,
+}
+
+impl Epoch {
+    fn nanoseconds(&self) -> i64 {
+        match self {
+            Self::Nanoseconds => 1,
+            Self::Microseconds => 1_000,
+            Self::Milliseconds => 1_000_000,
+            Self::Seconds => 1_000_000_000,
+            Self::Minutes => 60 * 1_000_000_000,
+            Self::Hours => 60 * 60 * 1_000_000_000,
+        }
+    }
+}
+
+/// Body of an InfluxDB 1.x query response
+#[derive(Debug, Serialize)]
+struct InfluxQLResponse {
+    results: Vec<StatementResult>,
+}
+
+/// The results of one statement of an InfluxQL query
+#[derive(Debug, Serialize)]
+struct StatementResult {
+    statement_id: usize,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    series: Vec<Series>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+    /// True if more chunks of the results follow
+    #[serde(skip_serializing_if = "is_false")]
+    partial: bool,
+}
+
+impl StatementResult {
+    /// Splits the results into chunks of a series of at most `chunk_size`
+    /// rows each
+    fn into_chunks(self, chunk_size: usize) -> Vec<Self> {
+        if self.series.is_empty() {
+            return vec![self];
+        }
+
+        let statement_id = self.statement_id;
+        let mut chunks = vec![];
+        for series in self.series {
+            let mut values = series.values.into_iter().peekable();
+            loop {
+                let chunk: Vec<_> = values.by_ref().take(chunk_size).collect();
+                let partial = values.peek().is_some();
+                chunks.push(Self {
+                    statement_id,
+                    series: vec![Series {
+                        name: series.name.clone(),
+                        tags: series.tags.clone(),
+                        columns: series.columns.clone(),
+                        values: chunk,
+                        partial,
+                    }],
+                    error: None,
+                    partial: true,
+                });
+                if !partial {
+                    break;
+                }
+            }
+        }
+
+        if let Some(last) = chunks.last_mut() {
+            last.partial = false;
+        }
+        chunks
+    }
+}
+
+/// The rows of a measurement with the same `GROUP BY` tag values
+#[derive(Debug, PartialEq, Serialize)]
+struct Series {
+    name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tags: Option<BTreeMap<String, String>>,
+    columns: Vec<String>,
+    values: Vec<Vec<Value>>,
+    /// True if more rows of the series follow in the next chunk
+    #[serde(skip_serializing_if = "is_false")]
+    partial: bool,
+}
+
+fn is_false(value: &bool) -> bool {
+    !value
+}
+
+/// Runs the InfluxQL query of `req` against a namespace of `server`, and
+/// returns the results in the JSON format of the InfluxDB 1.x `/query`
+/// endpoint, as a line per chunk of rows with `chunked=true`.
+///
+/// Errors of a statement are reported in its results, like InfluxDB 1.x does.
+pub async fn query_influxql<S>(server: &S, req: Request<Body>) -> Result<Response<Body>, Error>
+where
+    S: QueryDatabaseProvider,
+{
+    let span_ctx: Option<SpanContext> = req.extensions().get().cloned();
+    let params = InfluxQLParams::from_request(req).await?;
+
+    let statements = match InfluxQLPlanner::parse(&params.q) {
+        Ok(statements) => statements,
+        Err(e) => {
+            let body = serde_json::json!({ "error": e.to_string() });
+            return Ok(json_response(StatusCode::BAD_REQUEST, &body));
+        }
+    };
+
+    let _permit = server
+        .acquire_semaphore(span_ctx.child_span("query rate limit semaphore"))
+        .await;
+    let namespace = params.namespace()?;
+    info!(db_name=%namespace, influxql_query=%params.q, "http query_influxql");
+
+    let db = match server
+        .db(&namespace, span_ctx.child_span("get namespace"))
+        .await
+    {
+        Some(db) => db,
+        None => {
+            let results = InfluxQLResponse {
+                results: vec![StatementResult {
+                    statement_id: 0,
+                    series: vec![],
+                    error: Some(format!("database not found: {}", namespace)),
+                    partial: false,
+                }],
+            };
+            return Ok(json_response(StatusCode::OK, &results));
+        }
+    };
+
+    let ctx = db.new_query_context(span_ctx);
+    let query_completed_token = db.record_query(&ctx, "influxql", Box::new(params.q.clone()));
+
+    let mut results = Vec::with_capacity(statements.len());
+    let mut success = true;
+    for (statement_id, statement) in statements.into_iter().enumerate() {
+        let result = match run_influxql_statement(&ctx, Arc::clone(&db), statement).await {
+            Ok((batches, group_by)) => StatementResult {
+                statement_id,
+                series: series(&batches, &group_by, params.epoch),
+                error: None,
+                partial: false,
+            },
+            Err(e) => {
+                success = false;
+                let message = match e {
+                    DataFusionError::Plan(message) => message,
+                    e => e.to_string(),
+                };
+                StatementResult {
+                    statement_id,
+                    series: vec![],
+                    error: Some(message),
+                    partial: false,
+                }
+            }
+        };
+        results.push(result);
+    }
+
+    if success {
+        query_completed_token.set_success();
+    }
+
+    if params.chunked {
+        let chunk_size = params
+            .chunk_size
+            .filter(|size| *size > 0)
+            .unwrap_or(DEFAULT_CHUNK_SIZE);
+        return Ok(chunked_response(results, chunk_size));
+    }
+    Ok(json_response(StatusCode::OK, &InfluxQLResponse { results }))
+}
+
+/// A response with a line for each chunk of `results`, like the chunked
+/// responses of InfluxDB 1.x
+fn chunked_response(results: Vec<StatementResult>, chunk_size: usize) -> Response<Body> {
+    let mut body = vec![];
+    for result in results {
+        for chunk in result.into_chunks(chunk_size) {
+            let response = InfluxQLResponse {
+                results: vec![chunk],
+            };
+            serde_json::to_writer(&mut body, &response).expect("results are serializable");
+            body.push(b'\n');
+        }
+    }
+
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(CONTENT_TYPE, "application/json")
+        .body(Body::from(body))
+        .expect("valid response")
+}
+
+/// Plans and runs `statement`, returning its results and `GROUP BY` tags
+async fn run_influxql_statement<D>(
+    ctx: &IOxSessionContext,
+    db: Arc<D>,
+    statement: Statement,
+) -> Result<(Vec<RecordBatch>, Vec<String>), DataFusionError>
+where
+    D: QueryDatabase + 'static,
+{
+    let plan = Planner::new(ctx).influxql(db, statement).await?;
+    let physical_plan = ctx.create_physical_plan(&plan.plan).await?;
+    let batches = ctx.collect(physical_plan).await?;
+    Ok((batches, plan.group_by))
+}
+
+fn json_response(status: StatusCode, body: &impl Serialize) -> Response<Body> {
+    Response::builder()
+        .status(status)
+        .header(CONTENT_TYPE, "application/json")
+        .body(Body::from(
+            serde_json::to_vec(body).expect("results are serializable"),
+        ))
+        .expect("valid response")
+}
+
+/// Splits the rows of the results of an InfluxQL statement, which are sorted
+/// by measurement and `group_by` tags, into series
+fn series(batches: &[RecordBatch], group_by: &[String], epoch: Option<Epoch>) -> Vec<Series> {
+    let mut series: Vec<Series> = vec![];
+
+    for batch in batches {
+        let schema = batch.schema();
+        let measurements = schema
+            .index_of(MEASUREMENT_COLUMN_NAME)
+            .ok()
+            .map(|idx| batch.column(idx));
+        let tags: Vec<_> = group_by
+            .iter()
+            .map(|tag| schema.index_of(tag).ok().map(|idx| batch.column(idx)))
+            .collect();
+        let columns: Vec<_> = schema
+            .fields()
+            .iter()
+            .zip(batch.columns())
+            .filter(|(field, _)| {
+                field.name() != MEASUREMENT_COLUMN_NAME && !group_by.contains(field.name())
+            })
+            .collect();
+
+        for row in 0..batch.num_rows() {
+            let name = measurements
+                .map(|array| string_value(array, row))
+                .unwrap_or_default();
+            let tags = (!group_by.is_empty()).then(|| {
+                group_by
+                    .iter()
+                    .zip(&tags)
+                    .map(|(tag, array)| {
+                        let value = array
+                            .map(|array| string_value(array, row))
+                            .unwrap_or_default();
+                        (tag.clone(), value)
+                    })
+                    .collect()
+            });
+
+            let values = columns
+                .iter()
+                .map(|(_, array)| json_value(array, row, epoch))
+                .collect();
+
+            match series.last_mut() {
+                Some(last) if last.name == name && last.tags == tags => last.values.push(values),
+                _ => series.push(Series {
+                    name,
+                    tags,
+                    columns: columns
+                        .iter()
+                        .map(|(field, _)| field.name().clone())
+                        .collect(),
+                    values: vec![values],
+                    partial: false,
+                }),
+            }
+        }
+    }
+
+    series
+}
+
+fn string_value(array: &ArrayRef, row: usize) -> String {
+    if array.is_null(row) {
+        return String::new();
+    }
+    array_value_to_string(array, row).unwrap_or_default()
+}
+
+/// The JSON value of `row` of `array`
+fn json_value(array: &ArrayRef, row: usize, epoch: Option<Epoch>) -> Value {
+    if array.is_null(row) {
+        return Value::Null;
+    }
+
+    match array.data_type() {
+        DataType::Int64 => as_primitive_array::<Int64Type>(array).value(row).into(),
+        DataType::UInt64 => as_primitive_array::<UInt64Type>(array).value(row).into(),
+        DataType::Float64 => as_primitive_array::<Float64Type>(array).value(row).into(),
+        DataType::Boolean => as_boolean_array(array).value(row).into(),
+        DataType::Utf8 => as_string_array(array).value(row).into(),
+        DataType::Timestamp(TimeUnit::Nanosecond, _) => {
+            let nanos = array
+                .as_any()
+                .downcast_ref::<TimestampNanosecondArray>()
+                .expect("timestamp array")
+                .value(row);
+            match epoch {
+                Some(epoch) => (nanos / epoch.nanoseconds()).into(),
+                None => Time::from_timestamp_nanos(nanos)
+                    .date_time()
+                    .to_rfc3339_opts(SecondsFormat::AutoSi, true)
+                    .into(),
+            }
+        }
+        _ => array_value_to_string(array, row).map_or(Value::Null, Value::String),
+    }
+}
+
+type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+/// State of the stream of encoded results
+struct EncodeState {
+    batches: SendableRecordBatchStream,
+    /// The optimized schema of `batches`
+    schema: SchemaRef,
+    encoder: Encoder,
+    query_completed_token: QueryCompletedToken,
+    /// Held until all results are sent
+    #[allow(dead_code)]
+    permit: InstrumentedAsyncOwnedSemaphorePermit,
+    done: bool,
+}
+
+/// Encodes `batches` one by one, recording the query as successful once all
+/// results are encoded
+fn encode(
+    batches: SendableRecordBatchStream,
+    schema: SchemaRef,
+    encoder: Encoder,
+    query_completed_token: QueryCompletedToken,
+    permit: InstrumentedAsyncOwnedSemaphorePermit,
+) -> impl Stream<Item = Result<Bytes, BoxError>> + Send + 'static {
+    let state = EncodeState {
+        batches,
+        schema,
+        encoder,
+        query_completed_token,
+        permit,
+        done: false,
+    };
+
+    futures::stream::unfold(state, |mut state| async move {
+        if state.done {
+            return None;
+        }
+
+        let chunk = match state.batches.next().await {
+            Some(Ok(batch)) => optimize_record_batch(&batch, Arc::clone(&state.schema))
+                .and_then(|batch| state.encoder.encode(&batch))
+                .map_err(Into::into),
+            Some(Err(e)) => {
+                state.done = true;
+                Err(e.into())
+            }
+            None => {
+                state.done = true;
+                let chunk = state.encoder.finish();
+                if chunk.is_ok() {
+                    state.query_completed_token.set_success();
+                }
+                chunk.map_err(Into::into)
+            }
+        };
+
+        Some((chunk, state))
+    })
+}
+
+/// A buffer shared with a writer, so that its output can be taken while the
+/// writer is still in use
+#[derive(Debug, Clone, Default)]
+struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+impl SharedBuffer {
+    fn take(&self) -> Bytes {
+        std::mem::take(&mut *self.0.lock().expect("not poisoned")).into()
+    }
+}
+
+impl Write for SharedBuffer {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().expect("not poisoned").write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Encodes record batches in one of the [`QueryFormat`]s
+enum Encoder {
+    Csv {
+        with_header: bool,
+    },
+    Json,
+    Arrow {
+        writer: StreamWriter<SharedBuffer>,
+        buffer: SharedBuffer,
+    },
+}
+
+impl Encoder {
+    fn try_new(format: QueryFormat, schema: &Schema) -> Result<Self, ArrowError> {
+        Ok(match format {
+            QueryFormat::Csv => Self::Csv { with_header: true },
+            QueryFormat::Json => Self::Json,
+            QueryFormat::Arrow => {
+                let buffer = SharedBuffer::default();
+                let writer = StreamWriter::try_new(buffer.clone(), schema)?;
+                Self::Arrow { writer, buffer }
+            }
+        })
+    }
+
+    /// The encoding of `batch`
+    fn encode(&mut self, batch: &RecordBatch) -> Result<Bytes, ArrowError> {
+        let mut bytes = vec![];
+
+        match self {
+            Self::Csv { with_header } => {
+                let mut writer = arrow::csv::WriterBuilder::new()
+                    .has_headers(*with_header)
+                    .build(&mut bytes);
+                writer.write(batch)?;
+                *with_header = false;
+            }
+            Self::Json => {
+                let mut writer = arrow::json::LineDelimitedWriter::new(&mut bytes);
+                writer.write_batches(std::slice::from_ref(batch))?;
+                writer.finish()?;
+            }
+            Self::Arrow { writer, buffer } => {
+                writer.write(batch)?;
+                return Ok(buffer.take());
+            }
+        }
+
+        Ok(bytes.into())
+    }
+
+    /// Any output left once all batches are encoded
+    fn finish(&mut self) -> Result<Bytes, ArrowError> {
+        match self {
+            Self::Csv { .. } | Self::Json => Ok(Bytes::new()),
+            Self::Arrow { writer, buffer } => {
+                writer.finish()?;
+                Ok(buffer.take())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::ipc::reader::StreamReader;
+    use arrow_util::assert_batches_eq;
+    use iox_query::test::TestChunk;
+    use service_common::test_util::TestDatabaseStore;
+
+    const QUERY: &str = "SELECT 1 AS n, 'a' AS s UNION ALL SELECT 2, 'b' ORDER BY n";
+
+    async fn query(server: &TestDatabaseStore, req: Request<Body>) -> (String, Bytes) {
+        let response = query_sql(server, req).await.unwrap();
+        let content_type = response.headers()[CONTENT_TYPE]
+            .to_str()
+            .unwrap()
+            .to_string();
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        (content_type, body)
+    }
+
+    fn get(query: &str) -> Request<Body> {
+        Request::get(format!("{}?{}", QUERY_SQL_PATH, query))
+            .body(Body::empty())
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn query_formats() {
+        let server = TestDatabaseStore::new();
+        server.db_or_create("my_db").await;
+        let q = serde_urlencoded::to_string([("db", "my_db"), ("q", QUERY)]).unwrap();
+
+        let (content_type, body) = query(&server, get(&q)).await;
+        assert_eq!(content_type, "application/jsonl");
+        assert_eq!(body, "{\"n\":1,\"s\":\"a\"}\n{\"n\":2,\"s\":\"b\"}\n");
+
+        let (content_type, body) = query(&server, get(&format!("{}&format=csv", q))).await;
+        assert_eq!(content_type, "text/csv");
+        assert_eq!(body, "n,s\n1,a\n2,b\n");
+
+        let (content_type, body) = query(&server, get(&format!("{}&format=arrow", q))).await;
+        assert_eq!(content_type, "application/vnd.apache.arrow.stream");
+        let batches = StreamReader::try_new(body.as_ref(), None)
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_batches_eq!(
+            &[
+                "+---+---+",
+                "| n | s |",
+                "+---+---+",
+                "| 1 | a |",
+                "| 2 | b |",
+                "+---+---+"
+            ],
+            &batches
+        );
+    }
+
+    #[tokio::test]
+    async fn query_formats_with_tags() {
+        let server = influxql_server().await;
+        let q = serde_urlencoded::to_string([
+            ("db", "my_db"),
+            ("q", "SELECT tag1, field_int FROM h2o ORDER BY field_int"),
+        ])
+        .unwrap();
+
+        let (_, body) = query(&server, get(&q)).await;
+        let rows = std::str::from_utf8(&body)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect::<Vec<Value>>();
+        assert_eq!(
+            rows,
+            vec![
+                serde_json::json!({"tag1": "VT", "field_int": 10}),
+                serde_json::json!({"tag1": "UT", "field_int": 70}),
+                serde_json::json!({"tag1": "WA", "field_int": 1000}),
+            ]
+        );
+
+        let (_, body) = query(&server, get(&format!("{}&format=csv", q))).await;
+        assert_eq!(body, "tag1,field_int\nVT,10\nUT,70\nWA,1000\n");
+
+        let (_, body) = query(&server, get(&format!("{}&format=arrow", q))).await;
+        let batches = StreamReader::try_new(body.as_ref(), None)
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(
+            batches[0].schema().field(0).data_type(),
+            &DataType::Utf8,
+            "tags are not dictionaries"
+        );
+        assert_batches_eq!(
+            &[
+                "+------+-----------+",
+                "| tag1 | field_int |",
+                "+------+-----------+",
+                "| VT   | 10        |",
+                "| UT   | 70        |",
+                "| WA   | 1000      |",
+                "+------+-----------+",
+            ],
+            &batches
+        );
+    }
+
+    #[tokio::test]
+    async fn query_post() {
+        let server = TestDatabaseStore::new();
+        server.db_or_create("my_db").await;
+
+        let form = serde_urlencoded::to_string([("db", "my_db"), ("q", QUERY), ("format", "csv")])
+            .unwrap();
+        let req = Request::post(QUERY_SQL_PATH)
+            .body(Body::from(form))
+            .unwrap();
+        let (_, body) = query(&server, req).await;
+        assert_eq!(body, "n,s\n1,a\n2,b\n");
+
+        let json = serde_json::json!({"db": "my_db", "q": QUERY, "format": "csv"}).to_string();
+        let req = Request::post(QUERY_SQL_PATH)
+            .header(CONTENT_TYPE, "application/json")
+            .body(Body::from(json))
+            .unwrap();
+        let (_, body) = query(&server, req).await;
+        assert_eq!(body, "n,s\n1,a\n2,b\n");
+    }
+
+    #[tokio::test]
+    async fn query_errors() {
+        let server = TestDatabaseStore::new();
+        server.db_or_create("my_db").await;
+
+        let error = query_sql(&server, get("db=my_db")).await.unwrap_err();
+        assert!(matches!(error, Error::InvalidParams(_)), "{}", error);
+
+        let error = query_sql(&server, get("db=other_db&q=SELECT%201"))
+            .await
+            .unwrap_err();
+        assert!(matches!(error, Error::NamespaceNotFound(_)), "{}", error);
+
+        let error = query_sql(&server, get("db=my_db&q=SELECT%20*%20FROM%20nope"))
+            .await
+            .unwrap_err();
+        assert_eq!(
+            error.to_http_api_error().response().status(),
+            hyper::StatusCode::BAD_REQUEST
+        );
+
+        let req = Request::put(QUERY_SQL_PATH).body(Body::empty()).unwrap();
+        let error = query_sql(&server, req).await.unwrap_err();
+        assert!(matches!(error, Error::MethodNotAllowed(_)), "{}", error);
+    }
+
+    async fn influxql_server() -> TestDatabaseStore {
+        let server = TestDatabaseStore::new();
+        let h2o = TestChunk::new("h2o")
+            .with_id(1)
+            .with_time_column()
+            .with_tag_column("tag1")
+            .with_tag_column("tag2")
+            .with_i64_field_column("field_int")
+            .with_three_rows_of_data();
+        server
+            .db_or_create("my_db")
+            .await
+            .add_chunk("p1", Arc::new(h2o));
+        server
+    }
+
+    async fn query_influxql_json(server: &TestDatabaseStore, req: Request<Body>) -> Value {
+        let response = query_influxql(server, req).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        serde_json::from_slice(&body).unwrap()
+    }
+
+    fn get_influxql(params: &[(&str, &str)]) -> Request<Body> {
+        let query = serde_urlencoded::to_string(params).unwrap();
+        Request::get(format!("{}?{}", QUERY_INFLUXQL_PATH, query))
+            .body(Body::empty())
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn influxql_series() {
+        let server = influxql_server().await;
+
+        let req = get_influxql(&[
+            ("db", "my_db"),
+            (
+                "q",
+                "SELECT field_int FROM h2o WHERE tag1 != 'VT'; SHOW MEASUREMENTS",
+            ),
+        ]);
+        assert_eq!(
+            query_influxql_json(&server, req).await,
+            serde_json::json!({"results": [
+                {
+                    "statement_id": 0,
+                    "series": [{
+                        "name": "h2o",
+                        "columns": ["time", "field_int"],
+                        "values": [
+                            ["1970-01-01T00:00:00.000008Z", 1000],
+                            ["1970-01-01T00:00:00.000020Z", 70],
+                        ],
+                    }],
+                },
+                {
+                    "statement_id": 1,
+                    "series": [{
+                        "name": "measurements",
+                        "columns": ["name"],
+                        "values": [["h2o"]],
+                    }],
+                },
+            ]})
+        );
+
+        let req = get_influxql(&[
+            ("db", "my_db"),
+            ("rp", "autogen"),
+            ("q", "SELECT field_int FROM h2o GROUP BY tag2"),
+            ("epoch", "u"),
+        ]);
+        assert_eq!(
+            query_influxql_json(&server, req).await,
+            serde_json::json!({"results": [{
+                "statement_id": 0,
+                "series": [
+                    {
+                        "name": "h2o",
+                        "tags": {"tag2": "NC"},
+                        "columns": ["time", "field_int"],
+                        "values": [[10, 10]],
+                    },
+                    {
+                        "name": "h2o",
+                        "tags": {"tag2": "RI"},
+                        "columns": ["time", "field_int"],
+                        "values": [[20, 70]],
+                    },
+                    {
+                        "name": "h2o",
+                        "tags": {"tag2": "SC"},
+                        "columns": ["time", "field_int"],
+                        "values": [[8, 1000]],
+                    },
+                ],
+            }]})
+        );
+    }
+
+    #[tokio::test]
+    async fn influxql_chunked() {
+        let server = influxql_server().await;
+
+        let req = get_influxql(&[
+            ("db", "my_db"),
+            ("q", "SELECT field_int FROM h2o; SHOW MEASUREMENTS"),
+            ("epoch", "u"),
+            ("chunked", "true"),
+            ("chunk_size", "2"),
+        ]);
+        let response = query_influxql(&server, req).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        let chunks: Vec<Value> = std::str::from_utf8(&body)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(
+            chunks,
+            vec![
+                serde_json::json!({"results": [{
+                    "statement_id": 0,
+                    "series": [{
+                        "name": "h2o",
+                        "columns": ["time", "field_int"],
+                        "values": [[8, 1000], [10, 10]],
+                        "partial": true,
+                    }],
+                    "partial": true,
+                }]}),
+                serde_json::json!({"results": [{
+                    "statement_id": 0,
+                    "series": [{
+                        "name": "h2o",
+                        "columns": ["time", "field_int"],
+                        "values": [[20, 70]],
+                    }],
+                }]}),
+                serde_json::json!({"results": [{
+                    "statement_id": 1,
+                    "series": [{
+                        "name": "measurements",
+                        "columns": ["name"],
+                        "values": [["h2o"]],
+                    }],
+                }]}),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn influxql_post() {
+        let server = influxql_server().await;
+
+        let form = serde_urlencoded::to_string([("q", "SHOW TAG KEYS FROM h2o")]).unwrap();
+        let req = Request::post(format!("{}?db=my_db", QUERY_INFLUXQL_PATH))
+            .header(CONTENT_TYPE, "application/x-www-form-urlencoded")
+            .body(Body::from(form))
+            .unwrap();
+        assert_eq!(
+            query_influxql_json(&server, req).await,
+            serde_json::json!({"results": [{
+                "statement_id": 0,
+                "series": [{
+                    "name": "h2o",
+                    "columns": ["tagKey"],
+                    "values": [["tag1"], ["tag2"]],
+                }],
+            }]})
+        );
+    }
+
+    #[tokio::test]
+    async fn influxql_errors() {
+        let server = influxql_server().await;
+
+        // statements fail on their own
+        let req = get_influxql(&[
+            ("db", "my_db"),
+            (
+                "q",
+                "SELECT field_int FROM h2o GROUP BY time(1m); SHOW MEASUREMENTS",
+            ),
+        ]);
+        let results = query_influxql_json(&server, req).await;
+        assert_eq!(
+            results["results"][0]["error"],
+            "Invalid InfluxQL statement: GROUP BY time() requires an aggregate function"
+        );
+        assert_eq!(results["results"][1]["series"][0]["values"][0][0], "h2o");
+
+        // so does a missing namespace
+        let req = get_influxql(&[
+            ("db", "my_db"),
+            ("rp", "one_week"),
+            ("q", "SHOW MEASUREMENTS"),
+        ]);
+        assert_eq!(
+            query_influxql_json(&server, req).await,
+            serde_json::json!({"results": [{
+                "statement_id": 0,
+                "error": "database not found: my_db/one_week",
+            }]})
+        );
+
+        // but parse errors fail the whole query
+        let response = query_influxql(&server, get_influxql(&[("db", "my_db"), ("q", "SELECT")]))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        let body: Value = serde_json::from_slice(&body).unwrap();
+        assert!(body["error"].is_string(), "{}", body);
+
+        let error = query_influxql(&server, get_influxql(&[("q", "SHOW MEASUREMENTS")]))
+            .await
+            .unwrap_err();
+        assert!(matches!(error, Error::InvalidParams(_)), "{}", error);
+    }
+}
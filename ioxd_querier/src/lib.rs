@@ -26,6 +26,7 @@ use std::{
 use thiserror::Error;
 use trace::TraceCollector;
 
+mod http;
 mod rpc;
 
 pub struct QuerierServerType<C: QuerierHandler> {
@@ -66,12 +67,20 @@ impl<C: QuerierHandler + std::fmt::Debug + 'static> ServerType for QuerierServer
         self.trace_collector.as_ref().map(Arc::clone)
     }
 
-    /// Just return "not found".
+    /// Routes the SQL query endpoint, everything else is "not found".
     async fn route_http_request(
         &self,
-        _req: Request<Body>,
+        req: Request<Body>,
     ) -> Result<Response<Body>, Box<dyn HttpApiErrorSource>> {
-        Err(Box::new(IoxHttpError::NotFound))
+        match req.uri().path() {
+            http::QUERY_SQL_PATH => http::query_sql(self.database.as_ref(), req)
+                .await
+                .map_err(|e| Box::new(e) as _),
+            http::QUERY_INFLUXQL_PATH => http::query_influxql(self.database.as_ref(), req)
+                .await
+                .map_err(|e| Box::new(e) as _),
+            _ => Err(Box::new(IoxHttpError::NotFound)),
+        }
     }
 
     /// Provide a placeholder gRPC service.
@@ -108,7 +117,7 @@ impl<C: QuerierHandler + std::fmt::Debug + 'static> ServerType for QuerierServer
     }
 }
 
-/// Simple error struct, we're not really providing an HTTP interface for the compactor.
+/// Error for requests to paths the querier doesn't serve.
 #[derive(Debug)]
 pub enum IoxHttpError {
     NotFound,
@@ -30,6 +30,13 @@ fn namespace_to_proto(namespace: Namespace) -> proto::Namespace {
     proto::Namespace {
         id: namespace.id.get(),
         name: namespace.name,
+        retention_duration: namespace
+            .retention_duration
+            .unwrap_or_else(|| iox_catalog::INFINITE_RETENTION_POLICY.to_string()),
+        max_tables: namespace.max_tables,
+        max_columns_per_table: namespace.max_columns_per_table,
+        deleted_at: namespace.deleted_at.map(|t| t.get()),
+        partition_template: namespace.partition_template.map(Into::into),
     }
 }
 
@@ -49,6 +56,80 @@ impl proto::namespace_service_server::NamespaceService for NamespaceServiceImpl
             namespaces,
         }))
     }
+
+    // Namespaces are managed through the router, the querier only reads them.
+
+    async fn create_namespace(
+        &self,
+        _request: tonic::Request<proto::CreateNamespaceRequest>,
+    ) -> Result<tonic::Response<proto::CreateNamespaceResponse>, tonic::Status> {
+        Err(tonic::Status::unimplemented(
+            "namespaces can only be created through the router",
+        ))
+    }
+
+    async fn update_namespace_retention(
+        &self,
+        _request: tonic::Request<proto::UpdateNamespaceRetentionRequest>,
+    ) -> Result<tonic::Response<proto::UpdateNamespaceRetentionResponse>, tonic::Status> {
+        Err(tonic::Status::unimplemented(
+            "namespaces can only be updated through the router",
+        ))
+    }
+
+    async fn update_namespace_limits(
+        &self,
+        _request: tonic::Request<proto::UpdateNamespaceLimitsRequest>,
+    ) -> Result<tonic::Response<proto::UpdateNamespaceLimitsResponse>, tonic::Status> {
+        Err(tonic::Status::unimplemented(
+            "namespaces can only be updated through the router",
+        ))
+    }
+
+    async fn delete_namespace(
+        &self,
+        _request: tonic::Request<proto::DeleteNamespaceRequest>,
+    ) -> Result<tonic::Response<proto::DeleteNamespaceResponse>, tonic::Status> {
+        Err(tonic::Status::unimplemented(
+            "namespaces can only be deleted through the router",
+        ))
+    }
+
+    async fn restore_namespace(
+        &self,
+        _request: tonic::Request<proto::RestoreNamespaceRequest>,
+    ) -> Result<tonic::Response<proto::RestoreNamespaceResponse>, tonic::Status> {
+        Err(tonic::Status::unimplemented(
+            "namespaces can only be restored through the router",
+        ))
+    }
+
+    async fn drop_table(
+        &self,
+        _request: tonic::Request<proto::DropTableRequest>,
+    ) -> Result<tonic::Response<proto::DropTableResponse>, tonic::Status> {
+        Err(tonic::Status::unimplemented(
+            "tables can only be dropped through the router",
+        ))
+    }
+
+    async fn drop_column(
+        &self,
+        _request: tonic::Request<proto::DropColumnRequest>,
+    ) -> Result<tonic::Response<proto::DropColumnResponse>, tonic::Status> {
+        Err(tonic::Status::unimplemented(
+            "columns can only be dropped through the router",
+        ))
+    }
+
+    async fn update_table_partition_template(
+        &self,
+        _request: tonic::Request<proto::UpdateTablePartitionTemplateRequest>,
+    ) -> Result<tonic::Response<proto::UpdateTablePartitionTemplateResponse>, tonic::Status> {
+        Err(tonic::Status::unimplemented(
+            "partition templates can only be updated through the router",
+        ))
+    }
 }
 
 #[cfg(test)]
@@ -132,10 +213,20 @@ mod tests {
                     proto::Namespace {
                         id: 1,
                         name: "namespace2".to_string(),
+                        retention_duration: "1y".to_string(),
+                        max_tables: 10000,
+                        max_columns_per_table: 1000,
+                        deleted_at: None,
+                        partition_template: None,
                     },
                     proto::Namespace {
                         id: 2,
                         name: "namespace1".to_string(),
+                        retention_duration: "1y".to_string(),
+                        max_tables: 10000,
+                        max_columns_per_table: 1000,
+                        deleted_at: None,
+                        partition_template: None,
                     },
                 ]
             }
@@ -19,11 +19,12 @@ use observability_deps::tracing::info;
 use router::{
     dml_handlers::{
         DmlHandler, DmlHandlerChainExt, FanOutAdaptor, InstrumentationDecorator,
-        NamespaceAutocreation, Partitioner, SchemaValidator, ShardedWriteBuffer,
-        WriteSummaryAdapter,
+        NamespaceAutocreation, Partitioner, RetentionValidator, SchemaValidator,
+        ShardedWriteBuffer, WriteSummaryAdapter,
     },
     namespace_cache::{
-        metrics::InstrumentedCache, MemoryNamespaceCache, NamespaceCache, ShardedCache,
+        metrics::InstrumentedCache, CacheInvalidator, MemoryNamespaceCache, NamespaceCache,
+        ShardedCache, TtlCache,
     },
     sequencer::Sequencer,
     server::{grpc::GrpcDelegate, http::HttpDelegate, RouterServer},
@@ -33,12 +34,18 @@ use std::{
     collections::BTreeSet,
     fmt::{Debug, Display},
     sync::Arc,
+    time::Duration,
 };
 use thiserror::Error;
 use tokio_util::sync::CancellationToken;
 use trace::TraceCollector;
 use write_summary::WriteSummary;
 
+/// How long a namespace schema is cached before being reloaded from the
+/// catalog, bounding how long a namespace deleted or updated through another
+/// router keeps being served from this router's cache.
+const NAMESPACE_CACHE_TTL: Duration = Duration::from_secs(60);
+
 #[derive(Debug, Error)]
 pub enum Error {
     #[error("failed to initialise write buffer connection: {0}")]
@@ -52,6 +59,9 @@ pub enum Error {
 
     #[error("failed to initialize sharded cache: {0}")]
     Sharder(#[from] sharder::Error),
+
+    #[error("invalid retention period for new namespaces: {0}")]
+    Retention(#[from] data_types::RetentionDurationError),
 }
 
 pub type Result<T, E = Error> = std::result::Result<T, E>;
@@ -115,6 +125,7 @@ where
         let builder = setup_builder!(builder_input, self);
         add_service!(builder, self.server.grpc().write_service());
         add_service!(builder, self.server.grpc().schema_service());
+        add_service!(builder, self.server.grpc().namespace_service());
         add_service!(builder, self.server.grpc().catalog_service());
         add_service!(builder, self.server.grpc().object_store_service());
         serve_builder!(builder);
@@ -159,8 +170,13 @@ pub async fn create_router_server_type(
     object_store: Arc<DynObjectStore>,
     write_buffer_config: &WriteBufferConfig,
     query_pool_name: &str,
+    new_namespace_retention: &str,
     request_limit: usize,
 ) -> Result<Arc<dyn ServerType>> {
+    // Reject an invalid retention period at startup, rather than on the first
+    // write that creates a namespace.
+    data_types::parse_retention_duration(new_namespace_retention)?;
+
     // Initialise the sharded write buffer and instrument it with DML handler
     // metrics.
     let write_buffer = init_write_buffer(
@@ -175,10 +191,17 @@ pub async fn create_router_server_type(
     // Initialise an instrumented namespace cache to be shared with the schema
     // validator, and namespace auto-creator that reports cache hit/miss/update
     // metrics.
+    //
+    // Entries expire after NAMESPACE_CACHE_TTL so that namespace changes made
+    // through another router (which only invalidates its own cache) are
+    // eventually observed by this one.
     let ns_cache = Arc::new(InstrumentedCache::new(
-        Arc::new(ShardedCache::new(
-            std::iter::repeat_with(|| Arc::new(MemoryNamespaceCache::default())).take(10),
-        )?),
+        Arc::new(TtlCache::new(
+            Arc::new(ShardedCache::new(
+                std::iter::repeat_with(|| Arc::new(MemoryNamespaceCache::default())).take(10),
+            )?),
+            NAMESPACE_CACHE_TTL,
+        )),
         &*metrics,
     ));
 
@@ -192,11 +215,27 @@ pub async fn create_router_server_type(
     let schema_validator =
         InstrumentationDecorator::new("schema_validator", &*metrics, schema_validator);
 
-    // Add a write partitioner into the handler stack that splits by the date
+    // Initialise and instrument the retention validator, rejecting writes
+    // older than the retention period of their namespace.
+    let retention_validator =
+        RetentionValidator::new(Arc::clone(&catalog), Arc::clone(&ns_cache), &*metrics);
+    let retention_validator =
+        InstrumentationDecorator::new("retention_validator", &*metrics, retention_validator);
+
+    // Evict namespaces changed through the namespace API from the cache, so
+    // the validators above observe the change on the next write.
+    let ns_invalidator = Arc::new(CacheInvalidator::new(Arc::clone(&ns_cache)));
+
+    // Add a write partitioner into the handler stack that splits writes by the
+    // partition template of their namespace (or table), defaulting to the date
     // portion of the write's timestamp.
-    let partitioner = Partitioner::new(PartitionTemplate {
-        parts: vec![TemplatePart::TimeFormat("%Y-%m-%d".to_owned())],
-    });
+    let partitioner = Partitioner::new(
+        PartitionTemplate {
+            parts: vec![TemplatePart::TimeFormat("%Y-%m-%d".to_owned())],
+        },
+        Arc::clone(&catalog),
+        Arc::clone(&ns_cache),
+    );
     let partitioner = InstrumentationDecorator::new("partitioner", &*metrics, partitioner);
 
     ////////////////////////////////////////////////////////////////////////////
@@ -246,7 +285,7 @@ pub async fn create_router_server_type(
         ns_cache,
         topic_id,
         query_id,
-        iox_catalog::INFINITE_RETENTION_POLICY.to_owned(),
+        new_namespace_retention.to_owned(),
     );
     //
     ////////////////////////////////////////////////////////////////////////////
@@ -257,6 +296,7 @@ pub async fn create_router_server_type(
     // pipeline, starting with the namespace creator (for testing purposes) and
     // write partitioner that yields a set of partitioned batches.
     let handler_stack = ns_creator
+        .and_then(retention_validator)
         .and_then(schema_validator)
         .and_then(partitioner)
         // Once writes have been partitioned, they are processed in parallel.
@@ -286,6 +326,9 @@ pub async fn create_router_server_type(
         handler_stack,
         schema_catalog,
         object_store,
+        topic_id,
+        query_id,
+        ns_invalidator,
         Arc::clone(&metrics),
     );
 
@@ -7,7 +7,8 @@ use crate::{
     MutableBatch,
 };
 use chrono::{format::StrftimeItems, TimeZone, Utc};
-use data_types::{PartitionTemplate, TemplatePart};
+use data_types::{PartitionTemplate, RegexCapture, StrftimeColumn, TemplatePart};
+use regex::Regex;
 use schema::TIME_COLUMN_NAME;
 use std::ops::Range;
 
@@ -33,6 +34,8 @@ enum Template<'a> {
     Column(&'a Column, &'a str),
     MissingColumn(&'a str),
     TimeFormat(&'a [i64], StrftimeItems<'a>),
+    RegexCapture(&'a Column, &'a str, &'a Regex, &'a str),
+    StrftimeColumn(&'a Column, &'a [i64], &'a str, StrftimeItems<'a>),
 }
 
 impl<'a> Template<'a> {
@@ -68,6 +71,31 @@ impl<'a> Template<'a> {
                     .format_with_items(format.clone());
                 write!(out, "{}", formatted)
             }
+            Template::RegexCapture(col, col_name, regex, replacement) => {
+                out.write_str(col_name)?;
+
+                let value = match &col.data {
+                    _ if !col.valid.get(idx) => None,
+                    ColumnData::String(col_data, _) => col_data.get(idx),
+                    ColumnData::Tag(col_data, dictionary, _) => dictionary.lookup_id(col_data[idx]),
+                    _ => None,
+                };
+
+                if let Some(captures) = value.and_then(|v| regex.captures(v)) {
+                    let mut expanded = String::new();
+                    captures.expand(replacement, &mut expanded);
+                    out.write_char('_')?;
+                    out.write_str(&expanded)?;
+                }
+                Ok(())
+            }
+            Template::StrftimeColumn(col, t, _, format) if col.valid.get(idx) => {
+                let formatted = Utc
+                    .timestamp_nanos(t[idx])
+                    .format_with_items(format.clone());
+                write!(out, "{}", formatted)
+            }
+            Template::StrftimeColumn(_, _, col_name, _) => out.write_str(col_name),
         }
     }
 }
@@ -94,8 +122,30 @@ fn partition_keys<'a>(
                 |col| Template::Column(col, name),
             ),
             TemplatePart::TimeFormat(fmt) => Template::TimeFormat(time, StrftimeItems::new(fmt)),
-            TemplatePart::RegexCapture(_) => unimplemented!(),
-            TemplatePart::StrftimeColumn(_) => unimplemented!(),
+            TemplatePart::RegexCapture(RegexCapture {
+                column,
+                regex,
+                replacement,
+            }) => match batch.column(column) {
+                Ok(col) if matches!(col.data, ColumnData::String(..) | ColumnData::Tag(..)) => {
+                    Template::RegexCapture(col, column, regex.regex(), replacement)
+                }
+                _ => Template::MissingColumn(column),
+            },
+            TemplatePart::StrftimeColumn(StrftimeColumn { column, format }) => {
+                match batch.column(column) {
+                    Ok(col) => match &col.data {
+                        ColumnData::I64(col_data, _) => Template::StrftimeColumn(
+                            col,
+                            col_data.as_slice(),
+                            column,
+                            StrftimeItems::new(format),
+                        ),
+                        _ => Template::MissingColumn(column),
+                    },
+                    Err(_) => Template::MissingColumn(column),
+                }
+            }
         })
         .collect();
 
@@ -150,6 +200,7 @@ where
 mod tests {
     use super::*;
     use crate::writer::Writer;
+    use data_types::TemplateRegex;
     use rand::prelude::*;
 
     fn make_rng() -> StdRng {
@@ -234,4 +285,133 @@ mod tests {
             ]
         )
     }
+
+    #[test]
+    fn test_partition_regex_capture() {
+        let mut batch = MutableBatch::new();
+        let mut writer = Writer::new(&mut batch, 5);
+
+        writer
+            .write_time("time", vec![1, 2, 3, 4, 5].into_iter())
+            .unwrap();
+
+        writer
+            .write_tag(
+                "host",
+                Some(&[0b00011101]),
+                vec!["web-01", "db-02", "bananas", "api-03"].into_iter(),
+            )
+            .unwrap();
+
+        writer
+            .write_string(
+                "url",
+                None,
+                vec!["/v1/a", "/v2/b", "/c", "/v1/d", "/v1/e"].into_iter(),
+            )
+            .unwrap();
+
+        writer
+            .write_f64("f64", None, vec![1., 2., 3., 4., 5.].into_iter())
+            .unwrap();
+
+        let template = PartitionTemplate {
+            parts: vec![
+                TemplatePart::RegexCapture(RegexCapture {
+                    column: "host".to_string(),
+                    regex: TemplateRegex::new(r"^(?P<role>[a-z]+)-(\d+)$").unwrap(),
+                    replacement: "${role}$2".to_string(),
+                }),
+                TemplatePart::RegexCapture(RegexCapture {
+                    column: "url".to_string(),
+                    regex: TemplateRegex::new(r"^/(v\d)/").unwrap(),
+                    replacement: "$1".to_string(),
+                }),
+                // Non-string and missing columns render as just the column name
+                TemplatePart::RegexCapture(RegexCapture {
+                    column: "f64".to_string(),
+                    regex: TemplateRegex::new("(.*)").unwrap(),
+                    replacement: "$1".to_string(),
+                }),
+                TemplatePart::RegexCapture(RegexCapture {
+                    column: "bananas".to_string(),
+                    regex: TemplateRegex::new("(.*)").unwrap(),
+                    replacement: "$1".to_string(),
+                }),
+            ],
+        };
+
+        writer.commit();
+
+        let keys: Vec<_> = partition_keys(&batch, "foo", &template).collect();
+
+        assert_eq!(
+            keys,
+            vec![
+                "host_web01-url_v1-f64-bananas".to_string(),
+                "host-url_v2-f64-bananas".to_string(),
+                "host_db02-url-f64-bananas".to_string(),
+                "host-url_v1-f64-bananas".to_string(),
+                "host_api03-url_v1-f64-bananas".to_string(),
+            ]
+        )
+    }
+
+    #[test]
+    fn test_partition_strftime_column() {
+        let mut batch = MutableBatch::new();
+        let mut writer = Writer::new(&mut batch, 3);
+
+        writer
+            .write_time("time", vec![1, 2, 3].into_iter())
+            .unwrap();
+
+        // 2022-08-01 and 2022-09-15, in nanoseconds
+        writer
+            .write_i64(
+                "created",
+                Some(&[0b00000101]),
+                vec![1_659_312_000_000_000_000, 1_663_200_000_000_000_000].into_iter(),
+            )
+            .unwrap();
+
+        writer
+            .write_string("name", None, vec!["a", "b", "c"].into_iter())
+            .unwrap();
+
+        let template = PartitionTemplate {
+            parts: vec![
+                TemplatePart::StrftimeColumn(StrftimeColumn {
+                    column: "created".to_string(),
+                    format: "%Y-%m".to_string(),
+                }),
+                TemplatePart::StrftimeColumn(StrftimeColumn {
+                    column: "time".to_string(),
+                    format: "%Y".to_string(),
+                }),
+                // Non-integer and missing columns render as just the column name
+                TemplatePart::StrftimeColumn(StrftimeColumn {
+                    column: "name".to_string(),
+                    format: "%Y".to_string(),
+                }),
+                TemplatePart::StrftimeColumn(StrftimeColumn {
+                    column: "bananas".to_string(),
+                    format: "%Y".to_string(),
+                }),
+            ],
+        };
+
+        writer.commit();
+
+        let keys: Vec<_> = partition_keys(&batch, "foo", &template).collect();
+
+        assert_eq!(
+            keys,
+            vec![
+                "2022-08-1970-name-bananas".to_string(),
+                "created-1970-name-bananas".to_string(),
+                "2022-09-1970-name-bananas".to_string(),
+            ]
+        )
+    }
 }
@@ -9,6 +9,7 @@ pub use eager::add_eager_decls;
 pub use expressions::add_expressions;
 pub use lazy::add_lazy_decls;
 pub use series::add_series_decls;
+pub(crate) use values::NuDataFrame;
 
 use nu_protocol::engine::StateWorkingSet;
 
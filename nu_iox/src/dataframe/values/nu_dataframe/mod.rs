@@ -2,6 +2,7 @@ mod between_values;
 mod conversion;
 mod custom_value;
 mod operations;
+mod record_batch;
 
 pub use conversion::{Column, ColumnMap};
 pub use operations::Axis;
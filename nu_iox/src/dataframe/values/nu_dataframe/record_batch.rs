@@ -0,0 +1,155 @@
+use super::NuDataFrame;
+use arrow::array::{export_array_into_raw, ArrayRef};
+use arrow::compute::cast;
+use arrow::datatypes::DataType as ArrowDataType;
+use arrow::ffi::{FFI_ArrowArray, FFI_ArrowSchema};
+use arrow::record_batch::RecordBatch;
+use nu_protocol::{ShellError, Span};
+use polars::export::arrow::{array::Array, ffi};
+use polars::prelude::{DataFrame, Series};
+use std::sync::Arc;
+
+impl NuDataFrame {
+    /// Creates a dataframe from the record batches of an IOx query.
+    ///
+    /// Every batch becomes one chunk of each column and the buffers are
+    /// handed to polars through the Arrow C data interface, so the data is
+    /// not copied, except for dictionary encoded columns which are decoded.
+    pub fn try_from_record_batches(
+        batches: &[RecordBatch],
+        span: Span,
+    ) -> Result<Self, ShellError> {
+        let schema = match batches.first() {
+            Some(batch) => batch.schema(),
+            None => return Ok(DataFrame::default().into()),
+        };
+
+        if batches.iter().any(|batch| batch.schema() != schema) {
+            return Err(dataframe_error(
+                "the query returned batches with different schemas".into(),
+                span,
+            ));
+        }
+
+        let columns = schema
+            .fields()
+            .iter()
+            .enumerate()
+            .map(|(idx, field)| {
+                let chunks = batches
+                    .iter()
+                    .map(|batch| export_array(batch.column(idx), span))
+                    .collect::<Result<Vec<_>, ShellError>>()?;
+
+                Series::try_from((field.name().as_str(), chunks))
+                    .map_err(|e| dataframe_error(e.to_string(), span))
+            })
+            .collect::<Result<Vec<_>, ShellError>>()?;
+
+        let df = DataFrame::new(columns).map_err(|e| dataframe_error(e.to_string(), span))?;
+
+        Ok(df.into())
+    }
+}
+
+/// Moves an arrow-rs array into an arrow2 array for polars
+fn export_array(array: &ArrayRef, span: Span) -> Result<Box<dyn Array>, ShellError> {
+    // IOx sends tags as dictionaries with i32 keys, which polars cannot use
+    // as categoricals, so they become plain strings
+    let array = match array.data_type() {
+        ArrowDataType::Dictionary(_, value_type) => {
+            cast(array, value_type).map_err(|e| dataframe_error(e.to_string(), span))?
+        }
+        _ => Arc::clone(array),
+    };
+
+    // arrow2 owns the C structs; arrow-rs exports into them, handing over
+    // the buffers together with their release callbacks
+    let mut ffi_array = ffi::ArrowArray::empty();
+    let mut ffi_schema = ffi::ArrowSchema::empty();
+
+    // SAFETY: both structs are the #[repr(C)] ArrowArray/ArrowSchema of the
+    // Arrow C data interface, which arrow-rs writes into without reading or
+    // dropping the (empty) previous contents
+    unsafe {
+        export_array_into_raw(
+            array,
+            &mut ffi_array as *mut ffi::ArrowArray as *mut FFI_ArrowArray,
+            &mut ffi_schema as *mut ffi::ArrowSchema as *mut FFI_ArrowSchema,
+        )
+    }
+    .map_err(|e| dataframe_error(e.to_string(), span))?;
+
+    // SAFETY: the structs were just populated by a C data interface producer,
+    // and arrow2 now calls their release callbacks when it is done with them
+    unsafe {
+        let field = ffi::import_field_from_c(&ffi_schema)
+            .map_err(|e| dataframe_error(e.to_string(), span))?;
+        ffi::import_array_from_c(Box::new(ffi_array), field.data_type)
+            .map_err(|e| dataframe_error(e.to_string(), span))
+    }
+}
+
+fn dataframe_error(msg: String, span: Span) -> ShellError {
+    ShellError::GenericError(
+        "Error creating dataframe".into(),
+        msg,
+        Some(span),
+        None,
+        Vec::new(),
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use arrow::array::{DictionaryArray, Float64Array, TimestampNanosecondArray};
+    use arrow::datatypes::Int32Type;
+    use polars::prelude::{DataType, TimeUnit};
+
+    #[test]
+    fn converts_record_batches_into_chunks() {
+        let batch = |tags: Vec<&'static str>, values: Vec<f64>, times: Vec<i64>| {
+            let tags: DictionaryArray<Int32Type> = tags.into_iter().collect();
+            RecordBatch::try_from_iter(vec![
+                ("host", Arc::new(tags) as ArrayRef),
+                ("usage", Arc::new(Float64Array::from(values)) as ArrayRef),
+                (
+                    "time",
+                    Arc::new(TimestampNanosecondArray::from(times)) as ArrayRef,
+                ),
+            ])
+            .unwrap()
+        };
+
+        let batches = vec![
+            batch(vec!["a", "b"], vec![0.5, 1.5], vec![1, 2]),
+            batch(vec!["a"], vec![2.5], vec![3]),
+        ];
+
+        let df = NuDataFrame::try_from_record_batches(&batches, Span::test_data())
+            .unwrap()
+            .df;
+
+        assert_eq!(df.shape(), (3, 3));
+        assert_eq!(df.column("usage").unwrap().n_chunks(), 2);
+        assert_eq!(
+            df.dtypes(),
+            vec![
+                DataType::Utf8,
+                DataType::Float64,
+                DataType::Datetime(TimeUnit::Nanoseconds, None)
+            ]
+        );
+        assert_eq!(df.column("usage").unwrap().f64().unwrap().sum(), Some(4.5));
+    }
+
+    #[test]
+    fn converts_empty_result() {
+        let df = NuDataFrame::try_from_record_batches(&[], Span::test_data())
+            .unwrap()
+            .df;
+
+        assert_eq!(df.shape(), (0, 0));
+    }
+}
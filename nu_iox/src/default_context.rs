@@ -284,6 +284,7 @@ pub fn create_default_context() -> EngineState {
             FromIcs,
             FromIni,
             FromJson,
+            FromLp,
             FromNuon,
             FromOds,
             FromSsv,
@@ -299,6 +300,7 @@ pub fn create_default_context() -> EngineState {
             ToCsv,
             ToHtml,
             ToJson,
+            ToLp,
             ToMd,
             ToNuon,
             ToText,
@@ -412,10 +414,23 @@ pub fn create_default_context() -> EngineState {
 
         // Iox
         bind_command! {
+            Ioxdelete,
             Ioxnamespace,
+            Ioxparquetfiles,
+            Ioxpartitions,
+            Ioxschema,
             Ioxsql,
+            Ioxstoreget,
+            Ioxstorage,
+            IoxstorageMeasurementNames,
+            IoxstorageReadFilter,
+            IoxstorageReadGroup,
+            IoxstorageTagKeys,
+            IoxstorageTagValues,
+            IoxstorageWindowAggregate,
             Ioxwrite,
             Ioxwritefile,
+            Ioxwriteinfo,
         }
 
         // Deprecated
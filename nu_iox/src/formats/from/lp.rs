@@ -0,0 +1,315 @@
+use chrono::{TimeZone, Utc};
+use influxdb_line_protocol::{parse_lines, FieldValue};
+use nu_engine::CallExt;
+use nu_protocol::ast::Call;
+use nu_protocol::engine::{Command, EngineState, Stack};
+use nu_protocol::{
+    Category, Config, Example, PipelineData, ShellError, Signature, Span, Spanned, SyntaxShape,
+    Value,
+};
+
+#[derive(Clone)]
+pub struct FromLp;
+
+impl Command for FromLp {
+    fn name(&self) -> &str {
+        "from lp"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build("from lp")
+            .named(
+                "precision",
+                SyntaxShape::String,
+                "precision of the timestamps: 'ns', 'us', 'ms' or 's', defaults to 'ns'",
+                Some('p'),
+            )
+            .category(Category::Formats)
+    }
+
+    fn usage(&self) -> &str {
+        "Parse text as InfluxDB line protocol and create table."
+    }
+
+    fn extra_usage(&self) -> &str {
+        "Tags and fields named 'measurement' or 'time', or with the key of a field or tag of the same line, get a '_tag' or '_field' suffix."
+    }
+
+    fn run(
+        &self,
+        engine_state: &EngineState,
+        stack: &mut Stack,
+        call: &Call,
+        input: PipelineData,
+    ) -> Result<nu_protocol::PipelineData, ShellError> {
+        let head = call.head;
+        let precision = Precision::from_flag(call.get_flag(engine_state, stack, "precision")?)?;
+        let config = engine_state.get_config();
+        from_lp(input, precision, head, config)
+    }
+
+    fn examples(&self) -> Vec<Example> {
+        vec![
+            Example {
+                description: "Convert line protocol to a table",
+                example: "'cpu,host=a usage=0.5,count=3i 1000000000' | from lp",
+                result: Some(Value::List {
+                    vals: vec![Value::Record {
+                        cols: vec![
+                            "measurement".to_string(),
+                            "host".to_string(),
+                            "usage".to_string(),
+                            "count".to_string(),
+                            "time".to_string(),
+                        ],
+                        vals: vec![
+                            Value::test_string("cpu"),
+                            Value::test_string("a"),
+                            Value::Float {
+                                val: 0.5,
+                                span: Span::test_data(),
+                            },
+                            Value::test_int(3),
+                            Value::Date {
+                                val: Utc.timestamp(1, 0).into(),
+                                span: Span::test_data(),
+                            },
+                        ],
+                        span: Span::test_data(),
+                    }],
+                    span: Span::test_data(),
+                }),
+            },
+            Example {
+                description: "Convert line protocol with second precision timestamps to a table",
+                example: "'cpu,host=a usage=0.5 1' | from lp --precision s",
+                result: Some(Value::List {
+                    vals: vec![Value::Record {
+                        cols: vec![
+                            "measurement".to_string(),
+                            "host".to_string(),
+                            "usage".to_string(),
+                            "time".to_string(),
+                        ],
+                        vals: vec![
+                            Value::test_string("cpu"),
+                            Value::test_string("a"),
+                            Value::Float {
+                                val: 0.5,
+                                span: Span::test_data(),
+                            },
+                            Value::Date {
+                                val: Utc.timestamp(1, 0).into(),
+                                span: Span::test_data(),
+                            },
+                        ],
+                        span: Span::test_data(),
+                    }],
+                    span: Span::test_data(),
+                }),
+            },
+        ]
+    }
+}
+
+/// The unit of the timestamps in line protocol
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Precision {
+    Nanoseconds,
+    Microseconds,
+    Milliseconds,
+    Seconds,
+}
+
+impl Precision {
+    /// Parses the value of a `--precision` flag, defaulting to nanoseconds
+    pub(crate) fn from_flag(flag: Option<Spanned<String>>) -> Result<Self, ShellError> {
+        match flag {
+            None => Ok(Self::Nanoseconds),
+            Some(Spanned { item, span }) => match item.as_str() {
+                "ns" => Ok(Self::Nanoseconds),
+                "us" => Ok(Self::Microseconds),
+                "ms" => Ok(Self::Milliseconds),
+                "s" => Ok(Self::Seconds),
+                _ => Err(ShellError::UnsupportedInput(
+                    "the only possible values for precision are 'ns', 'us', 'ms' and 's'".into(),
+                    span,
+                )),
+            },
+        }
+    }
+
+    /// The number of nanoseconds in one unit of this precision
+    pub(crate) fn nanos_per_unit(&self) -> i64 {
+        match self {
+            Self::Nanoseconds => 1,
+            Self::Microseconds => 1_000,
+            Self::Milliseconds => 1_000_000,
+            Self::Seconds => 1_000_000_000,
+        }
+    }
+}
+
+fn from_lp(
+    input: PipelineData,
+    precision: Precision,
+    head: Span,
+    config: &Config,
+) -> Result<PipelineData, ShellError> {
+    let concat_string = input.collect_string("", config)?;
+
+    let rows = parse_lines(&concat_string)
+        .enumerate()
+        .map(|(idx, line)| {
+            let line = line.map_err(|e| {
+                ShellError::UnsupportedInput(
+                    format!("Could not parse line protocol at line {}: {}", idx + 1, e),
+                    head,
+                )
+            })?;
+
+            let mut cols = vec!["measurement".to_string()];
+            let mut vals = vec![Value::String {
+                val: line.series.measurement.to_string(),
+                span: head,
+            }];
+
+            let tags = line.series.tag_set.iter().flatten();
+            let is_tag = |key: &str| tags.clone().any(|(tag, _)| tag.as_str() == key);
+            let is_field = |key: &str| {
+                line.field_set
+                    .iter()
+                    .any(|(field, _)| field.as_str() == key)
+            };
+
+            // a column name can only appear once in a record, so tags and
+            // fields named like another column get a suffix
+            for (key, value) in tags.clone() {
+                cols.push(column_name(key, "_tag", is_field(key.as_str())));
+                vals.push(Value::String {
+                    val: value.to_string(),
+                    span: head,
+                });
+            }
+
+            for (key, value) in &line.field_set {
+                cols.push(column_name(key, "_field", is_tag(key.as_str())));
+                vals.push(field_to_value(value, head));
+            }
+
+            cols.push("time".to_string());
+            vals.push(match line.timestamp {
+                Some(timestamp) => {
+                    let nanos = timestamp
+                        .checked_mul(precision.nanos_per_unit())
+                        .ok_or_else(|| {
+                            ShellError::UnsupportedInput(
+                                format!("Timestamp out of range at line {}", idx + 1),
+                                head,
+                            )
+                        })?;
+
+                    Value::Date {
+                        val: Utc.timestamp_nanos(nanos).into(),
+                        span: head,
+                    }
+                }
+                None => Value::Nothing { span: head },
+            });
+
+            Ok(Value::Record {
+                cols,
+                vals,
+                span: head,
+            })
+        })
+        .collect::<Result<Vec<_>, ShellError>>()?;
+
+    Ok(PipelineData::Value(
+        Value::List {
+            vals: rows,
+            span: head,
+        },
+        None,
+    ))
+}
+
+/// The column of a tag or field named `key`, with `suffix` appended if the
+/// name is taken by the measurement, the time or a key of the other kind
+fn column_name(key: &str, suffix: &str, is_other_kind: bool) -> String {
+    if is_other_kind || key == "measurement" || key == "time" {
+        format!("{}{}", key, suffix)
+    } else {
+        key.to_string()
+    }
+}
+
+fn field_to_value(value: &FieldValue<'_>, span: Span) -> Value {
+    match value {
+        FieldValue::I64(val) => Value::Int { val: *val, span },
+        // Nushell has no unsigned integers, so values too large for an i64
+        // are widened to floats
+        FieldValue::U64(val) => match i64::try_from(*val) {
+            Ok(val) => Value::Int { val, span },
+            Err(_) => Value::Float {
+                val: *val as f64,
+                span,
+            },
+        },
+        FieldValue::F64(val) => Value::Float { val: *val, span },
+        FieldValue::String(val) => Value::String {
+            val: val.to_string(),
+            span,
+        },
+        FieldValue::Boolean(val) => Value::Bool { val: *val, span },
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_examples() {
+        use crate::test_examples;
+
+        test_examples(FromLp {})
+    }
+
+    #[test]
+    fn renames_colliding_columns() {
+        let input = PipelineData::Value(
+            Value::test_string("cpu,time=a,host=b,usage=c measurement=1i,usage=0.5 1"),
+            None,
+        );
+
+        let rows = from_lp(
+            input,
+            Precision::Nanoseconds,
+            Span::test_data(),
+            &Config::default(),
+        )
+        .unwrap()
+        .into_value(Span::test_data());
+
+        let cols = match rows {
+            Value::List { vals, .. } => match &vals[0] {
+                Value::Record { cols, .. } => cols.clone(),
+                other => panic!("unexpected row: {:?}", other),
+            },
+            other => panic!("unexpected rows: {:?}", other),
+        };
+        assert_eq!(
+            cols,
+            vec![
+                "measurement",
+                "time_tag",
+                "host",
+                "usage_tag",
+                "measurement_field",
+                "usage_field",
+                "time",
+            ]
+        );
+    }
+}
@@ -5,6 +5,7 @@ mod eml;
 mod ics;
 mod ini;
 mod json;
+mod lp;
 mod nuon;
 mod ods;
 mod ssv;
@@ -24,6 +25,7 @@ pub use eml::FromEml;
 pub use ics::FromIcs;
 pub use ini::FromIni;
 pub use json::FromJson;
+pub use lp::FromLp;
 pub use nuon::FromNuon;
 pub use ods::FromOds;
 pub use ssv::FromSsv;
@@ -33,3 +35,5 @@ pub use xlsx::FromXlsx;
 pub use xml::FromXml;
 pub use yaml::FromYaml;
 pub use yaml::FromYml;
+
+pub(crate) use lp::Precision;
@@ -0,0 +1,319 @@
+use crate::formats::from::Precision;
+use influxdb_line_protocol::LineProtocolBuilder;
+use nu_engine::CallExt;
+use nu_protocol::ast::Call;
+use nu_protocol::engine::{Command, EngineState, Stack};
+use nu_protocol::{
+    Category, Config, Example, PipelineData, ShellError, Signature, Span, SyntaxShape, Value,
+};
+
+#[derive(Clone)]
+pub struct ToLp;
+
+impl Command for ToLp {
+    fn name(&self) -> &str {
+        "to lp"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build("to lp")
+            .named(
+                "measurement",
+                SyntaxShape::String,
+                "name of the measurement, defaults to the 'measurement' column of each row",
+                Some('m'),
+            )
+            .named(
+                "tags",
+                SyntaxShape::List(Box::new(SyntaxShape::String)),
+                "columns to write as tags, left out of rows where they are empty",
+                Some('t'),
+            )
+            .named(
+                "fields",
+                SyntaxShape::List(Box::new(SyntaxShape::String)),
+                "columns to write as fields, defaults to every column that is not a tag, the measurement or the time",
+                Some('f'),
+            )
+            .named(
+                "time",
+                SyntaxShape::String,
+                "column holding the timestamp, defaults to 'time'",
+                None,
+            )
+            .named(
+                "precision",
+                SyntaxShape::String,
+                "precision of the timestamps: 'ns', 'us', 'ms' or 's', defaults to 'ns'",
+                Some('p'),
+            )
+            .category(Category::Formats)
+    }
+
+    fn examples(&self) -> Vec<Example> {
+        vec![
+            Example {
+                description: "Outputs line protocol with the host column as a tag",
+                example: "[[measurement host usage]; [cpu a 0.5]] | to lp --tags [host]",
+                result: Some(Value::test_string("cpu,host=a usage=0.5\n")),
+            },
+            Example {
+                description: "Outputs line protocol for a fixed measurement with timestamps",
+                example: "[[host count time]; [a 3 1000]] | to lp -m cpu -t [host]",
+                result: Some(Value::test_string("cpu,host=a count=3i 1000\n")),
+            },
+            Example {
+                description: "Leaves out tags with empty values",
+                example: "[[host region usage]; [a '' 0.5]] | to lp -m cpu -t [host region]",
+                result: Some(Value::test_string("cpu,host=a usage=0.5\n")),
+            },
+            Example {
+                description: "Outputs line protocol with second precision timestamps from dates",
+                example: "[[host usage time]; [a 0.5 (date now)]] | to lp -m cpu -t [host] -p s",
+                result: None,
+            },
+        ]
+    }
+
+    fn usage(&self) -> &str {
+        "Convert table into InfluxDB line protocol text"
+    }
+
+    fn run(
+        &self,
+        engine_state: &EngineState,
+        stack: &mut Stack,
+        call: &Call,
+        input: PipelineData,
+    ) -> Result<nu_protocol::PipelineData, ShellError> {
+        let head = call.head;
+        let options = LpOptions::from_call(engine_state, stack, call)?;
+        let config = engine_state.get_config();
+        to_lp(input, &options, head, config)
+    }
+}
+
+/// Describes how the columns of a table map onto line protocol
+#[derive(Clone, Debug)]
+pub(crate) struct LpOptions {
+    /// Measurement used for every row, instead of the `measurement` column
+    pub measurement: Option<String>,
+
+    /// Columns written as tags
+    pub tags: Vec<String>,
+
+    /// Columns written as fields, if not every remaining column
+    pub fields: Option<Vec<String>>,
+
+    /// Column holding the timestamp
+    pub time_column: String,
+
+    /// Unit of the written timestamps
+    pub precision: Precision,
+}
+
+impl LpOptions {
+    /// Reads the `--measurement`, `--tags`, `--fields`, `--time` and
+    /// `--precision` flags of a command
+    pub(crate) fn from_call(
+        engine_state: &EngineState,
+        stack: &mut Stack,
+        call: &Call,
+    ) -> Result<Self, ShellError> {
+        Ok(Self {
+            measurement: call.get_flag(engine_state, stack, "measurement")?,
+            tags: call
+                .get_flag(engine_state, stack, "tags")?
+                .unwrap_or_default(),
+            fields: call.get_flag(engine_state, stack, "fields")?,
+            time_column: call
+                .get_flag(engine_state, stack, "time")?
+                .unwrap_or_else(|| "time".to_string()),
+            precision: Precision::from_flag(call.get_flag(engine_state, stack, "precision")?)?,
+        })
+    }
+
+    fn is_field(&self, col: &str) -> bool {
+        match &self.fields {
+            Some(fields) => fields.iter().any(|f| f == col),
+            None => {
+                col != "measurement"
+                    && col != self.time_column
+                    && !self.tags.iter().any(|t| t == col)
+            }
+        }
+    }
+}
+
+fn to_lp(
+    input: PipelineData,
+    options: &LpOptions,
+    head: Span,
+    config: &Config,
+) -> Result<PipelineData, ShellError> {
+    let mut buf = vec![];
+
+    for value in input.into_iter() {
+        value_to_lp(&value, options, config, head, &mut buf)?;
+    }
+
+    Ok(PipelineData::Value(
+        Value::String {
+            val: String::from_utf8_lossy(&buf).into_owned(),
+            span: head,
+        },
+        None,
+    ))
+}
+
+/// A field value in one of the types line protocol supports
+enum LpField {
+    Int(i64),
+    Float(f64),
+    Bool(bool),
+    String(String),
+}
+
+macro_rules! add_field {
+    ($builder:expr, $key:expr, $value:expr) => {
+        match $value {
+            LpField::Int(v) => $builder.field($key, *v),
+            LpField::Float(v) => $builder.field($key, *v),
+            LpField::Bool(v) => $builder.field($key, *v),
+            LpField::String(v) => $builder.field($key, v.as_str()),
+        }
+    };
+}
+
+/// Renders a record as one line of line protocol, appending it to `buf`
+pub(crate) fn value_to_lp(
+    value: &Value,
+    options: &LpOptions,
+    config: &Config,
+    head: Span,
+    buf: &mut Vec<u8>,
+) -> Result<(), ShellError> {
+    let (cols, vals, span) = match value {
+        Value::Record { cols, vals, span } => (cols, vals, *span),
+        Value::Error { error } => return Err(error.clone()),
+        other => {
+            return Err(ShellError::UnsupportedInput(
+                "Expected a table or a record to convert to line protocol".to_string(),
+                other.span().unwrap_or(head),
+            ))
+        }
+    };
+
+    let column = |name: &str| {
+        cols.iter()
+            .position(|c| c == name)
+            .map(|idx| &vals[idx])
+            .filter(|v| !matches!(v, Value::Nothing { .. }))
+    };
+
+    let measurement = match &options.measurement {
+        Some(measurement) => measurement.clone(),
+        None => match column("measurement") {
+            Some(v) => v.clone().into_string("", config),
+            None => {
+                return Err(ShellError::GenericError(
+                    "No measurement for row".to_string(),
+                    "row has no 'measurement' column".to_string(),
+                    Some(span),
+                    Some("Pass --measurement or add a 'measurement' column".to_string()),
+                    Vec::new(),
+                ))
+            }
+        },
+    };
+
+    // line protocol has no empty tag values, so those tags are left out
+    let mut tags = vec![];
+    for tag in &options.tags {
+        if let Some(v) = column(tag) {
+            let value = v.clone().into_string("", config);
+            if !value.is_empty() {
+                tags.push((tag.as_str(), value));
+            }
+        }
+    }
+
+    let mut fields = vec![];
+    for (col, val) in cols.iter().zip(vals) {
+        if options.is_field(col) {
+            if let Some(field) = value_to_field(val, config) {
+                fields.push((col.as_str(), field));
+            }
+        }
+    }
+
+    let timestamp = match column(&options.time_column) {
+        None => None,
+        Some(Value::Int { val, .. }) => Some(*val),
+        Some(Value::Date { val, .. }) => {
+            Some(val.timestamp_nanos() / options.precision.nanos_per_unit())
+        }
+        Some(other) => {
+            return Err(ShellError::UnsupportedInput(
+                format!(
+                    "Expected a date or an integer in the '{}' column",
+                    options.time_column
+                ),
+                other.span().unwrap_or(span),
+            ))
+        }
+    };
+
+    let mut fields = fields.iter();
+    let (first_key, first_value) = fields.next().ok_or_else(|| {
+        ShellError::GenericError(
+            "No fields for row".to_string(),
+            "line protocol needs at least one non-empty field".to_string(),
+            Some(span),
+            Some("Use --tags and --fields to choose which columns are tags and fields".to_string()),
+            Vec::new(),
+        )
+    })?;
+
+    let mut builder = LineProtocolBuilder::new_with(&mut *buf).measurement(&measurement);
+    for (key, value) in &tags {
+        builder = builder.tag(key, value);
+    }
+
+    let mut builder = add_field!(builder, first_key, first_value);
+    for (key, value) in fields {
+        builder = add_field!(builder, key, value);
+    }
+
+    match timestamp {
+        Some(timestamp) => builder.timestamp(timestamp).close_line(),
+        None => builder.close_line(),
+    };
+
+    Ok(())
+}
+
+fn value_to_field(value: &Value, config: &Config) -> Option<LpField> {
+    match value {
+        Value::Nothing { .. } => None,
+        Value::Int { val, .. } => Some(LpField::Int(*val)),
+        Value::Float { val, .. } => Some(LpField::Float(*val)),
+        Value::Bool { val, .. } => Some(LpField::Bool(*val)),
+        Value::Filesize { val, .. } => Some(LpField::Int(*val)),
+        Value::Duration { val, .. } => Some(LpField::Int(*val)),
+        Value::Date { val, .. } => Some(LpField::String(val.to_rfc3339())),
+        other => Some(LpField::String(other.clone().into_string("", config))),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_examples() {
+        use crate::test_examples;
+
+        test_examples(ToLp {})
+    }
+}
@@ -3,6 +3,7 @@ mod csv;
 mod delimited;
 mod html;
 mod json;
+mod lp;
 mod md;
 mod nuon;
 mod text;
@@ -18,6 +19,7 @@ pub use self::url::ToUrl;
 pub use command::To;
 pub use html::ToHtml;
 pub use json::ToJson;
+pub use lp::ToLp;
 pub use md::ToMd;
 pub use nuon::ToNuon;
 pub use text::ToText;
@@ -26,3 +28,4 @@ pub use xml::ToXml;
 pub use yaml::ToYaml;
 
 pub(crate) use json::value_to_json_value;
+pub(crate) use lp::{value_to_lp, LpOptions};
@@ -0,0 +1,419 @@
+use super::clients::with_connection;
+use super::nuerror::{IoxRequest, NuIoxError};
+use crate::formats::{value_to_lp, LpOptions};
+use influxdb_iox_client::write::Client;
+use nu_protocol::{Config, PipelineData, ShellError, Span, Spanned, Value};
+
+/// Default number of lines sent to the router in one write request
+pub const DEFAULT_BATCH_SIZE: usize = 5_000;
+
+/// What happened to the line protocol sent by a write command
+#[derive(Debug, Default)]
+pub struct WriteSummary {
+    /// Number of lines the router accepted
+    pub lines: usize,
+
+    /// Number of bytes of line protocol the router accepted
+    pub bytes: usize,
+
+    /// Number of write requests sent
+    pub batches: usize,
+
+    /// The batches that failed, as `(batch number, lines in batch, error)`
+    pub errors: Vec<(usize, usize, String)>,
+}
+
+impl WriteSummary {
+    pub fn into_value(self, span: Span) -> Value {
+        let errors = self
+            .errors
+            .into_iter()
+            .map(|(batch, lines, error)| Value::Record {
+                cols: vec!["batch".into(), "lines".into(), "error".into()],
+                vals: vec![
+                    Value::int(batch as i64, span),
+                    Value::int(lines as i64, span),
+                    Value::string(error, span),
+                ],
+                span,
+            })
+            .collect();
+
+        Value::Record {
+            cols: vec![
+                "lines".into(),
+                "bytes".into(),
+                "batches".into(),
+                "errors".into(),
+            ],
+            vals: vec![
+                Value::int(self.lines as i64, span),
+                Value::Filesize {
+                    val: self.bytes as i64,
+                    span,
+                },
+                Value::int(self.batches as i64, span),
+                Value::List { vals: errors, span },
+            ],
+            span,
+        }
+    }
+}
+
+/// Collects line protocol into batches of `batch_size` lines and writes each
+/// full batch to the router, so arbitrarily large inputs are never held in
+/// memory at once.
+///
+/// Input may arrive in chunks that do not end on a line boundary, the
+/// incomplete tail of a chunk is kept until the rest of the line arrives.
+pub struct LpBatchWriter<'a> {
+    addr: &'a str,
+    dbname: &'a Spanned<String>,
+    span: Span,
+    batch_size: usize,
+
+    /// The argument holding the input, if it was not piped in
+    data_span: Option<Span>,
+
+    /// Incomplete line at the end of the input seen so far
+    pending: Vec<u8>,
+
+    /// Number of lines of input seen so far, including blank lines and
+    /// comments
+    input_lines: usize,
+
+    /// Lines of the batch being collected
+    batch: Vec<u8>,
+    batch_lines: usize,
+
+    /// The line of the input each line of the batch came from
+    batch_line_numbers: Vec<usize>,
+
+    summary: WriteSummary,
+
+    /// Why the first rejected batch was rejected
+    first_error: Option<NuIoxError>,
+}
+
+impl<'a> LpBatchWriter<'a> {
+    pub fn new(addr: &'a str, dbname: &'a Spanned<String>, batch_size: usize, span: Span) -> Self {
+        Self {
+            addr,
+            dbname,
+            span,
+            batch_size,
+            data_span: None,
+            pending: vec![],
+            input_lines: 0,
+            batch: vec![],
+            batch_lines: 0,
+            batch_line_numbers: vec![],
+            summary: WriteSummary::default(),
+            first_error: None,
+        }
+    }
+
+    /// Reports invalid line protocol at `span`, the argument the input came
+    /// from, rather than at the command
+    pub fn set_data_span(&mut self, span: Span) {
+        self.data_span = Some(span);
+    }
+
+    /// Adds raw line protocol text
+    pub fn push_bytes(&mut self, data: &[u8]) -> Result<(), ShellError> {
+        let mut pieces = data.split(|b| *b == b'\n');
+
+        // every piece but the last one was terminated by a newline
+        let mut last = pieces.next().unwrap_or_default();
+        for piece in pieces {
+            self.pending.extend_from_slice(last);
+            let line = std::mem::take(&mut self.pending);
+            self.push_line(&line)?;
+            last = piece;
+        }
+        self.pending.extend_from_slice(last);
+
+        Ok(())
+    }
+
+    /// Adds a pipeline value: line protocol strings or binary data, lists of
+    /// them, or records that are converted to line protocol using `options`
+    pub fn push_value(
+        &mut self,
+        value: &Value,
+        options: &LpOptions,
+        config: &Config,
+    ) -> Result<(), ShellError> {
+        match value {
+            Value::String { val, .. } => {
+                self.push_bytes(val.as_bytes())?;
+                self.end_of_chunk()
+            }
+            Value::Binary { val, .. } => self.push_bytes(val),
+            Value::List { vals, .. } => {
+                for val in vals {
+                    self.push_value(val, options, config)?;
+                }
+                Ok(())
+            }
+            Value::Record { .. } => {
+                let mut line = vec![];
+                value_to_lp(value, options, config, self.span, &mut line)?;
+                self.push_bytes(&line)
+            }
+            Value::Error { error } => Err(error.clone()),
+            other => Err(ShellError::UnsupportedInput(
+                "Expected line protocol strings or a table to write".to_string(),
+                other.span().unwrap_or(self.span),
+            )),
+        }
+    }
+
+    /// Adds all of the values of the pipeline input
+    pub fn push_pipeline(
+        &mut self,
+        input: PipelineData,
+        options: &LpOptions,
+        config: &Config,
+    ) -> Result<(), ShellError> {
+        match input {
+            // external streams are chunked arbitrarily, so lines may continue
+            // into the next chunk
+            PipelineData::ExternalStream {
+                stdout: Some(stream),
+                ..
+            } => {
+                for chunk in stream {
+                    match chunk? {
+                        Value::String { val, .. } => self.push_bytes(val.as_bytes())?,
+                        Value::Binary { val, .. } => self.push_bytes(&val)?,
+                        other => self.push_value(&other, options, config)?,
+                    }
+                }
+                Ok(())
+            }
+            input => {
+                for value in input.into_iter() {
+                    self.push_value(&value, options, config)?;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Writes any remaining lines and returns what was written.
+    ///
+    /// If batches were rejected but nothing was written at all, the write
+    /// fails with the reason the first batch was rejected.
+    pub fn finish(mut self) -> Result<WriteSummary, ShellError> {
+        self.end_of_chunk()?;
+        self.flush()?;
+
+        match self.first_error {
+            Some(error) if self.summary.lines == 0 => Err(error.into()),
+            _ => Ok(self.summary),
+        }
+    }
+
+    /// Treats the incomplete line seen so far as a whole line, as a string
+    /// value ends its last line even without a trailing newline
+    fn end_of_chunk(&mut self) -> Result<(), ShellError> {
+        if self.pending.is_empty() {
+            return Ok(());
+        }
+
+        let line = std::mem::take(&mut self.pending);
+        self.push_line(&line)
+    }
+
+    fn push_line(&mut self, line: &[u8]) -> Result<(), ShellError> {
+        self.input_lines += 1;
+
+        let line = trim_line(line);
+        if line.is_empty() || line.starts_with(b"#") {
+            return Ok(());
+        }
+
+        self.batch.extend_from_slice(line);
+        self.batch.push(b'\n');
+        self.batch_lines += 1;
+        self.batch_line_numbers.push(self.input_lines);
+
+        if self.batch_lines >= self.batch_size {
+            self.flush()?;
+        }
+
+        Ok(())
+    }
+
+    /// Sends the collected batch to the router.
+    ///
+    /// Rejected batches are recorded in the summary and writing carries on,
+    /// but an unreachable router or a missing namespace aborts the write.
+    fn flush(&mut self) -> Result<(), ShellError> {
+        if self.batch_lines == 0 {
+            return Ok(());
+        }
+
+        let batch = std::mem::take(&mut self.batch);
+        let batch_lines = std::mem::take(&mut self.batch_lines);
+        let line_numbers = std::mem::take(&mut self.batch_line_numbers);
+        let bytes = batch.len();
+
+        self.summary.batches += 1;
+        let batch_number = self.summary.batches;
+
+        let lp_data = match String::from_utf8(batch) {
+            Ok(lp_data) => lp_data,
+            Err(e) => {
+                self.summary
+                    .errors
+                    .push((batch_number, batch_lines, e.to_string()));
+                return Ok(());
+            }
+        };
+
+        let (addr, dbname, span) = (self.addr, self.dbname, self.span);
+        let mut request = IoxRequest::new(addr, "Write failed", span).namespace(dbname);
+        if let Some(data_span) = self.data_span {
+            request = request.data(data_span);
+        }
+
+        let result = with_connection(addr, span, |connection| {
+            let lp_data = &lp_data;
+            async move {
+                let mut client = Client::new(connection);
+
+                match client.write_lp(&dbname.item, lp_data, 0).await {
+                    Ok(lines) => Ok(Ok(lines)),
+                    Err(e) => match NuIoxError::from_client_error(e, &request) {
+                        error if error.is_fatal() => Err(ShellError::from(error)),
+                        error => Ok(Err(error)),
+                    },
+                }
+            }
+        })?;
+
+        match result {
+            Ok(lines) => {
+                self.summary.lines += lines;
+                self.summary.bytes += bytes;
+            }
+            Err(error) => {
+                // the error counts lines from the start of the batch
+                let error = match error {
+                    NuIoxError::InvalidLineProtocol {
+                        line: Some(line), ..
+                    } => {
+                        let input_line = line_numbers
+                            .get(line.saturating_sub(1))
+                            .copied()
+                            .unwrap_or(line);
+                        error.at_line(input_line)
+                    }
+                    error => error,
+                };
+
+                self.summary
+                    .errors
+                    .push((batch_number, batch_lines, error.to_string()));
+                self.first_error.get_or_insert(error);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn trim_line(line: &[u8]) -> &[u8] {
+    let start = line
+        .iter()
+        .position(|b| !b.is_ascii_whitespace())
+        .unwrap_or(line.len());
+    let end = line
+        .iter()
+        .rposition(|b| !b.is_ascii_whitespace())
+        .map_or(start, |end| end + 1);
+
+    &line[start..end]
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::formats::Precision;
+
+    #[test]
+    fn trims_lines() {
+        assert_eq!(trim_line(b"  cpu usage=1 1\r"), b"cpu usage=1 1");
+        assert_eq!(trim_line(b"cpu usage=1"), b"cpu usage=1");
+        assert_eq!(trim_line(b" \t "), b"");
+        assert_eq!(trim_line(b""), b"");
+    }
+
+    #[test]
+    fn batches_lines_split_across_chunks() {
+        let dbname = Spanned {
+            item: String::new(),
+            span: Span::test_data(),
+        };
+        let mut writer = LpBatchWriter::new("", &dbname, 100, Span::test_data());
+
+        writer.push_bytes(b"cpu usage=1 1\ncpu us").unwrap();
+        writer.push_bytes(b"age=2 2\n\n# comment\nmem").unwrap();
+        writer.push_bytes(b" used=3 3").unwrap();
+        writer.end_of_chunk().unwrap();
+
+        assert_eq!(writer.batch_lines, 3);
+        assert_eq!(writer.batch_line_numbers, vec![1, 2, 5]);
+        assert_eq!(
+            writer.batch,
+            b"cpu usage=1 1\ncpu usage=2 2\nmem used=3 3\n".to_vec()
+        );
+    }
+
+    #[test]
+    fn batches_tables_with_tags() {
+        let span = Span::test_data();
+        let dbname = Spanned {
+            item: String::new(),
+            span,
+        };
+        let mut writer = LpBatchWriter::new("", &dbname, 100, span);
+
+        let row = |host: &str, usage: f64, ts: i64| Value::Record {
+            cols: vec!["host".into(), "usage".into(), "ts".into()],
+            vals: vec![
+                Value::string(host, span),
+                Value::float(usage, span),
+                Value::int(ts, span),
+            ],
+            span,
+        };
+        let table = Value::List {
+            vals: vec![row("a", 0.5, 1), row("b", 1.5, 2)],
+            span,
+        };
+        let options = LpOptions {
+            measurement: Some("cpu".into()),
+            tags: vec!["host".into()],
+            fields: None,
+            time_column: "ts".into(),
+            precision: Precision::Nanoseconds,
+        };
+
+        writer
+            .push_pipeline(
+                PipelineData::Value(table, None),
+                &options,
+                &Config::default(),
+            )
+            .unwrap();
+
+        assert_eq!(writer.batch_lines, 2);
+        assert_eq!(
+            writer.batch,
+            b"cpu,host=a usage=0.5 1\ncpu,host=b usage=1.5 2\n".to_vec()
+        );
+    }
+}
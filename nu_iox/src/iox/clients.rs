@@ -0,0 +1,98 @@
+use super::util::{connect, get_runtime};
+use influxdb_iox_client::connection::Connection;
+use lazy_static::lazy_static;
+use nu_protocol::{ShellError, Span};
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex, MutexGuard};
+use tokio::runtime::Runtime;
+
+lazy_static! {
+    static ref IOX_CLIENTS: Mutex<IoxClients> = Mutex::new(IoxClients::default());
+}
+
+/// The tokio runtime and gRPC connections shared by every iox command of a
+/// shell session, so repeated commands do not pay for a new runtime and a
+/// new connection each time
+#[derive(Default)]
+struct IoxClients {
+    /// Runtime driving all requests, created on first use
+    runtime: Option<Arc<Runtime>>,
+
+    /// Open connections keyed by server address
+    connections: HashMap<String, Connection>,
+}
+
+fn iox_clients() -> MutexGuard<'static, IoxClients> {
+    // nothing in the cache can be left half updated, so a poisoned lock is
+    // still safe to use
+    IOX_CLIENTS.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Returns the shared runtime, creating it on first use
+pub fn iox_runtime() -> Result<Arc<Runtime>, ShellError> {
+    let mut clients = iox_clients();
+
+    match &clients.runtime {
+        Some(runtime) => Ok(Arc::clone(runtime)),
+        None => {
+            let num_threads: Option<usize> = None;
+            let runtime =
+                Arc::new(get_runtime(num_threads).map_err(|e| ShellError::IOError(e.to_string()))?);
+            clients.runtime = Some(Arc::clone(&runtime));
+            Ok(runtime)
+        }
+    }
+}
+
+/// Runs `future` to completion on the shared runtime
+pub fn block_on<F: Future>(future: F) -> Result<F::Output, ShellError> {
+    Ok(iox_runtime()?.block_on(future))
+}
+
+/// Returns the cached connection to `addr`, connecting if there is none yet
+pub async fn cached_connection(addr: &str, span: Span) -> Result<Connection, ShellError> {
+    if let Some(connection) = iox_clients().connections.get(addr) {
+        return Ok(connection.clone());
+    }
+
+    let connection = connect(addr, span).await?;
+    iox_clients()
+        .connections
+        .insert(addr.to_string(), connection.clone());
+
+    Ok(connection)
+}
+
+/// Drops the cached connection to `addr`, so the next command reconnects
+pub fn evict_connection(addr: &str) {
+    iox_clients().connections.remove(addr);
+}
+
+/// Runs `f` on the shared runtime with the cached connection to `addr`.
+///
+/// If `f` fails with a [`ShellError::NetworkFailure`] the connection is
+/// assumed to be broken (e.g. the server restarted), so it is dropped from
+/// the cache and `f` is retried once with a fresh connection.
+pub fn with_connection<T, F, Fut>(addr: &str, span: Span, mut f: F) -> Result<T, ShellError>
+where
+    F: FnMut(Connection) -> Fut,
+    Fut: Future<Output = Result<T, ShellError>>,
+{
+    block_on(async move {
+        let connection = cached_connection(addr, span).await?;
+
+        match f(connection).await {
+            Err(ShellError::NetworkFailure(_, _)) => {
+                evict_connection(addr);
+                let connection = cached_connection(addr, span).await?;
+                let result = f(connection).await;
+                if let Err(ShellError::NetworkFailure(_, _)) = &result {
+                    evict_connection(addr);
+                }
+                result
+            }
+            result => result,
+        }
+    })?
+}
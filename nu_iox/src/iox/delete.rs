@@ -0,0 +1,167 @@
+use super::clients::with_connection;
+use super::nuerror::IoxRequest;
+use super::util::{get_dbname, get_iox_addr, IoxService};
+use super::values::nanos_to_value;
+use data_types::DeletePredicate;
+use influxdb_iox_client::delete::Client;
+use influxdb_iox_client::error::Error;
+use nu_engine::CallExt;
+use nu_protocol::ast::Call;
+use nu_protocol::engine::{Command, EngineState, Stack};
+use nu_protocol::{
+    Category, Example, IntoPipelineData, PipelineData, ShellError, Signature, Span, Spanned,
+    SyntaxShape, Value,
+};
+use predicate::delete_predicate::parse_delete_predicate;
+
+#[derive(Clone)]
+pub struct Ioxdelete;
+
+impl Command for Ioxdelete {
+    fn name(&self) -> &str {
+        "ioxdelete"
+    }
+
+    fn signature(&self) -> nu_protocol::Signature {
+        Signature::build("ioxdelete")
+            .required_named(
+                "table",
+                SyntaxShape::String,
+                "name of the table to delete from",
+                Some('t'),
+            )
+            .required_named(
+                "start",
+                SyntaxShape::String,
+                "start of the time range to delete, as RFC3339 or nanoseconds since the epoch",
+                None,
+            )
+            .required_named(
+                "stop",
+                SyntaxShape::String,
+                "end of the time range to delete, as RFC3339 or nanoseconds since the epoch",
+                None,
+            )
+            .named(
+                "predicate",
+                SyntaxShape::String,
+                "only delete rows matching these conditions, e.g. \"host = 'a' and region != 'west'\"",
+                Some('p'),
+            )
+            .named(
+                "dbname",
+                SyntaxShape::String,
+                "name of the database to delete from",
+                Some('d'),
+            )
+            .named(
+                "host",
+                SyntaxShape::String,
+                "address of the IOx router, overriding IOX_ROUTER_ADDR and IOX_HOST",
+                None,
+            )
+            .category(Category::Filters)
+    }
+
+    fn usage(&self) -> &str {
+        "Delete data from a table of an Iox Database."
+    }
+
+    fn run(
+        &self,
+        engine_state: &EngineState,
+        stack: &mut Stack,
+        call: &Call,
+        _input: PipelineData,
+    ) -> Result<PipelineData, ShellError> {
+        let table = required_flag(engine_state, stack, call, "table")?;
+        let start = required_flag(engine_state, stack, call, "start")?;
+        let stop = required_flag(engine_state, stack, call, "stop")?;
+        let predicate: Option<Spanned<String>> = call.get_flag(engine_state, stack, "predicate")?;
+        let dbname = get_dbname(engine_state, stack, call)?;
+        let addr = get_iox_addr(engine_state, stack, call, IoxService::Router)?;
+
+        let predicate_str = predicate.as_ref().map_or("", |p| p.item.as_str());
+        let delete_predicate = parse_delete_predicate(&start.item, &stop.item, predicate_str)
+            .map_err(|e| {
+                ShellError::UnsupportedInput(
+                    format!("Invalid delete: {}", e),
+                    predicate.as_ref().map_or(call.head, |p| p.span),
+                )
+            })?;
+
+        tokio_block_delete(&addr, &dbname, &table.item, &delete_predicate, call.head)?;
+
+        Ok(Value::Record {
+            cols: vec![
+                "namespace".into(),
+                "table".into(),
+                "start".into(),
+                "stop".into(),
+                "predicate".into(),
+            ],
+            vals: vec![
+                Value::string(dbname.item, call.head),
+                Value::string(table.item, call.head),
+                nanos_to_value(delete_predicate.range.start(), call.head),
+                nanos_to_value(delete_predicate.range.end(), call.head),
+                Value::string(predicate_str, call.head),
+            ],
+            span: call.head,
+        }
+        .into_pipeline_data())
+    }
+
+    fn examples(&self) -> Vec<Example> {
+        vec![
+            Example {
+                description: "Delete all rows of the cpu table written during 2022",
+                example: r#"ioxdelete -t cpu --start 2022-01-01T00:00:00Z --stop 2023-01-01T00:00:00Z"#,
+                result: None,
+            },
+            Example {
+                description: "Delete the rows of host a from the cpu table of the bananas database",
+                example: r#"ioxdelete -d bananas -t cpu --start 0 --stop 1700000000000000000 -p "host = 'a'""#,
+                result: None,
+            },
+        ]
+    }
+}
+
+fn required_flag(
+    engine_state: &EngineState,
+    stack: &mut Stack,
+    call: &Call,
+    name: &str,
+) -> Result<Spanned<String>, ShellError> {
+    call.get_flag(engine_state, stack, name)?
+        .ok_or_else(|| ShellError::MissingParameter(name.to_string(), call.head))
+}
+
+pub fn tokio_block_delete(
+    addr: &str,
+    dbname: &Spanned<String>,
+    table: &str,
+    predicate: &DeletePredicate,
+    span: Span,
+) -> Result<(), ShellError> {
+    let request = IoxRequest::new(addr, "Unable to delete", span).namespace(dbname);
+
+    with_connection(addr, span, |connection| async move {
+        let result = Client::new(connection)
+            .delete(&dbname.item, table, predicate.clone().into())
+            .await;
+
+        match result {
+            Ok(()) => Ok(()),
+            Err(Error::Unimplemented(_)) => Err(ShellError::GenericError(
+                "Unable to delete".into(),
+                format!("the server at {} does not support deletes", addr),
+                Some(span),
+                Some("Use --host to pick a server that serves the delete API".into()),
+                Vec::new(),
+            )),
+            Err(e) => Err(request.error(e)),
+        }
+    })
+}
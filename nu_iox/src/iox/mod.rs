@@ -1,17 +1,39 @@
+mod batch;
+mod clients;
+mod delete;
 mod delimited;
 mod namespace;
 mod nuclient;
 mod nuerror;
+mod parquetfiles;
+mod partitions;
+mod schema;
 mod sql;
+mod storage;
+mod storeget;
+mod stream;
 mod util;
+mod values;
 mod write;
 mod writefile;
+mod writeinfo;
 
+pub use batch::*;
+pub use clients::*;
+pub use delete::Ioxdelete;
 pub use delimited::*;
 pub use namespace::Ioxnamespace;
 pub use nuclient::*;
 pub use nuerror::*;
+pub use parquetfiles::Ioxparquetfiles;
+pub use partitions::Ioxpartitions;
+pub use schema::Ioxschema;
 pub use sql::Ioxsql;
+pub use storage::*;
+pub use storeget::Ioxstoreget;
+pub use stream::QueryStream;
 pub use util::*;
+pub use values::*;
 pub use write::Ioxwrite;
 pub use writefile::Ioxwritefile;
+pub use writeinfo::Ioxwriteinfo;
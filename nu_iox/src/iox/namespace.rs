@@ -1,8 +1,12 @@
+use super::clients::with_connection;
 use super::delimited::from_delimited_data;
-use super::util::get_runtime;
+use super::nuerror::{IoxRequest, NuIoxError};
+use super::util::{get_iox_addr, IoxService};
 use nu_protocol::ast::Call;
 use nu_protocol::engine::{Command, EngineState, Stack};
-use nu_protocol::{Category, Example, PipelineData, ShellError, Signature, Span, Value};
+use nu_protocol::{
+    Category, Example, PipelineData, ShellError, Signature, Span, SyntaxShape, Value,
+};
 
 use csv::Trim;
 
@@ -15,7 +19,14 @@ impl Command for Ioxnamespace {
     }
 
     fn signature(&self) -> nu_protocol::Signature {
-        Signature::build("ioxnamespace").category(Category::Filters)
+        Signature::build("ioxnamespace")
+            .named(
+                "host",
+                SyntaxShape::String,
+                "address of the IOx querier, overriding IOX_QUERIER_ADDR and IOX_HOST",
+                None,
+            )
+            .category(Category::Filters)
     }
 
     fn usage(&self) -> &str {
@@ -25,11 +36,12 @@ impl Command for Ioxnamespace {
     fn run(
         &self,
         engine_state: &EngineState,
-        _stack: &mut Stack,
+        stack: &mut Stack,
         call: &Call,
         _input: PipelineData,
     ) -> Result<PipelineData, ShellError> {
-        let namespace_result = tokio_block_namespace();
+        let addr = get_iox_addr(engine_state, stack, call, IoxService::Querier)?;
+        let namespace_result = tokio_block_namespace(&addr, call.head)?;
 
         let no_infer = false;
         let noheaders = false;
@@ -38,7 +50,7 @@ impl Command for Ioxnamespace {
 
         let input = PipelineData::Value(
             Value::String {
-                val: namespace_result.unwrap(),
+                val: namespace_result,
                 span: call.head,
             },
             None,
@@ -51,32 +63,31 @@ impl Command for Ioxnamespace {
     }
 
     fn examples(&self) -> Vec<Example> {
-        vec![Example {
-            description: "Show the databases or namespaces",
-            example: r#"ioxnamespace"#,
-            result: None,
-        }]
+        vec![
+            Example {
+                description: "Show the databases or namespaces",
+                example: r#"ioxnamespace"#,
+                result: None,
+            },
+            Example {
+                description: "Show the namespaces known to a querier on another host",
+                example: r#"ioxnamespace --host querier.example.com"#,
+                result: None,
+            },
+        ]
     }
 }
 
-pub fn tokio_block_namespace() -> Result<String, std::io::Error> {
+pub fn tokio_block_namespace(addr: &str, span: Span) -> Result<String, ShellError> {
     use crate::iox::Nuclient;
-    use influxdb_iox_client::connection::Builder;
-    let num_threads: Option<usize> = None;
-    let tokio_runtime = get_runtime(num_threads)?;
-
-    let namespace = tokio_runtime.block_on(async move {
-        let connection = Builder::default()
-            .build("http://127.0.0.1:8082")
-            .await
-            .expect("client should be valid");
 
+    with_connection(addr, span, |connection| async move {
         let mut repl = Nuclient::new(connection);
         let _output_format = repl.set_output_format("csv");
 
-        let namespace = repl.list_namespaces().await.expect("namespaces");
-        namespace
-    });
-
-    Ok(namespace)
+        repl.list_namespaces().await.map_err(|e| {
+            let request = IoxRequest::new(addr, "Unable to list namespaces", span);
+            ShellError::from(NuIoxError::from_query_error(e, &request))
+        })
+    })
 }
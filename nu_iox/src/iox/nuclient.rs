@@ -8,7 +8,12 @@ use observability_deps::tracing::{debug, info};
 use snafu::{ResultExt, Snafu};
 
 use influxdb_iox_client::{
-    connection::Connection, flight::generated_types::ReadInfo, format::QueryOutputFormat,
+    connection::Connection,
+    flight::{
+        generated_types::{QueryType, ReadInfo},
+        PerformQuery,
+    },
+    format::QueryOutputFormat,
 };
 
 #[derive(Debug, Snafu)]
@@ -33,6 +38,9 @@ pub enum Error {
     RunningRemoteQuery {
         source: influxdb_iox_client::flight::Error,
     },
+
+    #[snafu(display("Error: no database selected"))]
+    NoDatabaseSelected,
 }
 
 pub type Result<T, E = Error> = std::result::Result<T, E>;
@@ -96,14 +104,42 @@ impl Nuclient {
         Ok(result_str)
     }
 
+    // Run a query against the currently selected remote database and return the raw record batches
+    pub async fn query_sql(&mut self, sql: String) -> Result<Vec<RecordBatch>> {
+        match &mut self.query_engine {
+            None => NoDatabaseSelectedSnafu.fail(),
+            Some(QueryEngine::Remote(db_name)) => {
+                info!(%db_name, %sql, "Running sql on remote database");
+
+                scrape_query(&mut self.flight_client, db_name, &sql).await
+            }
+        }
+    }
+
+    // Start a query against the currently selected remote database, returning its batches as they arrive
+    pub async fn stream_sql(&mut self, sql: String) -> Result<PerformQuery> {
+        match &mut self.query_engine {
+            None => NoDatabaseSelectedSnafu.fail(),
+            Some(QueryEngine::Remote(db_name)) => {
+                info!(%db_name, %sql, "Streaming sql from remote database");
+
+                self.flight_client
+                    .perform_query(ReadInfo {
+                        namespace_name: db_name.to_string(),
+                        sql_query: sql,
+                        params: vec![],
+                        query_type: QueryType::Sql.into(),
+                    })
+                    .await
+                    .context(RunningRemoteQuerySnafu)
+            }
+        }
+    }
+
     // Run a command against the currently selected remote database
     pub async fn run_sql(&mut self, sql: String) -> Result<String> {
         let batches = match &mut self.query_engine {
-            None => {
-                println!("Error: no database selected.");
-                println!("Hint: Run USE DATABASE <dbname> to select database");
-                return Ok("Error: no database selected".to_string());
-            }
+            None => return NoDatabaseSelectedSnafu.fail(),
             Some(QueryEngine::Remote(db_name)) => {
                 info!(%db_name, %sql, "Running sql on remote database");
 
@@ -121,11 +157,7 @@ impl Nuclient {
         let start = Instant::now();
 
         let batches = match &mut self.query_engine {
-            None => {
-                println!("Error: no database selected.");
-                println!("Hint: Run USE DATABASE <dbname> to select database");
-                return Ok(());
-            }
+            None => return NoDatabaseSelectedSnafu.fail(),
             Some(QueryEngine::Remote(db_name)) => {
                 info!(%db_name, %sql, "Running sql on remote database");
 
@@ -209,6 +241,8 @@ async fn scrape_query(
         .perform_query(ReadInfo {
             namespace_name: db_name.to_string(),
             sql_query: query.to_string(),
+            params: vec![],
+            query_type: QueryType::Sql.into(),
         })
         .await
         .context(RunningRemoteQuerySnafu)?;
@@ -1,120 +1,387 @@
-use nom::{bytes::complete::take_until, IResult};
-use nu_protocol::ast::Call;
-use nu_protocol::ShellError;
+use super::nuclient;
+use influxdb_iox_client::error::Error as ClientError;
+use influxdb_iox_client::flight::Error as FlightError;
+use nu_protocol::{ShellError, Span, Spanned};
 use std::fmt;
 
-#[derive(Copy, Clone, Debug)]
-pub enum CommandType {
-    Sql,
-    Write,
-    WriteFile,
-}
+/// The ways an iox command can fail.
+///
+/// Each kind is reported as its own [`ShellError`] pointing at the argument
+/// that caused it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum NuIoxError {
+    /// The namespace given by `--dbname` or `IOX_DBNAME` does not exist
+    NamespaceNotFound { namespace: String, span: Span },
+
+    /// The querier could not plan the SQL query
+    SqlPlanning { message: String, span: Span },
+
+    /// The server could not be reached
+    ConnectionRefused {
+        addr: String,
+        message: String,
+        span: Span,
+    },
+
+    /// The input is not valid line protocol
+    InvalidLineProtocol {
+        /// 1-based line of the input, if the error is about a single line
+        line: Option<usize>,
+        message: String,
+        span: Span,
+    },
 
-pub struct NuIoxErrorHandler {
-    #[allow(dead_code)]
-    ctype: CommandType,
-    error: String,
-    #[allow(dead_code)]
-    nu_iox_error: NuIoxError,
+    /// The server turned the request away because it is overloaded
+    ServerOverloaded {
+        addr: String,
+        message: String,
+        span: Span,
+    },
+
+    /// Any other failed request
+    Request {
+        title: String,
+        message: String,
+        span: Span,
+    },
 }
 
-#[derive(Clone, Debug)]
-pub enum NuIoxErrorType {
-    TableNotFound,
+impl NuIoxError {
+    /// Classifies an error returned by an IOx gRPC client
+    pub fn from_client_error(error: ClientError, request: &IoxRequest<'_>) -> Self {
+        match error {
+            ClientError::Unavailable(e) => Self::ConnectionRefused {
+                addr: request.addr.to_string(),
+                message: e.to_string(),
+                span: request.span,
+            },
+            ClientError::ResourceExhausted(e) => Self::ServerOverloaded {
+                addr: request.addr.to_string(),
+                message: e.to_string(),
+                span: request.span,
+            },
+            // requests scoped to a namespace only report it as missing,
+            // unknown tables and columns are planning errors
+            ClientError::NotFound(e) => match request.namespace {
+                Some(namespace) => Self::NamespaceNotFound {
+                    namespace: namespace.item.clone(),
+                    span: namespace.span,
+                },
+                None => request.other(e.to_string()),
+            },
+            ClientError::InvalidArgument(e) => match request.query {
+                Some(span) => Self::SqlPlanning {
+                    message: e.to_string(),
+                    span,
+                },
+                None => request.other(e.to_string()),
+            },
+            ClientError::Client(source) => match source.downcast_ref::<mutable_batch_lp::Error>() {
+                Some(e) => Self::InvalidLineProtocol {
+                    line: invalid_line(e),
+                    message: e.to_string(),
+                    span: request.data.unwrap_or(request.span),
+                },
+                None => request.other(source.to_string()),
+            },
+            error => request.other(error.to_string()),
+        }
+    }
+
+    /// Classifies an error returned by the IOx flight client
+    pub fn from_flight_error(error: FlightError, request: &IoxRequest<'_>) -> Self {
+        match error {
+            FlightError::GrpcError(status) => Self::from_client_error(status.into(), request),
+            error => request.other(error.to_string()),
+        }
+    }
+
+    /// Classifies an error from running a query through [`nuclient::Nuclient`]
+    pub fn from_query_error(error: nuclient::Error, request: &IoxRequest<'_>) -> Self {
+        match error {
+            nuclient::Error::RunningRemoteQuery { source } => {
+                Self::from_flight_error(source, request)
+            }
+            nuclient::Error::LoadingRemoteState { source } => {
+                match source.downcast::<ClientError>() {
+                    Ok(error) => Self::from_client_error(*error, request),
+                    Err(source) => request.other(source.to_string()),
+                }
+            }
+            error => request.other(error.to_string()),
+        }
+    }
+
+    /// The same error reported at line `line` of the whole input, for errors
+    /// found in a batch holding only part of it
+    pub fn at_line(self, line: usize) -> Self {
+        match self {
+            Self::InvalidLineProtocol { message, span, .. } => Self::InvalidLineProtocol {
+                line: Some(line),
+                message,
+                span,
+            },
+            other => other,
+        }
+    }
+
+    /// Whether every later request to the same server and namespace would
+    /// fail the same way, so there is no point in sending them
+    pub fn is_fatal(&self) -> bool {
+        matches!(
+            self,
+            Self::ConnectionRefused { .. } | Self::NamespaceNotFound { .. }
+        )
+    }
 }
 
-#[derive(Clone, Debug)]
-pub struct NuIoxError {
-    #[allow(dead_code)]
-    start: String,
-    error_type: NuIoxErrorType,
-    header: String,
-    status: String,
-    message: String,
+impl fmt::Display for NuIoxError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NamespaceNotFound { namespace, .. } => {
+                write!(f, "there is no namespace named '{}'", namespace)
+            }
+            Self::SqlPlanning { message, .. } => write!(f, "error planning query: {}", message),
+            Self::ConnectionRefused { addr, message, .. } => {
+                write!(f, "unable to connect to IOx at {}: {}", addr, message)
+            }
+            Self::InvalidLineProtocol {
+                line: Some(line),
+                message,
+                ..
+            } => write!(f, "invalid line protocol at line {}: {}", line, message),
+            Self::InvalidLineProtocol {
+                line: None,
+                message,
+                ..
+            } => write!(f, "invalid line protocol: {}", message),
+            Self::ServerOverloaded { addr, message, .. } => {
+                write!(f, "{} is overloaded: {}", addr, message)
+            }
+            Self::Request { message, .. } => write!(f, "{}", message),
+        }
+    }
 }
 
-// this enables the enum TableNotFound to call to_string on itself
-impl fmt::Display for NuIoxErrorType {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{:?}", self)
+impl From<NuIoxError> for ShellError {
+    fn from(error: NuIoxError) -> Self {
+        match error {
+            NuIoxError::NamespaceNotFound { namespace, span } => ShellError::GenericError(
+                "Namespace not found".into(),
+                format!("there is no namespace named '{}'", namespace),
+                Some(span),
+                Some("Use ioxnamespace to list the namespaces".into()),
+                Vec::new(),
+            ),
+            NuIoxError::SqlPlanning { message, span } => ShellError::GenericError(
+                "Error planning SQL query".into(),
+                message,
+                Some(span),
+                None,
+                Vec::new(),
+            ),
+            // a network failure makes `with_connection` retry with a fresh
+            // connection
+            NuIoxError::ConnectionRefused {
+                addr,
+                message,
+                span,
+            } => ShellError::NetworkFailure(
+                format!("Unable to connect to IOx at {}: {}", addr, message),
+                span,
+            ),
+            NuIoxError::InvalidLineProtocol {
+                line,
+                message,
+                span,
+            } => ShellError::GenericError(
+                "Invalid line protocol".into(),
+                match line {
+                    Some(line) => format!("line {}: {}", line, message),
+                    None => message,
+                },
+                Some(span),
+                None,
+                Vec::new(),
+            ),
+            NuIoxError::ServerOverloaded {
+                addr,
+                message,
+                span,
+            } => ShellError::GenericError(
+                "IOx server overloaded".into(),
+                format!("{} is overloaded: {}", addr, message),
+                Some(span),
+                Some("Retry later, or write smaller batches with --batch-size".into()),
+                Vec::new(),
+            ),
+            NuIoxError::Request {
+                title,
+                message,
+                span,
+            } => ShellError::GenericError(title, message, Some(span), None, Vec::new()),
+        }
     }
 }
 
-impl NuIoxError {
-    pub fn build(data: &str) -> Self {
-        let details = remove_details(data).unwrap().1;
-        let (message0, remainder) = get_message(details).unwrap();
-        let (status0, header0) = get_header(&remainder).unwrap();
+/// What a command asked an IOx server for, so a failed request can be
+/// reported against the argument that caused it
+#[derive(Clone, Copy, Debug)]
+pub struct IoxRequest<'a> {
+    /// Address of the server
+    addr: &'a str,
 
-        let header1 = remove_colon_from_string(&header0.to_string());
-        let message1 = remove_slash_from_string(&message0.to_string());
+    /// Title of errors that fit no other kind
+    title: &'a str,
 
+    /// The command, blamed when no argument fits better
+    span: Span,
+
+    /// The namespace the request runs against
+    namespace: Option<&'a Spanned<String>>,
+
+    /// The SQL query argument
+    query: Option<Span>,
+
+    /// The argument holding the data being written
+    data: Option<Span>,
+}
+
+impl<'a> IoxRequest<'a> {
+    pub fn new(addr: &'a str, title: &'a str, span: Span) -> Self {
         Self {
-            start: data.to_string(),
-            error_type: NuIoxErrorType::TableNotFound,
-            header: header1,
-            status: status0.to_string(),
-            message: message1,
+            addr,
+            title,
+            span,
+            namespace: None,
+            query: None,
+            data: None,
         }
     }
 
-    pub fn print(self) {
-        //println!("{:?}", self.start.trim());
-        println!("{:?}", self.error_type);
-        println!("{:?}", self.header.trim());
-        println!("{:?}", self.status.trim());
-        println!("{:?}", self.message.trim());
+    pub fn namespace(mut self, namespace: &'a Spanned<String>) -> Self {
+        self.namespace = Some(namespace);
+        self
     }
-}
 
-impl NuIoxErrorHandler {
-    pub fn new(ctype: CommandType, error: String) -> Self {
-        let nu_iox_error = NuIoxError::build(error.as_ref());
-        //nu_iox_error.print();
-        Self {
-            ctype,
-            error,
-            nu_iox_error,
-        }
+    pub fn query(mut self, span: Span) -> Self {
+        self.query = Some(span);
+        self
     }
 
-    // Check and see if its an error or a csv
-    pub fn nu_iox_error_check(&self) -> Result<String, ShellError> {
-        //println!("{:?}", self.error);
-        Ok(self.error.clone())
+    pub fn data(mut self, span: Span) -> Self {
+        self.data = Some(span);
+        self
     }
 
-    // Trigger an error to see what the Error looks like
-    pub fn nu_iox_error_generic(&self, call: &Call) -> Result<String, ShellError> {
-        return Err(ShellError::GenericError(
-            self.nu_iox_error.message.to_string(),
-            self.nu_iox_error.error_type.to_string(),
-            Some(call.head),
-            None,
-            Vec::new(),
-        ));
+    /// Converts an error returned by an IOx gRPC client into a `ShellError`
+    pub fn error(&self, error: ClientError) -> ShellError {
+        NuIoxError::from_client_error(error, self).into()
     }
-}
 
-fn remove_details(s: &str) -> IResult<&str, &str> {
-    let details: &'static str = ", details: ";
-    take_until(details)(s)
+    fn other(&self, message: String) -> NuIoxError {
+        NuIoxError::Request {
+            title: self.title.to_string(),
+            message,
+            span: self.span,
+        }
+    }
 }
 
-fn get_message(s: &str) -> IResult<&str, &str> {
-    let msg: &'static str = ", message: ";
-    take_until(msg)(s)
+fn invalid_line(error: &mutable_batch_lp::Error) -> Option<usize> {
+    match error {
+        mutable_batch_lp::Error::LineProtocol { line, .. }
+        | mutable_batch_lp::Error::Write { line, .. } => Some(*line),
+        _ => None,
+    }
 }
 
-fn get_header(s: &str) -> IResult<&str, &str> {
-    let header: &'static str = "status: ";
-    take_until(header)(s)
-}
+#[cfg(test)]
+mod test {
+    use super::*;
+    use tonic::Status;
 
-fn remove_slash_from_string(s: &String) -> String {
-    s.replace(&['(', ')', ',', '\"', ';', '\''][..], "")
-}
+    fn span(start: usize, end: usize) -> Span {
+        Span { start, end }
+    }
+
+    #[test]
+    fn classifies_status_codes() {
+        let namespace = Spanned {
+            item: "bananas".to_string(),
+            span: span(10, 17),
+        };
+        let request = IoxRequest::new("http://127.0.0.1:8082", "Query failed", span(0, 6))
+            .namespace(&namespace)
+            .query(span(20, 40));
+
+        let classify = |status: Status| NuIoxError::from_client_error(status.into(), &request);
 
-fn remove_colon_from_string(s: &String) -> String {
-    s.replace(&[':'][..], "")
+        assert_eq!(
+            classify(Status::not_found("Unknown namespace: bananas")),
+            NuIoxError::NamespaceNotFound {
+                namespace: "bananas".into(),
+                span: span(10, 17),
+            }
+        );
+        assert_eq!(
+            classify(Status::invalid_argument("table 'cpu' not found")),
+            NuIoxError::SqlPlanning {
+                message: "table 'cpu' not found".into(),
+                span: span(20, 40),
+            }
+        );
+        assert!(classify(Status::unavailable("connection refused")).is_fatal());
+        assert!(matches!(
+            classify(Status::resource_exhausted("buffer full")),
+            NuIoxError::ServerOverloaded { .. }
+        ));
+        assert!(matches!(
+            classify(Status::internal("oops")),
+            NuIoxError::Request { span: s, .. } if s == span(0, 6)
+        ));
+    }
+
+    #[test]
+    fn not_found_without_namespace_is_a_plain_error() {
+        let request = IoxRequest::new("http://127.0.0.1:8082", "Unable to get file", span(0, 6));
+
+        assert!(matches!(
+            NuIoxError::from_client_error(Status::not_found("no such file").into(), &request),
+            NuIoxError::Request { .. }
+        ));
+    }
+
+    #[test]
+    fn reports_the_invalid_line() {
+        let request =
+            IoxRequest::new("http://127.0.0.1:8081", "Write failed", span(0, 8)).data(span(9, 30));
+
+        let lp_error = mutable_batch_lp::lines_to_batches("cpu usage=1 1\ncpu usage=", 0)
+            .expect_err("invalid line protocol");
+        let error =
+            NuIoxError::from_client_error(ClientError::Client(Box::new(lp_error)), &request);
+        assert!(matches!(
+            error,
+            NuIoxError::InvalidLineProtocol { line: Some(2), .. }
+        ));
+
+        match error.at_line(42) {
+            NuIoxError::InvalidLineProtocol { line, span: s, .. } => {
+                assert_eq!(line, Some(42));
+                assert_eq!(s, span(9, 30));
+            }
+            other => panic!("unexpected error: {:?}", other),
+        }
+
+        // errors about the whole input have no line
+        let lp_error = mutable_batch_lp::lines_to_batches("", 0).expect_err("empty payload");
+        let error =
+            NuIoxError::from_client_error(ClientError::Client(Box::new(lp_error)), &request);
+        assert!(matches!(
+            error,
+            NuIoxError::InvalidLineProtocol { line: None, .. }
+        ));
+        assert!(!error.to_string().contains("line 1"));
+    }
 }
@@ -0,0 +1,151 @@
+use super::clients::with_connection;
+use super::util::{client_error, get_iox_addr, IoxService};
+use super::values::nanos_to_value;
+use influxdb_iox_client::catalog::generated_types::ParquetFile;
+use influxdb_iox_client::catalog::Client;
+use nu_engine::CallExt;
+use nu_protocol::ast::Call;
+use nu_protocol::engine::{Command, EngineState, Stack};
+use nu_protocol::{
+    Category, Example, IntoPipelineData, PipelineData, ShellError, Signature, Span, SyntaxShape,
+    Value,
+};
+
+#[derive(Clone)]
+pub struct Ioxparquetfiles;
+
+impl Command for Ioxparquetfiles {
+    fn name(&self) -> &str {
+        "ioxparquetfiles"
+    }
+
+    fn signature(&self) -> nu_protocol::Signature {
+        Signature::build("ioxparquetfiles")
+            .required(
+                "partition",
+                SyntaxShape::Int,
+                "id of the partition to list the parquet files of",
+            )
+            .named(
+                "host",
+                SyntaxShape::String,
+                "address of the IOx router, overriding IOX_ROUTER_ADDR and IOX_HOST",
+                None,
+            )
+            .category(Category::Filters)
+    }
+
+    fn usage(&self) -> &str {
+        "Show the parquet files of a partition of an Iox Database."
+    }
+
+    fn run(
+        &self,
+        engine_state: &EngineState,
+        stack: &mut Stack,
+        call: &Call,
+        _input: PipelineData,
+    ) -> Result<PipelineData, ShellError> {
+        let partition_id: i64 = call.req(engine_state, stack, 0)?;
+        let addr = get_iox_addr(engine_state, stack, call, IoxService::Router)?;
+
+        let files = tokio_block_parquetfiles(&addr, partition_id, call.head)?;
+
+        let rows = files
+            .into_iter()
+            .map(|file| parquet_file_to_value(file, call.head))
+            .collect();
+
+        Ok(Value::List {
+            vals: rows,
+            span: call.head,
+        }
+        .into_pipeline_data())
+    }
+
+    fn examples(&self) -> Vec<Example> {
+        vec![
+            Example {
+                description: "Show the parquet files of partition 1",
+                example: r#"ioxparquetfiles 1"#,
+                result: None,
+            },
+            Example {
+                description: "Show the total size of the parquet files of partition 1",
+                example: r#"ioxparquetfiles 1 | get file_size_bytes | math sum"#,
+                result: None,
+            },
+        ]
+    }
+}
+
+pub fn tokio_block_parquetfiles(
+    addr: &str,
+    partition_id: i64,
+    span: Span,
+) -> Result<Vec<ParquetFile>, ShellError> {
+    with_connection(addr, span, |connection| async move {
+        Client::new(connection)
+            .get_parquet_files_by_partition_id(partition_id)
+            .await
+            .map_err(|e| client_error(addr, "Unable to list parquet files", e, span))
+    })
+}
+
+fn parquet_file_to_value(file: ParquetFile, span: Span) -> Value {
+    let column_set = file
+        .column_set
+        .into_iter()
+        .map(|column_id| Value::int(column_id, span))
+        .collect();
+
+    // the catalog sends 0 for files that are not marked for deletion
+    let to_delete = match file.to_delete {
+        0 => Value::Nothing { span },
+        nanos => nanos_to_value(nanos, span),
+    };
+
+    Value::Record {
+        cols: vec![
+            "id".into(),
+            "sequencer_id".into(),
+            "namespace_id".into(),
+            "table_id".into(),
+            "partition_id".into(),
+            "object_store_id".into(),
+            "max_sequence_number".into(),
+            "min_time".into(),
+            "max_time".into(),
+            "to_delete".into(),
+            "file_size_bytes".into(),
+            "row_count".into(),
+            "compaction_level".into(),
+            "created_at".into(),
+            "column_set".into(),
+        ],
+        vals: vec![
+            Value::int(file.id, span),
+            Value::int(file.sequencer_id, span),
+            Value::int(file.namespace_id, span),
+            Value::int(file.table_id, span),
+            Value::int(file.partition_id, span),
+            Value::string(file.object_store_id, span),
+            Value::int(file.max_sequence_number, span),
+            nanos_to_value(file.min_time, span),
+            nanos_to_value(file.max_time, span),
+            to_delete,
+            Value::Filesize {
+                val: file.file_size_bytes,
+                span,
+            },
+            Value::int(file.row_count, span),
+            Value::int(file.compaction_level as i64, span),
+            nanos_to_value(file.created_at, span),
+            Value::List {
+                vals: column_set,
+                span,
+            },
+        ],
+        span,
+    }
+}
@@ -0,0 +1,143 @@
+use super::clients::with_connection;
+use super::schema::tokio_block_schema;
+use super::util::{client_error, get_dbname, get_iox_addr, IoxService};
+use influxdb_iox_client::catalog::generated_types::Partition;
+use influxdb_iox_client::catalog::Client;
+use nu_engine::CallExt;
+use nu_protocol::ast::Call;
+use nu_protocol::engine::{Command, EngineState, Stack};
+use nu_protocol::{
+    Category, Example, IntoPipelineData, PipelineData, ShellError, Signature, Span, Spanned,
+    SyntaxShape, Value,
+};
+
+#[derive(Clone)]
+pub struct Ioxpartitions;
+
+impl Command for Ioxpartitions {
+    fn name(&self) -> &str {
+        "ioxpartitions"
+    }
+
+    fn signature(&self) -> nu_protocol::Signature {
+        Signature::build("ioxpartitions")
+            .required(
+                "table",
+                SyntaxShape::String,
+                "name of the table to list the partitions of",
+            )
+            .named(
+                "dbname",
+                SyntaxShape::String,
+                "name of the database holding the table",
+                Some('d'),
+            )
+            .named(
+                "host",
+                SyntaxShape::String,
+                "address of the IOx router, overriding IOX_ROUTER_ADDR and IOX_HOST",
+                None,
+            )
+            .category(Category::Filters)
+    }
+
+    fn usage(&self) -> &str {
+        "Show the partitions of a table in an Iox Database."
+    }
+
+    fn run(
+        &self,
+        engine_state: &EngineState,
+        stack: &mut Stack,
+        call: &Call,
+        _input: PipelineData,
+    ) -> Result<PipelineData, ShellError> {
+        let table: Spanned<String> = call.req(engine_state, stack, 0)?;
+        let dbname = get_dbname(engine_state, stack, call)?;
+        let addr = get_iox_addr(engine_state, stack, call, IoxService::Router)?;
+
+        let schema = tokio_block_schema(&addr, &dbname, call.head)?;
+        let table_id = match schema.tables.get(&table.item) {
+            Some(table_schema) => table_schema.id,
+            None => {
+                return Err(ShellError::GenericError(
+                    "Table not found".into(),
+                    format!("no table '{}' in database '{}'", table.item, dbname.item),
+                    Some(table.span),
+                    Some("Use ioxschema to list the tables of the database".into()),
+                    Vec::new(),
+                ))
+            }
+        };
+
+        let partitions = tokio_block_partitions(&addr, table_id, call.head)?;
+
+        let rows = partitions
+            .into_iter()
+            .map(|partition| partition_to_value(partition, call.head))
+            .collect();
+
+        Ok(Value::List {
+            vals: rows,
+            span: call.head,
+        }
+        .into_pipeline_data())
+    }
+
+    fn examples(&self) -> Vec<Example> {
+        vec![
+            Example {
+                description: "Show the partitions of the cpu table in the bananas database",
+                example: r#"ioxpartitions -d bananas cpu"#,
+                result: None,
+            },
+            Example {
+                description: "Show the parquet files of every partition of the cpu table",
+                example: r#"ioxpartitions cpu | each { |p| ioxparquetfiles $p.id } | flatten"#,
+                result: None,
+            },
+        ]
+    }
+}
+
+pub fn tokio_block_partitions(
+    addr: &str,
+    table_id: i64,
+    span: Span,
+) -> Result<Vec<Partition>, ShellError> {
+    with_connection(addr, span, |connection| async move {
+        Client::new(connection)
+            .get_partitions_by_table_id(table_id)
+            .await
+            .map_err(|e| client_error(addr, "Unable to list partitions", e, span))
+    })
+}
+
+fn partition_to_value(partition: Partition, span: Span) -> Value {
+    let sort_key = partition
+        .array_sort_key
+        .into_iter()
+        .map(|column| Value::string(column, span))
+        .collect();
+
+    Value::Record {
+        cols: vec![
+            "id".into(),
+            "sequencer_id".into(),
+            "table_id".into(),
+            "key".into(),
+            "sort_key".into(),
+        ],
+        vals: vec![
+            Value::int(partition.id, span),
+            Value::int(partition.sequencer_id, span),
+            Value::int(partition.table_id, span),
+            Value::string(partition.key, span),
+            Value::List {
+                vals: sort_key,
+                span,
+            },
+        ],
+        span,
+    }
+}
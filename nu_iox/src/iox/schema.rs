@@ -0,0 +1,206 @@
+use super::clients::with_connection;
+use super::nuerror::IoxRequest;
+use super::util::{get_iox_addr, IoxService};
+use influxdb_iox_client::schema::generated_types::{column_schema::ColumnType, NamespaceSchema};
+use influxdb_iox_client::schema::Client;
+use nu_engine::CallExt;
+use nu_protocol::ast::Call;
+use nu_protocol::engine::{Command, EngineState, Stack};
+use nu_protocol::{
+    Category, Example, IntoPipelineData, PipelineData, ShellError, Signature, Span, Spanned,
+    SyntaxShape, Value,
+};
+
+#[derive(Clone)]
+pub struct Ioxschema;
+
+impl Command for Ioxschema {
+    fn name(&self) -> &str {
+        "ioxschema"
+    }
+
+    fn signature(&self) -> nu_protocol::Signature {
+        Signature::build("ioxschema")
+            .required(
+                "namespace",
+                SyntaxShape::String,
+                "name of the namespace to show the schema of",
+            )
+            .named(
+                "host",
+                SyntaxShape::String,
+                "address of the IOx router, overriding IOX_ROUTER_ADDR and IOX_HOST",
+                None,
+            )
+            .category(Category::Filters)
+    }
+
+    fn usage(&self) -> &str {
+        "Show the tables and columns of an Iox Database."
+    }
+
+    fn run(
+        &self,
+        engine_state: &EngineState,
+        stack: &mut Stack,
+        call: &Call,
+        _input: PipelineData,
+    ) -> Result<PipelineData, ShellError> {
+        let dbname: Spanned<String> = call.req(engine_state, stack, 0)?;
+        let addr = get_iox_addr(engine_state, stack, call, IoxService::Router)?;
+
+        let schema = tokio_block_schema(&addr, &dbname, call.head)?;
+
+        Ok(schema_to_value(&schema, call.head).into_pipeline_data())
+    }
+
+    fn examples(&self) -> Vec<Example> {
+        vec![
+            Example {
+                description: "Show the columns of every table in the bananas database",
+                example: r#"ioxschema bananas"#,
+                result: None,
+            },
+            Example {
+                description: "Show the tag columns of the cpu table",
+                example: r#"ioxschema bananas | where table == cpu && type == tag"#,
+                result: None,
+            },
+        ]
+    }
+}
+
+/// Fetches the schema of the namespace `dbname`
+pub fn tokio_block_schema(
+    addr: &str,
+    dbname: &Spanned<String>,
+    span: Span,
+) -> Result<NamespaceSchema, ShellError> {
+    let request = IoxRequest::new(addr, "Unable to get the schema", span).namespace(dbname);
+
+    with_connection(addr, span, |connection| async move {
+        Client::new(connection)
+            .get_schema(&dbname.item)
+            .await
+            .map_err(|e| request.error(e))
+    })
+}
+
+/// One row per column, with the tables and their columns sorted by name
+fn schema_to_value(schema: &NamespaceSchema, span: Span) -> Value {
+    let mut tables: Vec<_> = schema.tables.iter().collect();
+    tables.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+    let mut rows = vec![];
+    for (table_name, table) in tables {
+        let mut columns: Vec<_> = table.columns.iter().collect();
+        columns.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+        for (column_name, column) in columns {
+            rows.push(Value::Record {
+                cols: vec![
+                    "table".into(),
+                    "table_id".into(),
+                    "column".into(),
+                    "column_id".into(),
+                    "type".into(),
+                ],
+                vals: vec![
+                    Value::string(table_name, span),
+                    Value::int(table.id, span),
+                    Value::string(column_name, span),
+                    Value::int(column.id, span),
+                    Value::string(column_type_name(column.column_type), span),
+                ],
+                span,
+            });
+        }
+    }
+
+    Value::List { vals: rows, span }
+}
+
+fn column_type_name(column_type: i32) -> &'static str {
+    match ColumnType::from_i32(column_type) {
+        Some(ColumnType::I64) => "i64",
+        Some(ColumnType::U64) => "u64",
+        Some(ColumnType::F64) => "f64",
+        Some(ColumnType::Bool) => "bool",
+        Some(ColumnType::String) => "string",
+        Some(ColumnType::Time) => "time",
+        Some(ColumnType::Tag) => "tag",
+        Some(ColumnType::Unspecified) | None => "unknown",
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use influxdb_iox_client::schema::generated_types::{ColumnSchema, TableSchema};
+
+    #[test]
+    fn flattens_schema_into_sorted_rows() {
+        let span = Span::test_data();
+
+        let column = |id, column_type: ColumnType| ColumnSchema {
+            id,
+            column_type: column_type as i32,
+        };
+        let schema = NamespaceSchema {
+            id: 1,
+            kafka_topic_id: 1,
+            query_pool_id: 1,
+            tables: [(
+                "cpu".to_string(),
+                TableSchema {
+                    id: 7,
+                    columns: [
+                        ("time".to_string(), column(3, ColumnType::Time)),
+                        ("host".to_string(), column(1, ColumnType::Tag)),
+                    ]
+                    .into_iter()
+                    .collect(),
+                },
+            )]
+            .into_iter()
+            .collect(),
+        };
+
+        let cols = vec![
+            "table".to_string(),
+            "table_id".to_string(),
+            "column".to_string(),
+            "column_id".to_string(),
+            "type".to_string(),
+        ];
+        let expected = Value::List {
+            vals: vec![
+                Value::Record {
+                    cols: cols.clone(),
+                    vals: vec![
+                        Value::string("cpu", span),
+                        Value::int(7, span),
+                        Value::string("host", span),
+                        Value::int(1, span),
+                        Value::string("tag", span),
+                    ],
+                    span,
+                },
+                Value::Record {
+                    cols,
+                    vals: vec![
+                        Value::string("cpu", span),
+                        Value::int(7, span),
+                        Value::string("time", span),
+                        Value::int(3, span),
+                        Value::string("time", span),
+                    ],
+                    span,
+                },
+            ],
+            span,
+        };
+
+        assert_eq!(schema_to_value(&schema, span), expected);
+    }
+}
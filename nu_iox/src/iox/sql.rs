@@ -1,16 +1,16 @@
-use super::delimited::from_delimited_data;
-use super::nuerror::NuIoxErrorHandler;
+use super::nuerror::{IoxRequest, NuIoxError};
+use super::stream::QueryStream;
 
-use super::util::{get_env_var_from_engine, get_runtime, number_of_csv_records};
+use super::clients::with_connection;
+use super::util::{get_dbname, get_iox_addr, IoxService};
+use influxdb_iox_client::flight::PerformQuery;
 use nu_engine::CallExt;
 use nu_protocol::ast::Call;
 use nu_protocol::engine::{Command, EngineState, Stack};
 use nu_protocol::{
-    Category, Example, PipelineData, ShellError, Signature, Span, Spanned, SyntaxShape, Value,
+    Category, Example, ListStream, PipelineData, ShellError, Signature, Spanned, SyntaxShape,
 };
 
-use csv::Trim;
-
 #[derive(Clone)]
 pub struct Ioxsql;
 
@@ -20,7 +20,7 @@ impl Command for Ioxsql {
     }
 
     fn signature(&self) -> nu_protocol::Signature {
-        Signature::build("ioxsql")
+        let signature = Signature::build("ioxsql")
             .required(
                 "query",
                 SyntaxShape::String,
@@ -32,7 +32,21 @@ impl Command for Ioxsql {
                 "name of the database to search over",
                 Some('d'),
             )
-            .category(Category::Filters)
+            .named(
+                "host",
+                SyntaxShape::String,
+                "address of the IOx querier, overriding IOX_QUERIER_ADDR and IOX_HOST",
+                None,
+            );
+
+        #[cfg(feature = "dataframe")]
+        let signature = signature.switch(
+            "dataframe",
+            "return the result as a dataframe instead of a table",
+            None,
+        );
+
+        signature.category(Category::Filters)
     }
 
     fn usage(&self) -> &str {
@@ -47,51 +61,28 @@ impl Command for Ioxsql {
         _input: PipelineData,
     ) -> Result<PipelineData, ShellError> {
         let sql: Spanned<String> = call.req(engine_state, stack, 0)?;
-        let db: Option<String> = call.get_flag(engine_state, stack, "dbname")?;
-
-        let dbname = if let Some(name) = db {
-            name
-        } else {
-            get_env_var_from_engine(stack, engine_state, "IOX_DBNAME").unwrap()
-        };
-
-        let sql_result = tokio_block_sql(&dbname, &sql);
-        //println!("sql_result = {:?}", sql_result);
-
-        let numofrecords = number_of_csv_records(&sql_result.as_ref().unwrap());
-        //println!("number of csv records = {:?}", numofrecords);
-
-        let not_csv_data = match numofrecords.unwrap() {
-            d if d > 0 => false,
-            _ => true,
-        };
-
-        if not_csv_data {
-            let nierrorhandler = NuIoxErrorHandler::new(
-                super::nuerror::CommandType::Sql,
-                sql_result.as_ref().unwrap().to_string(),
-            );
-
-            nierrorhandler.nu_iox_error_check()?;
-            nierrorhandler.nu_iox_error_generic(call)?;
+        let dbname = get_dbname(engine_state, stack, call)?;
+        let addr = get_iox_addr(engine_state, stack, call, IoxService::Querier)?;
+
+        let query = tokio_block_sql(&addr, &dbname, &sql, call)?;
+
+        #[cfg(feature = "dataframe")]
+        if call.has_flag("dataframe") {
+            let request = IoxRequest::new(&addr, "Query failed", call.head)
+                .namespace(&dbname)
+                .query(sql.span);
+            let batches = tokio_block_collect(query, &request)?;
+            let df = crate::dataframe::NuDataFrame::try_from_record_batches(&batches, call.head)?;
+            return Ok(PipelineData::Value(df.into_value(call.head), None));
         }
-        let no_infer = false;
-        let noheaders = false;
-        let separator: char = ',';
-        let trim = Trim::None;
-
-        let input = PipelineData::Value(
-            Value::String {
-                val: sql_result.unwrap(),
-                span: call.head,
-            },
-            None,
-        );
 
-        let name = Span::new(0, 0);
-        let config = engine_state.get_config();
+        let ctrlc = engine_state.ctrlc.clone();
+        let rows = QueryStream::new(query, addr, dbname, sql.span, ctrlc.clone(), call.head)?;
 
-        from_delimited_data(noheaders, no_infer, separator, trim, input, name, config)
+        Ok(PipelineData::ListStream(
+            ListStream::from_stream(rows, ctrlc),
+            None,
+        ))
     }
 
     fn examples(&self) -> Vec<Example> {
@@ -106,36 +97,55 @@ impl Command for Ioxsql {
                 example: r#"ioxsql "select * from cpu"#,
                 result: None,
             },
+            Example {
+                description: "Show the first rows of a large table without waiting for the rest",
+                example: r#"ioxsql "select * from cpu" | first 20"#,
+                result: None,
+            },
+            Example {
+                description: "Load a query result into a dataframe and aggregate it",
+                example: r#"ioxsql --dataframe "select * from cpu" | group-by host | agg (col usage | mean)"#,
+                result: None,
+            },
+            Example {
+                description: "Run an sql query against a querier on another host",
+                example: r#"ioxsql --host http://querier.example.com:8082 "select * from cpu"#,
+                result: None,
+            },
         ]
     }
 }
 
-pub fn tokio_block_sql(dbname: &String, sql: &Spanned<String>) -> Result<String, std::io::Error> {
-    use crate::iox::Nuclient;
-    use influxdb_iox_client::connection::Builder;
-    let num_threads: Option<usize> = None;
-    let tokio_runtime = get_runtime(num_threads)?;
-
-    let sql_result = tokio_runtime.block_on(async move {
-        let connection = Builder::default()
-            .build("http://127.0.0.1:8082")
-            .await
-            .expect("client should be valid");
-
+/// Starts the query, returning once the querier has planned it and is
+/// about to send the first batch
+pub fn tokio_block_sql(
+    addr: &str,
+    dbname: &Spanned<String>,
+    sql: &Spanned<String>,
+    call: &Call,
+) -> Result<PerformQuery, ShellError> {
+    use crate::iox::{Nuclient, QueryEngine};
+
+    let request = IoxRequest::new(addr, "Query failed", call.head)
+        .namespace(dbname)
+        .query(sql.span);
+
+    with_connection(addr, call.head, |connection| async move {
         let mut repl = Nuclient::new(connection);
-        repl.use_database(dbname.to_string());
-        let _output_format = repl.set_output_format("csv");
-
-        // let rsql = repl.run_sql(sql.item.to_string()).await.expect("run_sql");
-        // rsql
+        repl.set_query_engine(QueryEngine::Remote(dbname.item.clone()));
 
-        let rsql = repl.run_sql(sql.item.to_string()).await;
-
-        match rsql {
-            Ok(res) => res,
-            Err(error) => error.to_string(),
-        }
-    });
+        repl.stream_sql(sql.item.to_string())
+            .await
+            .map_err(|error| ShellError::from(NuIoxError::from_query_error(error, &request)))
+    })
+}
 
-    Ok(sql_result)
+/// Waits for all of the batches of a query
+#[cfg(feature = "dataframe")]
+fn tokio_block_collect(
+    mut query: PerformQuery,
+    request: &IoxRequest<'_>,
+) -> Result<Vec<arrow::record_batch::RecordBatch>, ShellError> {
+    super::clients::block_on(query.collect())?
+        .map_err(|error| NuIoxError::from_flight_error(error, request).into())
 }
@@ -0,0 +1,42 @@
+use nu_engine::get_full_help;
+use nu_protocol::{
+    ast::Call,
+    engine::{Command, EngineState, Stack},
+    Category, IntoPipelineData, PipelineData, ShellError, Signature, Value,
+};
+
+#[derive(Clone)]
+pub struct Ioxstorage;
+
+impl Command for Ioxstorage {
+    fn name(&self) -> &str {
+        "ioxstorage"
+    }
+
+    fn signature(&self) -> Signature {
+        Signature::build("ioxstorage").category(Category::Filters)
+    }
+
+    fn usage(&self) -> &str {
+        "InfluxRPC storage queries against the Iox Database."
+    }
+
+    fn run(
+        &self,
+        engine_state: &EngineState,
+        stack: &mut Stack,
+        call: &Call,
+        _input: PipelineData,
+    ) -> Result<PipelineData, ShellError> {
+        Ok(Value::String {
+            val: get_full_help(
+                &Ioxstorage.signature(),
+                &Ioxstorage.examples(),
+                engine_state,
+                stack,
+            ),
+            span: call.head,
+        }
+        .into_pipeline_data())
+    }
+}
@@ -0,0 +1,60 @@
+use super::request::{storage_signature, StorageArgs};
+use super::response::strings_to_value;
+use influxdb_storage_client::generated_types::MeasurementNamesRequest;
+use nu_protocol::ast::Call;
+use nu_protocol::engine::{Command, EngineState, Stack};
+use nu_protocol::{Category, Example, IntoPipelineData, PipelineData, ShellError, Signature};
+
+#[derive(Clone)]
+pub struct SubCommand;
+
+impl Command for SubCommand {
+    fn name(&self) -> &str {
+        "ioxstorage measurement-names"
+    }
+
+    fn signature(&self) -> Signature {
+        storage_signature("ioxstorage measurement-names").category(Category::Filters)
+    }
+
+    fn usage(&self) -> &str {
+        "List the measurements with series matching a predicate in the Iox Database."
+    }
+
+    fn run(
+        &self,
+        engine_state: &EngineState,
+        stack: &mut Stack,
+        call: &Call,
+        _input: PipelineData,
+    ) -> Result<PipelineData, ShellError> {
+        let args = StorageArgs::from_call(engine_state, stack, call)?;
+        let request = MeasurementNamesRequest {
+            source: Some(args.source.clone()),
+            range: Some(args.range.clone()),
+            predicate: args.predicate.clone(),
+        };
+
+        let names = args.run(call.head, |mut client| {
+            let request = request.clone();
+            async move { client.measurement_names(request).await }
+        })?;
+
+        Ok(strings_to_value(names, call.head).into_pipeline_data())
+    }
+
+    fn examples(&self) -> Vec<Example> {
+        vec![
+            Example {
+                description: "List all measurements",
+                example: r#"ioxstorage measurement-names"#,
+                result: None,
+            },
+            Example {
+                description: "List the measurements written to by host a",
+                example: r#"ioxstorage measurement-names -p "host = 'a'""#,
+                result: None,
+            },
+        ]
+    }
+}
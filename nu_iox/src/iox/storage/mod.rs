@@ -0,0 +1,17 @@
+mod command;
+mod measurement_names;
+mod read_filter;
+mod read_group;
+mod request;
+mod response;
+mod tag_keys;
+mod tag_values;
+mod window_aggregate;
+
+pub use command::Ioxstorage;
+pub use measurement_names::SubCommand as IoxstorageMeasurementNames;
+pub use read_filter::SubCommand as IoxstorageReadFilter;
+pub use read_group::SubCommand as IoxstorageReadGroup;
+pub use tag_keys::SubCommand as IoxstorageTagKeys;
+pub use tag_values::SubCommand as IoxstorageTagValues;
+pub use window_aggregate::SubCommand as IoxstorageWindowAggregate;
@@ -0,0 +1,65 @@
+use super::request::{storage_signature, StorageArgs};
+use super::response::frames_to_value;
+use influxdb_storage_client::generated_types::{
+    read_filter_request::KeySort, ReadFilterRequest, TagKeyMetaNames,
+};
+use nu_protocol::ast::Call;
+use nu_protocol::engine::{Command, EngineState, Stack};
+use nu_protocol::{Category, Example, IntoPipelineData, PipelineData, ShellError, Signature};
+
+#[derive(Clone)]
+pub struct SubCommand;
+
+impl Command for SubCommand {
+    fn name(&self) -> &str {
+        "ioxstorage read-filter"
+    }
+
+    fn signature(&self) -> Signature {
+        storage_signature("ioxstorage read-filter").category(Category::Filters)
+    }
+
+    fn usage(&self) -> &str {
+        "Read every point of the series matching a predicate from the Iox Database."
+    }
+
+    fn run(
+        &self,
+        engine_state: &EngineState,
+        stack: &mut Stack,
+        call: &Call,
+        _input: PipelineData,
+    ) -> Result<PipelineData, ShellError> {
+        let args = StorageArgs::from_call(engine_state, stack, call)?;
+        let request = ReadFilterRequest {
+            read_source: Some(args.source.clone()),
+            range: Some(args.range.clone()),
+            predicate: args.predicate.clone(),
+            // IOx does not support any other sort
+            key_sort: KeySort::Unspecified as i32,
+            tag_key_meta_names: TagKeyMetaNames::Text as i32,
+        };
+
+        let frames = args.run(call.head, |mut client| {
+            let request = request.clone();
+            async move { client.read_filter(request).await }
+        })?;
+
+        Ok(frames_to_value(frames, call.head)?.into_pipeline_data())
+    }
+
+    fn examples(&self) -> Vec<Example> {
+        vec![
+            Example {
+                description: "Read the usage field of host a",
+                example: r#"ioxstorage read-filter -p "host = 'a' AND _field = 'usage'""#,
+                result: None,
+            },
+            Example {
+                description: "Read all series of the cpu measurement written in 2022",
+                example: r#"ioxstorage read-filter -p "_measurement = 'cpu'" --start 2022-01-01T00:00:00Z --stop 2023-01-01T00:00:00Z"#,
+                result: None,
+            },
+        ]
+    }
+}
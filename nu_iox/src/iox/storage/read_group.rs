@@ -0,0 +1,98 @@
+use super::request::{parse_aggregate, storage_signature, StorageArgs};
+use super::response::frames_to_value;
+use influxdb_storage_client::generated_types::{
+    read_group_request::Group, Aggregate, ReadGroupRequest,
+};
+use nu_engine::CallExt;
+use nu_protocol::ast::Call;
+use nu_protocol::engine::{Command, EngineState, Stack};
+use nu_protocol::{
+    Category, Example, IntoPipelineData, PipelineData, ShellError, Signature, Spanned, SyntaxShape,
+};
+
+#[derive(Clone)]
+pub struct SubCommand;
+
+impl Command for SubCommand {
+    fn name(&self) -> &str {
+        "ioxstorage read-group"
+    }
+
+    fn signature(&self) -> Signature {
+        storage_signature("ioxstorage read-group")
+            .named(
+                "group-keys",
+                SyntaxShape::List(Box::new(SyntaxShape::String)),
+                "tag keys to group the series by",
+                Some('g'),
+            )
+            .named(
+                "aggregate",
+                SyntaxShape::String,
+                "aggregate applied to each group: 'count', 'sum', 'min', 'max', 'mean', 'first' or 'last'",
+                Some('a'),
+            )
+            .category(Category::Filters)
+    }
+
+    fn usage(&self) -> &str {
+        "Read the series matching a predicate from the Iox Database, grouped by tag keys."
+    }
+
+    fn run(
+        &self,
+        engine_state: &EngineState,
+        stack: &mut Stack,
+        call: &Call,
+        _input: PipelineData,
+    ) -> Result<PipelineData, ShellError> {
+        let args = StorageArgs::from_call(engine_state, stack, call)?;
+        let group_keys: Vec<String> = call
+            .get_flag(engine_state, stack, "group-keys")?
+            .unwrap_or_default();
+        let aggregate = call
+            .get_flag::<Spanned<String>>(engine_state, stack, "aggregate")?
+            .map(|aggregate| parse_aggregate(&aggregate))
+            .transpose()?
+            .map(|aggregate| Aggregate {
+                r#type: aggregate as i32,
+            });
+
+        let group = if group_keys.is_empty() {
+            Group::None
+        } else {
+            Group::By
+        };
+
+        let request = ReadGroupRequest {
+            read_source: Some(args.source.clone()),
+            range: Some(args.range.clone()),
+            predicate: args.predicate.clone(),
+            group_keys,
+            group: group as i32,
+            aggregate,
+        };
+
+        let frames = args.run(call.head, |mut client| {
+            let request = request.clone();
+            async move { client.read_group(request).await }
+        })?;
+
+        Ok(frames_to_value(frames, call.head)?.into_pipeline_data())
+    }
+
+    fn examples(&self) -> Vec<Example> {
+        vec![
+            Example {
+                description: "Sum the usage of each host",
+                example: r#"ioxstorage read-group -g [host] -a sum -p "_field = 'usage'""#,
+                result: None,
+            },
+            Example {
+                description: "Read the cpu series grouped by region and host",
+                example: r#"ioxstorage read-group -g [region host] -p "_measurement = 'cpu'""#,
+                result: None,
+            },
+        ]
+    }
+}
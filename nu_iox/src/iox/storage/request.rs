@@ -0,0 +1,223 @@
+use crate::iox::clients::with_connection;
+use crate::iox::util::{client_error, get_dbname, get_iox_addr, IoxService};
+use chrono::DateTime;
+use generated_types::google::protobuf::Any;
+use influxdb_storage_client::generated_types::{
+    aggregate::AggregateType, Predicate, TimestampRange,
+};
+use influxdb_storage_client::{Client, OrgAndBucket};
+use influxrpc_parser::predicate::expr_to_rpc_predicate;
+use nu_engine::CallExt;
+use nu_protocol::ast::Call;
+use nu_protocol::engine::{EngineState, Stack};
+use nu_protocol::{ShellError, Signature, Span, Spanned, SyntaxShape};
+use std::future::Future;
+use std::num::NonZeroU64;
+
+/// Earliest time a storage request can ask for, as in `influxdb_iox storage`
+const MIN_TIMESTAMP: i64 = -9_223_372_036_854_775_806;
+
+/// Latest time a storage request can ask for, as in `influxdb_iox storage`
+const MAX_TIMESTAMP: i64 = 9_223_372_036_854_775_806;
+
+/// Adds the flags shared by every `ioxstorage` subcommand
+pub fn storage_signature(name: &str) -> Signature {
+    Signature::build(name)
+        .named(
+            "dbname",
+            SyntaxShape::String,
+            "name of the database, as <org id>_<bucket id> in hex",
+            Some('d'),
+        )
+        .named(
+            "host",
+            SyntaxShape::String,
+            "address of the IOx querier, overriding IOX_QUERIER_ADDR and IOX_HOST",
+            None,
+        )
+        .named(
+            "start",
+            SyntaxShape::String,
+            "start of the time range (inclusive), as RFC3339 or nanoseconds since the epoch",
+            None,
+        )
+        .named(
+            "stop",
+            SyntaxShape::String,
+            "end of the time range (exclusive), as RFC3339 or nanoseconds since the epoch",
+            None,
+        )
+        .named(
+            "predicate",
+            SyntaxShape::String,
+            "only return series matching this expression, e.g. \"host = 'a' AND _field = 'usage'\"",
+            Some('p'),
+        )
+}
+
+/// The parts shared by every storage request, read from the flags of a call
+pub struct StorageArgs {
+    /// Address of the querier
+    pub addr: String,
+
+    /// The org and bucket of the database, encoded for the request
+    pub source: Any,
+
+    pub range: TimestampRange,
+
+    /// `None` if no predicate was given
+    pub predicate: Option<Predicate>,
+}
+
+impl StorageArgs {
+    pub fn from_call(
+        engine_state: &EngineState,
+        stack: &mut Stack,
+        call: &Call,
+    ) -> Result<Self, ShellError> {
+        let dbname = get_dbname(engine_state, stack, call)?;
+        let addr = get_iox_addr(engine_state, stack, call, IoxService::Querier)?;
+
+        let start = match call.get_flag(engine_state, stack, "start")? {
+            Some(start) => parse_timestamp(start)?,
+            None => MIN_TIMESTAMP,
+        };
+        let stop = match call.get_flag(engine_state, stack, "stop")? {
+            Some(stop) => parse_timestamp(stop)?,
+            None => MAX_TIMESTAMP,
+        };
+
+        let predicate = match call.get_flag::<Spanned<String>>(engine_state, stack, "predicate")? {
+            Some(expr) if !expr.item.is_empty() => {
+                Some(expr_to_rpc_predicate(&expr.item).map_err(|e| {
+                    ShellError::UnsupportedInput(format!("Invalid predicate: {}", e), expr.span)
+                })?)
+            }
+            _ => None,
+        };
+
+        let bucket = parse_db_name(&dbname.item, dbname.span)?;
+
+        Ok(Self {
+            addr,
+            source: Client::read_source(&bucket, 0),
+            range: TimestampRange { start, end: stop },
+            predicate,
+        })
+    }
+
+    /// Runs `f` with a storage client connected to the querier
+    pub fn run<T, F, Fut>(&self, span: Span, mut f: F) -> Result<T, ShellError>
+    where
+        F: FnMut(Client) -> Fut,
+        Fut: Future<Output = Result<T, tonic::Status>>,
+    {
+        let addr = self.addr.as_str();
+
+        with_connection(addr, span, |connection| {
+            let request = f(Client::new(connection));
+            async move {
+                request.await.map_err(|status| {
+                    client_error(addr, "Storage request failed", status.into(), span)
+                })
+            }
+        })
+    }
+}
+
+/// Parses the name of an aggregate, as accepted by `influxdb_iox storage`
+pub fn parse_aggregate(value: &Spanned<String>) -> Result<AggregateType, ShellError> {
+    match value.item.to_lowercase().as_str() {
+        "none" => Ok(AggregateType::None),
+        "count" => Ok(AggregateType::Count),
+        "sum" => Ok(AggregateType::Sum),
+        "min" => Ok(AggregateType::Min),
+        "max" => Ok(AggregateType::Max),
+        "mean" => Ok(AggregateType::Mean),
+        "first" => Ok(AggregateType::First),
+        "last" => Ok(AggregateType::Last),
+        _ => Err(ShellError::UnsupportedInput(
+            "the only possible aggregates are 'none', 'count', 'sum', 'min', 'max', 'mean', 'first' and 'last'".into(),
+            value.span,
+        )),
+    }
+}
+
+/// Parses nanoseconds since the epoch or an RFC3339 timestamp
+fn parse_timestamp(value: Spanned<String>) -> Result<i64, ShellError> {
+    if let Ok(nanos) = value.item.parse::<i64>() {
+        return Ok(nanos);
+    }
+
+    DateTime::parse_from_rfc3339(&value.item)
+        .map(|t| t.timestamp_nanos())
+        .map_err(|_| {
+            ShellError::UnsupportedInput(
+                format!(
+                    "'{}' is neither an RFC3339 timestamp nor nanoseconds since the epoch",
+                    value.item
+                ),
+                value.span,
+            )
+        })
+}
+
+/// The storage API addresses a database by org and bucket id, which IOx
+/// names `<org id>_<bucket id>` with both ids as 16 digit hex
+fn parse_db_name(dbname: &str, span: Span) -> Result<OrgAndBucket, ShellError> {
+    let invalid = || {
+        ShellError::GenericError(
+            "Invalid database name for storage requests".into(),
+            format!("'{}' is not <org id>_<bucket id>", dbname),
+            Some(span),
+            Some(
+                "Storage requests need a database named like 00000000000000aa_00000000000000bb"
+                    .into(),
+            ),
+            Vec::new(),
+        )
+    };
+
+    let (org, bucket) = dbname.split_once('_').ok_or_else(invalid)?;
+    let parse_id = |id: &str| {
+        u64::from_str_radix(id, 16)
+            .ok()
+            .and_then(NonZeroU64::new)
+            .ok_or_else(invalid)
+    };
+
+    Ok(OrgAndBucket::new(parse_id(org)?, parse_id(bucket)?))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parses_timestamps() {
+        let spanned = |s: &str| Spanned {
+            item: s.to_string(),
+            span: Span::test_data(),
+        };
+
+        assert_eq!(parse_timestamp(spanned("1970-01-01T00:00:00Z")).unwrap(), 0);
+        assert_eq!(
+            parse_timestamp(spanned("2028-01-01T15:00:00Z")).unwrap(),
+            1830351600000000000
+        );
+        assert_eq!(parse_timestamp(spanned("-12345")).unwrap(), -12345);
+        assert!(parse_timestamp(spanned("yesterday")).is_err());
+    }
+
+    #[test]
+    fn parses_db_names() {
+        let span = Span::test_data();
+
+        let bucket = parse_db_name("00000000000000aa_00000000000000bb", span).unwrap();
+        assert_eq!(bucket.org_id().get(), 0xaa);
+        assert_eq!(bucket.bucket_id().get(), 0xbb);
+
+        assert!(parse_db_name("bananas", span).is_err());
+        assert!(parse_db_name("0_1", span).is_err());
+    }
+}
@@ -0,0 +1,236 @@
+use crate::iox::values::nanos_to_value;
+use influxdb_storage_client::generated_types::read_response::{frame::Data, SeriesFrame};
+use nu_protocol::{ShellError, Span, Value};
+
+/// Converts the frames of a read response into one row per point.
+///
+/// Every row holds the tags of its series, including `_measurement` and
+/// `_field`, followed by the `_value` and `_time` of the point.
+pub fn frames_to_value(frames: Vec<Data>, span: Span) -> Result<Value, ShellError> {
+    let mut rows = vec![];
+    let mut series: Option<(Vec<String>, Vec<Value>)> = None;
+
+    for frame in frames {
+        match frame {
+            // the series frames of a group carry all of its tags, so the
+            // group frame itself adds nothing to the rows
+            Data::Group(_) => {}
+            Data::Series(frame) => series = Some(series_tags(frame, span)?),
+            Data::FloatPoints(points) => push_points(
+                &mut rows,
+                &series,
+                points.timestamps,
+                points.values.into_iter().map(|v| Value::float(v, span)),
+                span,
+            )?,
+            Data::IntegerPoints(points) => push_points(
+                &mut rows,
+                &series,
+                points.timestamps,
+                points.values.into_iter().map(|v| Value::int(v, span)),
+                span,
+            )?,
+            // Nushell has no unsigned integers, so values too large for an
+            // i64 are widened to floats
+            Data::UnsignedPoints(points) => push_points(
+                &mut rows,
+                &series,
+                points.timestamps,
+                points.values.into_iter().map(|v| match i64::try_from(v) {
+                    Ok(v) => Value::int(v, span),
+                    Err(_) => Value::float(v as f64, span),
+                }),
+                span,
+            )?,
+            Data::BooleanPoints(points) => push_points(
+                &mut rows,
+                &series,
+                points.timestamps,
+                points.values.into_iter().map(|v| Value::boolean(v, span)),
+                span,
+            )?,
+            Data::StringPoints(points) => push_points(
+                &mut rows,
+                &series,
+                points.timestamps,
+                points.values.into_iter().map(|v| Value::string(v, span)),
+                span,
+            )?,
+        }
+    }
+
+    Ok(Value::List { vals: rows, span })
+}
+
+/// Converts a list of names, e.g. tag keys, into a list of strings
+pub fn strings_to_value(strings: Vec<String>, span: Span) -> Value {
+    Value::List {
+        vals: strings
+            .into_iter()
+            .map(|s| Value::string(tag_key_name(s), span))
+            .collect(),
+        span,
+    }
+}
+
+fn series_tags(frame: SeriesFrame, span: Span) -> Result<(Vec<String>, Vec<Value>), ShellError> {
+    let mut cols = Vec::with_capacity(frame.tags.len());
+    let mut vals = Vec::with_capacity(frame.tags.len());
+
+    for tag in frame.tags {
+        let key = match tag.key.as_slice() {
+            [0] => "_measurement".to_string(),
+            [255] => "_field".to_string(),
+            _ => String::from_utf8(tag.key).map_err(|e| {
+                ShellError::UnsupportedInput(format!("Invalid tag key: {}", e), span)
+            })?,
+        };
+
+        cols.push(key);
+        vals.push(Value::string(String::from_utf8_lossy(&tag.value), span));
+    }
+
+    Ok((cols, vals))
+}
+
+fn push_points(
+    rows: &mut Vec<Value>,
+    series: &Option<(Vec<String>, Vec<Value>)>,
+    timestamps: Vec<i64>,
+    values: impl Iterator<Item = Value>,
+    span: Span,
+) -> Result<(), ShellError> {
+    let (tag_cols, tag_vals) = series.as_ref().ok_or_else(|| {
+        ShellError::GenericError(
+            "Invalid storage response".into(),
+            "points frame before any series frame".into(),
+            Some(span),
+            None,
+            Vec::new(),
+        )
+    })?;
+
+    let mut cols = tag_cols.clone();
+    cols.push("_value".into());
+    cols.push("_time".into());
+
+    for (timestamp, value) in timestamps.into_iter().zip(values) {
+        let mut vals = tag_vals.clone();
+        vals.push(value);
+        vals.push(nanos_to_value(timestamp, span));
+
+        rows.push(Value::Record {
+            cols: cols.clone(),
+            vals,
+            span,
+        });
+    }
+
+    Ok(())
+}
+
+/// The storage client renders the special measurement and field keys as
+/// `_m(0x00)` and `_f(0xff)`, this uses the names that appear in the rows
+fn tag_key_name(key: String) -> String {
+    match key.as_str() {
+        "_m(0x00)" => "_measurement".into(),
+        "_f(0xff)" => "_field".into(),
+        _ => key,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use influxdb_storage_client::generated_types::{
+        read_response::{FloatPointsFrame, IntegerPointsFrame},
+        Tag,
+    };
+
+    fn series(tags: Vec<(&[u8], &str)>) -> Data {
+        Data::Series(SeriesFrame {
+            tags: tags
+                .into_iter()
+                .map(|(key, value)| Tag {
+                    key: key.to_vec(),
+                    value: value.as_bytes().to_vec(),
+                })
+                .collect(),
+            data_type: 0,
+        })
+    }
+
+    #[test]
+    fn converts_frames_into_rows() {
+        let span = Span::test_data();
+
+        let frames = vec![
+            series(vec![
+                (&b"_measurement"[..], "cpu"),
+                (&b"host"[..], "a"),
+                (&b"_field"[..], "usage"),
+            ]),
+            Data::FloatPoints(FloatPointsFrame {
+                timestamps: vec![1, 2],
+                values: vec![0.5, 1.5],
+            }),
+            series(vec![(&[0][..], "cpu"), (&[255][..], "count")]),
+            Data::IntegerPoints(IntegerPointsFrame {
+                timestamps: vec![3],
+                values: vec![7],
+            }),
+        ];
+
+        let row = |cols: &[&str], vals: Vec<Value>| Value::Record {
+            cols: cols.iter().map(|c| c.to_string()).collect(),
+            vals,
+            span,
+        };
+        let expected = Value::List {
+            vals: vec![
+                row(
+                    &["_measurement", "host", "_field", "_value", "_time"],
+                    vec![
+                        Value::string("cpu", span),
+                        Value::string("a", span),
+                        Value::string("usage", span),
+                        Value::float(0.5, span),
+                        nanos_to_value(1, span),
+                    ],
+                ),
+                row(
+                    &["_measurement", "host", "_field", "_value", "_time"],
+                    vec![
+                        Value::string("cpu", span),
+                        Value::string("a", span),
+                        Value::string("usage", span),
+                        Value::float(1.5, span),
+                        nanos_to_value(2, span),
+                    ],
+                ),
+                row(
+                    &["_measurement", "_field", "_value", "_time"],
+                    vec![
+                        Value::string("cpu", span),
+                        Value::string("count", span),
+                        Value::int(7, span),
+                        nanos_to_value(3, span),
+                    ],
+                ),
+            ],
+            span,
+        };
+
+        assert_eq!(frames_to_value(frames, span).unwrap(), expected);
+    }
+
+    #[test]
+    fn rejects_points_without_series() {
+        let frames = vec![Data::FloatPoints(FloatPointsFrame {
+            timestamps: vec![1],
+            values: vec![0.5],
+        })];
+
+        assert!(frames_to_value(frames, Span::test_data()).is_err());
+    }
+}
@@ -0,0 +1,53 @@
+use super::request::{storage_signature, StorageArgs};
+use super::response::strings_to_value;
+use influxdb_storage_client::generated_types::TagKeysRequest;
+use nu_protocol::ast::Call;
+use nu_protocol::engine::{Command, EngineState, Stack};
+use nu_protocol::{Category, Example, IntoPipelineData, PipelineData, ShellError, Signature};
+
+#[derive(Clone)]
+pub struct SubCommand;
+
+impl Command for SubCommand {
+    fn name(&self) -> &str {
+        "ioxstorage tag-keys"
+    }
+
+    fn signature(&self) -> Signature {
+        storage_signature("ioxstorage tag-keys").category(Category::Filters)
+    }
+
+    fn usage(&self) -> &str {
+        "List the tag keys of the series matching a predicate in the Iox Database."
+    }
+
+    fn run(
+        &self,
+        engine_state: &EngineState,
+        stack: &mut Stack,
+        call: &Call,
+        _input: PipelineData,
+    ) -> Result<PipelineData, ShellError> {
+        let args = StorageArgs::from_call(engine_state, stack, call)?;
+        let request = TagKeysRequest {
+            tags_source: Some(args.source.clone()),
+            range: Some(args.range.clone()),
+            predicate: args.predicate.clone(),
+        };
+
+        let keys = args.run(call.head, |mut client| {
+            let request = request.clone();
+            async move { client.tag_keys(request).await }
+        })?;
+
+        Ok(strings_to_value(keys, call.head).into_pipeline_data())
+    }
+
+    fn examples(&self) -> Vec<Example> {
+        vec![Example {
+            description: "List the tag keys of the cpu measurement",
+            example: r#"ioxstorage tag-keys -p "_measurement = 'cpu'""#,
+            result: None,
+        }]
+    }
+}
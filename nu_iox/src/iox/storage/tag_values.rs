@@ -0,0 +1,82 @@
+use super::request::{storage_signature, StorageArgs};
+use super::response::strings_to_value;
+use influxdb_storage_client::generated_types::TagValuesRequest;
+use nu_engine::CallExt;
+use nu_protocol::ast::Call;
+use nu_protocol::engine::{Command, EngineState, Stack};
+use nu_protocol::{
+    Category, Example, IntoPipelineData, PipelineData, ShellError, Signature, SyntaxShape,
+};
+
+#[derive(Clone)]
+pub struct SubCommand;
+
+impl Command for SubCommand {
+    fn name(&self) -> &str {
+        "ioxstorage tag-values"
+    }
+
+    fn signature(&self) -> Signature {
+        storage_signature("ioxstorage tag-values")
+            .required(
+                "tag_key",
+                SyntaxShape::String,
+                "tag key to list the values of, '_measurement' and '_field' are accepted too",
+            )
+            .category(Category::Filters)
+    }
+
+    fn usage(&self) -> &str {
+        "List the values of a tag in the series matching a predicate in the Iox Database."
+    }
+
+    fn run(
+        &self,
+        engine_state: &EngineState,
+        stack: &mut Stack,
+        call: &Call,
+        _input: PipelineData,
+    ) -> Result<PipelineData, ShellError> {
+        let args = StorageArgs::from_call(engine_state, stack, call)?;
+        let tag_key: String = call.req(engine_state, stack, 0)?;
+
+        let request = TagValuesRequest {
+            tags_source: Some(args.source.clone()),
+            range: Some(args.range.clone()),
+            predicate: args.predicate.clone(),
+            tag_key: tag_key_bytes(&tag_key),
+        };
+
+        let values = args.run(call.head, |mut client| {
+            let request = request.clone();
+            async move { client.tag_values(request).await }
+        })?;
+
+        Ok(strings_to_value(values, call.head).into_pipeline_data())
+    }
+
+    fn examples(&self) -> Vec<Example> {
+        vec![
+            Example {
+                description: "List the hosts that wrote to the cpu measurement",
+                example: r#"ioxstorage tag-values host -p "_measurement = 'cpu'""#,
+                result: None,
+            },
+            Example {
+                description: "List the fields of the cpu measurement",
+                example: r#"ioxstorage tag-values _field -p "_measurement = 'cpu'""#,
+                result: None,
+            },
+        ]
+    }
+}
+
+/// The storage API names the measurement and field of a series with
+/// special tag keys
+fn tag_key_bytes(tag_key: &str) -> Vec<u8> {
+    match tag_key {
+        "_measurement" | "_m" => vec![0],
+        "_field" | "_f" => vec![255],
+        _ => tag_key.as_bytes().to_vec(),
+    }
+}
@@ -0,0 +1,127 @@
+use super::request::{parse_aggregate, storage_signature, StorageArgs};
+use super::response::frames_to_value;
+use influxdb_storage_client::generated_types::{
+    Aggregate, ReadWindowAggregateRequest, TagKeyMetaNames,
+};
+use nu_engine::CallExt;
+use nu_protocol::ast::Call;
+use nu_protocol::engine::{Command, EngineState, Stack};
+use nu_protocol::{
+    Category, Example, IntoPipelineData, PipelineData, ShellError, Signature, Spanned, SyntaxShape,
+    Value,
+};
+
+#[derive(Clone)]
+pub struct SubCommand;
+
+impl Command for SubCommand {
+    fn name(&self) -> &str {
+        "ioxstorage window-aggregate"
+    }
+
+    fn signature(&self) -> Signature {
+        storage_signature("ioxstorage window-aggregate")
+            .required_named(
+                "every",
+                SyntaxShape::Duration,
+                "width of each window, e.g. 1min",
+                Some('e'),
+            )
+            .named(
+                "offset",
+                SyntaxShape::Duration,
+                "offset of the windows from the epoch, defaults to 0sec",
+                None,
+            )
+            .required_named(
+                "aggregate",
+                SyntaxShape::List(Box::new(SyntaxShape::String)),
+                "aggregates computed for each window: 'count', 'sum', 'min', 'max', 'mean', 'first' or 'last'",
+                Some('a'),
+            )
+            .category(Category::Filters)
+    }
+
+    fn usage(&self) -> &str {
+        "Aggregate the series matching a predicate from the Iox Database over time windows."
+    }
+
+    fn run(
+        &self,
+        engine_state: &EngineState,
+        stack: &mut Stack,
+        call: &Call,
+        _input: PipelineData,
+    ) -> Result<PipelineData, ShellError> {
+        let args = StorageArgs::from_call(engine_state, stack, call)?;
+        let window_every = duration_flag(engine_state, stack, call, "every")?
+            .ok_or_else(|| ShellError::MissingParameter("every".into(), call.head))?;
+        let offset = duration_flag(engine_state, stack, call, "offset")?.unwrap_or(0);
+        let aggregate = call
+            .get_flag::<Vec<String>>(engine_state, stack, "aggregate")?
+            .ok_or_else(|| ShellError::MissingParameter("aggregate".into(), call.head))?
+            .into_iter()
+            .map(|item| {
+                let aggregate = Spanned {
+                    item,
+                    span: call.head,
+                };
+                parse_aggregate(&aggregate).map(|aggregate| Aggregate {
+                    r#type: aggregate as i32,
+                })
+            })
+            .collect::<Result<Vec<_>, ShellError>>()?;
+
+        let request = ReadWindowAggregateRequest {
+            read_source: Some(args.source.clone()),
+            range: Some(args.range.clone()),
+            predicate: args.predicate.clone(),
+            window_every,
+            offset,
+            aggregate,
+            window: None,
+            tag_key_meta_names: TagKeyMetaNames::Text as i32,
+        };
+
+        let frames = args.run(call.head, |mut client| {
+            let request = request.clone();
+            async move { client.read_window_aggregate(request).await }
+        })?;
+
+        Ok(frames_to_value(frames, call.head)?.into_pipeline_data())
+    }
+
+    fn examples(&self) -> Vec<Example> {
+        vec![
+            Example {
+                description: "Average the usage of host a over 10 minute windows",
+                example: r#"ioxstorage window-aggregate -e 10min -a [mean] -p "host = 'a' AND _field = 'usage'""#,
+                result: None,
+            },
+            Example {
+                description: "Count the points of each series per hour, starting at half past",
+                example: r#"ioxstorage window-aggregate -e 1hr --offset 30min -a [count]"#,
+                result: None,
+            },
+        ]
+    }
+}
+
+/// Reads a duration flag as nanoseconds
+fn duration_flag(
+    engine_state: &EngineState,
+    stack: &mut Stack,
+    call: &Call,
+    name: &str,
+) -> Result<Option<i64>, ShellError> {
+    match call.get_flag::<Value>(engine_state, stack, name)? {
+        None => Ok(None),
+        Some(Value::Duration { val, .. }) | Some(Value::Int { val, .. }) if val >= 0 => {
+            Ok(Some(val))
+        }
+        Some(other) => Err(ShellError::UnsupportedInput(
+            format!("--{} must be a positive duration", name),
+            other.span().unwrap_or(call.head),
+        )),
+    }
+}
@@ -0,0 +1,93 @@
+use super::clients::with_connection;
+use super::util::{client_error, get_iox_addr, IoxService};
+use futures::TryStreamExt;
+use influxdb_iox_client::store::Client;
+use nu_engine::CallExt;
+use nu_protocol::ast::Call;
+use nu_protocol::engine::{Command, EngineState, Stack};
+use nu_protocol::{
+    Category, Example, IntoPipelineData, PipelineData, ShellError, Signature, Span, Spanned,
+    SyntaxShape, Value,
+};
+
+#[derive(Clone)]
+pub struct Ioxstoreget;
+
+impl Command for Ioxstoreget {
+    fn name(&self) -> &str {
+        "ioxstoreget"
+    }
+
+    fn signature(&self) -> nu_protocol::Signature {
+        Signature::build("ioxstoreget")
+            .required(
+                "uuid",
+                SyntaxShape::String,
+                "object store id of the parquet file, as listed by ioxparquetfiles",
+            )
+            .named(
+                "host",
+                SyntaxShape::String,
+                "address of the IOx router, overriding IOX_ROUTER_ADDR and IOX_HOST",
+                None,
+            )
+            .category(Category::Filters)
+    }
+
+    fn usage(&self) -> &str {
+        "Download a parquet file from the object store of an Iox Database."
+    }
+
+    fn run(
+        &self,
+        engine_state: &EngineState,
+        stack: &mut Stack,
+        call: &Call,
+        _input: PipelineData,
+    ) -> Result<PipelineData, ShellError> {
+        let uuid: Spanned<String> = call.req(engine_state, stack, 0)?;
+        let addr = get_iox_addr(engine_state, stack, call, IoxService::Router)?;
+
+        let data = tokio_block_storeget(&addr, &uuid.item, call.head)?;
+
+        Ok(Value::Binary {
+            val: data,
+            span: call.head,
+        }
+        .into_pipeline_data())
+    }
+
+    fn examples(&self) -> Vec<Example> {
+        vec![
+            Example {
+                description: "Save a parquet file to disk",
+                example: r#"ioxstoreget 5d0ad8dc-1d7a-4e9e-9f3b-6f5bc8d0a8a1 | save cpu.parquet"#,
+                result: None,
+            },
+            Example {
+                description: "Save the first parquet file of partition 1",
+                example: r#"ioxstoreget (ioxparquetfiles 1 | get 0.object_store_id) | save file.parquet"#,
+                result: None,
+            },
+        ]
+    }
+}
+
+/// Fetches the whole parquet file, which the router streams in chunks
+pub fn tokio_block_storeget(addr: &str, uuid: &str, span: Span) -> Result<Vec<u8>, ShellError> {
+    with_connection(addr, span, |connection| async move {
+        let mut stream = Client::new(connection)
+            .get_parquet_file_by_object_store_id(uuid.to_string())
+            .await
+            .map_err(|e| client_error(addr, "Unable to get parquet file", e, span))?;
+
+        let mut data = vec![];
+        while let Some(response) = stream.try_next().await.map_err(|status| {
+            client_error(addr, "Unable to get parquet file", status.into(), span)
+        })? {
+            data.extend_from_slice(&response.data);
+        }
+
+        Ok(data)
+    })
+}
@@ -0,0 +1,131 @@
+use super::clients::iox_runtime;
+use super::nuerror::{IoxRequest, NuIoxError};
+use super::values::record_batch_to_values;
+use influxdb_iox_client::flight::PerformQuery;
+use nu_protocol::{ShellError, Span, Spanned, Value};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use std::vec::IntoIter;
+use tokio::runtime::Runtime;
+
+/// How often a query waiting for its next batch checks for Ctrl-C
+const CTRLC_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// The rows of a running query, fetched from the querier one record batch at
+/// a time as they are consumed.
+///
+/// Dropping the stream drops the flight request, which cancels the query on
+/// the querier, so `ioxsql "select *" | first 20` only waits for the batches
+/// holding the first 20 rows. The same happens on Ctrl-C, even while waiting
+/// for a batch.
+pub struct QueryStream {
+    runtime: Arc<Runtime>,
+
+    /// `None` once the query is finished, failed or cancelled
+    query: Option<PerformQuery>,
+
+    /// Rows of the last batch not yet returned
+    rows: IntoIter<Value>,
+
+    ctrlc: Option<Arc<AtomicBool>>,
+
+    /// What the query was, to report errors that happen mid stream
+    addr: String,
+    dbname: Spanned<String>,
+    sql_span: Span,
+    span: Span,
+}
+
+impl QueryStream {
+    pub fn new(
+        query: PerformQuery,
+        addr: String,
+        dbname: Spanned<String>,
+        sql_span: Span,
+        ctrlc: Option<Arc<AtomicBool>>,
+        span: Span,
+    ) -> Result<Self, ShellError> {
+        Ok(Self {
+            runtime: iox_runtime()?,
+            query: Some(query),
+            rows: vec![].into_iter(),
+            ctrlc,
+            addr,
+            dbname,
+            sql_span,
+            span,
+        })
+    }
+
+    fn interrupted(&self) -> bool {
+        self.ctrlc
+            .as_ref()
+            .map_or(false, |ctrlc| ctrlc.load(Ordering::SeqCst))
+    }
+
+    /// Waits for the next batch of the query, or `None` on Ctrl-C
+    fn next_batch(&mut self) -> Option<Result<Option<Vec<Value>>, ShellError>> {
+        let query = self.query.as_mut()?;
+        let ctrlc = self.ctrlc.clone();
+
+        let batch = self.runtime.block_on(async move {
+            tokio::select! {
+                batch = query.next() => Some(batch),
+                _ = wait_for_ctrlc(ctrlc) => None,
+            }
+        })?;
+
+        Some(match batch {
+            Ok(Some(batch)) => record_batch_to_values(&batch, self.span).map(Some),
+            Ok(None) => Ok(None),
+            Err(e) => {
+                let request = IoxRequest::new(&self.addr, "Query failed", self.span)
+                    .namespace(&self.dbname)
+                    .query(self.sql_span);
+                Err(NuIoxError::from_flight_error(e, &request).into())
+            }
+        })
+    }
+}
+
+impl Iterator for QueryStream {
+    type Item = Value;
+
+    fn next(&mut self) -> Option<Value> {
+        loop {
+            if self.interrupted() {
+                self.query = None;
+                return None;
+            }
+
+            if let Some(row) = self.rows.next() {
+                return Some(row);
+            }
+
+            match self.next_batch() {
+                Some(Ok(Some(rows))) => self.rows = rows.into_iter(),
+                Some(Err(error)) => {
+                    self.query = None;
+                    return Some(Value::Error { error });
+                }
+                Some(Ok(None)) | None => {
+                    self.query = None;
+                    return None;
+                }
+            }
+        }
+    }
+}
+
+/// Completes once `ctrlc` is set, never if there is no Ctrl-C handler
+async fn wait_for_ctrlc(ctrlc: Option<Arc<AtomicBool>>) {
+    match ctrlc {
+        Some(ctrlc) => {
+            while !ctrlc.load(Ordering::SeqCst) {
+                tokio::time::sleep(CTRLC_POLL_INTERVAL).await;
+            }
+        }
+        None => futures::future::pending().await,
+    }
+}
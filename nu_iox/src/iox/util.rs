@@ -1,12 +1,19 @@
+use super::nuerror::{IoxRequest, NuIoxError};
 use csv::ReaderBuilder;
+use influxdb_iox_client::connection::{Builder, Connection};
+use nu_engine::CallExt;
+use nu_protocol::ast::Call;
 use nu_protocol::engine::{EngineState, Stack};
-use nu_protocol::ShellError;
-use tokio::runtime::{Builder, Runtime};
+use nu_protocol::{ShellError, Span, Spanned};
+use tokio::runtime::{self, Runtime};
 
 pub fn tokio_block02() -> Result<(), std::io::Error> {
     use influxdb_iox_client::{
         connection::Builder,
-        flight::{generated_types::ReadInfo, Client},
+        flight::{
+            generated_types::{QueryType, ReadInfo},
+            Client,
+        },
     };
 
     let num_threads: Option<usize> = None;
@@ -25,6 +32,8 @@ pub fn tokio_block02() -> Result<(), std::io::Error> {
             .perform_query(ReadInfo {
                 namespace_name: "postgresql:///iox_shared".to_string(),
                 sql_query: "select * from h2o_temperature".to_string(),
+                params: vec![],
+                query_type: QueryType::Sql.into(),
             })
             .await
             .expect("query request should work");
@@ -72,7 +81,6 @@ pub fn get_runtime(num_threads: Option<usize>) -> Result<Runtime, std::io::Error
     // That means use eprintln!() instead of error!() and so on. The log emitter
     // requires a running tokio runtime and is initialised after this function.
 
-    //use tokio::runtime::Builder;
     let kind = std::io::ErrorKind::Other;
     match num_threads {
         None => Runtime::new(),
@@ -90,8 +98,8 @@ pub fn get_runtime(num_threads: Option<usize>) -> Result<Runtime, std::io::Error
                     );
                     Err(std::io::Error::new(kind, msg))
                 }
-                1 => Builder::new_current_thread().enable_all().build(),
-                _ => Builder::new_multi_thread()
+                1 => runtime::Builder::new_current_thread().enable_all().build(),
+                _ => runtime::Builder::new_multi_thread()
                     .enable_all()
                     .worker_threads(num_threads)
                     .build(),
@@ -100,23 +108,170 @@ pub fn get_runtime(num_threads: Option<usize>) -> Result<Runtime, std::io::Error
     }
 }
 
+/// Default address of the IOx querier gRPC API
+pub const DEFAULT_QUERIER_ADDR: &str = "http://127.0.0.1:8082";
+
+/// Default address of the IOx router gRPC API
+pub const DEFAULT_ROUTER_ADDR: &str = "http://127.0.0.1:8081";
+
+/// The IOx server a command talks to
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum IoxService {
+    Querier,
+    Router,
+}
+
+impl IoxService {
+    /// The environment variable holding the full address of this service
+    pub fn addr_env_var(&self) -> &'static str {
+        match self {
+            Self::Querier => "IOX_QUERIER_ADDR",
+            Self::Router => "IOX_ROUTER_ADDR",
+        }
+    }
+
+    /// The address used when nothing is configured
+    pub fn default_addr(&self) -> &'static str {
+        match self {
+            Self::Querier => DEFAULT_QUERIER_ADDR,
+            Self::Router => DEFAULT_ROUTER_ADDR,
+        }
+    }
+
+    /// The port used when a configured host does not name one
+    pub fn default_port(&self) -> u16 {
+        match self {
+            Self::Querier => 8082,
+            Self::Router => 8081,
+        }
+    }
+}
+
+/// Resolves the address of the IOx `service` for a command.
+///
+/// In order of precedence this is the `--host` flag of the command, the
+/// `IOX_QUERIER_ADDR`/`IOX_ROUTER_ADDR` environment variable, the `IOX_HOST`
+/// environment variable and finally the local default address. Hosts without
+/// a scheme or port get `http://` and the default port of the service.
+pub fn get_iox_addr(
+    engine_state: &EngineState,
+    stack: &mut Stack,
+    call: &Call,
+    service: IoxService,
+) -> Result<String, ShellError> {
+    let host: Option<String> = call.get_flag(engine_state, stack, "host")?;
+
+    let addr = host
+        .or_else(|| get_env_var_from_engine(stack, engine_state, service.addr_env_var()))
+        .or_else(|| get_env_var_from_engine(stack, engine_state, "IOX_HOST"));
+
+    Ok(match addr {
+        Some(addr) => normalize_addr(&addr, service.default_port()),
+        None => service.default_addr().to_string(),
+    })
+}
+
+/// Resolves the namespace a command runs against, either from the `--dbname`
+/// flag or the `IOX_DBNAME` environment variable.
+///
+/// The span is that of the flag, or of the command for the environment
+/// variable, so errors about the namespace point at where it came from.
+pub fn get_dbname(
+    engine_state: &EngineState,
+    stack: &mut Stack,
+    call: &Call,
+) -> Result<Spanned<String>, ShellError> {
+    let db: Option<Spanned<String>> = call.get_flag(engine_state, stack, "dbname")?;
+
+    db.or_else(|| {
+        get_env_var_from_engine(stack, engine_state, "IOX_DBNAME").map(|item| Spanned {
+            item,
+            span: call.head,
+        })
+    })
+    .ok_or_else(|| {
+        ShellError::GenericError(
+            "No database selected".into(),
+            "no database name given".into(),
+            Some(call.head),
+            Some("Pass --dbname or set the IOX_DBNAME environment variable".into()),
+            Vec::new(),
+        )
+    })
+}
+
+/// Reads the `--batch-size` flag of a write command
+pub fn get_batch_size(
+    engine_state: &EngineState,
+    stack: &mut Stack,
+    call: &Call,
+    default: usize,
+) -> Result<usize, ShellError> {
+    let batch_size: Option<Spanned<i64>> = call.get_flag(engine_state, stack, "batch-size")?;
+
+    match batch_size {
+        None => Ok(default),
+        Some(Spanned { item, span }) => usize::try_from(item)
+            .ok()
+            .filter(|size| *size > 0)
+            .ok_or_else(|| {
+                ShellError::UnsupportedInput("batch size must be greater than zero".into(), span)
+            }),
+    }
+}
+
+/// Connects to the IOx server at `addr`, reporting an unreachable server as a
+/// network failure at `span`
+pub async fn connect(addr: &str, span: Span) -> Result<Connection, ShellError> {
+    Builder::default().build(addr).await.map_err(|e| {
+        NuIoxError::ConnectionRefused {
+            addr: addr.to_string(),
+            message: e.to_string(),
+            span,
+        }
+        .into()
+    })
+}
+
+/// Converts an error from an IOx gRPC client for a request that is not
+/// scoped to a namespace into a `ShellError`
+pub fn client_error(
+    addr: &str,
+    title: &str,
+    error: influxdb_iox_client::error::Error,
+    span: Span,
+) -> ShellError {
+    IoxRequest::new(addr, title, span).error(error)
+}
+
+fn normalize_addr(addr: &str, default_port: u16) -> String {
+    let (scheme, authority) = match addr.split_once("://") {
+        Some((scheme, authority)) => (scheme, authority),
+        None => ("http", addr),
+    };
+
+    let host = authority.split('/').next().unwrap_or_default();
+    let has_port = host
+        .rsplit_once(':')
+        .map(|(_, port)| !port.contains(']'))
+        .unwrap_or(false);
+
+    if has_port {
+        format!("{}://{}", scheme, authority)
+    } else {
+        format!("{}://{}:{}", scheme, host, default_port)
+    }
+}
+
 pub fn get_env_var_from_engine(
     stack: &mut Stack,
     engine_state: &EngineState,
     env: &str,
-) -> Result<String, ShellError> {
-    let result = stack
+) -> Option<String> {
+    stack
         .get_env_var(engine_state, env)
         .map(|v| v.as_string().unwrap_or_default())
-        .filter(|v| !v.is_empty());
-
-    let result_env = if let Some(env_name) = result {
-        env_name
-    } else {
-        "you need to throw an error if the environment variable does not exist".to_string()
-    };
-
-    Ok(result_env)
+        .filter(|v| !v.is_empty())
 }
 
 pub fn number_of_csv_records(data: &str) -> Result<usize, Box<dyn std::error::Error>> {
@@ -125,3 +280,24 @@ pub fn number_of_csv_records(data: &str) -> Result<usize, Box<dyn std::error::Er
     //println!("Number of records = {:?}", numofrecords);
     Ok(numofrecords)
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn normalizes_addresses() {
+        assert_eq!(normalize_addr("myhost", 8082), "http://myhost:8082");
+        assert_eq!(normalize_addr("myhost:9000", 8082), "http://myhost:9000");
+        assert_eq!(
+            normalize_addr("https://myhost", 8081),
+            "https://myhost:8081"
+        );
+        assert_eq!(
+            normalize_addr("http://10.0.0.1:8090", 8081),
+            "http://10.0.0.1:8090"
+        );
+        assert_eq!(normalize_addr("[::1]", 8082), "http://[::1]:8082");
+        assert_eq!(normalize_addr("[::1]:9000", 8082), "http://[::1]:9000");
+    }
+}
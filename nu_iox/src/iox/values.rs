@@ -0,0 +1,267 @@
+use arrow::{
+    array::{
+        Array, ArrayRef, BooleanArray, Float64Array, Int64Array, StringArray,
+        TimestampNanosecondArray, UInt64Array,
+    },
+    compute::cast,
+    datatypes::{DataType, TimeUnit},
+    record_batch::RecordBatch,
+    util::display::array_value_to_string,
+};
+use chrono::{DateTime, FixedOffset, TimeZone, Utc};
+use nu_protocol::{ShellError, Span, Value};
+
+/// Converts the record batches of a query result into a Nushell table.
+///
+/// Every row becomes a `Value::Record` whose columns follow the schema of
+/// its batch. Nulls become `Value::Nothing` and an empty result becomes an
+/// empty list.
+pub fn record_batches_to_value(batches: &[RecordBatch], span: Span) -> Result<Value, ShellError> {
+    let mut rows = vec![];
+
+    for batch in batches {
+        rows.extend(record_batch_to_values(batch, span)?);
+    }
+
+    Ok(Value::List { vals: rows, span })
+}
+
+/// Converts a single record batch into one `Value::Record` per row.
+pub fn record_batch_to_values(batch: &RecordBatch, span: Span) -> Result<Vec<Value>, ShellError> {
+    let schema = batch.schema();
+    let cols: Vec<String> = schema.fields().iter().map(|f| f.name().clone()).collect();
+
+    let mut columns = batch
+        .columns()
+        .iter()
+        .map(|array| column_to_values(array, span).map(|vals| vals.into_iter()))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let rows = (0..batch.num_rows())
+        .map(|_| {
+            let vals = columns
+                .iter_mut()
+                .map(|column| column.next().unwrap_or(Value::Nothing { span }))
+                .collect();
+
+            Value::Record {
+                cols: cols.clone(),
+                vals,
+                span,
+            }
+        })
+        .collect();
+
+    Ok(rows)
+}
+
+/// Converts every slot of an arrow array into the matching Nushell value.
+///
+/// Column types without a direct Nushell counterpart are rendered as strings
+/// using the arrow display formatting.
+fn column_to_values(array: &ArrayRef, span: Span) -> Result<Vec<Value>, ShellError> {
+    let values = match array.data_type() {
+        DataType::Int64 => {
+            let array = array.as_any().downcast_ref::<Int64Array>().unwrap();
+            map_rows(array, span, |row| Value::Int {
+                val: array.value(row),
+                span,
+            })
+        }
+        DataType::UInt64 => {
+            let array = array.as_any().downcast_ref::<UInt64Array>().unwrap();
+            map_rows(array, span, |row| {
+                let val = array.value(row);
+                // Nushell has no unsigned integers, so values that do not
+                // fit into an i64 are widened to floats rather than wrapped
+                match i64::try_from(val) {
+                    Ok(val) => Value::Int { val, span },
+                    Err(_) => Value::Float {
+                        val: val as f64,
+                        span,
+                    },
+                }
+            })
+        }
+        DataType::Float64 => {
+            let array = array.as_any().downcast_ref::<Float64Array>().unwrap();
+            map_rows(array, span, |row| Value::Float {
+                val: array.value(row),
+                span,
+            })
+        }
+        DataType::Boolean => {
+            let array = array.as_any().downcast_ref::<BooleanArray>().unwrap();
+            map_rows(array, span, |row| Value::Bool {
+                val: array.value(row),
+                span,
+            })
+        }
+        DataType::Utf8 => {
+            let array = array.as_any().downcast_ref::<StringArray>().unwrap();
+            map_rows(array, span, |row| Value::String {
+                val: array.value(row).to_string(),
+                span,
+            })
+        }
+        DataType::Timestamp(TimeUnit::Nanosecond, _) => {
+            let array = array
+                .as_any()
+                .downcast_ref::<TimestampNanosecondArray>()
+                .unwrap();
+            map_rows(array, span, |row| Value::Date {
+                val: nanos_to_date(array.value(row)),
+                span,
+            })
+        }
+        DataType::Timestamp(_, _) => {
+            let array = cast_array(
+                array,
+                &DataType::Timestamp(TimeUnit::Nanosecond, None),
+                span,
+            )?;
+            column_to_values(&array, span)?
+        }
+        DataType::Dictionary(_, _) => {
+            let array = cast_array(array, &DataType::Utf8, span)?;
+            column_to_values(&array, span)?
+        }
+        _ => {
+            let mut values = Vec::with_capacity(array.len());
+            for row in 0..array.len() {
+                if array.is_null(row) {
+                    values.push(Value::Nothing { span });
+                } else {
+                    let val = array_value_to_string(array, row)
+                        .map_err(|e| ShellError::UnsupportedInput(e.to_string(), span))?;
+                    values.push(Value::String { val, span });
+                }
+            }
+            values
+        }
+    };
+
+    Ok(values)
+}
+
+fn map_rows(array: &dyn Array, span: Span, f: impl Fn(usize) -> Value) -> Vec<Value> {
+    (0..array.len())
+        .map(|row| {
+            if array.is_null(row) {
+                Value::Nothing { span }
+            } else {
+                f(row)
+            }
+        })
+        .collect()
+}
+
+fn cast_array(array: &ArrayRef, to_type: &DataType, span: Span) -> Result<ArrayRef, ShellError> {
+    cast(array, to_type).map_err(|e| ShellError::UnsupportedInput(e.to_string(), span))
+}
+
+fn nanos_to_date(nanos: i64) -> DateTime<FixedOffset> {
+    Utc.timestamp_nanos(nanos).into()
+}
+
+/// Converts a timestamp in nanoseconds since the epoch into a `Value::Date`
+pub fn nanos_to_value(nanos: i64, span: Span) -> Value {
+    Value::Date {
+        val: nanos_to_date(nanos),
+        span,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use arrow::array::DictionaryArray;
+    use arrow::datatypes::Int32Type;
+    use std::sync::Arc;
+
+    #[test]
+    fn converts_typed_columns() {
+        let span = Span::test_data();
+
+        let tag: DictionaryArray<Int32Type> = vec![Some("007"), None].into_iter().collect();
+        let batch = RecordBatch::try_from_iter(vec![
+            ("tag", Arc::new(tag) as ArrayRef),
+            (
+                "count",
+                Arc::new(UInt64Array::from(vec![Some(5), None])) as ArrayRef,
+            ),
+            (
+                "usage",
+                Arc::new(Float64Array::from(vec![Some(1.5), Some(2.0)])) as ArrayRef,
+            ),
+            (
+                "up",
+                Arc::new(BooleanArray::from(vec![Some(true), None])) as ArrayRef,
+            ),
+            (
+                "time",
+                Arc::new(TimestampNanosecondArray::from(vec![1_000_000_000, 0])) as ArrayRef,
+            ),
+        ])
+        .unwrap();
+
+        let value = record_batches_to_value(&[batch], span).unwrap();
+
+        let expected = Value::List {
+            vals: vec![
+                Value::Record {
+                    cols: vec![
+                        "tag".into(),
+                        "count".into(),
+                        "usage".into(),
+                        "up".into(),
+                        "time".into(),
+                    ],
+                    vals: vec![
+                        Value::string("007", span),
+                        Value::int(5, span),
+                        Value::float(1.5, span),
+                        Value::boolean(true, span),
+                        Value::Date {
+                            val: nanos_to_date(1_000_000_000),
+                            span,
+                        },
+                    ],
+                    span,
+                },
+                Value::Record {
+                    cols: vec![
+                        "tag".into(),
+                        "count".into(),
+                        "usage".into(),
+                        "up".into(),
+                        "time".into(),
+                    ],
+                    vals: vec![
+                        Value::Nothing { span },
+                        Value::Nothing { span },
+                        Value::float(2.0, span),
+                        Value::Nothing { span },
+                        Value::Date {
+                            val: nanos_to_date(0),
+                            span,
+                        },
+                    ],
+                    span,
+                },
+            ],
+            span,
+        };
+
+        assert_eq!(value, expected);
+    }
+
+    #[test]
+    fn converts_empty_result() {
+        let span = Span::test_data();
+
+        let value = record_batches_to_value(&[], span).unwrap();
+
+        assert_eq!(value, Value::List { vals: vec![], span });
+    }
+}
@@ -1,10 +1,12 @@
-use super::util::{get_env_var_from_engine, get_runtime};
+use super::batch::{LpBatchWriter, DEFAULT_BATCH_SIZE};
+use super::util::{get_batch_size, get_dbname, get_iox_addr, IoxService};
+use crate::formats::LpOptions;
 use nu_engine::CallExt;
 use nu_protocol::ast::Call;
 use nu_protocol::engine::{Command, EngineState, Stack};
 
 use nu_protocol::{
-    Category, Example, PipelineData, ShellError, Signature, Spanned, SyntaxShape, Value,
+    Category, Example, IntoPipelineData, PipelineData, ShellError, Signature, Spanned, SyntaxShape,
 };
 
 #[derive(Clone)]
@@ -17,10 +19,10 @@ impl Command for Ioxwrite {
 
     fn signature(&self) -> nu_protocol::Signature {
         Signature::build("ioxwrite")
-            .required(
+            .optional(
                 "data",
                 SyntaxShape::String,
-                "Line protocol string to write to Iox",
+                "Line protocol string to write to Iox, instead of the pipeline input",
             )
             .named(
                 "dbname",
@@ -28,6 +30,42 @@ impl Command for Ioxwrite {
                 "name of the database to write to",
                 Some('d'),
             )
+            .named(
+                "host",
+                SyntaxShape::String,
+                "address of the IOx router, overriding IOX_ROUTER_ADDR and IOX_HOST",
+                None,
+            )
+            .named(
+                "batch-size",
+                SyntaxShape::Int,
+                "number of lines sent per write request, defaults to 5000",
+                Some('b'),
+            )
+            .named(
+                "measurement",
+                SyntaxShape::String,
+                "for table input, name of the measurement, defaults to the 'measurement' column",
+                Some('m'),
+            )
+            .named(
+                "tags",
+                SyntaxShape::List(Box::new(SyntaxShape::String)),
+                "for table input, columns to write as tags",
+                Some('t'),
+            )
+            .named(
+                "fields",
+                SyntaxShape::List(Box::new(SyntaxShape::String)),
+                "for table input, columns to write as fields, defaults to all other columns",
+                Some('f'),
+            )
+            .named(
+                "time",
+                SyntaxShape::String,
+                "for table input, column holding the timestamp, defaults to 'time'",
+                None,
+            )
             .category(Category::Filters)
     }
 
@@ -40,27 +78,25 @@ impl Command for Ioxwrite {
         engine_state: &EngineState,
         stack: &mut Stack,
         call: &Call,
-        _input: PipelineData,
+        input: PipelineData,
     ) -> Result<PipelineData, ShellError> {
-        let lp_data: Spanned<String> = call.req(engine_state, stack, 0)?;
-        let db: Option<String> = call.get_flag(engine_state, stack, "dbname")?;
-
-        let dbname = if let Some(name) = db {
-            name
-        } else {
-            get_env_var_from_engine(stack, engine_state, "IOX_DBNAME").unwrap()
-        };
-
-        println!("dbname = {:?}", dbname);
-
-        let nol_result = tokio_block_write(&dbname, &lp_data);
-
-        println!("{:?}", nol_result);
+        let lp_data: Option<Spanned<String>> = call.opt(engine_state, stack, 0)?;
+        let dbname = get_dbname(engine_state, stack, call)?;
+        let addr = get_iox_addr(engine_state, stack, call, IoxService::Router)?;
+        let batch_size = get_batch_size(engine_state, stack, call, DEFAULT_BATCH_SIZE)?;
+        let options = LpOptions::from_call(engine_state, stack, call)?;
+        let config = engine_state.get_config();
+
+        let mut writer = LpBatchWriter::new(&addr, &dbname, batch_size, call.head);
+        match lp_data {
+            Some(lp_data) => {
+                writer.set_data_span(lp_data.span);
+                writer.push_bytes(lp_data.item.as_bytes())?
+            }
+            None => writer.push_pipeline(input, &options, config)?,
+        }
 
-        Ok(PipelineData::Value(
-            Value::Nothing { span: call.head },
-            None,
-        ))
+        Ok(writer.finish()?.into_value(call.head).into_pipeline_data())
     }
 
     fn examples(&self) -> Vec<Example> {
@@ -81,40 +117,15 @@ impl Command for Ioxwrite {
                 result: None,
             },
             Example {
-                description: "Write some line protocol data out to Iox using the default db",
-                example: r#"ioxwrite "cpu,region=pa user=9599 222522""#,
+                description: "Write line protocol piped in from a file in batches of 1000 lines",
+                example: r#"open --raw ./temperature.lp | ioxwrite -b 1000"#,
+                result: None,
+            },
+            Example {
+                description: "Write a table out to Iox, using the host and region columns as tags",
+                example: r#"open data.csv | ioxwrite -m cpu --tags [host region]"#,
                 result: None,
             },
         ]
     }
 }
-
-pub fn tokio_block_write(
-    dbname: &String,
-    lp_data: &Spanned<String>,
-) -> Result<String, std::io::Error> {
-    use influxdb_iox_client::{connection::Builder, write::Client};
-
-    let num_threads: Option<usize> = None;
-    let tokio_runtime = get_runtime(num_threads)?;
-
-    let nol_result = tokio_runtime.block_on(async move {
-        let connection = Builder::default()
-            .build("http://127.0.0.1:8081")
-            .await
-            .expect("client should be valid");
-
-        let mut client = Client::new(connection);
-
-        let numoflines = client
-            .write_lp(dbname.to_string(), lp_data.item.to_string(), 0)
-            .await;
-
-        match numoflines {
-            Ok(res) => res.to_string(),
-            Err(error) => error.to_string(),
-        }
-    });
-
-    Ok(nol_result)
-}
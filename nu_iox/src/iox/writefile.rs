@@ -1,11 +1,15 @@
-use super::util::{get_env_var_from_engine, get_runtime};
+use super::batch::{LpBatchWriter, DEFAULT_BATCH_SIZE};
+use super::util::{get_batch_size, get_dbname, get_iox_addr, IoxService};
+use crate::formats::LpOptions;
 use nu_engine::CallExt;
 use nu_protocol::ast::Call;
 use nu_protocol::engine::{Command, EngineState, Stack};
 use std::fs::File;
 use std::io::Read;
 
-use nu_protocol::{Category, Example, PipelineData, ShellError, Signature, SyntaxShape, Value};
+use nu_protocol::{
+    Category, Example, IntoPipelineData, PipelineData, ShellError, Signature, Spanned, SyntaxShape,
+};
 
 #[derive(Clone)]
 pub struct Ioxwritefile;
@@ -17,10 +21,10 @@ impl Command for Ioxwritefile {
 
     fn signature(&self) -> nu_protocol::Signature {
         Signature::build("ioxwritefile")
-            .required(
+            .optional(
                 "filename",
                 SyntaxShape::String,
-                "File name that contains influxdb line protocol data",
+                "File name that contains influxdb line protocol data, instead of the pipeline input",
             )
             .named(
                 "dbname",
@@ -28,6 +32,42 @@ impl Command for Ioxwritefile {
                 "name of the database to write to",
                 Some('d'),
             )
+            .named(
+                "host",
+                SyntaxShape::String,
+                "address of the IOx router, overriding IOX_ROUTER_ADDR and IOX_HOST",
+                None,
+            )
+            .named(
+                "batch-size",
+                SyntaxShape::Int,
+                "number of lines sent per write request, defaults to 5000",
+                Some('b'),
+            )
+            .named(
+                "measurement",
+                SyntaxShape::String,
+                "for table input, name of the measurement, defaults to the 'measurement' column",
+                Some('m'),
+            )
+            .named(
+                "tags",
+                SyntaxShape::List(Box::new(SyntaxShape::String)),
+                "for table input, columns to write as tags",
+                Some('t'),
+            )
+            .named(
+                "fields",
+                SyntaxShape::List(Box::new(SyntaxShape::String)),
+                "for table input, columns to write as fields, defaults to all other columns",
+                Some('f'),
+            )
+            .named(
+                "time",
+                SyntaxShape::String,
+                "for table input, column holding the timestamp, defaults to 'time'",
+                None,
+            )
             .category(Category::Filters)
     }
 
@@ -40,34 +80,38 @@ impl Command for Ioxwritefile {
         engine_state: &EngineState,
         stack: &mut Stack,
         call: &Call,
-        _input: PipelineData,
+        input: PipelineData,
     ) -> Result<PipelineData, ShellError> {
-        let filename: String = call.req(engine_state, stack, 0)?;
-        let db: Option<String> = call.get_flag(engine_state, stack, "dbname")?;
-
-        let dbname = if let Some(name) = db {
-            name
-        } else {
-            get_env_var_from_engine(stack, engine_state, "IOX_DBNAME").unwrap()
-        };
-
-        println!("dbname = {:?}", dbname);
-
-        //let mut file = File::open(filename).unwrap();
-        let mut file = File::open(filename)
-            .map_err(|e| ShellError::ReadingFile(e.to_string(), call.span()))?;
-
-        let mut lp_data = String::new();
-        let _ = file.read_to_string(&mut lp_data);
-
-        let nol_result = tokio_block_writefile(&dbname, &lp_data);
-
-        println!("{:?}", nol_result);
+        let filename: Option<Spanned<String>> = call.opt(engine_state, stack, 0)?;
+        let dbname = get_dbname(engine_state, stack, call)?;
+        let addr = get_iox_addr(engine_state, stack, call, IoxService::Router)?;
+        let batch_size = get_batch_size(engine_state, stack, call, DEFAULT_BATCH_SIZE)?;
+        let options = LpOptions::from_call(engine_state, stack, call)?;
+        let config = engine_state.get_config();
+
+        let mut writer = LpBatchWriter::new(&addr, &dbname, batch_size, call.head);
+        match filename {
+            Some(filename) => {
+                writer.set_data_span(filename.span);
+                let mut file = File::open(&filename.item)
+                    .map_err(|e| ShellError::ReadingFile(e.to_string(), filename.span))?;
+
+                // stream the file in chunks rather than reading it into memory
+                let mut chunk = vec![0; 64 * 1024];
+                loop {
+                    let len = file
+                        .read(&mut chunk)
+                        .map_err(|e| ShellError::ReadingFile(e.to_string(), filename.span))?;
+                    if len == 0 {
+                        break;
+                    }
+                    writer.push_bytes(&chunk[..len])?;
+                }
+            }
+            None => writer.push_pipeline(input, &options, config)?,
+        }
 
-        Ok(PipelineData::Value(
-            Value::Nothing { span: call.head },
-            None,
-        ))
+        Ok(writer.finish()?.into_value(call.head).into_pipeline_data())
     }
 
     fn examples(&self) -> Vec<Example> {
@@ -82,33 +126,45 @@ impl Command for Ioxwritefile {
                 example: r#"ioxwritefile ./ioxnotes/lineproto/popnm.lp"#,
                 result: None,
             },
+            Example {
+                description:
+                    "Write a large line protocol file out to Iox in batches of 20000 lines",
+                example: r#"ioxwritefile -b 20000 ./backfill.lp"#,
+                result: None,
+            },
+            Example {
+                description: "Write line protocol piped in from another command out to Iox",
+                example: r#"open --raw ./temperature.lp | ioxwritefile"#,
+                result: None,
+            },
+            Example {
+                description: "Write a table out to Iox, with the host column as a tag",
+                example: r#"open ./cpu.csv | ioxwritefile --measurement cpu --tags [host]"#,
+                result: None,
+            },
         ]
     }
 }
 
-pub fn tokio_block_writefile(dbname: &String, lp_data: &String) -> Result<String, std::io::Error> {
-    use influxdb_iox_client::{connection::Builder, write::Client};
-
-    let num_threads: Option<usize> = None;
-    let tokio_runtime = get_runtime(num_threads)?;
-
-    let nol_result = tokio_runtime.block_on(async move {
-        let connection = Builder::default()
-            .build("http://127.0.0.1:8081")
-            .await
-            .expect("client should be valid");
-
-        let mut client = Client::new(connection);
+#[cfg(test)]
+mod test {
+    use super::*;
+    use nu_parser::parse;
+    use nu_protocol::engine::StateWorkingSet;
 
-        let numoflines = client
-            .write_lp(dbname.to_string(), lp_data.to_string(), 0)
-            .await;
+    #[test]
+    fn accepts_table_options() {
+        let engine_state = EngineState::new();
+        let mut working_set = StateWorkingSet::new(&engine_state);
+        working_set.add_decl(Box::new(Ioxwritefile));
 
-        match numoflines {
-            Ok(res) => res.to_string(),
-            Err(error) => error.to_string(),
-        }
-    });
-
-    Ok(nol_result)
+        let (_, err) = parse(
+            &mut working_set,
+            None,
+            b"ioxwritefile -d db --measurement cpu --tags [host region] --fields [usage] --time ts",
+            false,
+            &[],
+        );
+        assert!(err.is_none(), "unexpected parse error: {:?}", err);
+    }
 }
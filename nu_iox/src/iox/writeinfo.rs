@@ -0,0 +1,112 @@
+use super::clients::with_connection;
+use super::util::{client_error, get_iox_addr, IoxService};
+use influxdb_iox_client::write_info::generated_types::{
+    GetWriteInfoResponse, KafkaPartitionStatus,
+};
+use influxdb_iox_client::write_info::Client;
+use nu_engine::CallExt;
+use nu_protocol::ast::Call;
+use nu_protocol::engine::{Command, EngineState, Stack};
+use nu_protocol::{
+    Category, Example, IntoPipelineData, PipelineData, ShellError, Signature, Span, Spanned,
+    SyntaxShape, Value,
+};
+
+#[derive(Clone)]
+pub struct Ioxwriteinfo;
+
+impl Command for Ioxwriteinfo {
+    fn name(&self) -> &str {
+        "ioxwriteinfo"
+    }
+
+    fn signature(&self) -> nu_protocol::Signature {
+        Signature::build("ioxwriteinfo")
+            .required(
+                "token",
+                SyntaxShape::String,
+                "write token returned by the router for a write",
+            )
+            .named(
+                "host",
+                SyntaxShape::String,
+                "address of the IOx querier, overriding IOX_QUERIER_ADDR and IOX_HOST",
+                None,
+            )
+            .category(Category::Filters)
+    }
+
+    fn usage(&self) -> &str {
+        "Show how far the ingesters have processed a write to an Iox Database."
+    }
+
+    fn run(
+        &self,
+        engine_state: &EngineState,
+        stack: &mut Stack,
+        call: &Call,
+        _input: PipelineData,
+    ) -> Result<PipelineData, ShellError> {
+        let token: Spanned<String> = call.req(engine_state, stack, 0)?;
+        let addr = get_iox_addr(engine_state, stack, call, IoxService::Querier)?;
+
+        let write_info = tokio_block_writeinfo(&addr, &token.item, call.head)?;
+
+        Ok(write_info_to_value(write_info, call.head).into_pipeline_data())
+    }
+
+    fn examples(&self) -> Vec<Example> {
+        vec![
+            Example {
+                description: "Show the status of a write in every kafka partition it went to",
+                example: r#"ioxwriteinfo MXxodHRwOi8vMTI3LjAuMC4xOjgwODF8..."#,
+                result: None,
+            },
+            Example {
+                description: "Check whether a write is readable everywhere",
+                example: r#"ioxwriteinfo $token | all? status == readable"#,
+                result: None,
+            },
+        ]
+    }
+}
+
+pub fn tokio_block_writeinfo(
+    addr: &str,
+    token: &str,
+    span: Span,
+) -> Result<GetWriteInfoResponse, ShellError> {
+    with_connection(addr, span, |connection| async move {
+        Client::new(connection)
+            .get_write_info(token)
+            .await
+            .map_err(|e| client_error(addr, "Unable to get write info", e, span))
+    })
+}
+
+fn write_info_to_value(write_info: GetWriteInfoResponse, span: Span) -> Value {
+    let rows = write_info
+        .kafka_partition_infos
+        .into_iter()
+        .map(|info| Value::Record {
+            cols: vec!["kafka_partition_id".into(), "status".into()],
+            vals: vec![
+                Value::int(info.kafka_partition_id.into(), span),
+                Value::string(status_name(info.status), span),
+            ],
+            span,
+        })
+        .collect();
+
+    Value::List { vals: rows, span }
+}
+
+fn status_name(status: i32) -> &'static str {
+    match KafkaPartitionStatus::from_i32(status) {
+        Some(KafkaPartitionStatus::Durable) => "durable",
+        Some(KafkaPartitionStatus::Readable) => "readable",
+        Some(KafkaPartitionStatus::Persisted) => "persisted",
+        Some(KafkaPartitionStatus::Unknown) => "unknown",
+        Some(KafkaPartitionStatus::Unspecified) | None => "unspecified",
+    }
+}
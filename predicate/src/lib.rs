@@ -19,7 +19,7 @@ use arrow::{
     },
     datatypes::SchemaRef,
 };
-use data_types::{InfluxDbType, TableSummary, TimestampRange};
+use data_types::{InfluxDbType, TableSummary, TimestampRange, MAX_NANO_TIME};
 use datafusion::{
     error::DataFusionError,
     logical_expr::{binary_expr, utils::expr_to_columns},
@@ -445,6 +445,17 @@ impl Predicate {
         self
     }
 
+    /// Restricts the timestamp range to timestamps `>= min_time`, intersecting
+    /// it with any existing range
+    pub fn with_min_time(mut self, min_time: i64) -> Self {
+        let range = match self.range {
+            Some(range) => TimestampRange::new(range.start().max(min_time), range.end()),
+            None => TimestampRange::new(min_time, MAX_NANO_TIME + 1),
+        };
+        self.range = Some(range);
+        self
+    }
+
     /// Adds an expression to the list of general purpose predicates
     pub fn with_expr(mut self, expr: Expr) -> Self {
         self.exprs.push(expr);
@@ -604,7 +615,7 @@ impl From<ValueExpr> for Expr {
 mod tests {
     use super::*;
     use arrow::datatypes::DataType as ArrowDataType;
-    use data_types::{ColumnSummary, InfluxDbType, StatValues, MAX_NANO_TIME, MIN_NANO_TIME};
+    use data_types::{ColumnSummary, InfluxDbType, StatValues, MIN_NANO_TIME};
     use datafusion::logical_plan::{col, lit};
     use schema::builder::SchemaBuilder;
     use test_helpers::maybe_start_logging;
@@ -790,6 +801,24 @@ mod tests {
         assert_eq!(p.with_clear_timestamp_if_max_range(), expected);
     }
 
+    #[test]
+    fn test_with_min_time() {
+        let p = Predicate::new().with_min_time(10);
+        assert_eq!(p.range, Some(TimestampRange::new(10, MAX_NANO_TIME + 1)));
+
+        // narrows an existing range
+        let p = Predicate::new().with_range(0, 100).with_min_time(10);
+        assert_eq!(p.range, Some(TimestampRange::new(10, 100)));
+
+        // never widens an existing range
+        let p = Predicate::new().with_range(50, 100).with_min_time(10);
+        assert_eq!(p.range, Some(TimestampRange::new(50, 100)));
+
+        // a range entirely before the minimum time becomes empty
+        let p = Predicate::new().with_range(0, 5).with_min_time(10);
+        assert_eq!(p.range, Some(TimestampRange::new(5, 5)));
+    }
+
     #[test]
     fn test_apply_to_table_summary() {
         maybe_start_logging();
@@ -16,7 +16,7 @@ use iox_catalog::interface::{get_schema_by_name, Catalog};
 use iox_time::TimeProvider;
 use std::{
     collections::{HashMap, HashSet},
-    mem::size_of_val,
+    mem::{size_of, size_of_val},
     sync::Arc,
     time::Duration,
 };
@@ -72,22 +72,36 @@ impl NamespaceCache {
                 let backoff_config = backoff_config.clone();
 
                 async move {
-                    let schema = Backoff::new(&backoff_config)
+                    let (schema, dropped_column_ids) = Backoff::new(&backoff_config)
                         .retry_all_errors("get namespace schema", || async {
                             let mut repos = catalog.repositories().await;
-                            match get_schema_by_name(&namespace_name, repos.as_mut()).await {
-                                Ok(schema) => Ok(Some(schema)),
+                            let schema = match get_schema_by_name(&namespace_name, repos.as_mut())
+                                .await
+                            {
+                                Ok(schema) => schema,
                                 Err(iox_catalog::interface::Error::NamespaceNotFoundByName {
                                     ..
-                                }) => Ok(None),
-                                Err(e) => Err(e),
-                            }
+                                }) => return Ok(None),
+                                Err(e) => return Err(e),
+                            };
+
+                            let dropped_column_ids = repos
+                                .columns()
+                                .list_by_namespace_id(schema.id)
+                                .await?
+                                .into_iter()
+                                .filter(|c| c.deleted_at.is_some())
+                                .map(|c| c.id)
+                                .collect::<HashSet<_>>();
+
+                            Ok(Some((schema, dropped_column_ids)))
                         })
                         .await
                         .expect("retry forever")?;
 
                     Some(Arc::new(CachedNamespace {
                         schema: Arc::new(schema),
+                        dropped_column_ids,
                     }))
                 }
             },
@@ -143,7 +157,8 @@ impl NamespaceCache {
     /// Get namespace schema by name.
     ///
     /// Expire namespace if the cached schema does NOT cover the given set of columns. The set is given as a list of
-    /// pairs of table name and column set.
+    /// pairs of table name and column set. Columns that were dropped are considered covered, because they are never
+    /// part of the schema.
     pub async fn schema(
         &self,
         name: Arc<str>,
@@ -155,7 +170,9 @@ impl NamespaceCache {
                 should_cover.iter().any(|(table_name, columns)| {
                     if let Some(table) = namespace.schema.tables.get(*table_name) {
                         let covered: HashSet<_> = table.columns.values().map(|c| c.id).collect();
-                        columns.iter().any(|col| !covered.contains(col))
+                        columns.iter().any(|col| {
+                            !covered.contains(col) && !namespace.dropped_column_ids.contains(col)
+                        })
                     } else {
                         // table unknown => need to update
                         true
@@ -177,12 +194,16 @@ impl NamespaceCache {
 #[derive(Debug, Clone)]
 struct CachedNamespace {
     schema: Arc<NamespaceSchema>,
+
+    /// IDs of the dropped columns of the namespace, which are excluded from `schema`.
+    dropped_column_ids: HashSet<ColumnId>,
 }
 
 impl CachedNamespace {
     /// RAM-bytes EXCLUDING `self`.
     fn size(&self) -> usize {
         self.schema.size() - size_of_val(&self.schema)
+            + self.dropped_column_ids.capacity() * size_of::<ColumnId>()
     }
 }
 
@@ -232,6 +253,7 @@ mod tests {
             id: ns1.namespace.id,
             kafka_topic_id: ns1.namespace.kafka_topic_id,
             query_pool_id: ns1.namespace.query_pool_id,
+            retention_period_ns: ns1.namespace.retention_period_ns(),
             tables: BTreeMap::from([
                 (
                     String::from("table1"),
@@ -260,6 +282,7 @@ mod tests {
                                 },
                             ),
                         ]),
+                        partition_template: None,
                     },
                 ),
                 (
@@ -282,9 +305,11 @@ mod tests {
                                 },
                             ),
                         ]),
+                        partition_template: None,
                     },
                 ),
             ]),
+            partition_template: None,
         };
         assert_eq!(schema1_a.as_ref(), &expected_schema_1);
         assert_histogram_metric_count(&catalog.metric_registry, "namespace_get_by_name", 1);
@@ -297,6 +322,7 @@ mod tests {
             id: ns2.namespace.id,
             kafka_topic_id: ns2.namespace.kafka_topic_id,
             query_pool_id: ns2.namespace.query_pool_id,
+            retention_period_ns: ns2.namespace.retention_period_ns(),
             tables: BTreeMap::from([(
                 String::from("table1"),
                 TableSchema {
@@ -308,8 +334,10 @@ mod tests {
                             column_type: ColumnType::Time,
                         },
                     )]),
+                    partition_template: None,
                 },
             )]),
+            partition_template: None,
         };
         assert_eq!(schema2.as_ref(), &expected_schema_2);
         assert_histogram_metric_count(&catalog.metric_registry, "namespace_get_by_name", 2);
@@ -453,5 +481,52 @@ mod tests {
             .await
             .is_some());
         assert_histogram_metric_count(&catalog.metric_registry, "namespace_get_by_name", 6);
+
+        // ========== dropped column ==========
+        catalog
+            .catalog()
+            .repositories()
+            .await
+            .columns()
+            .soft_delete(c1.column.id)
+            .await
+            .unwrap();
+
+        // the cached schema still contains the dropped column
+        let schema = cache
+            .schema(
+                Arc::from("ns1"),
+                &[("t1", &HashSet::from([c1.column.id]))],
+                None,
+            )
+            .await
+            .unwrap();
+        assert!(schema.tables["t1"].columns.contains_key("c1"));
+        assert_histogram_metric_count(&catalog.metric_registry, "namespace_get_by_name", 6);
+
+        // cache timeout
+        catalog.mock_time_provider().inc(TTL_EXISTING);
+
+        let schema = cache
+            .schema(
+                Arc::from("ns1"),
+                &[("t1", &HashSet::from([c1.column.id, c2.column.id]))],
+                None,
+            )
+            .await
+            .unwrap();
+        assert!(!schema.tables["t1"].columns.contains_key("c1"));
+        assert_histogram_metric_count(&catalog.metric_registry, "namespace_get_by_name", 7);
+
+        // files containing the dropped column don't expire the cached schema
+        assert!(cache
+            .schema(
+                Arc::from("ns1"),
+                &[("t1", &HashSet::from([c1.column.id, c2.column.id]))],
+                None
+            )
+            .await
+            .is_some());
+        assert_histogram_metric_count(&catalog.metric_registry, "namespace_get_by_name", 7);
     }
 }
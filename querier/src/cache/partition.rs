@@ -10,7 +10,7 @@ use cache_system::{
     cache::{driver::CacheDriver, metrics::CacheWithMetrics, Cache},
     loader::{metrics::MetricsLoader, FunctionLoader},
 };
-use data_types::{PartitionId, SequencerId};
+use data_types::{ParquetFileId, PartitionId, PartitionKey, PartitionTemplate, SequencerId};
 use iox_catalog::interface::Catalog;
 use iox_time::TimeProvider;
 use schema::sort::SortKey;
@@ -57,22 +57,50 @@ impl PartitionCache {
                 let backoff_config = backoff_config.clone();
 
                 async move {
-                    let partition = Backoff::new(&backoff_config)
-                        .retry_all_errors("get partition_key", || async {
-                            catalog
-                                .repositories()
-                                .await
-                                .partitions()
-                                .get_by_id(partition_id)
-                                .await
-                        })
-                        .await
-                        .expect("retry forever")
-                        .expect("partition gone from catalog?!");
+                    let get_partition = || async {
+                        Backoff::new(&backoff_config)
+                            .retry_all_errors("get partition_key", || async {
+                                catalog
+                                    .repositories()
+                                    .await
+                                    .partitions()
+                                    .get_by_id(partition_id)
+                                    .await
+                            })
+                            .await
+                            .expect("retry forever")
+                            .expect("partition gone from catalog?!")
+                    };
+                    let mut partition = get_partition().await;
+
+                    // The ingester may clear the template at any time, and the parquet files
+                    // persisted afterwards may contain data the template does not apply to. So
+                    // only trust the template for the files that existed before it was read.
+                    let mut max_parquet_file_id = None;
+                    if partition.partition_template.is_some() {
+                        max_parquet_file_id = Backoff::new(&backoff_config)
+                            .retry_all_errors("list parquet files of partition", || async {
+                                catalog
+                                    .repositories()
+                                    .await
+                                    .parquet_files()
+                                    .list_by_partition_not_to_delete(partition_id)
+                                    .await
+                            })
+                            .await
+                            .expect("retry forever")
+                            .into_iter()
+                            .map(|f| f.id)
+                            .max();
+                        partition = get_partition().await;
+                    }
 
                     CachedPartition {
                         sequencer_id: partition.sequencer_id,
+                        partition_key: partition.partition_key.clone(),
                         sort_key: Arc::new(partition.sort_key()),
+                        partition_template: partition.partition_template.map(Arc::new),
+                        max_parquet_file_id,
                     }
                 }
             },
@@ -112,6 +140,44 @@ impl PartitionCache {
         self.cache.get(partition_id, ((), span)).await.sequencer_id
     }
 
+    /// Get partition key.
+    pub async fn partition_key(
+        &self,
+        partition_id: PartitionId,
+        span: Option<Span>,
+    ) -> PartitionKey {
+        self.cache.get(partition_id, ((), span)).await.partition_key
+    }
+
+    /// Get the partition template the partition key was derived with, if it is known to apply to
+    /// the data in the given parquet file.
+    ///
+    /// Expire partition if the parquet file was created after the cached template was read,
+    /// because the template may have been cleared since.
+    pub async fn partition_template(
+        &self,
+        partition_id: PartitionId,
+        parquet_file_id: ParquetFileId,
+        span: Option<Span>,
+    ) -> Option<Arc<PartitionTemplate>> {
+        let covers = |cached_partition: &CachedPartition| match cached_partition.max_parquet_file_id
+        {
+            Some(max_parquet_file_id) => parquet_file_id <= max_parquet_file_id,
+            None => false,
+        };
+
+        self.backend.remove_if(&partition_id, |cached_partition| {
+            cached_partition.partition_template.is_some() && !covers(cached_partition)
+        });
+
+        let cached_partition = self.cache.get(partition_id, ((), span)).await;
+        if covers(&cached_partition) {
+            cached_partition.partition_template
+        } else {
+            None
+        }
+    }
+
     /// Get sort key
     ///
     /// Expire partition if the cached sort key does NOT cover the given set of columns.
@@ -141,12 +207,18 @@ impl PartitionCache {
 #[derive(Debug, Clone)]
 struct CachedPartition {
     sequencer_id: SequencerId,
+    partition_key: PartitionKey,
     sort_key: Arc<Option<SortKey>>,
+    partition_template: Option<Arc<PartitionTemplate>>,
+    /// The largest ID of the parquet files of the partition before `partition_template` was read
+    max_parquet_file_id: Option<ParquetFileId>,
 }
 
 impl CachedPartition {
     /// RAM-bytes EXCLUDING `self`.
     fn size(&self) -> usize {
+        // Arc content of the partition key
+        self.partition_key.inner().len() +
         // Arc heap allocation
         size_of_val(self.sort_key.as_ref()) +
         // Arc content
@@ -154,6 +226,11 @@ impl CachedPartition {
             .as_ref()
             .as_ref()
             .map(|sk| sk.size() - size_of_val(sk))
+            .unwrap_or_default() +
+        // Arc content of the partition template
+        self.partition_template
+            .as_ref()
+            .map(|t| t.size())
             .unwrap_or_default()
     }
 }
@@ -162,7 +239,8 @@ impl CachedPartition {
 mod tests {
     use super::*;
     use crate::cache::{ram::test_util::test_ram_pool, test_util::assert_histogram_metric_count};
-    use iox_tests::util::TestCatalog;
+    use data_types::{ColumnType, TemplatePart};
+    use iox_tests::util::{TestCatalog, TestParquetFileBuilder};
 
     #[tokio::test]
     async fn test_sequencer_id() {
@@ -207,6 +285,129 @@ mod tests {
         assert_histogram_metric_count(&catalog.metric_registry, "partition_get_by_id", 2);
     }
 
+    #[tokio::test]
+    async fn test_partition_key() {
+        let catalog = TestCatalog::new();
+
+        let ns = catalog.create_namespace("ns").await;
+        let t = ns.create_table("table").await;
+        let s1 = ns.create_sequencer(1).await;
+        let p1 = t
+            .with_sequencer(&s1)
+            .create_partition("k1")
+            .await
+            .partition
+            .clone();
+        let p2 = t
+            .with_sequencer(&s1)
+            .create_partition("k2")
+            .await
+            .partition
+            .clone();
+
+        let cache = PartitionCache::new(
+            catalog.catalog(),
+            BackoffConfig::default(),
+            catalog.time_provider(),
+            &catalog.metric_registry(),
+            test_ram_pool(),
+            true,
+        );
+
+        let key1 = cache.partition_key(p1.id, None).await;
+        assert_eq!(key1, p1.partition_key);
+        assert_histogram_metric_count(&catalog.metric_registry, "partition_get_by_id", 1);
+
+        let key2 = cache.partition_key(p2.id, None).await;
+        assert_eq!(key2, p2.partition_key);
+        assert_histogram_metric_count(&catalog.metric_registry, "partition_get_by_id", 2);
+
+        let key1 = cache.partition_key(p1.id, None).await;
+        assert_eq!(key1, p1.partition_key);
+        assert_histogram_metric_count(&catalog.metric_registry, "partition_get_by_id", 2);
+    }
+
+    #[tokio::test]
+    async fn test_partition_template() {
+        let catalog = TestCatalog::new();
+
+        let ns = catalog.create_namespace("ns").await;
+        let t = ns.create_table("table").await;
+        t.create_column("tag", ColumnType::Tag).await;
+        t.create_column("f", ColumnType::F64).await;
+        t.create_column("time", ColumnType::Time).await;
+        let s1 = ns.create_sequencer(1).await;
+        let template = PartitionTemplate {
+            parts: vec![TemplatePart::Column("tag".to_owned())],
+        };
+        let p1 = t
+            .with_sequencer(&s1)
+            .create_partition_with_template("tag_a", &template)
+            .await;
+        let f1 = p1
+            .create_parquet_file(
+                TestParquetFileBuilder::default().with_line_protocol("table,tag=a f=1 1"),
+            )
+            .await
+            .parquet_file
+            .clone();
+        let p2 = t.with_sequencer(&s1).create_partition("tag_b").await;
+        let f2 = p2
+            .create_parquet_file(
+                TestParquetFileBuilder::default().with_line_protocol("table,tag=b f=1 1"),
+            )
+            .await
+            .parquet_file
+            .clone();
+
+        let cache = PartitionCache::new(
+            catalog.catalog(),
+            BackoffConfig::default(),
+            catalog.time_provider(),
+            &catalog.metric_registry(),
+            test_ram_pool(),
+            true,
+        );
+
+        let template1 = cache.partition_template(p1.partition.id, f1.id, None).await;
+        assert_eq!(template1.as_deref(), Some(&template));
+        assert_histogram_metric_count(&catalog.metric_registry, "partition_get_by_id", 2);
+
+        let template2 = cache.partition_template(p2.partition.id, f2.id, None).await;
+        assert_eq!(template2, None);
+        assert_histogram_metric_count(&catalog.metric_registry, "partition_get_by_id", 3);
+
+        let template1 = cache.partition_template(p1.partition.id, f1.id, None).await;
+        assert_eq!(template1.as_deref(), Some(&template));
+        assert_histogram_metric_count(&catalog.metric_registry, "partition_get_by_id", 3);
+
+        // a file persisted after the template was cleared expires the cached template
+        catalog
+            .catalog()
+            .repositories()
+            .await
+            .partitions()
+            .clear_partition_template(p1.partition.id)
+            .await
+            .unwrap();
+        let f3 = p1
+            .create_parquet_file(
+                TestParquetFileBuilder::default()
+                    .with_line_protocol("table,tag=b f=1 2")
+                    .with_max_seq(2),
+            )
+            .await
+            .parquet_file
+            .clone();
+        let template1 = cache.partition_template(p1.partition.id, f3.id, None).await;
+        assert_eq!(template1, None);
+        assert_histogram_metric_count(&catalog.metric_registry, "partition_get_by_id", 4);
+
+        let template1 = cache.partition_template(p1.partition.id, f1.id, None).await;
+        assert_eq!(template1, None);
+        assert_histogram_metric_count(&catalog.metric_registry, "partition_get_by_id", 4);
+    }
+
     #[tokio::test]
     async fn test_sort_key() {
         let catalog = TestCatalog::new();
@@ -17,7 +17,7 @@ use std::{
 use trace::span::{Span, SpanRecorder};
 use uuid::Uuid;
 
-use self::util::create_basic_summary;
+use self::util::{apply_partition_key_values, create_basic_summary};
 
 mod query_access;
 pub(crate) mod util;
@@ -51,6 +51,9 @@ pub struct ChunkMeta {
 
     /// Compaction level of the parquet file of the chunk
     compaction_level: CompactionLevel,
+
+    /// Values of the columns encoded in the partition key of the chunk, `None` if NULL.
+    partition_key_values: Vec<(String, Option<String>)>,
 }
 
 impl ChunkMeta {
@@ -162,16 +165,20 @@ impl ChunkStage {
     }
 }
 
-impl From<Arc<ParquetChunk>> for ChunkStage {
-    fn from(parquet_chunk: Arc<ParquetChunk>) -> Self {
-        let table_summary = Arc::new(create_basic_summary(
-            parquet_chunk.rows() as u64,
+impl ChunkStage {
+    /// Create a parquet stage, with statistics for the columns in the partition key of `meta`.
+    fn new_parquet(parquet_chunk: Arc<ParquetChunk>, meta: &ChunkMeta) -> Self {
+        let row_count = parquet_chunk.rows() as u64;
+        let mut table_summary = create_basic_summary(
+            row_count,
             &parquet_chunk.schema(),
             parquet_chunk.timestamp_min_max(),
-        ));
+        );
+        apply_partition_key_values(&mut table_summary, row_count, &meta.partition_key_values);
+
         Self::Parquet {
             parquet_chunk,
-            table_summary,
+            table_summary: Arc::new(table_summary),
         }
     }
 }
@@ -291,7 +298,7 @@ impl QuerierChunk {
         let stage: ChunkStage = if let Some(rb_chunk) = rb_chunk {
             rb_chunk.into()
         } else {
-            parquet_chunk.into()
+            ChunkStage::new_parquet(parquet_chunk, &meta)
         };
 
         Self {
@@ -495,6 +502,38 @@ impl ChunkAdapter {
             .as_ref()
             .expect("partition sort key should be set when a parquet file exists");
 
+        // Gather the column values encoded in the partition key, so chunks can be pruned by them.
+        // The key is only interpreted with the template recorded for the partition, never with
+        // the current template of the table, which may have changed since the key was derived.
+        let partition_template = self
+            .catalog_cache
+            .partition()
+            .partition_template(
+                parquet_file.partition_id,
+                parquet_file.id,
+                span_recorder.child_span("cache GET partition template"),
+            )
+            .await;
+        let partition_key_values = match partition_template {
+            Some(template) => {
+                let partition_key = self
+                    .catalog_cache
+                    .partition()
+                    .partition_key(
+                        parquet_file.partition_id,
+                        span_recorder.child_span("cache GET partition key"),
+                    )
+                    .await;
+                template
+                    .column_values(&table_name, partition_key.inner())
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|(column, value)| (column.to_owned(), value.map(ToOwned::to_owned)))
+                    .collect()
+            }
+            None => vec![],
+        };
+
         // NOTE: Because we've looked up the sort key AFTER the namespace schema, it may contain columns for which we
         //       don't have any schema information yet. This is OK because we've ensured that all file columns are known
         //       withing the schema and if a column is NOT part of the file, it will also not be part of the chunk sort
@@ -543,6 +582,7 @@ impl ChunkAdapter {
             partition_id: parquet_file.partition_id,
             max_sequence_number: parquet_file.max_sequence_number,
             compaction_level: parquet_file.compaction_level,
+            partition_key_values,
         });
 
         Some(ChunkParts {
@@ -564,7 +604,7 @@ pub mod tests {
     use super::*;
     use arrow::{datatypes::DataType, record_batch::RecordBatch};
     use arrow_util::assert_batches_eq;
-    use data_types::ColumnType;
+    use data_types::{ColumnType, PartitionTemplate, StatValues, Statistics, TemplatePart};
     use futures::StreamExt;
     use iox_query::{exec::IOxSessionContext, QueryChunk, QueryChunkMeta};
     use iox_tests::util::{TestCatalog, TestNamespace, TestParquetFileBuilder};
@@ -689,6 +729,144 @@ pub mod tests {
         assert_eq!(catalog_metrics1, catalog_metrics2);
     }
 
+    #[tokio::test]
+    async fn test_partition_key_values_in_summary() {
+        maybe_start_logging();
+        let catalog = TestCatalog::new();
+
+        let ns = catalog.create_namespace("ns").await;
+        // the current template of the namespace is never used to interpret partition keys
+        catalog
+            .catalog()
+            .repositories()
+            .await
+            .namespaces()
+            .update_partition_template(
+                "ns",
+                Some(&PartitionTemplate {
+                    parts: vec![TemplatePart::TimeFormat("%Y".to_owned())],
+                }),
+            )
+            .await
+            .unwrap();
+        let sequencer = ns.create_sequencer(1).await;
+        let table = ns.create_table("table").await;
+        table.create_column("tag1", ColumnType::Tag).await;
+        table.create_column("field_int", ColumnType::I64).await;
+        table.create_column("time", ColumnType::Time).await;
+        let template = PartitionTemplate {
+            parts: vec![TemplatePart::Column("tag1".to_owned())],
+        };
+        let lp = "table,tag1=WA field_int=1000i 8000\ntable,tag1=WA field_int=10i 10000";
+        let partition = table
+            .with_sequencer(&sequencer)
+            .create_partition_with_template("tag1_WA", &template)
+            .await
+            .update_sort_key(SortKey::from_columns(["tag1", "time"]))
+            .await;
+        let parquet_file = Arc::new(
+            partition
+                .create_parquet_file(TestParquetFileBuilder::default().with_line_protocol(lp))
+                .await
+                .parquet_file,
+        );
+        let untemplated_parquet_file = Arc::new(
+            table
+                .with_sequencer(&sequencer)
+                .create_partition("tag1_OR")
+                .await
+                .update_sort_key(SortKey::from_columns(["tag1", "time"]))
+                .await
+                .create_parquet_file(
+                    TestParquetFileBuilder::default()
+                        .with_line_protocol("table,tag1=OR field_int=1i 20000"),
+                )
+                .await
+                .parquet_file,
+        );
+
+        let adapter = ChunkAdapter::new(
+            Arc::new(CatalogCache::new_testing(
+                catalog.catalog(),
+                catalog.time_provider(),
+                catalog.metric_registry(),
+            )),
+            ParquetStorage::new(catalog.object_store()),
+            catalog.metric_registry(),
+            HashMap::new(),
+        );
+        let tag1_stats = |chunk: &QuerierChunk| {
+            chunk
+                .summary()
+                .unwrap()
+                .column("tag1")
+                .unwrap()
+                .stats
+                .clone()
+        };
+        let unknown = |total_count| {
+            Statistics::String(StatValues {
+                min: None,
+                max: None,
+                total_count,
+                null_count: None,
+                distinct_count: None,
+            })
+        };
+
+        let chunk = adapter
+            .new_chunk(ns.namespace.name.clone().into(), parquet_file, None)
+            .await
+            .unwrap();
+        assert_eq!(
+            tag1_stats(&chunk),
+            Statistics::String(StatValues {
+                min: Some("WA".to_owned()),
+                max: Some("WA".to_owned()),
+                total_count: 2,
+                null_count: Some(0),
+                distinct_count: None,
+            })
+        );
+
+        // a partition without a recorded template does not get statistics from its key
+        let chunk = adapter
+            .new_chunk(
+                ns.namespace.name.clone().into(),
+                untemplated_parquet_file,
+                None,
+            )
+            .await
+            .unwrap();
+        assert_eq!(tag1_stats(&chunk), unknown(1));
+
+        // data partitioned with another template cleared the template, and files persisted
+        // afterwards do not get statistics from the key either
+        catalog
+            .catalog()
+            .repositories()
+            .await
+            .partitions()
+            .clear_partition_template(partition.partition.id)
+            .await
+            .unwrap();
+        let parquet_file = Arc::new(
+            partition
+                .create_parquet_file(
+                    TestParquetFileBuilder::default()
+                        .with_line_protocol("table,tag1=OR field_int=1i 20000")
+                        .with_max_seq(2),
+                )
+                .await
+                .parquet_file,
+        );
+        let chunk = adapter
+            .new_chunk(ns.namespace.name.clone().into(), parquet_file, None)
+            .await
+            .unwrap();
+        assert_eq!(tag1_stats(&chunk), unknown(1));
+    }
+
     /// collect data for the given chunk
     async fn collect_read_filter(chunk: &dyn QueryChunk) -> Vec<RecordBatch> {
         chunk
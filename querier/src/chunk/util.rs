@@ -82,6 +82,32 @@ pub fn create_basic_summary(
     TableSummary { columns }
 }
 
+/// Add the column values encoded in the partition key of a chunk to its table summary.
+///
+/// All rows of a partition share the value of the tag and string columns in its partition
+/// template, so [min](StatValues::min)/[max](StatValues::max) of these columns are set to that
+/// value, or all rows are counted as [nulls](StatValues::null_count) if the value is `None`.
+/// Values of other column types are ignored.
+pub fn apply_partition_key_values(
+    summary: &mut TableSummary,
+    row_count: u64,
+    values: &[(String, Option<String>)],
+) {
+    for (column, value) in values {
+        let stats = match summary.columns.iter_mut().find(|c| &c.name == column) {
+            Some(ColumnSummary {
+                stats: Statistics::String(stats),
+                ..
+            }) => stats,
+            _ => continue,
+        };
+
+        stats.min = value.clone();
+        stats.max = value.clone();
+        stats.null_count = Some(if value.is_some() { 0 } else { row_count });
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use schema::builder::SchemaBuilder;
@@ -281,6 +307,56 @@ mod tests {
         assert_eq!(actual, expected);
     }
 
+    #[test]
+    fn test_apply_partition_key_values() {
+        let schema = SchemaBuilder::new()
+            .tag("tag")
+            .tag("null_tag")
+            .influx_field("field_string", InfluxFieldType::String)
+            .influx_field("field_integer", InfluxFieldType::Integer)
+            .timestamp()
+            .build()
+            .unwrap();
+        let ts_min_max = TimestampMinMax { min: 42, max: 42 };
+        let mut actual = create_basic_summary(3, &schema, ts_min_max);
+
+        apply_partition_key_values(
+            &mut actual,
+            3,
+            &[
+                (String::from("tag"), Some(String::from("west"))),
+                (String::from("null_tag"), None),
+                (String::from("field_string"), Some(String::from("foo"))),
+                (String::from("field_integer"), Some(String::from("1"))),
+                (String::from("unknown"), Some(String::from("bar"))),
+            ],
+        );
+
+        let mut expected = create_basic_summary(3, &schema, ts_min_max);
+        expected.columns[0].stats = Statistics::String(StatValues {
+            min: Some(String::from("west")),
+            max: Some(String::from("west")),
+            total_count: 3,
+            null_count: Some(0),
+            distinct_count: None,
+        });
+        expected.columns[1].stats = Statistics::String(StatValues {
+            min: None,
+            max: None,
+            total_count: 3,
+            null_count: Some(3),
+            distinct_count: None,
+        });
+        expected.columns[2].stats = Statistics::String(StatValues {
+            min: Some(String::from("foo")),
+            max: Some(String::from("foo")),
+            total_count: 3,
+            null_count: Some(0),
+            distinct_count: None,
+        });
+        assert_eq!(actual, expected);
+    }
+
     fn full_schema() -> Schema {
         SchemaBuilder::new()
             .tag("tag")
@@ -88,6 +88,17 @@ impl QueryDatabaseProvider for QuerierDatabase {
         self.namespace(name, span).await
     }
 
+    async fn db_names(&self) -> Vec<String> {
+        let mut names: Vec<_> = self
+            .namespaces()
+            .await
+            .into_iter()
+            .map(|namespace| namespace.name)
+            .collect();
+        names.sort_unstable();
+        names
+    }
+
     async fn acquire_semaphore(&self, span: Option<Span>) -> InstrumentedAsyncOwnedSemaphorePermit {
         Arc::clone(&self.query_execution_semaphore)
             .acquire_owned(span)
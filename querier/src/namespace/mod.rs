@@ -84,6 +84,7 @@ impl QuerierNamespace {
                     exec: Arc::clone(&exec),
                     max_query_bytes: max_table_query_bytes,
                     prune_metrics: Arc::clone(&prune_metrics),
+                    retention_period_ns: schema.retention_period_ns,
                 }));
 
                 (table_name, table)
@@ -65,6 +65,7 @@ pub struct QuerierTableArgs {
     pub exec: Arc<Executor>,
     pub max_query_bytes: usize,
     pub prune_metrics: Arc<PruneMetrics>,
+    pub retention_period_ns: Option<i64>,
 }
 
 /// Table representation for the querier.
@@ -102,6 +103,9 @@ pub struct QuerierTable {
 
     /// Metrics for chunk pruning.
     prune_metrics: Arc<PruneMetrics>,
+
+    /// Retention period of the namespace in nanoseconds, `None` keeps data forever.
+    retention_period_ns: Option<i64>,
 }
 
 impl QuerierTable {
@@ -118,6 +122,7 @@ impl QuerierTable {
             exec,
             max_query_bytes,
             prune_metrics,
+            retention_period_ns,
         } = args;
 
         let reconciler = Reconciler::new(
@@ -138,6 +143,7 @@ impl QuerierTable {
             exec,
             max_query_bytes,
             prune_metrics,
+            retention_period_ns,
         }
     }
 
@@ -192,6 +198,26 @@ impl QuerierTable {
 
         let catalog_cache = self.chunk_adapter.catalog_cache();
 
+        // Data older than the retention period of the namespace must never be returned. Narrow
+        // the predicate so that expired partitions and parquet files are pruned and the
+        // ingesters filter out expired rows, and hide expired rows of partially expired parquet
+        // files behind a delete predicate.
+        let retention_cutoff = self.retention_period_ns.map(|retention_period_ns| {
+            catalog_cache
+                .time_provider()
+                .now()
+                .timestamp_nanos()
+                .saturating_sub(retention_period_ns)
+        });
+        let retention_predicate;
+        let predicate = match retention_cutoff {
+            Some(cutoff) => {
+                retention_predicate = predicate.clone().with_min_time(cutoff);
+                &retention_predicate
+            }
+            None => predicate,
+        };
+
         // ask ingesters for data, also optimistically fetching catalog
         // contents at the same time to pre-warm cache
         let (partitions, _parquet_files, _tombstones) = join!(
@@ -273,6 +299,7 @@ impl QuerierTable {
                 partitions,
                 tombstones.to_vec(),
                 parquet_files,
+                retention_cutoff,
                 span_recorder.child_span("reconcile"),
             )
             .await
@@ -447,8 +474,12 @@ mod tests {
         QuerierChunkLoadSetting,
     };
     use assert_matches::assert_matches;
-    use data_types::{ChunkId, ColumnType, CompactionLevel, ParquetFileId, SequenceNumber};
+    use data_types::{
+        ChunkId, ColumnType, CompactionLevel, ParquetFileId, SequenceNumber, TimestampRange,
+        MIN_NANO_TIME,
+    };
     use iox_tests::util::{TestCatalog, TestParquetFileBuilder, TestTable};
+    use iox_time::Time;
     use predicate::Predicate;
     use schema::{builder::SchemaBuilder, InfluxFieldType};
     use std::sync::Arc;
@@ -609,6 +640,73 @@ mod tests {
         assert_eq!(chunks[4].delete_predicates().len(), 0);
     }
 
+    #[tokio::test]
+    async fn test_retention() {
+        maybe_start_logging();
+        let catalog = TestCatalog::new();
+
+        // the namespace retention period is 1 year
+        let ns = catalog.create_namespace("ns").await;
+        let retention_period_ns = ns.namespace.retention_period_ns().unwrap();
+        let table = ns.create_table("table").await;
+        let sequencer = ns.create_sequencer(1).await;
+        let partition = table.with_sequencer(&sequencer).create_partition("k").await;
+        table.create_column("time", ColumnType::Time).await;
+        table.create_column("foo", ColumnType::F64).await;
+
+        // data before timestamp 100 is outside of the retention period
+        catalog
+            .mock_time_provider()
+            .set(Time::from_timestamp_nanos(retention_period_ns + 100));
+
+        let querier_table = TestQuerierTable::new(&catalog, &table).await;
+
+        // pruned because all of its data is expired
+        let builder = TestParquetFileBuilder::default()
+            .with_line_protocol("table foo=1 11\ntable foo=2 22")
+            .with_max_seq(2)
+            .with_min_time(11)
+            .with_max_time(22);
+        partition.create_parquet_file(builder).await;
+
+        // kept, with a delete predicate hiding the expired rows
+        let builder = TestParquetFileBuilder::default()
+            .with_line_protocol("table foo=3 33\ntable foo=4 133")
+            .with_max_seq(3)
+            .with_min_time(33)
+            .with_max_time(133);
+        let file2 = partition.create_parquet_file(builder).await;
+
+        // kept as is because none of its data is expired
+        let builder = TestParquetFileBuilder::default()
+            .with_line_protocol("table foo=5 144")
+            .with_max_seq(4)
+            .with_min_time(144)
+            .with_max_time(144);
+        let file3 = partition.create_parquet_file(builder).await;
+
+        let mut chunks = querier_table.chunks().await.unwrap();
+        chunks.sort_by_key(|c| c.id());
+        assert_eq!(chunks.len(), 2);
+
+        assert_eq!(
+            chunks[0].id(),
+            ChunkId::new_test(file2.parquet_file.id.get() as u128),
+        );
+        let delete_predicates = chunks[0].delete_predicates();
+        assert_eq!(delete_predicates.len(), 1);
+        assert_eq!(
+            delete_predicates[0].range,
+            TimestampRange::new(MIN_NANO_TIME, 99)
+        );
+
+        assert_eq!(
+            chunks[1].id(),
+            ChunkId::new_test(file3.parquet_file.id.get() as u128),
+        );
+        assert_eq!(chunks[1].delete_predicates().len(), 0);
+    }
+
     #[tokio::test]
     async fn test_compactor_collision() {
         maybe_start_logging();
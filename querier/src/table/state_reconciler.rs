@@ -2,8 +2,11 @@
 
 mod interface;
 
-use data_types::{CompactionLevel, PartitionId, SequencerId, Tombstone, TombstoneId};
-use iox_query::QueryChunk;
+use data_types::{
+    CompactionLevel, DeletePredicate, PartitionId, SequencerId, TimestampRange, Tombstone,
+    TombstoneId, MIN_NANO_TIME,
+};
+use iox_query::{QueryChunk, QueryChunkMeta};
 use observability_deps::tracing::debug;
 use schema::sort::SortKey;
 use snafu::Snafu;
@@ -51,11 +54,15 @@ impl Reconciler {
 
     /// Reconciles ingester state (ingester_partitions) and catalog state (parquet_files and
     /// tombstones), producing a list of chunks to query
+    ///
+    /// Rows of parquet files older than `retention_cutoff` (in nanoseconds), if any, are
+    /// excluded from the chunks.
     pub(crate) async fn reconcile(
         &self,
         ingester_partitions: Vec<IngesterPartition>,
         tombstones: Vec<Arc<Tombstone>>,
         parquet_files: Vec<QuerierChunk>,
+        retention_cutoff: Option<i64>,
         span: Option<Span>,
     ) -> Result<Vec<Arc<dyn QueryChunk>>, ReconcileError> {
         let span_recorder = SpanRecorder::new(span);
@@ -64,6 +71,7 @@ impl Reconciler {
                 &ingester_partitions,
                 tombstones,
                 parquet_files,
+                retention_cutoff,
                 span_recorder.child_span("build_chunks_from_parquet"),
             )
             .await?;
@@ -87,6 +95,7 @@ impl Reconciler {
         ingester_partitions: &[IngesterPartition],
         tombstones: Vec<Arc<Tombstone>>,
         parquet_files: Vec<QuerierChunk>,
+        retention_cutoff: Option<i64>,
         span: Option<Span>,
     ) -> Result<Vec<Box<dyn UpdatableQuerierChunk>>, ReconcileError> {
         let span_recorder = SpanRecorder::new(span);
@@ -125,6 +134,16 @@ impl Reconciler {
 
         debug!(num_chunks=%parquet_files.len(), "Created chunks from parquet files");
 
+        // Delete predicate hiding the rows older than the retention cutoff. The time range of a
+        // delete predicate is inclusive at both ends.
+        let retention = retention_cutoff.map(|cutoff| {
+            let delete_predicate = Arc::new(DeletePredicate {
+                range: TimestampRange::new(MIN_NANO_TIME, cutoff - 1),
+                exprs: vec![],
+            });
+            (cutoff, delete_predicate)
+        });
+
         let mut chunks: Vec<Box<dyn UpdatableQuerierChunk>> =
             Vec::with_capacity(parquet_files.len() + ingester_partitions.len());
 
@@ -192,6 +211,21 @@ impl Reconciler {
                 chunk
             };
 
+            // only partially expired files need the retention delete predicate, fully expired
+            // files were already pruned by the retention time range of the query predicate
+            let chunk = match &retention {
+                Some((cutoff, delete_predicate))
+                    if chunk
+                        .timestamp_min_max()
+                        .map_or(true, |min_max| min_max.min < *cutoff) =>
+                {
+                    let mut delete_predicates = chunk.delete_predicates().to_vec();
+                    delete_predicates.push(Arc::clone(delete_predicate));
+                    chunk.with_delete_predicates(delete_predicates)
+                }
+                _ => chunk,
+            };
+
             chunks.push(Box::new(chunk) as Box<dyn UpdatableQuerierChunk>);
         }
 
@@ -51,6 +51,7 @@ pub async fn querier_table(
         exec: catalog.exec(),
         max_query_bytes: usize::MAX,
         prune_metrics: Arc::new(PruneMetrics::new(&catalog.metric_registry())),
+        retention_period_ns: catalog_schema.retention_period_ns,
     })
 }
 
@@ -20,6 +20,12 @@
 //!                      ║            │           ║           │
 //!                      ║            ▼           ║
 //!                      ║  ┌──────────────────┐  ║           │
+//!                      ║  │    Retention     │  ║
+//!                      ║  │    Validation    │─ ─ ─ ─ ─ ─ ─ ┤
+//!                      ║  └──────────────────┘  ║
+//!                      ║            │           ║           │
+//!                      ║            ▼           ║
+//!                      ║  ┌──────────────────┐  ║           │
 //!                      ║  │   Partitioner    │  ║
 //!                      ║  └──────────────────┘  ║           │
 //!                      ║            │           ║  ┌─────────────────┐
@@ -62,6 +68,10 @@
 //! the LP and splitting them into batches per partition, before passing each
 //! partitioned batch through the rest of the request pipeline.
 //!
+//! The [`RetentionValidator`] rejects writes containing data older than the
+//! retention period of the namespace, using the retention period stored in the
+//! [`NamespaceSchema`].
+//!
 //! Writes then pass through the [`SchemaValidator`] applying schema enforcement
 //! (a NOP layer for deletes) which pushes additive schema changes to the
 //! catalog and populates the [`NamespaceCache`], converging it to match the set
@@ -79,6 +89,9 @@ pub use r#trait::*;
 mod schema_validation;
 pub use schema_validation::*;
 
+mod retention_validation;
+pub use retention_validation::*;
+
 pub mod nop;
 
 mod sharded_write_buffer;
@@ -148,7 +148,9 @@ mod tests {
                 id: NamespaceId::new(1),
                 kafka_topic_id: KafkaTopicId::new(2),
                 query_pool_id: QueryPoolId::new(3),
+                retention_period_ns: None,
                 tables: Default::default(),
+                partition_template: None,
             },
         );
 
@@ -224,6 +226,8 @@ mod tests {
                 query_pool_id: QueryPoolId::new(42),
                 max_tables: 10000,
                 max_columns_per_table: 1000,
+                deleted_at: None,
+                partition_template: None,
             }
         );
     }
@@ -1,15 +1,24 @@
 use super::DmlHandler;
+use crate::namespace_cache::{
+    get_or_load_schema, metrics::InstrumentedCache, MemoryNamespaceCache, NamespaceCache,
+};
 use async_trait::async_trait;
 use data_types::{DatabaseName, DeletePredicate, PartitionKey, PartitionTemplate};
 use hashbrown::HashMap;
+use iox_catalog::interface::Catalog;
 use mutable_batch::{MutableBatch, PartitionWrite, WritePayload};
-use observability_deps::tracing::*;
+use std::sync::Arc;
 use thiserror::Error;
 use trace::ctx::SpanContext;
 
 /// An error raised by the [`Partitioner`] handler.
 #[derive(Debug, Error)]
 pub enum PartitionError {
+    /// The namespace schema containing the partition templates could not be
+    /// loaded from the catalog.
+    #[error("failed to read namespace schema from catalog: {0}")]
+    NamespaceLookup(iox_catalog::interface::Error),
+
     /// Failed to write to the partitioned table batch.
     #[error("error batching into partitioned write: {0}")]
     BatchWrite(#[from] mutable_batch::Error),
@@ -40,26 +49,48 @@ impl<T> Partitioned<T> {
 }
 
 /// A [`DmlHandler`] implementation that splits per-table [`MutableBatch`] into
-/// partitioned per-table [`MutableBatch`] instances according to a configured
-/// [`PartitionTemplate`]. Deletes pass through unmodified.
+/// partitioned per-table [`MutableBatch`] instances according to the
+/// [`PartitionTemplate`] of each table. Deletes pass through unmodified.
+///
+/// The template of a table is read from the [`NamespaceSchema`] in the
+/// [`NamespaceCache`], falling back to loading (and caching) the schema from
+/// the catalog on a cache miss (see [`get_or_load_schema`]). A table without a
+/// template of its own uses the template of its namespace, and if the namespace
+/// has none either, the default template this handler was initialised with.
 ///
 /// A vector of partitions are returned to the caller, or the first error that
 /// occurs during partitioning.
+///
+/// [`NamespaceSchema`]: data_types::NamespaceSchema
 #[derive(Debug)]
-pub struct Partitioner {
-    partition_template: PartitionTemplate,
+pub struct Partitioner<C = Arc<InstrumentedCache<MemoryNamespaceCache>>> {
+    default_template: PartitionTemplate,
+    catalog: Arc<dyn Catalog>,
+    cache: C,
 }
 
-impl Partitioner {
+impl<C> Partitioner<C> {
     /// Initialise a new [`Partitioner`], splitting writes according to the
-    /// specified [`PartitionTemplate`].
-    pub fn new(partition_template: PartitionTemplate) -> Self {
-        Self { partition_template }
+    /// partition templates in `ns_cache` and `catalog`, or `default_template`
+    /// for namespaces without one.
+    pub fn new(
+        default_template: PartitionTemplate,
+        catalog: Arc<dyn Catalog>,
+        ns_cache: C,
+    ) -> Self {
+        Self {
+            default_template,
+            catalog,
+            cache: ns_cache,
+        }
     }
 }
 
 #[async_trait]
-impl DmlHandler for Partitioner {
+impl<C> DmlHandler for Partitioner<C>
+where
+    C: NamespaceCache,
+{
     type WriteError = PartitionError;
     type DeleteError = PartitionError;
 
@@ -69,18 +100,26 @@ impl DmlHandler for Partitioner {
     /// Partition the per-table [`MutableBatch`].
     async fn write(
         &self,
-        _namespace: &DatabaseName<'static>,
+        namespace: &DatabaseName<'static>,
         batch: Self::WriteInput,
         _span_ctx: Option<SpanContext>,
     ) -> Result<Self::WriteOutput, Self::WriteError> {
+        let schema = get_or_load_schema(&self.cache, namespace, self.catalog.as_ref())
+            .await
+            .map_err(PartitionError::NamespaceLookup)?;
+
         // A collection of partition-keyed, per-table MutableBatch instances.
         let mut partitions: HashMap<PartitionKey, HashMap<_, MutableBatch>> = HashMap::default();
 
         for (table_name, batch) in batch {
-            // Partition the table batch according to the configured partition
-            // template and write it into the partition-keyed map.
+            let template = schema
+                .partition_template_for(&table_name)
+                .unwrap_or(&self.default_template);
+
+            // Partition the table batch according to its partition template
+            // and write it into the partition-keyed map.
             for (partition_key, partition_payload) in
-                PartitionWrite::partition(&table_name, &batch, &self.partition_template)
+                PartitionWrite::partition(&table_name, &batch, template)
             {
                 let partition = partitions.entry(partition_key).or_default();
                 let table_batch = partition
@@ -114,12 +153,183 @@ impl DmlHandler for Partitioner {
 mod tests {
     use super::*;
     use assert_matches::assert_matches;
-    use data_types::TemplatePart;
+    use data_types::{
+        KafkaTopicId, NamespaceId, NamespaceSchema, QueryPoolId, TableId, TableSchema, TemplatePart,
+    };
+    use iox_catalog::mem::MemCatalog;
+    use once_cell::sync::Lazy;
+
+    static NAMESPACE: Lazy<DatabaseName<'static>> = Lazy::new(|| "bananas".try_into().unwrap());
 
     /// The default timestamp applied to test LP if the write does not specify
     /// one.
     const DEFAULT_TIMESTAMP_NANOS: i64 = 42000000000000000;
 
+    fn day_template() -> PartitionTemplate {
+        PartitionTemplate {
+            parts: vec![TemplatePart::TimeFormat("%Y-%m-%d".to_owned())],
+        }
+    }
+
+    /// Initialise a [`Partitioner`] with a cached `schema` for [`NAMESPACE`],
+    /// partitioning by day by default.
+    fn partitioner(
+        catalog: Arc<dyn Catalog>,
+        schema: Option<NamespaceSchema>,
+    ) -> Partitioner<Arc<MemoryNamespaceCache>> {
+        let cache = Arc::new(MemoryNamespaceCache::default());
+        if let Some(schema) = schema {
+            cache.put_schema(NAMESPACE.clone(), schema);
+        }
+        Partitioner::new(day_template(), catalog, cache)
+    }
+
+    fn empty_schema() -> NamespaceSchema {
+        NamespaceSchema::new(
+            NamespaceId::new(1),
+            KafkaTopicId::new(1),
+            QueryPoolId::new(1),
+            None,
+        )
+    }
+
+    fn lp_to_writes(lp: &str) -> HashMap<String, MutableBatch> {
+        let (writes, _) = mutable_batch_lp::lines_to_batches_stats(lp, DEFAULT_TIMESTAMP_NANOS)
+            .expect("failed to build test writes from LP");
+        writes
+    }
+
+    /// Partition `lp` and return the sorted table names in each partition.
+    async fn partition<C: NamespaceCache>(
+        partitioner: &Partitioner<C>,
+        lp: &str,
+    ) -> HashMap<PartitionKey, Vec<String>> {
+        partitioner
+            .write(&*NAMESPACE, lp_to_writes(lp), None)
+            .await
+            .expect("partitioning should succeed")
+            .into_iter()
+            .map(|partition| {
+                let mut tables = partition.payload.keys().cloned().collect::<Vec<_>>();
+                tables.sort();
+                (partition.key, tables)
+            })
+            .collect()
+    }
+
+    fn keys(want: &[(&str, &[&str])]) -> HashMap<PartitionKey, Vec<String>> {
+        want.iter()
+            .map(|(key, tables)| {
+                (
+                    PartitionKey::from(*key),
+                    tables.iter().map(ToString::to_string).collect(),
+                )
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_namespace_template() {
+        let metrics = Arc::new(metric::Registry::new());
+        let mut schema = empty_schema();
+        schema.partition_template = Some(PartitionTemplate {
+            parts: vec![
+                TemplatePart::TimeFormat("%Y-%m-%d %H".to_owned()),
+                TemplatePart::Column("region".to_owned()),
+            ],
+        });
+        let partitioner = partitioner(Arc::new(MemCatalog::new(metrics)), Some(schema));
+
+        let got = partition(
+            &partitioner,
+            "\
+                bananas,region=west val=42i 1\n\
+                bananas,region=east val=42i 3600000000000\n\
+                platanos val=42i 1\n\
+            ",
+        )
+        .await;
+        assert_eq!(
+            got,
+            keys(&[
+                ("1970-01-01 00-region_west", &["bananas"]),
+                ("1970-01-01 01-region_east", &["bananas"]),
+                ("1970-01-01 00-region", &["platanos"]),
+            ])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_table_template_overrides_namespace() {
+        let metrics = Arc::new(metric::Registry::new());
+        let mut schema = empty_schema();
+        let mut table = TableSchema::new(TableId::new(1));
+        table.partition_template = Some(PartitionTemplate {
+            parts: vec![
+                TemplatePart::Table,
+                TemplatePart::Column("region".to_owned()),
+            ],
+        });
+        schema.tables.insert("platanos".to_owned(), table);
+        let partitioner = partitioner(Arc::new(MemCatalog::new(metrics)), Some(schema));
+
+        let got = partition(
+            &partitioner,
+            "\
+                bananas,region=west val=42i 1\n\
+                platanos,region=west val=42i 1\n\
+            ",
+        )
+        .await;
+        assert_eq!(
+            got,
+            keys(&[
+                ("1970-01-01", &["bananas"]),
+                ("platanos-region_west", &["platanos"]),
+            ])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_schema_loaded_from_catalog() {
+        let metrics = Arc::new(metric::Registry::new());
+        let catalog: Arc<dyn Catalog> = Arc::new(MemCatalog::new(metrics));
+        let partitioner = partitioner(Arc::clone(&catalog), None);
+
+        // The namespace does not exist yet
+        let err = partitioner
+            .write(&*NAMESPACE, lp_to_writes("bananas val=42i 1"), None)
+            .await
+            .expect_err("write to a missing namespace should fail");
+        assert_matches!(err, PartitionError::NamespaceLookup(_));
+
+        let mut repos = catalog.repositories().await;
+        let kafka = repos.kafka_topics().create_or_get("k").await.unwrap();
+        let pool = repos.query_pools().create_or_get("p").await.unwrap();
+        repos
+            .namespaces()
+            .create(NAMESPACE.as_str(), "inf", kafka.id, pool.id)
+            .await
+            .unwrap();
+        repos
+            .namespaces()
+            .update_partition_template(
+                NAMESPACE.as_str(),
+                Some(&PartitionTemplate {
+                    parts: vec![TemplatePart::TimeFormat("%Y".to_owned())],
+                }),
+            )
+            .await
+            .unwrap();
+        drop(repos);
+
+        let got = partition(&partitioner, "bananas val=42i 1").await;
+        assert_eq!(got, keys(&[("1970", &["bananas"])]));
+
+        // The schema should have been cached as a side effect of the lookup.
+        assert!(partitioner.cache.get_schema(&*NAMESPACE).is_some());
+    }
+
     // Generate a test case that partitions "lp".
     //
     // Assert the partition-to-table mapping in "want_writes" and assert the
@@ -134,16 +344,13 @@ mod tests {
             paste::paste! {
                 #[tokio::test]
                 async fn [<test_write_ $name>]() {
-                    let partition_template = PartitionTemplate {
-                        parts: vec![TemplatePart::TimeFormat("%Y-%m-%d".to_owned())],
-                    };
-
-                    let partitioner = Partitioner::new(partition_template);
-                    let ns = DatabaseName::new("bananas").expect("valid db name");
+                    let metrics = Arc::new(metric::Registry::new());
+                    let catalog: Arc<dyn Catalog> = Arc::new(MemCatalog::new(metrics));
+                    let partitioner = partitioner(catalog, Some(empty_schema()));
 
-                    let (writes, _) = mutable_batch_lp::lines_to_batches_stats($lp, DEFAULT_TIMESTAMP_NANOS).expect("failed to parse test LP");
+                    let writes = lp_to_writes($lp);
 
-                    let handler_ret = partitioner.write(&ns, writes, None).await;
+                    let handler_ret = partitioner.write(&*NAMESPACE, writes, None).await;
                     assert_matches!(handler_ret, $($want_handler_ret)+);
 
                     // Check the partition -> table mapping.
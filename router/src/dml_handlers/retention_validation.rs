@@ -0,0 +1,284 @@
+use super::DmlHandler;
+use crate::namespace_cache::{
+    get_or_load_schema, metrics::InstrumentedCache, MemoryNamespaceCache, NamespaceCache,
+};
+use async_trait::async_trait;
+use data_types::{DatabaseName, DeletePredicate};
+use hashbrown::HashMap;
+use iox_catalog::interface::Catalog;
+use iox_time::{SystemProvider, TimeProvider};
+use metric::U64Counter;
+use mutable_batch::MutableBatch;
+use observability_deps::tracing::*;
+use std::sync::Arc;
+use thiserror::Error;
+use trace::ctx::SpanContext;
+
+/// Errors emitted during retention validation.
+#[derive(Debug, Error)]
+pub enum RetentionError {
+    /// The requested namespace could not be found in the catalog.
+    #[error("failed to read namespace schema from catalog: {0}")]
+    NamespaceLookup(iox_catalog::interface::Error),
+
+    /// The write contains rows older than the namespace retention period.
+    #[error(
+        "data in table {table_name} is outside of the retention period: minimum \
+        timestamp {min_time} is before the retention cutoff {cutoff}"
+    )]
+    OutsideRetention {
+        /// The table containing the expired rows.
+        table_name: String,
+        /// The oldest timestamp in the table batch, in nanoseconds.
+        min_time: i64,
+        /// The oldest timestamp the namespace accepts, in nanoseconds.
+        cutoff: i64,
+    },
+}
+
+/// A [`RetentionValidator`] rejects writes containing rows with timestamps
+/// that fall outside of the retention period of their namespace.
+///
+/// The retention period is read from the [`NamespaceSchema`] in the
+/// [`NamespaceCache`], falling back to loading (and caching) the schema from
+/// the catalog on a cache miss (see [`get_or_load_schema`]). Namespaces with
+/// an infinite retention period accept all writes.
+///
+/// A request containing a single expired row is rejected as a whole, in the
+/// same "all or nothing" manner as schema validation.
+///
+/// [`NamespaceSchema`]: data_types::NamespaceSchema
+#[derive(Debug)]
+pub struct RetentionValidator<C = Arc<InstrumentedCache<MemoryNamespaceCache>>> {
+    catalog: Arc<dyn Catalog>,
+    cache: C,
+    time_provider: Arc<dyn TimeProvider>,
+
+    outside_retention: U64Counter,
+}
+
+impl<C> RetentionValidator<C> {
+    /// Initialise a new [`RetentionValidator`], loading namespace schemas from
+    /// `ns_cache` and `catalog`.
+    pub fn new(catalog: Arc<dyn Catalog>, ns_cache: C, metrics: &metric::Registry) -> Self {
+        Self::new_with_time_provider(catalog, ns_cache, Arc::new(SystemProvider::new()), metrics)
+    }
+
+    /// Initialise a new [`RetentionValidator`] that computes the retention
+    /// cutoff with `time_provider`.
+    pub fn new_with_time_provider(
+        catalog: Arc<dyn Catalog>,
+        ns_cache: C,
+        time_provider: Arc<dyn TimeProvider>,
+        metrics: &metric::Registry,
+    ) -> Self {
+        let outside_retention = metrics
+            .register_metric::<U64Counter>(
+                "retention_validation_outside_retention",
+                "number of requests rejected for containing data outside of the namespace retention period",
+            )
+            .recorder(&[]);
+
+        Self {
+            catalog,
+            cache: ns_cache,
+            time_provider,
+            outside_retention,
+        }
+    }
+}
+
+#[async_trait]
+impl<C> DmlHandler for RetentionValidator<C>
+where
+    C: NamespaceCache,
+{
+    type WriteError = RetentionError;
+    type DeleteError = RetentionError;
+
+    type WriteInput = HashMap<String, MutableBatch>;
+    type WriteOutput = Self::WriteInput;
+
+    /// Validate that all rows in `batches` are within the retention period of
+    /// `namespace`.
+    ///
+    /// # Errors
+    ///
+    /// If `namespace` does not exist, [`RetentionError::NamespaceLookup`] is
+    /// returned.
+    ///
+    /// If any table batch contains a row older than the retention cutoff,
+    /// [`RetentionError::OutsideRetention`] is returned.
+    async fn write(
+        &self,
+        namespace: &DatabaseName<'static>,
+        batches: Self::WriteInput,
+        _span_ctx: Option<SpanContext>,
+    ) -> Result<Self::WriteOutput, Self::WriteError> {
+        let schema = get_or_load_schema(&self.cache, namespace, self.catalog.as_ref())
+            .await
+            .map_err(RetentionError::NamespaceLookup)?;
+        let retention_period_ns = match schema.retention_period_ns {
+            Some(v) => v,
+            None => return Ok(batches),
+        };
+
+        let cutoff = self
+            .time_provider
+            .now()
+            .timestamp_nanos()
+            .saturating_sub(retention_period_ns);
+
+        for (table_name, batch) in &batches {
+            let min_time = match batch.timestamp_summary().and_then(|v| v.stats.min) {
+                Some(v) => v,
+                None => continue,
+            };
+
+            if min_time < cutoff {
+                warn!(
+                    %namespace,
+                    %table_name,
+                    min_time,
+                    cutoff,
+                    "write contains data outside of the retention period"
+                );
+                self.outside_retention.inc(1);
+                return Err(RetentionError::OutsideRetention {
+                    table_name: table_name.clone(),
+                    min_time,
+                    cutoff,
+                });
+            }
+        }
+
+        Ok(batches)
+    }
+
+    /// Deletes are passed through unchanged.
+    async fn delete(
+        &self,
+        _namespace: &DatabaseName<'static>,
+        _table_name: &str,
+        _predicate: &DeletePredicate,
+        _span_ctx: Option<SpanContext>,
+    ) -> Result<(), Self::DeleteError> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_matches::assert_matches;
+    use iox_catalog::mem::MemCatalog;
+    use iox_time::{MockProvider, Time};
+    use once_cell::sync::Lazy;
+
+    static NAMESPACE: Lazy<DatabaseName<'static>> = Lazy::new(|| "bananas".try_into().unwrap());
+
+    /// One hour, in nanoseconds.
+    const HOUR: i64 = 3_600_000_000_000;
+
+    /// The time of the mock time provider, in nanoseconds.
+    const NOW: i64 = 10 * HOUR;
+
+    async fn create_catalog(retention: &str) -> Arc<dyn Catalog> {
+        let metrics = Arc::new(metric::Registry::new());
+        let catalog: Arc<dyn Catalog> = Arc::new(MemCatalog::new(metrics));
+
+        let mut repos = catalog.repositories().await;
+        let kafka = repos.kafka_topics().create_or_get("k").await.unwrap();
+        let pool = repos.query_pools().create_or_get("p").await.unwrap();
+        repos
+            .namespaces()
+            .create(NAMESPACE.as_str(), retention, kafka.id, pool.id)
+            .await
+            .unwrap();
+
+        catalog
+    }
+
+    fn validator(catalog: Arc<dyn Catalog>) -> RetentionValidator<Arc<MemoryNamespaceCache>> {
+        RetentionValidator::new_with_time_provider(
+            catalog,
+            Arc::new(MemoryNamespaceCache::default()),
+            Arc::new(MockProvider::new(Time::from_timestamp_nanos(NOW))),
+            &metric::Registry::default(),
+        )
+    }
+
+    fn lp_to_writes(lp: &str) -> HashMap<String, MutableBatch> {
+        let (writes, _) = mutable_batch_lp::lines_to_batches_stats(lp, 42)
+            .expect("failed to build test writes from LP");
+        writes
+    }
+
+    #[tokio::test]
+    async fn test_infinite_retention_accepts_all() {
+        let catalog = create_catalog(iox_catalog::INFINITE_RETENTION_POLICY).await;
+        let handler = validator(catalog);
+
+        let writes = lp_to_writes("bananas,tag1=A val=42i 1");
+        handler
+            .write(&*NAMESPACE, writes, None)
+            .await
+            .expect("write within infinite retention should succeed");
+    }
+
+    #[tokio::test]
+    async fn test_within_retention() {
+        let catalog = create_catalog("2h").await;
+        let handler = validator(catalog);
+
+        let lp = format!(
+            "bananas,tag1=A val=42i {}\nplatanos,tag1=B val=42i {}",
+            NOW - HOUR,
+            NOW + HOUR
+        );
+        handler
+            .write(&*NAMESPACE, lp_to_writes(&lp), None)
+            .await
+            .expect("write within the retention period should succeed");
+
+        // The schema should have been cached as a side effect of the lookup.
+        assert!(handler.cache.get_schema(&*NAMESPACE).is_some());
+    }
+
+    #[tokio::test]
+    async fn test_outside_retention() {
+        let catalog = create_catalog("2h").await;
+        let handler = validator(catalog);
+
+        let lp = format!(
+            "bananas,tag1=A val=42i {}\nplatanos,tag1=B val=42i {}\nplatanos,tag1=B val=42i {}",
+            NOW,
+            NOW,
+            NOW - 3 * HOUR
+        );
+        let err = handler
+            .write(&*NAMESPACE, lp_to_writes(&lp), None)
+            .await
+            .expect_err("write outside of the retention period should fail");
+
+        assert_matches!(err, RetentionError::OutsideRetention { table_name, min_time, cutoff } => {
+            assert_eq!(table_name, "platanos");
+            assert_eq!(min_time, NOW - 3 * HOUR);
+            assert_eq!(cutoff, NOW - 2 * HOUR);
+        });
+    }
+
+    #[tokio::test]
+    async fn test_namespace_not_found() {
+        let metrics = Arc::new(metric::Registry::new());
+        let catalog: Arc<dyn Catalog> = Arc::new(MemCatalog::new(metrics));
+        let handler = validator(catalog);
+
+        let err = handler
+            .write(&*NAMESPACE, lp_to_writes("bananas val=42i 1"), None)
+            .await
+            .expect_err("write to a missing namespace should fail");
+
+        assert_matches!(err, RetentionError::NamespaceLookup(_));
+    }
+}
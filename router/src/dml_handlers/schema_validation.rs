@@ -1,10 +1,12 @@
 use super::DmlHandler;
-use crate::namespace_cache::{metrics::InstrumentedCache, MemoryNamespaceCache, NamespaceCache};
+use crate::namespace_cache::{
+    get_or_load_schema, metrics::InstrumentedCache, MemoryNamespaceCache, NamespaceCache,
+};
 use async_trait::async_trait;
 use data_types::{DatabaseName, DeletePredicate};
 use hashbrown::HashMap;
 use iox_catalog::{
-    interface::{get_schema_by_name, Catalog, Error as CatalogError},
+    interface::{Catalog, Error as CatalogError},
     validate_or_insert_schema,
 };
 use metric::U64Counter;
@@ -152,31 +154,13 @@ where
         batches: Self::WriteInput,
         _span_ctx: Option<SpanContext>,
     ) -> Result<Self::WriteOutput, Self::WriteError> {
-        let mut repos = self.catalog.repositories().await;
-
         // Load the namespace schema from the cache, falling back to pulling it
         // from the global catalog (if it exists).
-        let schema = self.cache.get_schema(namespace);
-        let schema = match schema {
-            Some(v) => v,
-            None => {
-                // Pull the schema from the global catalog or error if it does
-                // not exist.
-                let schema = get_schema_by_name(namespace, repos.deref_mut())
-                    .await
-                    .map_err(|e| {
-                        warn!(error=%e, %namespace, "failed to retrieve namespace schema");
-                        SchemaError::NamespaceLookup(e)
-                    })
-                    .map(Arc::new)?;
-
-                self.cache
-                    .put_schema(namespace.clone(), Arc::clone(&schema));
-
-                trace!(%namespace, "schema cache populated");
-                schema
-            }
-        };
+        let schema = get_or_load_schema(&self.cache, namespace, self.catalog.as_ref())
+            .await
+            .map_err(SchemaError::NamespaceLookup)?;
+
+        let mut repos = self.catalog.repositories().await;
 
         let maybe_new_schema = validate_or_insert_schema(
             batches.iter().map(|(k, v)| (k.as_str(), v)),
@@ -470,6 +454,49 @@ mod tests {
         assert_eq!(1, handler.service_limit_hit.fetch());
     }
 
+    #[tokio::test]
+    async fn test_write_dropped_column() {
+        let catalog = create_catalog().await;
+        let metrics = Arc::new(metric::Registry::default());
+        let handler = SchemaValidator::new(
+            Arc::clone(&catalog),
+            Arc::new(MemoryNamespaceCache::default()),
+            &*metrics,
+        );
+
+        // First write sets the schema
+        let writes = lp_to_writes("bananas,tag1=A,tag2=B val=42i 123456");
+        handler
+            .write(&*NAMESPACE, writes, None)
+            .await
+            .expect("request should succeed");
+
+        // Drop the "val" column and invalidate the cached schema
+        let column_id =
+            handler.cache.get_schema(&*NAMESPACE).unwrap().tables["bananas"].columns["val"].id;
+        catalog
+            .repositories()
+            .await
+            .columns()
+            .soft_delete(column_id)
+            .await
+            .expect("failed to drop column");
+        handler.cache.remove_schema(&*NAMESPACE);
+
+        // Writing to the dropped column name creates a new column, which may have another type
+        let writes = lp_to_writes("bananas,tag1=A,tag2=B val=42.5 123456");
+        handler
+            .write(&*NAMESPACE, writes, None)
+            .await
+            .expect("request should succeed");
+        assert_eq!(0, handler.schema_conflict.fetch());
+
+        let ns = handler.cache.get_schema(&*NAMESPACE).unwrap();
+        let val = &ns.tables["bananas"].columns["val"];
+        assert_ne!(val.id, column_id);
+        assert_eq!(val.column_type, ColumnType::F64);
+    }
+
     #[tokio::test]
     async fn test_write_delete_passthrough_ok() {
         const NAMESPACE: &str = "NAMESPACE_IS_NOT_VALIDATED";
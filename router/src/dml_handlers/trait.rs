@@ -1,4 +1,6 @@
-use super::{partitioner::PartitionError, NamespaceCreationError, SchemaError, ShardError};
+use super::{
+    partitioner::PartitionError, NamespaceCreationError, RetentionError, SchemaError, ShardError,
+};
 use async_trait::async_trait;
 use data_types::{DatabaseName, DeletePredicate};
 use std::{error::Error, fmt::Debug, sync::Arc};
@@ -21,6 +23,10 @@ pub enum DmlError {
     #[error(transparent)]
     Schema(#[from] SchemaError),
 
+    /// The request contains data outside of the namespace retention period.
+    #[error(transparent)]
+    Retention(#[from] RetentionError),
+
     /// Failed to create the request namespace.
     #[error(transparent)]
     NamespaceCreation(#[from] NamespaceCreationError),
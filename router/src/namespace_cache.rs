@@ -6,10 +6,18 @@ pub use memory::*;
 mod sharded_cache;
 pub use sharded_cache::*;
 
+mod invalidator;
+pub use invalidator::*;
+
+mod ttl;
+pub use ttl::*;
+
 pub mod metrics;
 
 use data_types::{DatabaseName, NamespaceSchema};
-use std::{fmt::Debug, sync::Arc};
+use iox_catalog::interface::{get_schema_by_name, Catalog};
+use observability_deps::tracing::*;
+use std::{fmt::Debug, ops::DerefMut, sync::Arc};
 
 /// An abstract cache of [`NamespaceSchema`].
 pub trait NamespaceCache: Debug + Send + Sync {
@@ -24,4 +32,42 @@ pub trait NamespaceCache: Debug + Send + Sync {
         namespace: DatabaseName<'static>,
         schema: impl Into<Arc<NamespaceSchema>>,
     ) -> Option<Arc<NamespaceSchema>>;
+
+    /// Remove the [`NamespaceSchema`] mapped to `namespace`, returning it if
+    /// it was cached.
+    ///
+    /// The next lookup of `namespace` misses the cache and reloads the schema
+    /// from the catalog.
+    fn remove_schema(&self, namespace: &DatabaseName<'_>) -> Option<Arc<NamespaceSchema>>;
+}
+
+/// Return the [`NamespaceSchema`] for `namespace` from `cache`, falling back to
+/// loading it from `catalog` (if it exists) and populating `cache` on a miss.
+///
+/// The catalog is only accessed on a cache miss.
+pub async fn get_or_load_schema<C>(
+    cache: &C,
+    namespace: &DatabaseName<'static>,
+    catalog: &dyn Catalog,
+) -> Result<Arc<NamespaceSchema>, iox_catalog::interface::Error>
+where
+    C: NamespaceCache,
+{
+    if let Some(schema) = cache.get_schema(namespace) {
+        return Ok(schema);
+    }
+
+    let mut repos = catalog.repositories().await;
+    let schema = get_schema_by_name(namespace, repos.deref_mut())
+        .await
+        .map_err(|e| {
+            warn!(error=%e, %namespace, "failed to retrieve namespace schema");
+            e
+        })
+        .map(Arc::new)?;
+
+    cache.put_schema(namespace.clone(), Arc::clone(&schema));
+
+    trace!(%namespace, "schema cache populated");
+    Ok(schema)
 }
@@ -0,0 +1,69 @@
+use super::NamespaceCache;
+use data_types::DatabaseName;
+use observability_deps::tracing::*;
+use service_grpc_namespace::NamespaceChangeObserver;
+
+/// A [`NamespaceChangeObserver`] that evicts changed namespaces from the
+/// wrapped [`NamespaceCache`], causing their schema to be reloaded from the
+/// catalog on next use.
+#[derive(Debug)]
+pub struct CacheInvalidator<C> {
+    cache: C,
+}
+
+impl<C> CacheInvalidator<C> {
+    /// Invalidate entries of `cache` when their namespace changes.
+    pub fn new(cache: C) -> Self {
+        Self { cache }
+    }
+}
+
+impl<C> NamespaceChangeObserver for CacheInvalidator<C>
+where
+    C: NamespaceCache,
+{
+    fn namespace_changed(&self, namespace: &str) {
+        // A name that is not a valid database name can never have been cached.
+        let namespace = match DatabaseName::new(namespace) {
+            Ok(v) => v,
+            Err(_) => return,
+        };
+
+        if self.cache.remove_schema(&namespace).is_some() {
+            debug!(%namespace, "invalidated cached namespace schema");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::namespace_cache::MemoryNamespaceCache;
+    use data_types::{KafkaTopicId, NamespaceId, NamespaceSchema, QueryPoolId};
+    use std::sync::Arc;
+
+    #[test]
+    fn test_invalidate() {
+        let ns = DatabaseName::new("bananas").expect("database name is valid");
+        let cache = Arc::new(MemoryNamespaceCache::default());
+        cache.put_schema(
+            ns.clone(),
+            NamespaceSchema {
+                id: NamespaceId::new(1),
+                kafka_topic_id: KafkaTopicId::new(1),
+                query_pool_id: QueryPoolId::new(1),
+                retention_period_ns: None,
+                tables: Default::default(),
+                partition_template: None,
+            },
+        );
+
+        let invalidator = CacheInvalidator::new(Arc::clone(&cache));
+        invalidator.namespace_changed("platanos");
+        invalidator.namespace_changed("not a valid name!");
+        assert!(cache.get_schema(&ns).is_some());
+
+        invalidator.namespace_changed("bananas");
+        assert!(cache.get_schema(&ns).is_none());
+    }
+}
@@ -23,6 +23,10 @@ impl NamespaceCache for Arc<MemoryNamespaceCache> {
     ) -> Option<Arc<NamespaceSchema>> {
         self.cache.write().insert(namespace, schema.into())
     }
+
+    fn remove_schema(&self, namespace: &DatabaseName<'_>) -> Option<Arc<NamespaceSchema>> {
+        self.cache.write().remove(namespace)
+    }
 }
 
 #[cfg(test)]
@@ -41,7 +45,9 @@ mod tests {
             id: NamespaceId::new(42),
             kafka_topic_id: KafkaTopicId::new(24),
             query_pool_id: QueryPoolId::new(1234),
+            retention_period_ns: None,
             tables: Default::default(),
+            partition_template: None,
         };
         assert!(cache.put_schema(ns.clone(), schema1.clone()).is_none());
         assert_eq!(*cache.get_schema(&ns).expect("lookup failure"), schema1);
@@ -50,7 +56,9 @@ mod tests {
             id: NamespaceId::new(2),
             kafka_topic_id: KafkaTopicId::new(2),
             query_pool_id: QueryPoolId::new(2),
+            retention_period_ns: None,
             tables: Default::default(),
+            partition_template: None,
         };
 
         assert_eq!(
@@ -60,5 +68,14 @@ mod tests {
             schema1
         );
         assert_eq!(*cache.get_schema(&ns).expect("lookup failure"), schema2);
+
+        assert_eq!(
+            *cache
+                .remove_schema(&ns)
+                .expect("should have existing schema"),
+            schema2
+        );
+        assert!(cache.get_schema(&ns).is_none());
+        assert!(cache.remove_schema(&ns).is_none());
     }
 }
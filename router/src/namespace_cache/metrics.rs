@@ -130,6 +130,19 @@ where
             }
         }
     }
+
+    fn remove_schema(&self, namespace: &DatabaseName<'_>) -> Option<Arc<NamespaceSchema>> {
+        let res = self.inner.remove_schema(namespace);
+
+        // Remove the evicted namespace stats from the counts.
+        if let Some(v) = &res {
+            let stats = NamespaceStats::new(&**v);
+            self.table_count.dec(stats.table_count);
+            self.column_count.dec(stats.column_count);
+        }
+
+        res
+    }
 }
 
 #[derive(Debug)]
@@ -185,6 +198,7 @@ mod tests {
                     TableSchema {
                         id: TableId::new(i as _),
                         columns,
+                        partition_template: None,
                     },
                 )
             })
@@ -194,7 +208,9 @@ mod tests {
             id: NamespaceId::new(42),
             kafka_topic_id: KafkaTopicId::new(24),
             query_pool_id: QueryPoolId::new(1234),
+            retention_period_ns: None,
             tables,
+            partition_template: None,
         }
     }
 
@@ -377,5 +393,11 @@ mod tests {
             ("result", "hit"),
             1,
         );
+
+        // Remove the new namespace
+        assert!(cache.remove_schema(&ns).is_some());
+        assert!(cache.remove_schema(&ns).is_none());
+        assert_eq!(cache.table_count.observe(), Observation::U64Gauge(2));
+        assert_eq!(cache.column_count.observe(), Observation::U64Gauge(11));
     }
 }
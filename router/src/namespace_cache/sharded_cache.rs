@@ -34,6 +34,10 @@ where
     ) -> Option<Arc<NamespaceSchema>> {
         self.shards.hash(&namespace).put_schema(namespace, schema)
     }
+
+    fn remove_schema(&self, namespace: &DatabaseName<'_>) -> Option<Arc<NamespaceSchema>> {
+        self.shards.hash(namespace).remove_schema(namespace)
+    }
 }
 
 #[cfg(test)]
@@ -59,7 +63,9 @@ mod tests {
             id: NamespaceId::new(id),
             kafka_topic_id: KafkaTopicId::new(1),
             query_pool_id: QueryPoolId::new(1),
+            retention_period_ns: None,
             tables: Default::default(),
+            partition_template: None,
         }
     }
 
@@ -0,0 +1,150 @@
+use super::NamespaceCache;
+use data_types::{DatabaseName, NamespaceSchema};
+use hashbrown::HashMap;
+use iox_time::{SystemProvider, Time, TimeProvider};
+use parking_lot::Mutex;
+use std::{sync::Arc, time::Duration};
+
+/// A [`TtlCache`] decorates a [`NamespaceCache`], expiring entries a fixed
+/// duration after they were first placed in the cache.
+///
+/// Namespace changes made through the namespace API are only evicted from the
+/// cache of the router serving the request (see [`CacheInvalidator`]). Expiring
+/// entries bounds how long other routers keep using a stale schema, such as
+/// that of a deleted namespace or one with an outdated retention period.
+///
+/// Replacing a cached schema (for example after adding a column) does not
+/// extend the lifetime of the entry, as the new schema is derived from the
+/// cached one and may be just as stale.
+///
+/// [`CacheInvalidator`]: super::CacheInvalidator
+#[derive(Debug)]
+pub struct TtlCache<T> {
+    inner: T,
+    ttl: Duration,
+    time_provider: Arc<dyn TimeProvider>,
+
+    /// The time at which each cached namespace expires.
+    expires_at: Mutex<HashMap<DatabaseName<'static>, Time>>,
+}
+
+impl<T> TtlCache<T> {
+    /// Expire entries of `inner` `ttl` after they are inserted.
+    pub fn new(inner: T, ttl: Duration) -> Self {
+        Self {
+            inner,
+            ttl,
+            time_provider: Arc::new(SystemProvider::default()),
+            expires_at: Default::default(),
+        }
+    }
+
+    /// Use `time_provider` to determine when entries expire.
+    pub fn with_time_provider(self, time_provider: Arc<dyn TimeProvider>) -> Self {
+        Self {
+            time_provider,
+            ..self
+        }
+    }
+}
+
+impl<T> NamespaceCache for Arc<TtlCache<T>>
+where
+    T: NamespaceCache,
+{
+    fn get_schema(&self, namespace: &DatabaseName<'_>) -> Option<Arc<NamespaceSchema>> {
+        let mut expires_at = self.expires_at.lock();
+        match expires_at.get(namespace) {
+            Some(t) if *t > self.time_provider.now() => self.inner.get_schema(namespace),
+            Some(_) => {
+                expires_at.remove(namespace);
+                self.inner.remove_schema(namespace);
+                None
+            }
+            None => self.inner.get_schema(namespace),
+        }
+    }
+
+    fn put_schema(
+        &self,
+        namespace: DatabaseName<'static>,
+        schema: impl Into<Arc<NamespaceSchema>>,
+    ) -> Option<Arc<NamespaceSchema>> {
+        let mut expires_at = self.expires_at.lock();
+        let now = self.time_provider.now();
+        let expiry = expires_at
+            .entry(namespace.clone())
+            .or_insert_with(|| now + self.ttl);
+
+        // A put after expiry (without an intervening get) starts a new entry.
+        if *expiry <= now {
+            *expiry = now + self.ttl;
+        }
+
+        self.inner.put_schema(namespace, schema)
+    }
+
+    fn remove_schema(&self, namespace: &DatabaseName<'_>) -> Option<Arc<NamespaceSchema>> {
+        self.expires_at.lock().remove(namespace);
+        self.inner.remove_schema(namespace)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::namespace_cache::MemoryNamespaceCache;
+    use data_types::{KafkaTopicId, NamespaceId, QueryPoolId};
+    use iox_time::MockProvider;
+
+    const TTL: Duration = Duration::from_secs(60);
+
+    fn schema(id: i64) -> NamespaceSchema {
+        NamespaceSchema {
+            id: NamespaceId::new(id),
+            kafka_topic_id: KafkaTopicId::new(1),
+            query_pool_id: QueryPoolId::new(1),
+            retention_period_ns: None,
+            tables: Default::default(),
+            partition_template: None,
+        }
+    }
+
+    #[test]
+    fn test_expiry() {
+        let ns = DatabaseName::new("test").expect("database name is valid");
+        let time_provider = Arc::new(MockProvider::new(Time::from_timestamp_nanos(0)));
+        let cache = Arc::new(
+            TtlCache::new(Arc::new(MemoryNamespaceCache::default()), TTL)
+                .with_time_provider(Arc::clone(&time_provider) as _),
+        );
+
+        assert!(cache.put_schema(ns.clone(), schema(1)).is_none());
+        assert_eq!(*cache.get_schema(&ns).expect("lookup failure"), schema(1));
+
+        // Replacing the schema does not extend the lifetime of the entry.
+        time_provider.inc(TTL / 2);
+        assert_eq!(
+            *cache
+                .put_schema(ns.clone(), schema(2))
+                .expect("should have existing schema"),
+            schema(1)
+        );
+        assert_eq!(*cache.get_schema(&ns).expect("lookup failure"), schema(2));
+
+        time_provider.inc(TTL / 2);
+        assert!(cache.get_schema(&ns).is_none());
+        assert!(cache.remove_schema(&ns).is_none());
+
+        // Once expired, a new entry gets a full TTL.
+        assert!(cache.put_schema(ns.clone(), schema(3)).is_none());
+        time_provider.inc(TTL - Duration::from_secs(1));
+        assert_eq!(*cache.get_schema(&ns).expect("lookup failure"), schema(3));
+
+        // Removing the entry resets its expiry.
+        assert!(cache.remove_schema(&ns).is_some());
+        assert!(cache.put_schema(ns.clone(), schema(4)).is_none());
+        time_provider.inc(Duration::from_secs(1));
+        assert_eq!(*cache.get_schema(&ns).expect("lookup failure"), schema(4));
+    }
+}
@@ -1,10 +1,11 @@
 //! gRPC service implementations for `router`.
 
-use crate::dml_handlers::{DmlError, DmlHandler, PartitionError};
+use crate::dml_handlers::{DmlError, DmlHandler, PartitionError, RetentionError};
+use data_types::{KafkaTopicId, QueryPoolId};
 use generated_types::{
     google::FieldViolation,
     influxdata::{
-        iox::{catalog::v1::*, object_store::v1::*, schema::v1::*},
+        iox::{catalog::v1::*, namespace::v1::*, object_store::v1::*, schema::v1::*},
         pbdata::v1::*,
     },
 };
@@ -16,6 +17,7 @@ use object_store::DynObjectStore;
 use observability_deps::tracing::*;
 use schema::selection::Selection;
 use service_grpc_catalog::CatalogService;
+use service_grpc_namespace::{NamespaceChangeObserver, NamespaceService};
 use service_grpc_object_store::ObjectStoreService;
 use service_grpc_schema::SchemaService;
 use std::sync::Arc;
@@ -33,21 +35,34 @@ pub struct GrpcDelegate<D> {
     dml_handler: Arc<D>,
     catalog: Arc<dyn Catalog>,
     object_store: Arc<DynObjectStore>,
+    kafka_topic_id: KafkaTopicId,
+    query_pool_id: QueryPoolId,
+    namespace_observer: Arc<dyn NamespaceChangeObserver>,
     metrics: Arc<metric::Registry>,
 }
 
 impl<D> GrpcDelegate<D> {
     /// Initialise a new gRPC handler, dispatching DML operations to `dml_handler`.
+    ///
+    /// Namespaces created through the [`NamespaceService`] are assigned to
+    /// `kafka_topic_id` and `query_pool_id`, and `namespace_observer` is
+    /// notified of every namespace changed through it.
     pub fn new(
         dml_handler: Arc<D>,
         catalog: Arc<dyn Catalog>,
         object_store: Arc<DynObjectStore>,
+        kafka_topic_id: KafkaTopicId,
+        query_pool_id: QueryPoolId,
+        namespace_observer: Arc<dyn NamespaceChangeObserver>,
         metrics: Arc<metric::Registry>,
     ) -> Self {
         Self {
             dml_handler,
             catalog,
             object_store,
+            kafka_topic_id,
+            query_pool_id,
+            namespace_observer,
             metrics,
         }
     }
@@ -78,6 +93,22 @@ where
         )))
     }
 
+    /// Acquire a [`NamespaceService`] gRPC service implementation.
+    ///
+    /// [`NamespaceService`]: generated_types::influxdata::iox::namespace::v1::namespace_service_server::NamespaceService.
+    pub fn namespace_service(
+        &self,
+    ) -> namespace_service_server::NamespaceServiceServer<NamespaceService> {
+        namespace_service_server::NamespaceServiceServer::new(
+            NamespaceService::new(
+                Arc::clone(&self.catalog),
+                self.kafka_topic_id,
+                self.query_pool_id,
+            )
+            .with_change_observer(Arc::clone(&self.namespace_observer)),
+        )
+    }
+
     /// Acquire a [`CatalogService`] gRPC service implementation.
     ///
     /// [`CatalogService`]: generated_types::influxdata::iox::catalog::v1::catalog_service_server::CatalogService.
@@ -198,12 +229,23 @@ where
             .write(&namespace, tables, span_ctx)
             .await
             .map_err(|e| match e.into() {
-                e @ DmlError::DatabaseNotFound(_) => Status::not_found(e.to_string()),
+                e @ (DmlError::DatabaseNotFound(_)
+                | DmlError::Retention(RetentionError::NamespaceLookup(
+                    iox_catalog::interface::Error::NamespaceNotFoundByName { .. },
+                ))
+                | DmlError::Partition(PartitionError::NamespaceLookup(
+                    iox_catalog::interface::Error::NamespaceNotFoundByName { .. },
+                ))) => Status::not_found(e.to_string()),
                 e @ DmlError::Schema(_) => Status::aborted(e.to_string()),
+                e @ DmlError::Retention(RetentionError::OutsideRetention { .. }) => {
+                    Status::invalid_argument(e.to_string())
+                }
 
                 e @ (DmlError::Internal(_)
                 | DmlError::WriteBuffer(_)
                 | DmlError::NamespaceCreation(_)
+                | DmlError::Retention(RetentionError::NamespaceLookup(_))
+                | DmlError::Partition(PartitionError::NamespaceLookup(_))
                 | DmlError::Partition(PartitionError::BatchWrite(_))) => {
                     Status::internal(e.to_string())
                 }
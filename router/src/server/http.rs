@@ -1,11 +1,17 @@
 //! HTTP service implementations for `router`.
 
-use crate::dml_handlers::{DmlError, DmlHandler, PartitionError, SchemaError};
+use crate::dml_handlers::{DmlError, DmlHandler, PartitionError, RetentionError, SchemaError};
 use bytes::{Bytes, BytesMut};
-use data_types::{org_and_bucket_to_database, OrgBucketMappingError};
+use data_types::{
+    db_and_rp_to_database, org_and_bucket_to_database, DatabaseName, DbRpMappingError,
+    OrgBucketMappingError,
+};
 use futures::StreamExt;
 use hashbrown::HashMap;
-use hyper::{header::CONTENT_ENCODING, Body, Method, Request, Response, StatusCode};
+use hyper::{
+    header::{CONTENT_ENCODING, CONTENT_TYPE},
+    Body, Method, Request, Response, StatusCode,
+};
 use iox_time::{SystemProvider, TimeProvider};
 use metric::U64Counter;
 use mutable_batch::MutableBatch;
@@ -32,6 +38,10 @@ pub enum Error {
     #[error(transparent)]
     InvalidOrgBucket(#[from] OrgBucketError),
 
+    /// An error with the db/rp in an InfluxDB 1.x request.
+    #[error(transparent)]
+    InvalidDbRp(#[from] DbRpError),
+
     /// The request body content is not valid utf8.
     #[error("body content is not valid utf8: {0}")]
     NonUtf8Body(Utf8Error),
@@ -81,6 +91,7 @@ impl Error {
         match self {
             Error::NoHandler => StatusCode::NOT_FOUND,
             Error::InvalidOrgBucket(_) => StatusCode::BAD_REQUEST,
+            Error::InvalidDbRp(_) => StatusCode::BAD_REQUEST,
             Error::ClientHangup(_) => StatusCode::BAD_REQUEST,
             Error::InvalidGzip(_) => StatusCode::BAD_REQUEST,
             Error::NonUtf8ContentHeader(_) => StatusCode::BAD_REQUEST,
@@ -103,6 +114,18 @@ impl From<&DmlError> for StatusCode {
         match e {
             DmlError::DatabaseNotFound(_) => StatusCode::NOT_FOUND,
 
+            // A soft deleted namespace is not found, even though the
+            // [`NamespaceAutocreation`] layer skips it as it already exists.
+            DmlError::Schema(SchemaError::NamespaceLookup(
+                iox_catalog::interface::Error::NamespaceNotFoundByName { .. },
+            ))
+            | DmlError::Retention(RetentionError::NamespaceLookup(
+                iox_catalog::interface::Error::NamespaceNotFoundByName { .. },
+            ))
+            | DmlError::Partition(PartitionError::NamespaceLookup(
+                iox_catalog::interface::Error::NamespaceNotFoundByName { .. },
+            )) => StatusCode::NOT_FOUND,
+
             // Schema validation error cases
             DmlError::Schema(SchemaError::NamespaceLookup(_)) => {
                 // While the [`NamespaceAutocreation`] layer is in use, this is
@@ -118,10 +141,19 @@ impl From<&DmlError> for StatusCode {
                 StatusCode::INTERNAL_SERVER_ERROR
             }
 
+            // Retention validation error cases
+            DmlError::Retention(RetentionError::NamespaceLookup(_)) => {
+                StatusCode::INTERNAL_SERVER_ERROR
+            }
+            DmlError::Retention(RetentionError::OutsideRetention { .. }) => StatusCode::BAD_REQUEST,
+
             DmlError::Internal(_) | DmlError::WriteBuffer(_) | DmlError::NamespaceCreation(_) => {
                 StatusCode::INTERNAL_SERVER_ERROR
             }
-            DmlError::Partition(PartitionError::BatchWrite(_)) => StatusCode::INTERNAL_SERVER_ERROR,
+            DmlError::Partition(PartitionError::NamespaceLookup(_))
+            | DmlError::Partition(PartitionError::BatchWrite(_)) => {
+                StatusCode::INTERNAL_SERVER_ERROR
+            }
         }
     }
 }
@@ -143,8 +175,32 @@ pub enum OrgBucketError {
     MappingFail(#[from] OrgBucketMappingError),
 }
 
+/// Errors returned when decoding the InfluxDB 1.x database / retention policy
+/// information from a HTTP request and deriving the database name from it.
+#[derive(Debug, Error)]
+pub enum DbRpError {
+    /// The request contains no db destination information.
+    #[error("no db destination provided")]
+    NotSpecified,
+
+    /// The request contains invalid parameters.
+    #[error("failed to deserialize db/rp/precision in request: {0}")]
+    DecodeFail(#[from] serde::de::value::Error),
+
+    /// The provided db/rp could not be converted into a database name.
+    #[error(transparent)]
+    MappingFail(#[from] DbRpMappingError),
+}
+
+/// The precision of line protocol timestamps of an InfluxDB 2.x write.
 #[derive(Debug, Deserialize)]
 enum Precision {
+    /// Only InfluxDB 1.x writes, see [`V1Precision`]
+    #[serde(skip)]
+    Hours,
+    /// Only InfluxDB 1.x writes, see [`V1Precision`]
+    #[serde(skip)]
+    Minutes,
     #[serde(rename = "s")]
     Seconds,
     #[serde(rename = "ms")]
@@ -165,6 +221,8 @@ impl Precision {
     /// Returns the multiplier to convert to nanosecond timestamps
     fn timestamp_base(&self) -> i64 {
         match self {
+            Precision::Hours => 60 * 60 * 1_000_000_000,
+            Precision::Minutes => 60 * 1_000_000_000,
             Precision::Seconds => 1_000_000_000,
             Precision::Milliseconds => 1_000_000,
             Precision::Microseconds => 1_000,
@@ -173,6 +231,43 @@ impl Precision {
     }
 }
 
+/// The precision of line protocol timestamps of an InfluxDB 1.x write, which
+/// accepts more units and spellings than InfluxDB 2.x.
+#[derive(Debug, Deserialize)]
+enum V1Precision {
+    #[serde(rename = "h")]
+    Hours,
+    #[serde(rename = "m")]
+    Minutes,
+    #[serde(rename = "s")]
+    Seconds,
+    #[serde(rename = "ms")]
+    Milliseconds,
+    #[serde(rename = "u", alias = "us", alias = "µ")]
+    Microseconds,
+    #[serde(rename = "n", alias = "ns")]
+    Nanoseconds,
+}
+
+impl Default for V1Precision {
+    fn default() -> Self {
+        Self::Nanoseconds
+    }
+}
+
+impl From<V1Precision> for Precision {
+    fn from(precision: V1Precision) -> Self {
+        match precision {
+            V1Precision::Hours => Self::Hours,
+            V1Precision::Minutes => Self::Minutes,
+            V1Precision::Seconds => Self::Seconds,
+            V1Precision::Milliseconds => Self::Milliseconds,
+            V1Precision::Microseconds => Self::Microseconds,
+            V1Precision::Nanoseconds => Self::Nanoseconds,
+        }
+    }
+}
+
 #[derive(Debug, Deserialize)]
 /// Org & bucket identifiers for a DML operation.
 pub struct WriteInfo {
@@ -199,6 +294,54 @@ impl<T> TryFrom<&Request<T>> for WriteInfo {
     }
 }
 
+#[derive(Debug, Deserialize)]
+/// Database & retention policy identifiers for an InfluxDB 1.x write.
+///
+/// The `u` and `p` credentials of 1.x clients are accepted and ignored.
+pub struct V1WriteInfo {
+    db: String,
+
+    #[serde(default)]
+    rp: String,
+
+    #[serde(default)]
+    precision: V1Precision,
+}
+
+impl<T> TryFrom<&Request<T>> for V1WriteInfo {
+    type Error = DbRpError;
+
+    fn try_from(req: &Request<T>) -> Result<Self, Self::Error> {
+        let query = req.uri().query().ok_or(DbRpError::NotSpecified)?;
+        let got: V1WriteInfo = serde_urlencoded::from_str(query)?;
+
+        // An empty db is not acceptable.
+        if got.db.is_empty() {
+            return Err(DbRpError::NotSpecified);
+        }
+
+        Ok(got)
+    }
+}
+
+/// Converts `err` into a response with the `{"error": "..."}` body InfluxDB
+/// 1.x clients expect.
+fn v1_error_response(err: &Error) -> Response<Body> {
+    let status = err.as_status_code();
+    if status.is_server_error() {
+        error!(error=%err, "error handling v1 write request");
+    } else {
+        debug!(error=%err, "error handling v1 write request");
+    }
+
+    let body = serde_json::json!({ "error": err.to_string() }).to_string();
+    Response::builder()
+        .status(status)
+        .header(CONTENT_TYPE, "application/json")
+        .body(Body::from(body))
+        .unwrap()
+}
+
 /// This type is responsible for servicing requests to the `router` HTTP
 /// endpoint.
 ///
@@ -318,29 +461,56 @@ where
         };
 
         // Route the request to a handler.
-        match (req.method(), req.uri().path()) {
-            (&Method::POST, "/api/v2/write") => self.write_handler(req).await,
-            (&Method::POST, "/api/v2/delete") => self.delete_handler(req).await,
+        let (result, is_v1) = match (req.method(), req.uri().path()) {
+            (&Method::POST, "/api/v2/write") => (self.write_handler(req).await, false),
+            (&Method::POST, "/api/v2/delete") => (self.delete_handler(req).await, false),
+            (&Method::POST, "/write") => (self.write_v1_handler(req).await, true),
             _ => return Err(Error::NoHandler),
-        }
-        .map(|summary| {
-            Response::builder()
+        };
+
+        match result {
+            Ok(summary) => Ok(Response::builder()
                 .status(StatusCode::NO_CONTENT)
                 .header(WRITE_TOKEN_HTTP_HEADER, summary.to_token())
                 .body(Body::empty())
-                .unwrap()
-        })
+                .unwrap()),
+            Err(e) if is_v1 => Ok(v1_error_response(&e)),
+            Err(e) => Err(e),
+        }
     }
 
     async fn write_handler(&self, req: Request<Body>) -> Result<WriteSummary, Error> {
-        let span_ctx: Option<SpanContext> = req.extensions().get().cloned();
-
         let write_info = WriteInfo::try_from(&req)?;
         let namespace = org_and_bucket_to_database(&write_info.org, &write_info.bucket)
             .map_err(OrgBucketError::MappingFail)?;
 
         trace!(org=%write_info.org, bucket=%write_info.bucket, %namespace, "processing write request");
 
+        self.write_lines(req, namespace, write_info.precision).await
+    }
+
+    /// Handles InfluxDB 1.x writes, mapping the db/rp of the request to a
+    /// namespace.
+    async fn write_v1_handler(&self, req: Request<Body>) -> Result<WriteSummary, Error> {
+        let write_info = V1WriteInfo::try_from(&req)?;
+        let namespace = db_and_rp_to_database(&write_info.db, &write_info.rp)
+            .map_err(DbRpError::MappingFail)?;
+
+        trace!(db=%write_info.db, rp=%write_info.rp, %namespace, "processing v1 write request");
+
+        self.write_lines(req, namespace, write_info.precision.into())
+            .await
+    }
+
+    /// Writes the line protocol body of `req` to `namespace`.
+    async fn write_lines(
+        &self,
+        req: Request<Body>,
+        namespace: DatabaseName<'static>,
+        precision: Precision,
+    ) -> Result<WriteSummary, Error> {
+        let span_ctx: Option<SpanContext> = req.extensions().get().cloned();
+
         // Read the HTTP body and convert it to a str.
         let body = self.read_body(req).await?;
         let body = std::str::from_utf8(&body).map_err(Error::NonUtf8Body)?;
@@ -350,7 +520,7 @@ where
         let default_time = self.time_provider.now().timestamp_nanos();
 
         let mut converter = LinesConverter::new(default_time);
-        converter.set_timestamp_base(write_info.precision.timestamp_base());
+        converter.set_timestamp_base(precision.timestamp_base());
         let (batches, stats) = match converter.write_lp(body).and_then(|_| converter.finish()) {
             Ok(v) => v,
             Err(mutable_batch_lp::Error::EmptyPayload) => {
@@ -365,11 +535,9 @@ where
             num_lines=stats.num_lines,
             num_fields=stats.num_fields,
             num_tables,
-            precision=?write_info.precision,
+            ?precision,
             body_size=body.len(),
             %namespace,
-            org=%write_info.org,
-            bucket=%write_info.bucket,
             "routing write",
         );
 
@@ -614,13 +782,13 @@ mod tests {
                     // and metrics should be recorded.
                     if let Ok(v) = got {
                         assert_eq!(v.status(), StatusCode::NO_CONTENT);
-                        if $uri.contains("/api/v2/write") {
+                        if $uri.contains("/api/v2/delete") {
+                            assert_metric_hit(&metrics, "http_delete_body_bytes_total", Some($body.len() as _));
+                        } else {
                             assert_metric_hit(&metrics, "http_write_lines_total", None);
                             assert_metric_hit(&metrics, "http_write_fields_total", None);
                             assert_metric_hit(&metrics, "http_write_tables_total", None);
                             assert_metric_hit(&metrics, "http_write_body_bytes_total", Some($body.len() as _));
-                        } else {
-                            assert_metric_hit(&metrics, "http_delete_body_bytes_total", Some($body.len() as _));
                         }
                     }
 
@@ -671,6 +839,31 @@ mod tests {
         };
     }
 
+    // Wrapper over test_http_handler specifically for InfluxDB 1.x write
+    // requests.
+    macro_rules! test_v1_write_handler {
+        (
+            $name:ident,
+            query_string = $query_string:expr,   // Request URI query string
+            body = $body:expr,                   // Request body content
+            dml_handler = $dml_handler:expr,     // DML write handler response (if called)
+            want_result = $want_result:pat,
+            want_dml_calls = $($want_dml_calls:tt )+
+        ) => {
+            paste::paste! {
+                test_http_handler!(
+                    [<v1_write_ $name>],
+                    uri = format!("https://bananas.example/write{}", $query_string),
+                    body = $body,
+                    dml_write_handler = $dml_handler,
+                    dml_delete_handler = [],
+                    want_result = $want_result,
+                    want_dml_calls = $($want_dml_calls)+
+                );
+            }
+        };
+    }
+
     // Wrapper over test_http_handler specifically for delete requests.
     macro_rules! test_delete_handler {
         (
@@ -766,6 +959,15 @@ mod tests {
         }
     );
 
+    test_write_handler!(
+        v1_only_precision,
+        query_string = "?org=bananas&bucket=test&precision=u",
+        body = "platanos,tag1=A,tag2=B val=42i 1647622847000000".as_bytes(),
+        dml_handler = [Ok(summary())],
+        want_result = Err(Error::InvalidOrgBucket(OrgBucketError::DecodeFail(_))),
+        want_dml_calls = []
+    );
+
     test_write_handler!(
         precision_overflow,
         // SECONDS, so multiplies the provided timestamp by 1,000,000,000
@@ -906,6 +1108,147 @@ mod tests {
         want_dml_calls = []
     );
 
+    test_v1_write_handler!(
+        ok,
+        query_string = "?db=bananas&u=user&p=password",
+        body = "platanos,tag1=A,tag2=B val=42i 123456".as_bytes(),
+        dml_handler = [Ok(summary())],
+        want_result = Ok(_),
+        want_dml_calls = [MockDmlHandlerCall::Write{namespace, ..}] => {
+            assert_eq!(namespace, "bananas");
+        }
+    );
+
+    test_v1_write_handler!(
+        ok_autogen_rp,
+        query_string = "?db=bananas&rp=autogen",
+        body = "platanos,tag1=A,tag2=B val=42i 123456".as_bytes(),
+        dml_handler = [Ok(summary())],
+        want_result = Ok(_),
+        want_dml_calls = [MockDmlHandlerCall::Write{namespace, ..}] => {
+            assert_eq!(namespace, "bananas");
+        }
+    );
+
+    test_v1_write_handler!(
+        ok_rp,
+        query_string = "?db=bananas&rp=one_week",
+        body = "platanos,tag1=A,tag2=B val=42i 123456".as_bytes(),
+        dml_handler = [Ok(summary())],
+        want_result = Ok(_),
+        want_dml_calls = [MockDmlHandlerCall::Write{namespace, ..}] => {
+            assert_eq!(namespace, "bananas/one_week");
+        }
+    );
+
+    test_v1_write_handler!(
+        ok_precision_u,
+        query_string = "?db=bananas&precision=u",
+        body = "platanos,tag1=A,tag2=B val=42i 1647622847000000".as_bytes(),
+        dml_handler = [Ok(summary())],
+        want_result = Ok(_),
+        want_dml_calls = [MockDmlHandlerCall::Write{namespace, write_input}] => {
+            assert_eq!(namespace, "bananas");
+
+            let table = write_input.get("platanos").expect("table not found");
+            let ts = table.timestamp_summary().expect("no timestamp summary");
+            assert_eq!(Some(1647622847000000000), ts.stats.min);
+        }
+    );
+
+    test_v1_write_handler!(
+        ok_precision_h,
+        query_string = "?db=bananas&precision=h",
+        body = "platanos,tag1=A,tag2=B val=42i 457672".as_bytes(),
+        dml_handler = [Ok(summary())],
+        want_result = Ok(_),
+        want_dml_calls = [MockDmlHandlerCall::Write{namespace, write_input}] => {
+            assert_eq!(namespace, "bananas");
+
+            let table = write_input.get("platanos").expect("table not found");
+            let ts = table.timestamp_summary().expect("no timestamp summary");
+            assert_eq!(Some(1647619200000000000), ts.stats.min);
+        }
+    );
+
+    /// Asserts that `response` is an InfluxDB 1.x error with `status` and a
+    /// message containing `want`.
+    async fn assert_v1_error(response: Response<Body>, status: StatusCode, want: &str) {
+        assert_eq!(response.status(), status);
+        assert_eq!(response.headers()[CONTENT_TYPE], "application/json");
+
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        let message = body["error"].as_str().expect("no error message");
+        assert!(
+            message.contains(want),
+            "{} does not contain {}",
+            message,
+            want
+        );
+    }
+
+    #[tokio::test]
+    async fn test_v1_write_errors() {
+        let dml_handler = Arc::new(
+            MockDmlHandler::default()
+                .with_write_return([Err(DmlError::DatabaseNotFound("bananas".to_string()))]),
+        );
+        let metrics = Arc::new(metric::Registry::default());
+        let delegate = HttpDelegate::new(MAX_BYTES, 100, Arc::clone(&dml_handler), &metrics);
+
+        let request = |query_string: &str, body: &'static str| {
+            Request::builder()
+                .uri(format!("https://bananas.example/write{}", query_string))
+                .method("POST")
+                .body(Body::from(body))
+                .unwrap()
+        };
+
+        let got = delegate.route(request("", "")).await.unwrap();
+        assert_v1_error(got, StatusCode::BAD_REQUEST, "no db destination provided").await;
+
+        let got = delegate.route(request("?db=", "")).await.unwrap();
+        assert_v1_error(got, StatusCode::BAD_REQUEST, "no db destination provided").await;
+
+        let got = delegate
+            .route(request("?db=bananas&precision=d", ""))
+            .await
+            .unwrap();
+        assert_v1_error(got, StatusCode::BAD_REQUEST, "precision").await;
+
+        let got = delegate
+            .route(request("?db=bananas", "not line protocol"))
+            .await
+            .unwrap();
+        assert_v1_error(
+            got,
+            StatusCode::BAD_REQUEST,
+            "failed to parse line protocol",
+        )
+        .await;
+
+        let got = delegate
+            .route(request("?db=bananas", "platanos val=42i 123456"))
+            .await
+            .unwrap();
+        assert_v1_error(got, StatusCode::NOT_FOUND, "bananas").await;
+
+        let body = "A".repeat(MAX_BYTES + 1);
+        let request = Request::builder()
+            .uri("https://bananas.example/write?db=bananas")
+            .method("POST")
+            .body(Body::from(body))
+            .unwrap();
+        let got = delegate.route(request).await.unwrap();
+        assert_v1_error(got, StatusCode::PAYLOAD_TOO_LARGE, "max request size").await;
+
+        assert_matches!(
+            dml_handler.calls().as_slice(),
+            [MockDmlHandlerCall::Write { .. }]
+        );
+    }
+
     test_delete_handler!(
         ok,
         query_string = "?org=bananas&bucket=test",
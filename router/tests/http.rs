@@ -53,7 +53,7 @@ type HttpDelegateStack = HttpDelegate<
                     >,
                     SchemaValidator<Arc<ShardedCache<Arc<MemoryNamespaceCache>>>>,
                 >,
-                Partitioner,
+                Partitioner<Arc<ShardedCache<Arc<MemoryNamespaceCache>>>>,
             >,
             WriteSummaryAdapter<
                 FanOutAdaptor<
@@ -108,10 +108,15 @@ impl TestContext {
             iox_catalog::INFINITE_RETENTION_POLICY.to_owned(),
         );
 
-        let schema_validator = SchemaValidator::new(Arc::clone(&catalog), ns_cache, &*metrics);
-        let partitioner = Partitioner::new(PartitionTemplate {
-            parts: vec![TemplatePart::TimeFormat("%Y-%m-%d".to_owned())],
-        });
+        let schema_validator =
+            SchemaValidator::new(Arc::clone(&catalog), Arc::clone(&ns_cache), &*metrics);
+        let partitioner = Partitioner::new(
+            PartitionTemplate {
+                parts: vec![TemplatePart::TimeFormat("%Y-%m-%d".to_owned())],
+            },
+            Arc::clone(&catalog),
+            Arc::clone(&ns_cache),
+        );
 
         let handler_stack = ns_creator
             .and_then(schema_validator)
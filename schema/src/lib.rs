@@ -21,6 +21,10 @@ use snafu::{OptionExt, Snafu};
 /// The name of the timestamp column in the InfluxDB datamodel
 pub const TIME_COLUMN_NAME: &str = "time";
 
+/// The name of the column holding the measurement of each row in results that
+/// span several measurements, such as those of InfluxQL queries
+pub const MEASUREMENT_COLUMN_NAME: &str = "iox::measurement";
+
 /// The Timezone to use for InfluxDB timezone (should be a constant)
 #[allow(non_snake_case)]
 pub fn TIME_DATA_TIMEZONE() -> Option<String> {
@@ -22,6 +22,9 @@ pub trait QueryDatabaseProvider: std::fmt::Debug + Send + Sync + 'static {
     /// Get database if it exists.
     async fn db(&self, name: &str, span: Option<Span>) -> Option<Arc<Self::Db>>;
 
+    /// Names of all databases, sorted.
+    async fn db_names(&self) -> Vec<String>;
+
     /// Acquire concurrency-limiting sempahore
     async fn acquire_semaphore(&self, span: Option<Span>) -> InstrumentedAsyncOwnedSemaphorePermit;
 }
@@ -4,7 +4,11 @@ use std::sync::Arc;
 use datafusion::physical_plan::ExecutionPlan;
 use iox_query::{
     exec::IOxSessionContext,
-    frontend::{influxrpc::InfluxRpcPlanner, sql::SqlQueryPlanner},
+    frontend::{
+        influxql::{ast::Statement, InfluxQLPlan, InfluxQLPlanner},
+        influxrpc::InfluxRpcPlanner,
+        sql::{QueryParams, SqlQueryPlanner},
+    },
     plan::{fieldlist::FieldListPlan, seriesset::SeriesSetPlans, stringset::StringSetPlan},
     Aggregate, QueryDatabase, WindowDuration,
 };
@@ -34,12 +38,40 @@ impl Planner {
     /// Plan a SQL query against the data in `database`, and return a
     /// DataFusion physical execution plan.
     pub async fn sql(&self, query: impl Into<String> + Send) -> Result<Arc<dyn ExecutionPlan>> {
+        self.sql_with_params(query, QueryParams::default()).await
+    }
+
+    /// Plan a SQL query with `params` bound to its placeholders, and return
+    /// a DataFusion physical execution plan.
+    pub async fn sql_with_params(
+        &self,
+        query: impl Into<String> + Send,
+        params: QueryParams,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
         let planner = SqlQueryPlanner::new();
         let query = query.into();
         let ctx = self.ctx.child_ctx("planner sql");
 
         self.ctx
-            .run(async move { planner.query(&query, &ctx).await })
+            .run(async move { planner.query_with_params(&query, &params, &ctx).await })
+            .await
+    }
+
+    /// Creates a plan of a parsed InfluxQL statement as described on
+    /// [`InfluxQLPlanner::plan`], on a separate threadpool
+    pub async fn influxql<D>(&self, database: Arc<D>, statement: Statement) -> Result<InfluxQLPlan>
+    where
+        D: QueryDatabase + 'static,
+    {
+        let planner = InfluxQLPlanner::new(self.ctx.child_ctx("planner influxql"));
+
+        self.ctx
+            .run(async move {
+                planner
+                    .plan(database.as_ref(), &statement)
+                    .await
+                    .map_err(|e| Error::Plan(e.to_string()))
+            })
             .await
     }
 
@@ -67,6 +67,10 @@ impl QueryDatabaseProvider for TestDatabaseStore {
         databases.get(name).cloned()
     }
 
+    async fn db_names(&self) -> Vec<String> {
+        self.databases.lock().keys().cloned().collect()
+    }
+
     async fn acquire_semaphore(&self, span: Option<Span>) -> InstrumentedAsyncOwnedSemaphorePermit {
         Arc::clone(&self.query_semaphore)
             .acquire_owned(span)
@@ -0,0 +1,548 @@
+//! Arrow Flight SQL support, so that standard clients such as the JDBC, ODBC
+//! and ADBC Flight SQL drivers can query IOx.
+//!
+//! Flight SQL commands are `google.protobuf.Any` messages sent as the `cmd`
+//! of a [`FlightDescriptor`](arrow_flight::FlightDescriptor) to
+//! `GetFlightInfo`, whose ticket echoes the command back to `DoGet`. Flight
+//! SQL catalogs are IOx namespaces. Statements have no catalog, so they run
+//! against the namespace named in the [`NAMESPACE_HEADER`] request header.
+
+use std::sync::Arc;
+
+use arrow::{
+    array::{
+        make_array, Array, ArrayData, ArrayRef, BinaryArray, BooleanArray, Int32Array, Int64Array,
+        StringArray, UInt32Array,
+    },
+    buffer::Buffer,
+    datatypes::{DataType, Field, Schema, SchemaRef, UnionMode},
+    ipc::writer::IpcWriteOptions,
+    record_batch::RecordBatch,
+};
+use arrow_flight::{
+    sql::{
+        ActionClosePreparedStatementRequest, ActionCreatePreparedStatementRequest,
+        CommandGetCatalogs, CommandGetDbSchemas, CommandGetSqlInfo, CommandGetTables,
+        CommandPreparedStatementQuery, CommandStatementQuery, ProstMessageExt, SqlInfo,
+    },
+    IpcMessage, SchemaAsIpc,
+};
+use datafusion::{catalog::schema::SchemaProvider, logical_expr::TableType};
+use iox_query::{exec::ExecutionContextProvider, DEFAULT_CATALOG};
+use prost::Message;
+use prost_types::Any;
+use service_common::QueryDatabaseProvider;
+use snafu::{OptionExt, ResultExt};
+use tonic::metadata::MetadataMap;
+use trace::{ctx::SpanContext, span::SpanExt};
+
+use crate::{
+    EncodeSchemaSnafu, InvalidFlightSqlCommandSnafu, InvalidPreparedStatementSnafu, MetadataSnafu,
+    NamespaceHeaderMissingSnafu, Result, UnsupportedFlightSqlCommandSnafu,
+};
+
+/// Request header naming the namespace Flight SQL statements run against
+pub const NAMESPACE_HEADER: &str = "iox-namespace-name";
+
+/// Action creating a prepared statement
+pub(crate) const CREATE_PREPARED_STATEMENT: &str = "CreatePreparedStatement";
+
+/// Action closing a prepared statement
+pub(crate) const CLOSE_PREPARED_STATEMENT: &str = "ClosePreparedStatement";
+
+/// Type URLs of all Flight SQL messages start with this
+const TYPE_URL_PREFIX: &str = "type.googleapis.com/arrow.flight.protocol.sql.";
+
+/// Name of the server reported by `CommandGetSqlInfo`
+const SERVER_NAME: &str = "InfluxDB IOx";
+
+/// The Flight SQL commands IOx supports
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum FlightSqlCommand {
+    /// Run a SQL query, from a statement or a prepared statement
+    Query(String),
+
+    /// Return metadata about the namespaces and the server
+    Metadata(MetadataCommand),
+}
+
+/// Flight SQL commands returning metadata
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum MetadataCommand {
+    Catalogs,
+    DbSchemas(CommandGetDbSchemas),
+    Tables(CommandGetTables),
+    SqlInfo(CommandGetSqlInfo),
+}
+
+impl FlightSqlCommand {
+    /// Decodes the Flight SQL command in `cmd`, `None` if it is not a Flight
+    /// SQL command
+    pub(crate) fn try_decode(cmd: &[u8]) -> Result<Option<Self>> {
+        let any = match Any::decode(cmd) {
+            Ok(any) if any.type_url.starts_with(TYPE_URL_PREFIX) => any,
+            _ => return Ok(None),
+        };
+
+        let command = if any.type_url == CommandStatementQuery::type_url() {
+            Self::Query(unpack::<CommandStatementQuery>(&any)?.query)
+        } else if any.type_url == CommandPreparedStatementQuery::type_url() {
+            let handle = unpack::<CommandPreparedStatementQuery>(&any)?.prepared_statement_handle;
+            Self::Query(prepared_statement_query(handle)?)
+        } else if any.type_url == CommandGetCatalogs::type_url() {
+            unpack::<CommandGetCatalogs>(&any)?;
+            Self::Metadata(MetadataCommand::Catalogs)
+        } else if any.type_url == CommandGetDbSchemas::type_url() {
+            Self::Metadata(MetadataCommand::DbSchemas(unpack(&any)?))
+        } else if any.type_url == CommandGetTables::type_url() {
+            Self::Metadata(MetadataCommand::Tables(unpack(&any)?))
+        } else if any.type_url == CommandGetSqlInfo::type_url() {
+            Self::Metadata(MetadataCommand::SqlInfo(unpack(&any)?))
+        } else {
+            return UnsupportedFlightSqlCommandSnafu {
+                type_url: any.type_url,
+            }
+            .fail();
+        };
+
+        Ok(Some(command))
+    }
+}
+
+impl MetadataCommand {
+    /// The schema of the metadata, as defined by the Flight SQL protocol
+    pub(crate) fn schema(&self) -> SchemaRef {
+        let utf8 = |name: &str, nullable: bool| Field::new(name, DataType::Utf8, nullable);
+
+        let fields = match self {
+            Self::Catalogs => vec![utf8("catalog_name", false)],
+            Self::DbSchemas(_) => vec![utf8("catalog_name", true), utf8("db_schema_name", false)],
+            Self::Tables(cmd) => {
+                let mut fields = vec![
+                    utf8("catalog_name", true),
+                    utf8("db_schema_name", true),
+                    utf8("table_name", false),
+                    utf8("table_type", false),
+                ];
+                if cmd.include_schema {
+                    fields.push(Field::new("table_schema", DataType::Binary, false));
+                }
+                fields
+            }
+            Self::SqlInfo(_) => vec![
+                Field::new("info_name", DataType::UInt32, false),
+                Field::new("value", sql_info_value_type(), false),
+            ],
+        };
+
+        Arc::new(Schema::new(fields))
+    }
+
+    /// Gathers the metadata from the namespaces of `server`
+    pub(crate) async fn execute<S>(
+        &self,
+        server: &S,
+        span_ctx: Option<SpanContext>,
+    ) -> Result<RecordBatch>
+    where
+        S: QueryDatabaseProvider,
+    {
+        let columns: Vec<ArrayRef> = match self {
+            Self::Catalogs => {
+                let names = server.db_names().await;
+                vec![Arc::new(StringArray::from_iter_values(names))]
+            }
+            Self::DbSchemas(cmd) => {
+                let schemas = schemas(
+                    server,
+                    cmd.catalog.as_deref(),
+                    cmd.db_schema_filter_pattern.as_deref(),
+                    span_ctx,
+                )
+                .await;
+
+                vec![
+                    Arc::new(StringArray::from_iter_values(
+                        schemas.iter().map(|(catalog, _, _)| catalog),
+                    )),
+                    Arc::new(StringArray::from_iter_values(
+                        schemas.iter().map(|(_, schema, _)| schema),
+                    )),
+                ]
+            }
+            Self::Tables(cmd) => tables(server, cmd, span_ctx).await?,
+            Self::SqlInfo(cmd) => sql_info(&cmd.info)?,
+        };
+
+        RecordBatch::try_new(self.schema(), columns).context(MetadataSnafu)
+    }
+}
+
+/// The namespace named in the [`NAMESPACE_HEADER`] of a request
+pub(crate) fn namespace_header(metadata: &MetadataMap) -> Result<String> {
+    metadata
+        .get(NAMESPACE_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .map(ToString::to_string)
+        .context(NamespaceHeaderMissingSnafu)
+}
+
+/// Decodes the `Any` in the body of a Flight SQL action
+pub(crate) fn unpack_action<M>(body: &[u8]) -> Result<M>
+where
+    M: ProstMessageExt,
+{
+    let any = Any::decode(body).context(InvalidFlightSqlCommandSnafu)?;
+    if any.type_url != M::type_url() {
+        return UnsupportedFlightSqlCommandSnafu {
+            type_url: any.type_url,
+        }
+        .fail();
+    }
+    unpack(&any)
+}
+
+/// The handle of a prepared statement, which is simply its query: IOx plans
+/// queries for every execution, so there is no state to keep between
+/// creating and closing the statement.
+pub(crate) fn prepared_statement_handle(request: ActionCreatePreparedStatementRequest) -> Vec<u8> {
+    request.query.into_bytes()
+}
+
+/// Checks the handle of a statement being closed
+pub(crate) fn close_prepared_statement(request: ActionClosePreparedStatementRequest) -> Result<()> {
+    prepared_statement_query(request.prepared_statement_handle).map(|_| ())
+}
+
+/// Encodes `schema` as an IPC message, the way Flight SQL results carry
+/// schemas
+pub(crate) fn encode_schema(schema: &Schema) -> Result<Vec<u8>> {
+    let options = IpcWriteOptions::default();
+    let IpcMessage(bytes) =
+        IpcMessage::try_from(SchemaAsIpc::new(schema, &options)).context(EncodeSchemaSnafu)?;
+    Ok(bytes)
+}
+
+fn unpack<M>(any: &Any) -> Result<M>
+where
+    M: ProstMessageExt,
+{
+    M::decode(any.value.as_slice()).context(InvalidFlightSqlCommandSnafu)
+}
+
+fn prepared_statement_query(handle: Vec<u8>) -> Result<String> {
+    String::from_utf8(handle).context(InvalidPreparedStatementSnafu)
+}
+
+/// The (namespace, schema name, schema) of every schema of the namespaces
+/// matching `catalog` whose name matches `db_schema_filter_pattern`
+async fn schemas<S>(
+    server: &S,
+    catalog: Option<&str>,
+    db_schema_filter_pattern: Option<&str>,
+    span_ctx: Option<SpanContext>,
+) -> Vec<(String, String, Arc<dyn SchemaProvider>)>
+where
+    S: QueryDatabaseProvider,
+{
+    let mut schemas = vec![];
+
+    for namespace in server.db_names().await {
+        if catalog.map_or(false, |catalog| catalog != namespace) {
+            continue;
+        }
+
+        let db = match server
+            .db(&namespace, span_ctx.child_span("get namespace"))
+            .await
+        {
+            Some(db) => db,
+            // removed since listing the names
+            None => continue,
+        };
+        let ctx = db.new_query_context(span_ctx.clone());
+        let catalog = match ctx.inner().catalog(DEFAULT_CATALOG) {
+            Some(catalog) => catalog,
+            None => continue,
+        };
+
+        let mut schema_names = catalog.schema_names();
+        schema_names.sort_unstable();
+
+        for schema_name in schema_names {
+            if !db_schema_filter_pattern.map_or(true, |pattern| like(pattern, &schema_name)) {
+                continue;
+            }
+            if let Some(schema) = catalog.schema(&schema_name) {
+                schemas.push((namespace.clone(), schema_name, schema));
+            }
+        }
+    }
+
+    schemas
+}
+
+async fn tables<S>(
+    server: &S,
+    cmd: &CommandGetTables,
+    span_ctx: Option<SpanContext>,
+) -> Result<Vec<ArrayRef>>
+where
+    S: QueryDatabaseProvider,
+{
+    let schemas = schemas(
+        server,
+        cmd.catalog.as_deref(),
+        cmd.db_schema_filter_pattern.as_deref(),
+        span_ctx,
+    )
+    .await;
+
+    let mut catalog_names = vec![];
+    let mut db_schema_names = vec![];
+    let mut table_names = vec![];
+    let mut table_types = vec![];
+    let mut table_schemas = vec![];
+
+    for (namespace, schema_name, schema) in schemas {
+        let mut names = schema.table_names();
+        names.sort_unstable();
+
+        for table_name in names {
+            if !cmd
+                .table_name_filter_pattern
+                .as_deref()
+                .map_or(true, |pattern| like(pattern, &table_name))
+            {
+                continue;
+            }
+            let table = match schema.table(&table_name) {
+                Some(table) => table,
+                None => continue,
+            };
+
+            let table_type = match table.table_type() {
+                TableType::Base => "TABLE",
+                TableType::View => "VIEW",
+                TableType::Temporary => "LOCAL TEMPORARY",
+            };
+            if !cmd.table_types.is_empty() && !cmd.table_types.iter().any(|t| t == table_type) {
+                continue;
+            }
+
+            if cmd.include_schema {
+                table_schemas.push(encode_schema(&table.schema())?);
+            }
+            catalog_names.push(namespace.clone());
+            db_schema_names.push(schema_name.clone());
+            table_names.push(table_name);
+            table_types.push(table_type);
+        }
+    }
+
+    let mut columns: Vec<ArrayRef> = vec![
+        Arc::new(StringArray::from_iter_values(catalog_names)),
+        Arc::new(StringArray::from_iter_values(db_schema_names)),
+        Arc::new(StringArray::from_iter_values(table_names)),
+        Arc::new(StringArray::from_iter_values(table_types)),
+    ];
+    if cmd.include_schema {
+        columns.push(Arc::new(BinaryArray::from(
+            table_schemas.iter().map(Vec::as_slice).collect::<Vec<_>>(),
+        )));
+    }
+
+    Ok(columns)
+}
+
+/// The type of the `value` column of `CommandGetSqlInfo` results.
+///
+/// The protocol defines further members for list and map values, which none
+/// of the information IOx reports needs.
+fn sql_info_value_type() -> DataType {
+    DataType::Union(
+        vec![
+            Field::new("string_value", DataType::Utf8, false),
+            Field::new("bool_value", DataType::Boolean, false),
+            Field::new("bigint_value", DataType::Int64, false),
+            Field::new("int32_bitmask", DataType::Int32, false),
+        ],
+        vec![0, 1, 2, 3],
+        UnionMode::Dense,
+    )
+}
+
+/// A value of the `value` union of `CommandGetSqlInfo` results
+enum SqlInfoValue {
+    String(&'static str),
+    Bool(bool),
+}
+
+/// The information IOx reports for `CommandGetSqlInfo`, restricted to `info`
+/// unless it is empty
+fn sql_info(info: &[u32]) -> Result<Vec<ArrayRef>> {
+    let all = [
+        (
+            SqlInfo::FlightSqlServerName,
+            SqlInfoValue::String(SERVER_NAME),
+        ),
+        (
+            SqlInfo::FlightSqlServerVersion,
+            SqlInfoValue::String(env!("CARGO_PKG_VERSION")),
+        ),
+        (SqlInfo::FlightSqlServerReadOnly, SqlInfoValue::Bool(true)),
+    ];
+
+    let mut names = vec![];
+    let mut type_ids = vec![];
+    let mut offsets = vec![];
+    let mut strings = vec![];
+    let mut bools = vec![];
+
+    for (name, value) in all {
+        let name = name as u32;
+        if !info.is_empty() && !info.contains(&name) {
+            continue;
+        }
+
+        names.push(name);
+        match value {
+            SqlInfoValue::String(v) => {
+                type_ids.push(0_i8);
+                offsets.push(strings.len() as i32);
+                strings.push(v);
+            }
+            SqlInfoValue::Bool(v) => {
+                type_ids.push(1_i8);
+                offsets.push(bools.len() as i32);
+                bools.push(v);
+            }
+        }
+    }
+
+    let values = ArrayData::builder(sql_info_value_type())
+        .len(names.len())
+        .add_buffer(Buffer::from_slice_ref(&type_ids))
+        .add_buffer(Buffer::from_slice_ref(&offsets))
+        .child_data(vec![
+            StringArray::from(strings).data().clone(),
+            BooleanArray::from(bools).data().clone(),
+            Int64Array::from(Vec::<i64>::new()).data().clone(),
+            Int32Array::from(Vec::<i32>::new()).data().clone(),
+        ])
+        .build()
+        .context(MetadataSnafu)?;
+
+    Ok(vec![Arc::new(UInt32Array::from(names)), make_array(values)])
+}
+
+/// Matches `value` against a SQL `LIKE` pattern, in which `%` matches any
+/// sequence of characters and `_` any single character
+fn like(pattern: &str, value: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let value: Vec<char> = value.chars().collect();
+
+    // matches[j] is true if the pattern so far matches value[..j]
+    let mut matches = vec![false; value.len() + 1];
+    matches[0] = true;
+
+    for p in pattern {
+        let mut next = vec![false; value.len() + 1];
+        if p == '%' {
+            let mut any = false;
+            for (j, m) in matches.iter().enumerate() {
+                any |= *m;
+                next[j] = any;
+            }
+        } else {
+            for j in 0..value.len() {
+                next[j + 1] = matches[j] && (p == '_' || p == value[j]);
+            }
+        }
+        matches = next;
+    }
+
+    matches[value.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn like_patterns() {
+        assert!(like("cpu", "cpu"));
+        assert!(!like("cpu", "cpu2"));
+        assert!(like("c_u", "cpu"));
+        assert!(!like("c_u", "cu"));
+        assert!(like("%", ""));
+        assert!(like("c%", "cpu"));
+        assert!(like("%u%", "cpu"));
+        assert!(like("%p%u", "cpu"));
+        assert!(!like("%x%", "cpu"));
+        assert!(!like("", "cpu"));
+    }
+
+    #[test]
+    fn decodes_commands() {
+        let cmd = CommandStatementQuery {
+            query: "SELECT 1".to_string(),
+        }
+        .as_any()
+        .encode_to_vec();
+        assert_eq!(
+            FlightSqlCommand::try_decode(&cmd).unwrap(),
+            Some(FlightSqlCommand::Query("SELECT 1".to_string()))
+        );
+
+        let cmd = CommandPreparedStatementQuery {
+            prepared_statement_handle: prepared_statement_handle(
+                ActionCreatePreparedStatementRequest {
+                    query: "SELECT 2".to_string(),
+                },
+            ),
+        }
+        .as_any()
+        .encode_to_vec();
+        assert_eq!(
+            FlightSqlCommand::try_decode(&cmd).unwrap(),
+            Some(FlightSqlCommand::Query("SELECT 2".to_string()))
+        );
+
+        let cmd = CommandGetCatalogs {}.as_any().encode_to_vec();
+        assert_eq!(
+            FlightSqlCommand::try_decode(&cmd).unwrap(),
+            Some(FlightSqlCommand::Metadata(MetadataCommand::Catalogs))
+        );
+
+        // IOx tickets are not Flight SQL commands
+        assert_eq!(
+            FlightSqlCommand::try_decode(
+                br#"{"database_name": "my_db", "sql_query": "SELECT 1;"}"#
+            )
+            .unwrap(),
+            None
+        );
+
+        let cmd = Any {
+            type_url: format!("{}CommandStatementUpdate", TYPE_URL_PREFIX),
+            value: vec![],
+        }
+        .encode_to_vec();
+        let error = FlightSqlCommand::try_decode(&cmd).unwrap_err().to_string();
+        assert!(error.contains("CommandStatementUpdate"), "{}", error);
+    }
+
+    #[test]
+    fn sql_info_values() {
+        let read_only = SqlInfo::FlightSqlServerReadOnly as u32;
+        let columns = sql_info(&[read_only]).unwrap();
+        let names = columns[0].as_any().downcast_ref::<UInt32Array>().unwrap();
+        assert_eq!(names.values(), &[read_only]);
+        assert_eq!(columns[1].len(), 1);
+
+        let columns = sql_info(&[]).unwrap();
+        assert_eq!(columns[0].len(), 3);
+        assert_eq!(columns[1].data_type(), &sql_info_value_type());
+    }
+}
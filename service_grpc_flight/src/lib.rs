@@ -1,19 +1,29 @@
 //! Implements the native gRPC IOx query API using Arrow Flight
 
-use arrow::error::ArrowError;
+mod flightsql;
+
+pub use flightsql::NAMESPACE_HEADER;
+
+use arrow::{datatypes::SchemaRef, error::ArrowError, record_batch::RecordBatch};
 use arrow_flight::{
     flight_service_server::{FlightService as Flight, FlightServiceServer as FlightServer},
-    Action, ActionType, Criteria, Empty, FlightData, FlightDescriptor, FlightInfo,
+    sql::{
+        ActionClosePreparedStatementRequest, ActionCreatePreparedStatementRequest,
+        ActionCreatePreparedStatementResult, ProstMessageExt,
+    },
+    Action, ActionType, Criteria, Empty, FlightData, FlightDescriptor, FlightEndpoint, FlightInfo,
     HandshakeRequest, HandshakeResponse, PutResult, SchemaAsIpc, SchemaResult, Ticket,
 };
 use arrow_util::optimize::{optimize_record_batch, optimize_schema};
 use bytes::{Bytes, BytesMut};
 use data_types::{DatabaseName, DatabaseNameError};
-use datafusion::physical_plan::ExecutionPlan;
+use datafusion::{physical_plan::ExecutionPlan, scalar::ScalarValue};
+use flightsql::FlightSqlCommand;
 use futures::{SinkExt, Stream, StreamExt};
 use generated_types::influxdata::iox::querier::v1 as proto;
 use iox_query::{
     exec::{ExecutionContextProvider, IOxSessionContext},
+    frontend::{influxql::InfluxQLPlanner, sql::QueryParams},
     QueryCompletedToken, QueryDatabase,
 };
 use observability_deps::tracing::{info, warn};
@@ -21,7 +31,7 @@ use pin_project::{pin_project, pinned_drop};
 use prost::Message;
 use serde::Deserialize;
 use service_common::{planner::Planner, QueryDatabaseProvider};
-use snafu::{ResultExt, Snafu};
+use snafu::{ensure, ResultExt, Snafu};
 use std::{fmt::Debug, pin::Pin, sync::Arc, task::Poll};
 use tokio::task::JoinHandle;
 use tonic::{Request, Response, Streaming};
@@ -70,6 +80,38 @@ pub enum Error {
 
     #[snafu(display("Error during protobuf serialization: {}", source))]
     Serialization { source: prost::EncodeError },
+
+    #[snafu(display("Invalid Flight SQL command: {}", source))]
+    InvalidFlightSqlCommand { source: prost::DecodeError },
+
+    #[snafu(display("Unsupported Flight SQL command: {}", type_url))]
+    UnsupportedFlightSqlCommand { type_url: String },
+
+    #[snafu(display("Invalid prepared statement handle: {}", source))]
+    InvalidPreparedStatement { source: std::string::FromUtf8Error },
+
+    #[snafu(display(
+        "Flight SQL statements need the namespace in the {} header",
+        flightsql::NAMESPACE_HEADER
+    ))]
+    NamespaceHeaderMissing,
+
+    #[snafu(display("Error building Flight SQL metadata: {}", source))]
+    Metadata { source: ArrowError },
+
+    #[snafu(display("Error encoding schema: {}", source))]
+    EncodeSchema { source: ArrowError },
+
+    #[snafu(display("Invalid InfluxQL query: {}", source))]
+    InvalidInfluxQL {
+        source: iox_query::frontend::influxql::Error,
+    },
+
+    #[snafu(display("InfluxQL queries must have exactly one statement, got {}", count))]
+    InfluxQLStatementCount { count: usize },
+
+    #[snafu(display("Unknown query type: {}", query_type))]
+    UnknownQueryType { query_type: i32 },
 }
 pub type Result<T, E = Error> = std::result::Result<T, E>;
 
@@ -85,11 +127,21 @@ impl From<Error> for tonic::Status {
             | Error::InvalidTicket { .. }
             | Error::InvalidTicketLegacy { .. }
             | Error::InvalidQuery { .. }
+            | Error::InvalidFlightSqlCommand { .. }
+            | Error::UnsupportedFlightSqlCommand { .. }
+            | Error::InvalidPreparedStatement { .. }
+            | Error::NamespaceHeaderMissing
+            | Error::InvalidInfluxQL { .. }
+            | Error::InfluxQLStatementCount { .. }
+            | Error::UnknownQueryType { .. }
             // TODO(edd): this should be `debug`. Keeping at info whilst IOx still in early development
             | Error::InvalidDatabaseName { .. } => info!(?err, msg),
             Error::Query { .. } => info!(?err, msg),
             Error::Optimize { .. }
-            | Error::Planning { .. } | Error::Serialization { .. } => warn!(?err, msg),
+            | Error::Planning { .. }
+            | Error::Serialization { .. }
+            | Error::Metadata { .. }
+            | Error::EncodeSchema { .. } => warn!(?err, msg),
         }
         err.to_status()
     }
@@ -113,6 +165,15 @@ impl Error {
             Self::Planning { .. } => Status::invalid_argument(self.to_string()),
             Self::Optimize { .. } => Status::internal(self.to_string()),
             Self::Serialization { .. } => Status::internal(self.to_string()),
+            Self::InvalidFlightSqlCommand { .. } => Status::invalid_argument(self.to_string()),
+            Self::UnsupportedFlightSqlCommand { .. } => Status::unimplemented(self.to_string()),
+            Self::InvalidPreparedStatement { .. } => Status::invalid_argument(self.to_string()),
+            Self::NamespaceHeaderMissing => Status::invalid_argument(self.to_string()),
+            Self::Metadata { .. } => Status::internal(self.to_string()),
+            Self::EncodeSchema { .. } => Status::internal(self.to_string()),
+            Self::InvalidInfluxQL { .. } => Status::invalid_argument(self.to_string()),
+            Self::InfluxQLStatementCount { .. } => Status::invalid_argument(self.to_string()),
+            Self::UnknownQueryType { .. } => Status::invalid_argument(self.to_string()),
         }
     }
 }
@@ -124,6 +185,22 @@ type TonicStream<T> = Pin<Box<dyn Stream<Item = Result<T, tonic::Status>> + Send
 struct ReadInfo {
     database_name: String,
     sql_query: String,
+
+    /// Only protobuf tickets carry parameters
+    #[serde(skip)]
+    params: QueryParams,
+
+    /// Legacy tickets are always SQL
+    #[serde(skip)]
+    query_type: QueryType,
+}
+
+/// Language of the query of a ticket
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+enum QueryType {
+    #[default]
+    Sql,
+    InfluxQL,
 }
 
 impl ReadInfo {
@@ -140,13 +217,51 @@ impl ReadInfo {
         let read_info =
             proto::ReadInfo::decode(Bytes::from(ticket.to_vec())).context(InvalidTicketSnafu {})?;
 
+        let query_type = match proto::QueryType::from_i32(read_info.query_type) {
+            Some(proto::QueryType::Unspecified | proto::QueryType::Sql) => QueryType::Sql,
+            Some(proto::QueryType::Influxql) => QueryType::InfluxQL,
+            None => {
+                return UnknownQueryTypeSnafu {
+                    query_type: read_info.query_type,
+                }
+                .fail()
+            }
+        };
+
         Ok(Self {
             database_name: read_info.namespace_name,
             sql_query: read_info.sql_query,
+            params: query_params(read_info.params),
+            query_type,
         })
     }
 }
 
+/// Converts the parameters of a ticket into values DataFusion can bind
+fn query_params(params: Vec<proto::QueryParam>) -> QueryParams {
+    use proto::query_param::Value;
+
+    params
+        .into_iter()
+        .fold(QueryParams::new(), |params, param| {
+            let value = match param.value {
+                // the type of NULL doesn't matter for a SQL literal
+                None => ScalarValue::Utf8(None),
+                Some(Value::BoolValue(v)) => ScalarValue::Boolean(Some(v)),
+                Some(Value::IntValue(v)) => ScalarValue::Int64(Some(v)),
+                Some(Value::UintValue(v)) => ScalarValue::UInt64(Some(v)),
+                Some(Value::FloatValue(v)) => ScalarValue::Float64(Some(v)),
+                Some(Value::StringValue(v)) => ScalarValue::Utf8(Some(v)),
+            };
+
+            if param.name.is_empty() {
+                params.with_positional(value)
+            } else {
+                params.with_named(param.name, value)
+            }
+        })
+}
+
 /// Concrete implementation of the gRPC Arrow Flight Service API
 #[derive(Debug)]
 struct FlightService<S>
@@ -156,11 +271,20 @@ where
     server: Arc<S>,
 }
 
+impl<S> FlightService<S>
+where
+    S: QueryDatabaseProvider,
+{
+    fn new(server: Arc<S>) -> Self {
+        Self { server }
+    }
+}
+
 pub fn make_server<S>(server: Arc<S>) -> FlightServer<impl Flight>
 where
     S: QueryDatabaseProvider,
 {
-    FlightServer::new(FlightService { server })
+    FlightServer::new(FlightService::new(server))
 }
 
 #[tonic::async_trait]
@@ -178,9 +302,19 @@ where
 
     async fn get_schema(
         &self,
-        _request: Request<FlightDescriptor>,
+        request: Request<FlightDescriptor>,
     ) -> Result<Response<SchemaResult>, tonic::Status> {
-        Err(tonic::Status::unimplemented("Not yet implemented"))
+        let span_ctx: Option<SpanContext> = request.extensions().get().cloned();
+        let namespace = flightsql::namespace_header(request.metadata());
+        let descriptor = request.into_inner();
+
+        let cmd = FlightSqlCommand::try_decode(&descriptor.cmd)?.ok_or_else(|| {
+            tonic::Status::unimplemented("GetSchema only supports Flight SQL commands")
+        })?;
+        let schema = self.flight_sql_schema(cmd, namespace, span_ctx).await?;
+
+        let options = arrow::ipc::writer::IpcWriteOptions::default();
+        Ok(Response::new(SchemaAsIpc::new(&schema, &options).into()))
     }
 
     async fn do_get(
@@ -189,8 +323,27 @@ where
     ) -> Result<Response<Self::DoGetStream>, tonic::Status> {
         let external_span_ctx: Option<RequestLogContext> = request.extensions().get().cloned();
         let span_ctx: Option<SpanContext> = request.extensions().get().cloned();
+        let namespace = flightsql::namespace_header(request.metadata());
         let ticket = request.into_inner();
 
+        // Flight SQL clients send back the command of the flight info as the ticket
+        match FlightSqlCommand::try_decode(&ticket.ticket)? {
+            Some(FlightSqlCommand::Query(query)) => {
+                let read_info = ReadInfo {
+                    database_name: namespace?,
+                    sql_query: query,
+                    params: QueryParams::new(),
+                    query_type: QueryType::Sql,
+                };
+                return self.run_query(read_info, span_ctx, external_span_ctx).await;
+            }
+            Some(FlightSqlCommand::Metadata(cmd)) => {
+                let batch = cmd.execute(self.server.as_ref(), span_ctx).await?;
+                return Ok(Response::new(batch_stream(&batch)));
+            }
+            None => {}
+        }
+
         // decode ticket
         let read_info = match ReadInfo::decode_protobuf(&ticket.ticket) {
             Ok(read_info) => read_info,
@@ -200,45 +353,7 @@ where
             }
         };
 
-        let permit = self
-            .server
-            .acquire_semaphore(span_ctx.child_span("query rate limit semaphore"))
-            .await;
-        info!(
-            db_name=%read_info.database_name,
-            sql_query=%read_info.sql_query,
-            trace=%external_span_ctx.format_jaeger(),
-            "flight do_get",
-        );
-
-        let database =
-            DatabaseName::new(&read_info.database_name).context(InvalidDatabaseNameSnafu)?;
-
-        let db = self
-            .server
-            .db(&database, span_ctx.child_span("get namespace"))
-            .await
-            .ok_or_else(|| tonic::Status::not_found(format!("Unknown namespace: {database}")))?;
-
-        let ctx = db.new_query_context(span_ctx);
-        let query_completed_token =
-            db.record_query(&ctx, "sql", Box::new(read_info.sql_query.clone()));
-
-        let physical_plan = Planner::new(&ctx)
-            .sql(&read_info.sql_query)
-            .await
-            .context(PlanningSnafu)?;
-
-        let output = GetStream::new(
-            ctx,
-            physical_plan,
-            read_info.database_name,
-            query_completed_token,
-            permit,
-        )
-        .await?;
-
-        Ok(Response::new(Box::pin(output) as Self::DoGetStream))
+        self.run_query(read_info, span_ctx, external_span_ctx).await
     }
 
     async fn handshake(
@@ -263,9 +378,32 @@ where
 
     async fn get_flight_info(
         &self,
-        _request: Request<FlightDescriptor>,
+        request: Request<FlightDescriptor>,
     ) -> Result<Response<FlightInfo>, tonic::Status> {
-        Err(tonic::Status::unimplemented("Not yet implemented"))
+        let span_ctx: Option<SpanContext> = request.extensions().get().cloned();
+        let namespace = flightsql::namespace_header(request.metadata());
+        let descriptor = request.into_inner();
+
+        let cmd = FlightSqlCommand::try_decode(&descriptor.cmd)?.ok_or_else(|| {
+            tonic::Status::unimplemented("GetFlightInfo only supports Flight SQL commands")
+        })?;
+        let schema = self.flight_sql_schema(cmd, namespace, span_ctx).await?;
+
+        // the command is all DoGet needs to run it
+        let endpoint = FlightEndpoint {
+            ticket: Some(Ticket {
+                ticket: descriptor.cmd.clone(),
+            }),
+            location: vec![],
+        };
+
+        Ok(Response::new(FlightInfo {
+            schema: flightsql::encode_schema(&schema)?,
+            flight_descriptor: Some(descriptor),
+            endpoint: vec![endpoint],
+            total_records: -1,
+            total_bytes: -1,
+        }))
     }
 
     async fn do_put(
@@ -277,16 +415,75 @@ where
 
     async fn do_action(
         &self,
-        _request: Request<Action>,
+        request: Request<Action>,
     ) -> Result<Response<Self::DoActionStream>, tonic::Status> {
-        Err(tonic::Status::unimplemented("Not yet implemented"))
+        let span_ctx: Option<SpanContext> = request.extensions().get().cloned();
+        let namespace = flightsql::namespace_header(request.metadata());
+        let action = request.into_inner();
+
+        let results = match action.r#type.as_str() {
+            flightsql::CREATE_PREPARED_STATEMENT => {
+                let request: ActionCreatePreparedStatementRequest =
+                    flightsql::unpack_action(&action.body)?;
+
+                // planning the query reports errors early and tells the client the schema
+                let cmd = FlightSqlCommand::Query(request.query.clone());
+                let schema = self.flight_sql_schema(cmd, namespace, span_ctx).await?;
+
+                let result = ActionCreatePreparedStatementResult {
+                    prepared_statement_handle: flightsql::prepared_statement_handle(request),
+                    dataset_schema: flightsql::encode_schema(&schema)?,
+                    parameter_schema: vec![],
+                };
+                vec![Ok(arrow_flight::Result {
+                    body: result.as_any().encode_to_vec(),
+                })]
+            }
+            flightsql::CLOSE_PREPARED_STATEMENT => {
+                let request: ActionClosePreparedStatementRequest =
+                    flightsql::unpack_action(&action.body)?;
+                flightsql::close_prepared_statement(request)?;
+                vec![]
+            }
+            other => {
+                return Err(tonic::Status::unimplemented(format!(
+                    "Unsupported action: {}",
+                    other
+                )))
+            }
+        };
+
+        Ok(Response::new(
+            Box::pin(futures::stream::iter(results)) as Self::DoActionStream
+        ))
     }
 
     async fn list_actions(
         &self,
         _request: Request<Empty>,
     ) -> Result<Response<Self::ListActionsStream>, tonic::Status> {
-        Err(tonic::Status::unimplemented("Not yet implemented"))
+        let actions = [
+            (
+                flightsql::CREATE_PREPARED_STATEMENT,
+                "Creates a Flight SQL prepared statement",
+            ),
+            (
+                flightsql::CLOSE_PREPARED_STATEMENT,
+                "Closes a Flight SQL prepared statement",
+            ),
+        ]
+        .into_iter()
+        .map(|(r#type, description)| {
+            Ok(ActionType {
+                r#type: r#type.to_string(),
+                description: description.to_string(),
+            })
+        })
+        .collect::<Vec<_>>();
+
+        Ok(Response::new(
+            Box::pin(futures::stream::iter(actions)) as Self::ListActionsStream
+        ))
     }
 
     async fn do_exchange(
@@ -297,6 +494,131 @@ where
     }
 }
 
+impl<S> FlightService<S>
+where
+    S: QueryDatabaseProvider,
+{
+    /// Plans and runs the query of `read_info`, streaming its results
+    async fn run_query(
+        &self,
+        read_info: ReadInfo,
+        span_ctx: Option<SpanContext>,
+        external_span_ctx: Option<RequestLogContext>,
+    ) -> Result<Response<TonicStream<FlightData>>, tonic::Status> {
+        let permit = self
+            .server
+            .acquire_semaphore(span_ctx.child_span("query rate limit semaphore"))
+            .await;
+        info!(
+            db_name=%read_info.database_name,
+            sql_query=%read_info.sql_query,
+            trace=%external_span_ctx.format_jaeger(),
+            "flight do_get",
+        );
+
+        let database =
+            DatabaseName::new(&read_info.database_name).context(InvalidDatabaseNameSnafu)?;
+
+        let db = self
+            .server
+            .db(&database, span_ctx.child_span("get namespace"))
+            .await
+            .ok_or_else(|| tonic::Status::not_found(format!("Unknown namespace: {database}")))?;
+
+        let ctx = db.new_query_context(span_ctx);
+        let (physical_plan, query_completed_token) = match read_info.query_type {
+            QueryType::Sql => {
+                let query_completed_token =
+                    db.record_query(&ctx, "sql", Box::new(read_info.sql_query.clone()));
+                let plan = Planner::new(&ctx)
+                    .sql_with_params(&read_info.sql_query, read_info.params)
+                    .await
+                    .context(PlanningSnafu)?;
+                (plan, query_completed_token)
+            }
+            QueryType::InfluxQL => {
+                let mut statements =
+                    InfluxQLPlanner::parse(&read_info.sql_query).context(InvalidInfluxQLSnafu)?;
+                ensure!(
+                    statements.len() == 1,
+                    InfluxQLStatementCountSnafu {
+                        count: statements.len()
+                    }
+                );
+
+                let query_completed_token =
+                    db.record_query(&ctx, "influxql", Box::new(read_info.sql_query.clone()));
+                let plan = Planner::new(&ctx)
+                    .influxql(Arc::clone(&db), statements.remove(0))
+                    .await
+                    .context(PlanningSnafu)?;
+                let plan = ctx
+                    .create_physical_plan(&plan.plan)
+                    .await
+                    .context(PlanningSnafu)?;
+                (plan, query_completed_token)
+            }
+        };
+
+        let output = GetStream::new(
+            ctx,
+            physical_plan,
+            read_info.database_name,
+            query_completed_token,
+            permit,
+        )
+        .await?;
+
+        Ok(Response::new(Box::pin(output) as TonicStream<FlightData>))
+    }
+
+    /// The schema of the results of a Flight SQL command, as streamed by
+    /// [`GetStream`]. Queries are planned against the namespace of the
+    /// request header.
+    async fn flight_sql_schema(
+        &self,
+        cmd: FlightSqlCommand,
+        namespace: Result<String>,
+        span_ctx: Option<SpanContext>,
+    ) -> Result<SchemaRef, tonic::Status> {
+        let query = match cmd {
+            FlightSqlCommand::Query(query) => query,
+            FlightSqlCommand::Metadata(cmd) => return Ok(cmd.schema()),
+        };
+
+        let namespace = namespace?;
+        let database = DatabaseName::new(&namespace).context(InvalidDatabaseNameSnafu)?;
+        let db = self
+            .server
+            .db(&database, span_ctx.child_span("get namespace"))
+            .await
+            .ok_or_else(|| tonic::Status::not_found(format!("Unknown namespace: {database}")))?;
+
+        let ctx = db.new_query_context(span_ctx);
+        let physical_plan = Planner::new(&ctx)
+            .sql(&query)
+            .await
+            .context(PlanningSnafu)?;
+
+        Ok(Arc::new(optimize_schema(&physical_plan.schema())))
+    }
+}
+
+/// Streams the schema and data of `batch`
+fn batch_stream(batch: &RecordBatch) -> TonicStream<FlightData> {
+    let options = arrow::ipc::writer::IpcWriteOptions::default();
+    let schema = FlightData::from(SchemaAsIpc::new(&batch.schema(), &options));
+    let (dictionaries, data) = arrow_flight::utils::flight_data_from_arrow_batch(batch, &options);
+
+    let flight_data: Vec<_> = std::iter::once(schema)
+        .chain(dictionaries)
+        .chain(std::iter::once(data))
+        .map(Ok)
+        .collect();
+
+    Box::pin(futures::stream::iter(flight_data))
+}
+
 #[pin_project(PinnedDrop)]
 struct GetStream {
     #[pin]
@@ -473,9 +795,7 @@ mod tests {
         // add some data
         test_storage.db_or_create("my_db").await;
 
-        let service = FlightService {
-            server: Arc::clone(&test_storage),
-        };
+        let service = FlightService::new(Arc::clone(&test_storage));
         let ticket = Ticket {
             ticket: br#"{"database_name": "my_db", "sql_query": "SELECT 1;"}"#.to_vec(),
         };
@@ -582,6 +902,352 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_decode_params() {
+        use proto::query_param::Value;
+
+        let ticket = proto::ReadInfo {
+            namespace_name: "my_db".to_string(),
+            sql_query: "SELECT * FROM cpu WHERE host = $host AND usage > $1".to_string(),
+            params: vec![
+                proto::QueryParam::named("host", Some(Value::StringValue("a'b".to_string()))),
+                proto::QueryParam::positional(Some(Value::FloatValue(1.5))),
+                proto::QueryParam::positional(None),
+            ],
+            query_type: proto::QueryType::Sql.into(),
+        }
+        .encode_to_vec();
+
+        let read_info = ReadInfo::decode_protobuf(&ticket).unwrap();
+        assert_eq!(
+            read_info.params,
+            QueryParams::new()
+                .with_named("host", ScalarValue::Utf8(Some("a'b".to_string())))
+                .with_positional(ScalarValue::Float64(Some(1.5)))
+                .with_positional(ScalarValue::Utf8(None))
+        );
+
+        // legacy tickets have no parameters
+        let read_info =
+            ReadInfo::decode_json(br#"{"database_name": "my_db", "sql_query": "SELECT 1;"}"#)
+                .unwrap();
+        assert!(read_info.params.is_empty());
+        assert_eq!(read_info.query_type, QueryType::Sql);
+    }
+
+    #[tokio::test]
+    async fn test_influxql_ticket() {
+        use iox_query::test::TestChunk;
+
+        let test_storage = Arc::new(TestDatabaseStore::new());
+        test_storage.db_or_create("my_db").await.add_chunk(
+            "p1",
+            Arc::new(TestChunk::new("h2o").with_id(1).with_time_column()),
+        );
+        let service = FlightService::new(Arc::clone(&test_storage));
+
+        let ticket = |query: &str| Ticket {
+            ticket: proto::ReadInfo {
+                namespace_name: "my_db".to_string(),
+                sql_query: query.to_string(),
+                params: vec![],
+                query_type: proto::QueryType::Influxql.into(),
+            }
+            .encode_to_vec(),
+        };
+
+        let batches = collect(
+            service
+                .do_get(tonic::Request::new(ticket("SHOW MEASUREMENTS")))
+                .await
+                .unwrap(),
+        )
+        .await;
+        arrow_util::assert_batches_eq!(
+            &[
+                "+------------------+------+",
+                "| iox::measurement | name |",
+                "+------------------+------+",
+                "| measurements     | h2o  |",
+                "+------------------+------+",
+            ],
+            &batches
+        );
+
+        let status = service
+            .do_get(tonic::Request::new(ticket(
+                "SHOW MEASUREMENTS; SHOW TAG KEYS",
+            )))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), tonic::Code::InvalidArgument);
+        assert_eq!(
+            status.message(),
+            "InfluxQL queries must have exactly one statement, got 2"
+        );
+
+        let status = service
+            .do_get(tonic::Request::new(ticket("SELECT FROM")))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), tonic::Code::InvalidArgument);
+    }
+
+    #[tokio::test]
+    async fn test_flight_sql_statement() {
+        use arrow_flight::sql::CommandStatementQuery;
+
+        let test_storage = Arc::new(TestDatabaseStore::new());
+        test_storage.db_or_create("my_db").await;
+        let service = FlightService::new(Arc::clone(&test_storage));
+
+        let cmd = CommandStatementQuery {
+            query: "SELECT 1 AS one".to_string(),
+        }
+        .as_any()
+        .encode_to_vec();
+        let descriptor = FlightDescriptor {
+            r#type: arrow_flight::flight_descriptor::DescriptorType::Cmd as i32,
+            cmd: cmd.clone(),
+            path: vec![],
+        };
+
+        // statements need a namespace
+        let status = service
+            .get_flight_info(tonic::Request::new(descriptor.clone()))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), tonic::Code::InvalidArgument);
+        assert!(status.message().contains(NAMESPACE_HEADER));
+
+        let info = service
+            .get_flight_info(namespace_request(descriptor))
+            .await
+            .unwrap()
+            .into_inner();
+        let ticket = info.endpoint[0].ticket.clone().unwrap();
+        assert_eq!(ticket.ticket, cmd);
+
+        let batches = collect(service.do_get(namespace_request(ticket)).await.unwrap()).await;
+        arrow_util::assert_batches_eq!(
+            &["+-----+", "| one |", "+-----+", "| 1   |", "+-----+"],
+            &batches
+        );
+    }
+
+    #[tokio::test]
+    async fn test_flight_sql_schema_matches_stream() {
+        use arrow_flight::{sql::CommandStatementQuery, IpcMessage};
+        use iox_query::test::TestChunk;
+
+        let test_storage = Arc::new(TestDatabaseStore::new());
+        test_storage.db_or_create("my_db").await.add_chunk(
+            "p1",
+            Arc::new(
+                TestChunk::new("h2o")
+                    .with_id(1)
+                    .with_tag_column("state")
+                    .with_time_column()
+                    .with_one_row_of_data(),
+            ),
+        );
+        let service = FlightService::new(Arc::clone(&test_storage));
+
+        let cmd = CommandStatementQuery {
+            query: "SELECT state, time FROM h2o".to_string(),
+        }
+        .as_any()
+        .encode_to_vec();
+        let descriptor = FlightDescriptor {
+            r#type: arrow_flight::flight_descriptor::DescriptorType::Cmd as i32,
+            cmd: cmd.clone(),
+            path: vec![],
+        };
+
+        let info = service
+            .get_flight_info(namespace_request(descriptor.clone()))
+            .await
+            .unwrap()
+            .into_inner();
+        let info_schema = arrow::datatypes::Schema::try_from(IpcMessage(info.schema)).unwrap();
+
+        let schema_result = service
+            .get_schema(namespace_request(descriptor))
+            .await
+            .unwrap()
+            .into_inner();
+        let schema_result = arrow::datatypes::Schema::try_from(&schema_result).unwrap();
+
+        let ticket = info.endpoint[0].ticket.clone().unwrap();
+        let batches = collect(service.do_get(namespace_request(ticket)).await.unwrap()).await;
+        let streamed = batches[0].schema();
+
+        // tags are dictionaries in the plan, but streamed as strings
+        assert_eq!(
+            streamed.field_with_name("state").unwrap().data_type(),
+            &arrow::datatypes::DataType::Utf8
+        );
+        assert_eq!(info_schema.fields(), streamed.fields());
+        assert_eq!(schema_result.fields(), streamed.fields());
+    }
+
+    #[tokio::test]
+    async fn test_flight_sql_prepared_statement() {
+        use arrow_flight::sql::{
+            ActionCreatePreparedStatementResult, CommandPreparedStatementQuery,
+        };
+
+        let test_storage = Arc::new(TestDatabaseStore::new());
+        test_storage.db_or_create("my_db").await;
+        let service = FlightService::new(Arc::clone(&test_storage));
+
+        let action = Action {
+            r#type: flightsql::CREATE_PREPARED_STATEMENT.to_string(),
+            body: ActionCreatePreparedStatementRequest {
+                query: "SELECT 2 AS two".to_string(),
+            }
+            .as_any()
+            .encode_to_vec(),
+        };
+        let mut results = service
+            .do_action(namespace_request(action))
+            .await
+            .unwrap()
+            .into_inner();
+        let result = results.next().await.unwrap().unwrap();
+        let any = prost_types::Any::decode(result.body.as_slice()).unwrap();
+        let result = ActionCreatePreparedStatementResult::decode(any.value.as_slice()).unwrap();
+        assert!(!result.dataset_schema.is_empty());
+
+        let ticket = Ticket {
+            ticket: CommandPreparedStatementQuery {
+                prepared_statement_handle: result.prepared_statement_handle.clone(),
+            }
+            .as_any()
+            .encode_to_vec(),
+        };
+        let batches = collect(service.do_get(namespace_request(ticket)).await.unwrap()).await;
+        arrow_util::assert_batches_eq!(
+            &["+-----+", "| two |", "+-----+", "| 2   |", "+-----+"],
+            &batches
+        );
+
+        let action = Action {
+            r#type: flightsql::CLOSE_PREPARED_STATEMENT.to_string(),
+            body: ActionClosePreparedStatementRequest {
+                prepared_statement_handle: result.prepared_statement_handle,
+            }
+            .as_any()
+            .encode_to_vec(),
+        };
+        let mut results = service
+            .do_action(tonic::Request::new(action))
+            .await
+            .unwrap()
+            .into_inner();
+        assert!(results.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_flight_sql_metadata() {
+        use arrow_flight::sql::{CommandGetCatalogs, CommandGetDbSchemas, CommandGetTables};
+
+        let test_storage = Arc::new(TestDatabaseStore::new());
+        test_storage.db_or_create("my_db").await;
+        test_storage.db_or_create("other_db").await;
+        let service = FlightService::new(Arc::clone(&test_storage));
+
+        let ticket = Ticket {
+            ticket: CommandGetCatalogs {}.as_any().encode_to_vec(),
+        };
+        let batches = collect(service.do_get(tonic::Request::new(ticket)).await.unwrap()).await;
+        arrow_util::assert_batches_eq!(
+            &[
+                "+--------------+",
+                "| catalog_name |",
+                "+--------------+",
+                "| my_db        |",
+                "| other_db     |",
+                "+--------------+",
+            ],
+            &batches
+        );
+
+        let ticket = Ticket {
+            ticket: CommandGetDbSchemas {
+                catalog: Some("my_db".to_string()),
+                db_schema_filter_pattern: Some("io%".to_string()),
+            }
+            .as_any()
+            .encode_to_vec(),
+        };
+        let batches = collect(service.do_get(tonic::Request::new(ticket)).await.unwrap()).await;
+        arrow_util::assert_batches_eq!(
+            &[
+                "+--------------+----------------+",
+                "| catalog_name | db_schema_name |",
+                "+--------------+----------------+",
+                "| my_db        | iox            |",
+                "+--------------+----------------+",
+            ],
+            &batches
+        );
+
+        let ticket = Ticket {
+            ticket: CommandGetTables {
+                catalog: None,
+                db_schema_filter_pattern: Some("information_schema".to_string()),
+                table_name_filter_pattern: Some("tab%".to_string()),
+                table_types: vec![],
+                include_schema: false,
+            }
+            .as_any()
+            .encode_to_vec(),
+        };
+        let batches = collect(service.do_get(tonic::Request::new(ticket)).await.unwrap()).await;
+        let tables = batches[0].project(&[0, 1, 2]).unwrap();
+        arrow_util::assert_batches_eq!(
+            &[
+                "+--------------+--------------------+------------+",
+                "| catalog_name | db_schema_name     | table_name |",
+                "+--------------+--------------------+------------+",
+                "| my_db        | information_schema | tables     |",
+                "| other_db     | information_schema | tables     |",
+                "+--------------+--------------------+------------+",
+            ],
+            &[tables]
+        );
+    }
+
+    fn namespace_request<T>(message: T) -> tonic::Request<T> {
+        let mut request = tonic::Request::new(message);
+        request
+            .metadata_mut()
+            .insert(NAMESPACE_HEADER, "my_db".parse().unwrap());
+        request
+    }
+
+    async fn collect(response: Response<TonicStream<FlightData>>) -> Vec<RecordBatch> {
+        let flight_data: Vec<_> = response
+            .into_inner()
+            .map(|data| data.unwrap())
+            .collect()
+            .await;
+
+        let schema = Arc::new(arrow::datatypes::Schema::try_from(&flight_data[0]).unwrap());
+        flight_data[1..]
+            .iter()
+            .map(|data| {
+                arrow_flight::utils::flight_data_to_arrow_batch(
+                    data,
+                    Arc::clone(&schema),
+                    &Default::default(),
+                )
+                .unwrap()
+            })
+            .collect()
+    }
+
     /// Assert that given future is pending.
     ///
     /// This will try to poll the future a bit to ensure that it is not stuck in tokios task preemption.
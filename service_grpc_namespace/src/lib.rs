@@ -0,0 +1,861 @@
+//! gRPC service for managing the lifecycle of namespaces. Used in router, but can be included in
+//! any gRPC server.
+
+#![deny(rustdoc::broken_intra_doc_links, rustdoc::bare_urls, rust_2018_idioms)]
+#![warn(
+    missing_copy_implementations,
+    missing_debug_implementations,
+    missing_docs,
+    clippy::explicit_iter_loop,
+    clippy::future_not_send,
+    clippy::use_self,
+    clippy::clone_on_ref_ptr
+)]
+
+use data_types::{
+    parse_retention_duration, ColumnType, DatabaseName, KafkaTopicId, PartitionTemplate,
+    QueryPoolId,
+};
+use generated_types::influxdata::iox::{
+    namespace::v1::*, partition_template::v1 as template_proto,
+};
+use iox_catalog::{
+    interface::{Catalog, Error as CatalogError},
+    INFINITE_RETENTION_POLICY,
+};
+use observability_deps::tracing::*;
+use schema::TIME_COLUMN_NAME;
+use std::{fmt::Debug, sync::Arc};
+use tonic::{Request, Response, Status};
+
+/// Notified by the [`NamespaceService`] after it changed the catalog state of a namespace, so
+/// that any cached copy of the namespace (or its schema) can be invalidated.
+///
+/// Only observers in the process serving the request are notified. Other routers and queriers
+/// keep using their cached copy until it expires, so a deleted namespace or a retention change
+/// is observed by them only after their cache TTL has elapsed.
+pub trait NamespaceChangeObserver: Debug + Send + Sync {
+    /// The namespace named `namespace` was changed in the catalog.
+    fn namespace_changed(&self, namespace: &str);
+}
+
+/// Implementation of the Namespace gRPC service
+#[derive(Debug)]
+pub struct NamespaceService {
+    /// Catalog.
+    catalog: Arc<dyn Catalog>,
+    /// The kafka topic new namespaces are assigned to.
+    kafka_topic_id: KafkaTopicId,
+    /// The query pool new namespaces are assigned to.
+    query_pool_id: QueryPoolId,
+    /// Notified of namespace changes, if set.
+    change_observer: Option<Arc<dyn NamespaceChangeObserver>>,
+}
+
+impl NamespaceService {
+    /// Create a new namespace service with the given catalog, creating namespaces in the given
+    /// kafka topic and query pool.
+    pub fn new(
+        catalog: Arc<dyn Catalog>,
+        kafka_topic_id: KafkaTopicId,
+        query_pool_id: QueryPoolId,
+    ) -> Self {
+        Self {
+            catalog,
+            kafka_topic_id,
+            query_pool_id,
+            change_observer: None,
+        }
+    }
+
+    /// Notify `observer` whenever this service changes an existing namespace.
+    pub fn with_change_observer(mut self, observer: Arc<dyn NamespaceChangeObserver>) -> Self {
+        self.change_observer = Some(observer);
+        self
+    }
+
+    fn notify_changed(&self, namespace: &str) {
+        if let Some(observer) = &self.change_observer {
+            observer.namespace_changed(namespace);
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl namespace_service_server::NamespaceService for NamespaceService {
+    async fn get_namespaces(
+        &self,
+        _request: Request<GetNamespacesRequest>,
+    ) -> Result<Response<GetNamespacesResponse>, Status> {
+        let mut repos = self.catalog.repositories().await;
+
+        let namespaces = repos.namespaces().list().await.map_err(|e| {
+            warn!(error=%e, "failed to list namespaces");
+            to_status(e)
+        })?;
+
+        Ok(Response::new(GetNamespacesResponse {
+            namespaces: namespaces.into_iter().map(to_namespace).collect(),
+        }))
+    }
+
+    async fn create_namespace(
+        &self,
+        request: Request<CreateNamespaceRequest>,
+    ) -> Result<Response<CreateNamespaceResponse>, Status> {
+        let req = request.into_inner();
+        let name =
+            DatabaseName::new(req.name).map_err(|e| Status::invalid_argument(e.to_string()))?;
+        let retention_duration = validate_retention(&req.retention_duration)?;
+        let partition_template = validate_partition_template(req.partition_template)?;
+
+        // Create the namespace with its partition template atomically
+        let mut txn = self.catalog.start_transaction().await.map_err(to_status)?;
+        let mut namespace = txn
+            .namespaces()
+            .create(
+                name.as_str(),
+                retention_duration,
+                self.kafka_topic_id,
+                self.query_pool_id,
+            )
+            .await
+            .map_err(|e| {
+                warn!(error=%e, %name, "failed to create namespace");
+                to_status(e)
+            })?;
+        if partition_template.is_some() {
+            namespace = txn
+                .namespaces()
+                .update_partition_template(name.as_str(), partition_template.as_ref())
+                .await
+                .map_err(to_status)?;
+        }
+        txn.commit().await.map_err(|e| {
+            warn!(error=%e, %name, "failed to create namespace");
+            to_status(e)
+        })?;
+
+        info!(%name, namespace_id=%namespace.id, "created namespace");
+        Ok(Response::new(CreateNamespaceResponse {
+            namespace: Some(to_namespace(namespace)),
+        }))
+    }
+
+    async fn update_namespace_retention(
+        &self,
+        request: Request<UpdateNamespaceRetentionRequest>,
+    ) -> Result<Response<UpdateNamespaceRetentionResponse>, Status> {
+        let req = request.into_inner();
+        let retention_duration = validate_retention(&req.retention_duration)?;
+
+        let mut repos = self.catalog.repositories().await;
+        let namespace = repos
+            .namespaces()
+            .update_retention_duration(&req.name, retention_duration)
+            .await
+            .map_err(|e| {
+                warn!(error=%e, %req.name, "failed to update namespace retention");
+                to_status(e)
+            })?;
+        self.notify_changed(&req.name);
+
+        Ok(Response::new(UpdateNamespaceRetentionResponse {
+            namespace: Some(to_namespace(namespace)),
+        }))
+    }
+
+    async fn update_namespace_limits(
+        &self,
+        request: Request<UpdateNamespaceLimitsRequest>,
+    ) -> Result<Response<UpdateNamespaceLimitsResponse>, Status> {
+        let req = request.into_inner();
+        if req.max_tables.is_none() && req.max_columns_per_table.is_none() {
+            return Err(Status::invalid_argument(
+                "at least one of max_tables or max_columns_per_table must be set",
+            ));
+        }
+        if let Some(v) = req.max_tables.filter(|v| *v <= 0) {
+            return Err(Status::invalid_argument(format!(
+                "max_tables must be positive, got {}",
+                v
+            )));
+        }
+        if let Some(v) = req.max_columns_per_table.filter(|v| *v <= 0) {
+            return Err(Status::invalid_argument(format!(
+                "max_columns_per_table must be positive, got {}",
+                v
+            )));
+        }
+
+        // Apply both limits atomically
+        let mut txn = self.catalog.start_transaction().await.map_err(to_status)?;
+
+        let mut namespace = None;
+        if let Some(v) = req.max_tables {
+            namespace = Some(
+                txn.namespaces()
+                    .update_table_limit(&req.name, v)
+                    .await
+                    .map_err(to_status)?,
+            );
+        }
+        if let Some(v) = req.max_columns_per_table {
+            namespace = Some(
+                txn.namespaces()
+                    .update_column_limit(&req.name, v)
+                    .await
+                    .map_err(to_status)?,
+            );
+        }
+
+        txn.commit().await.map_err(|e| {
+            warn!(error=%e, %req.name, "failed to update namespace limits");
+            to_status(e)
+        })?;
+        self.notify_changed(&req.name);
+
+        Ok(Response::new(UpdateNamespaceLimitsResponse {
+            namespace: namespace.map(to_namespace),
+        }))
+    }
+
+    async fn delete_namespace(
+        &self,
+        request: Request<DeleteNamespaceRequest>,
+    ) -> Result<Response<DeleteNamespaceResponse>, Status> {
+        let req = request.into_inner();
+
+        let mut repos = self.catalog.repositories().await;
+        let namespace = repos
+            .namespaces()
+            .soft_delete(&req.name)
+            .await
+            .map_err(|e| {
+                warn!(error=%e, %req.name, "failed to delete namespace");
+                to_status(e)
+            })?;
+
+        self.notify_changed(&req.name);
+
+        info!(%req.name, namespace_id=%namespace.id, "soft deleted namespace");
+        Ok(Response::new(DeleteNamespaceResponse {
+            namespace: Some(to_namespace(namespace)),
+        }))
+    }
+
+    async fn restore_namespace(
+        &self,
+        request: Request<RestoreNamespaceRequest>,
+    ) -> Result<Response<RestoreNamespaceResponse>, Status> {
+        let req = request.into_inner();
+
+        let mut repos = self.catalog.repositories().await;
+        let namespace = repos.namespaces().restore(&req.name).await.map_err(|e| {
+            warn!(error=%e, %req.name, "failed to restore namespace");
+            to_status(e)
+        })?;
+
+        self.notify_changed(&req.name);
+
+        info!(%req.name, namespace_id=%namespace.id, "restored namespace");
+        Ok(Response::new(RestoreNamespaceResponse {
+            namespace: Some(to_namespace(namespace)),
+        }))
+    }
+
+    async fn drop_table(
+        &self,
+        request: Request<DropTableRequest>,
+    ) -> Result<Response<DropTableResponse>, Status> {
+        let req = request.into_inner();
+
+        // Drop the table and flag its files for deletion atomically
+        let mut txn = self.catalog.start_transaction().await.map_err(to_status)?;
+
+        let namespace = txn
+            .namespaces()
+            .get_by_name(&req.namespace)
+            .await
+            .map_err(to_status)?
+            .ok_or_else(|| Status::not_found(format!("namespace {} not found", req.namespace)))?;
+        let table = txn
+            .tables()
+            .get_by_namespace_and_name(namespace.id, &req.table)
+            .await
+            .map_err(to_status)?
+            .filter(|t| t.deleted_at.is_none())
+            .ok_or_else(|| Status::not_found(format!("table {} not found", req.table)))?;
+
+        txn.tables()
+            .soft_delete(table.id)
+            .await
+            .map_err(to_status)?;
+
+        let files = txn
+            .parquet_files()
+            .list_by_table_not_to_delete(table.id)
+            .await
+            .map_err(to_status)?;
+        for file in &files {
+            txn.parquet_files()
+                .flag_for_delete(file.id)
+                .await
+                .map_err(to_status)?;
+        }
+
+        txn.commit().await.map_err(|e| {
+            warn!(error=%e, %req.namespace, %req.table, "failed to drop table");
+            to_status(e)
+        })?;
+        self.notify_changed(&req.namespace);
+
+        info!(
+            %req.namespace,
+            %req.table,
+            table_id=%table.id,
+            n_files=files.len(),
+            "dropped table"
+        );
+        Ok(Response::new(DropTableResponse {
+            table_id: table.id.get(),
+            parquet_files_flagged: files.len() as i64,
+        }))
+    }
+
+    async fn drop_column(
+        &self,
+        request: Request<DropColumnRequest>,
+    ) -> Result<Response<DropColumnResponse>, Status> {
+        let req = request.into_inner();
+
+        let mut repos = self.catalog.repositories().await;
+
+        let namespace = repos
+            .namespaces()
+            .get_by_name(&req.namespace)
+            .await
+            .map_err(to_status)?
+            .ok_or_else(|| Status::not_found(format!("namespace {} not found", req.namespace)))?;
+        let table = repos
+            .tables()
+            .get_by_namespace_and_name(namespace.id, &req.table)
+            .await
+            .map_err(to_status)?
+            .filter(|t| t.deleted_at.is_none())
+            .ok_or_else(|| Status::not_found(format!("table {} not found", req.table)))?;
+        let column = repos
+            .columns()
+            .list_by_table_id(table.id)
+            .await
+            .map_err(to_status)?
+            .into_iter()
+            .find(|c| c.name == req.column && c.deleted_at.is_none())
+            .ok_or_else(|| Status::not_found(format!("column {} not found", req.column)))?;
+
+        if column.column_type == ColumnType::Time as i16 {
+            return Err(Status::invalid_argument(
+                "the time column cannot be dropped",
+            ));
+        }
+
+        repos.columns().soft_delete(column.id).await.map_err(|e| {
+            warn!(error=%e, %req.namespace, %req.table, %req.column, "failed to drop column");
+            to_status(e)
+        })?;
+        self.notify_changed(&req.namespace);
+
+        info!(
+            %req.namespace,
+            %req.table,
+            %req.column,
+            column_id=%column.id,
+            "dropped column"
+        );
+        Ok(Response::new(DropColumnResponse {
+            column_id: column.id.get(),
+        }))
+    }
+
+    async fn update_table_partition_template(
+        &self,
+        request: Request<UpdateTablePartitionTemplateRequest>,
+    ) -> Result<Response<UpdateTablePartitionTemplateResponse>, Status> {
+        let req = request.into_inner();
+        if req.table.is_empty() {
+            return Err(Status::invalid_argument("table name must not be empty"));
+        }
+        let partition_template = validate_partition_template(req.partition_template)?;
+
+        // Create the table and set its template atomically, so that no write
+        // can be partitioned with the namespace template in between.
+        let mut txn = self.catalog.start_transaction().await.map_err(to_status)?;
+
+        let namespace = txn
+            .namespaces()
+            .get_by_name(&req.namespace)
+            .await
+            .map_err(to_status)?
+            .ok_or_else(|| Status::not_found(format!("namespace {} not found", req.namespace)))?;
+        let table = txn
+            .tables()
+            .create_or_get(&req.table, namespace.id)
+            .await
+            .map_err(to_status)?;
+        // All tables have a time column, see `validate_or_insert_schema`.
+        txn.columns()
+            .create_or_get(TIME_COLUMN_NAME, table.id, ColumnType::Time)
+            .await
+            .map_err(to_status)?;
+        let table = txn
+            .tables()
+            .update_partition_template(table.id, partition_template.as_ref())
+            .await
+            .map_err(to_status)?;
+
+        txn.commit().await.map_err(|e| {
+            warn!(error=%e, %req.namespace, %req.table, "failed to update table partition template");
+            to_status(e)
+        })?;
+        self.notify_changed(&req.namespace);
+
+        info!(
+            %req.namespace,
+            %req.table,
+            table_id=%table.id,
+            partition_template=?table.partition_template,
+            "updated table partition template"
+        );
+        Ok(Response::new(UpdateTablePartitionTemplateResponse {
+            table_id: table.id.get(),
+            partition_template: table.partition_template.map(Into::into),
+        }))
+    }
+}
+
+/// Convert and validate a requested partition template, rejecting templates
+/// that would fail to partition writes.
+fn validate_partition_template(
+    partition_template: Option<template_proto::PartitionTemplate>,
+) -> Result<Option<PartitionTemplate>, Status> {
+    let partition_template = match partition_template {
+        Some(v) => {
+            PartitionTemplate::try_from(v).map_err(|e| Status::invalid_argument(e.to_string()))?
+        }
+        None => return Ok(None),
+    };
+
+    partition_template
+        .validate()
+        .map_err(|e| Status::invalid_argument(e.to_string()))?;
+    Ok(Some(partition_template))
+}
+
+/// Validate a requested retention duration, defaulting an empty one to infinite.
+fn validate_retention(retention_duration: &str) -> Result<&str, Status> {
+    let retention_duration = retention_duration.trim();
+    if retention_duration.is_empty() {
+        return Ok(INFINITE_RETENTION_POLICY);
+    }
+
+    parse_retention_duration(retention_duration)
+        .map_err(|e| Status::invalid_argument(e.to_string()))?;
+    Ok(retention_duration)
+}
+
+fn to_status(e: CatalogError) -> Status {
+    match e {
+        CatalogError::NameExists { .. } => Status::already_exists(e.to_string()),
+        CatalogError::NamespaceNotFoundByName { .. }
+        | CatalogError::TableNotFound { .. }
+        | CatalogError::ColumnNotFound { .. } => Status::not_found(e.to_string()),
+        CatalogError::TableCreateLimitError { .. } => Status::failed_precondition(e.to_string()),
+        _ => Status::internal(e.to_string()),
+    }
+}
+
+fn to_namespace(namespace: data_types::Namespace) -> Namespace {
+    Namespace {
+        id: namespace.id.get(),
+        name: namespace.name,
+        retention_duration: namespace
+            .retention_duration
+            .unwrap_or_else(|| INFINITE_RETENTION_POLICY.to_string()),
+        max_tables: namespace.max_tables,
+        max_columns_per_table: namespace.max_columns_per_table,
+        deleted_at: namespace.deleted_at.map(|t| t.get()),
+        partition_template: namespace.partition_template.map(Into::into),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use generated_types::influxdata::iox::namespace::v1::namespace_service_server::NamespaceService;
+    use iox_catalog::{interface::get_schema_by_name, mem::MemCatalog};
+    use std::sync::Mutex;
+    use tonic::Code;
+
+    /// Records the names of the changed namespaces.
+    #[derive(Debug, Default)]
+    struct MockObserver {
+        changed: Mutex<Vec<String>>,
+    }
+
+    impl NamespaceChangeObserver for MockObserver {
+        fn namespace_changed(&self, namespace: &str) {
+            self.changed.lock().unwrap().push(namespace.to_string());
+        }
+    }
+
+    async fn service() -> super::NamespaceService {
+        let metrics = Arc::new(metric::Registry::default());
+        let catalog: Arc<dyn Catalog> = Arc::new(MemCatalog::new(metrics));
+        let mut repos = catalog.repositories().await;
+        let kafka = repos
+            .kafka_topics()
+            .create_or_get("iox_shared")
+            .await
+            .unwrap();
+        let pool = repos
+            .query_pools()
+            .create_or_get("iox_shared")
+            .await
+            .unwrap();
+        drop(repos);
+
+        super::NamespaceService::new(catalog, kafka.id, pool.id)
+    }
+
+    async fn create(grpc: &super::NamespaceService, name: &str, retention: &str) -> Namespace {
+        grpc.create_namespace(Request::new(CreateNamespaceRequest {
+            name: name.to_string(),
+            retention_duration: retention.to_string(),
+            partition_template: None,
+        }))
+        .await
+        .expect("rpc request should succeed")
+        .into_inner()
+        .namespace
+        .unwrap()
+    }
+
+    async fn list(grpc: &super::NamespaceService) -> Vec<Namespace> {
+        grpc.get_namespaces(Request::new(GetNamespacesRequest {}))
+            .await
+            .expect("rpc request should succeed")
+            .into_inner()
+            .namespaces
+    }
+
+    #[tokio::test]
+    async fn create_and_update_namespace() {
+        let grpc = service().await;
+
+        let namespace = create(&grpc, "bananas", "").await;
+        assert_eq!(namespace.name, "bananas");
+        assert_eq!(namespace.retention_duration, "inf");
+        assert_eq!(namespace.deleted_at, None);
+        assert_eq!(list(&grpc).await, vec![namespace.clone()]);
+
+        let status = grpc
+            .create_namespace(Request::new(CreateNamespaceRequest {
+                name: "bananas".to_string(),
+                retention_duration: "".to_string(),
+                partition_template: None,
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), Code::AlreadyExists);
+
+        let status = grpc
+            .create_namespace(Request::new(CreateNamespaceRequest {
+                name: "platanos".to_string(),
+                retention_duration: "not a duration".to_string(),
+                partition_template: None,
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), Code::InvalidArgument);
+
+        let updated = grpc
+            .update_namespace_retention(Request::new(UpdateNamespaceRetentionRequest {
+                name: "bananas".to_string(),
+                retention_duration: "30d".to_string(),
+            }))
+            .await
+            .expect("rpc request should succeed")
+            .into_inner()
+            .namespace
+            .unwrap();
+        assert_eq!(updated.retention_duration, "30d");
+
+        let updated = grpc
+            .update_namespace_limits(Request::new(UpdateNamespaceLimitsRequest {
+                name: "bananas".to_string(),
+                max_tables: Some(42),
+                max_columns_per_table: None,
+            }))
+            .await
+            .expect("rpc request should succeed")
+            .into_inner()
+            .namespace
+            .unwrap();
+        assert_eq!(updated.max_tables, 42);
+        assert_eq!(
+            updated.max_columns_per_table,
+            namespace.max_columns_per_table
+        );
+
+        let status = grpc
+            .update_namespace_limits(Request::new(UpdateNamespaceLimitsRequest {
+                name: "bananas".to_string(),
+                max_tables: None,
+                max_columns_per_table: Some(0),
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), Code::InvalidArgument);
+
+        let status = grpc
+            .update_namespace_retention(Request::new(UpdateNamespaceRetentionRequest {
+                name: "platanos".to_string(),
+                retention_duration: "30d".to_string(),
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), Code::NotFound);
+    }
+
+    #[tokio::test]
+    async fn delete_and_restore_namespace() {
+        let grpc = service().await;
+        let namespace = create(&grpc, "bananas", "1h").await;
+
+        let deleted = grpc
+            .delete_namespace(Request::new(DeleteNamespaceRequest {
+                name: "bananas".to_string(),
+            }))
+            .await
+            .expect("rpc request should succeed")
+            .into_inner()
+            .namespace
+            .unwrap();
+        assert!(deleted.deleted_at.is_some());
+        assert!(list(&grpc).await.is_empty());
+
+        let status = grpc
+            .delete_namespace(Request::new(DeleteNamespaceRequest {
+                name: "bananas".to_string(),
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), Code::NotFound);
+
+        let restored = grpc
+            .restore_namespace(Request::new(RestoreNamespaceRequest {
+                name: "bananas".to_string(),
+            }))
+            .await
+            .expect("rpc request should succeed")
+            .into_inner()
+            .namespace
+            .unwrap();
+        assert_eq!(restored, namespace);
+        assert_eq!(list(&grpc).await, vec![namespace]);
+
+        let status = grpc
+            .restore_namespace(Request::new(RestoreNamespaceRequest {
+                name: "bananas".to_string(),
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), Code::NotFound);
+    }
+
+    #[tokio::test]
+    async fn drop_table_and_column() {
+        let observer = Arc::new(MockObserver::default());
+        let grpc = service()
+            .await
+            .with_change_observer(Arc::clone(&observer) as _);
+        let namespace = create(&grpc, "bananas", "").await;
+
+        let mut repos = grpc.catalog.repositories().await;
+        let namespace_id = data_types::NamespaceId::new(namespace.id);
+        for table_name in ["platanos", "fruit"] {
+            let table = repos
+                .tables()
+                .create_or_get(table_name, namespace_id)
+                .await
+                .unwrap();
+            for (name, column_type) in [("time", ColumnType::Time), ("color", ColumnType::Tag)] {
+                repos
+                    .columns()
+                    .create_or_get(name, table.id, column_type)
+                    .await
+                    .unwrap();
+            }
+        }
+        drop(repos);
+
+        let dropped = grpc
+            .drop_table(Request::new(DropTableRequest {
+                namespace: "bananas".to_string(),
+                table: "platanos".to_string(),
+            }))
+            .await
+            .expect("rpc request should succeed")
+            .into_inner();
+        assert_eq!(dropped.parquet_files_flagged, 0);
+
+        let status = grpc
+            .drop_table(Request::new(DropTableRequest {
+                namespace: "bananas".to_string(),
+                table: "platanos".to_string(),
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), Code::NotFound);
+
+        grpc.drop_column(Request::new(DropColumnRequest {
+            namespace: "bananas".to_string(),
+            table: "fruit".to_string(),
+            column: "color".to_string(),
+        }))
+        .await
+        .expect("rpc request should succeed");
+
+        let status = grpc
+            .drop_column(Request::new(DropColumnRequest {
+                namespace: "bananas".to_string(),
+                table: "fruit".to_string(),
+                column: "time".to_string(),
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), Code::InvalidArgument);
+
+        let status = grpc
+            .drop_column(Request::new(DropColumnRequest {
+                namespace: "bananas".to_string(),
+                table: "platanos".to_string(),
+                column: "color".to_string(),
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), Code::NotFound);
+
+        let mut repos = grpc.catalog.repositories().await;
+        let schema = get_schema_by_name("bananas", repos.as_mut()).await.unwrap();
+        assert_eq!(schema.tables.keys().collect::<Vec<_>>(), ["fruit"]);
+        assert_eq!(
+            schema.tables["fruit"].columns.keys().collect::<Vec<_>>(),
+            ["time"]
+        );
+
+        // Only the successful drops notify the observer
+        assert_eq!(*observer.changed.lock().unwrap(), ["bananas", "bananas"]);
+    }
+
+    #[tokio::test]
+    async fn partition_templates() {
+        use template_proto::{template_part::Part, TemplatePart};
+
+        let template = |parts: Vec<Part>| template_proto::PartitionTemplate {
+            parts: parts
+                .into_iter()
+                .map(|p| TemplatePart { part: Some(p) })
+                .collect(),
+        };
+
+        let observer = Arc::new(MockObserver::default());
+        let grpc = service()
+            .await
+            .with_change_observer(Arc::clone(&observer) as _);
+
+        let hourly = template(vec![Part::TimeFormat("%Y-%m-%d %H".to_string())]);
+        let namespace = grpc
+            .create_namespace(Request::new(CreateNamespaceRequest {
+                name: "bananas".to_string(),
+                retention_duration: "".to_string(),
+                partition_template: Some(hourly.clone()),
+            }))
+            .await
+            .expect("rpc request should succeed")
+            .into_inner()
+            .namespace
+            .unwrap();
+        assert_eq!(namespace.partition_template, Some(hourly.clone()));
+        assert_eq!(list(&grpc).await, vec![namespace]);
+
+        // Templates that cannot be rendered are rejected, without creating the namespace
+        for invalid in [
+            template(vec![]),
+            template(vec![Part::TimeFormat("%Q".to_string())]),
+            template(vec![Part::RegexCapture(template_proto::RegexCapture {
+                column: "host".to_string(),
+                regex: "([a-z]+".to_string(),
+                replacement: "$1".to_string(),
+            })]),
+            template_proto::PartitionTemplate {
+                parts: vec![TemplatePart { part: None }],
+            },
+        ] {
+            let status = grpc
+                .create_namespace(Request::new(CreateNamespaceRequest {
+                    name: "platanos".to_string(),
+                    retention_duration: "".to_string(),
+                    partition_template: Some(invalid),
+                }))
+                .await
+                .unwrap_err();
+            assert_eq!(status.code(), Code::InvalidArgument);
+        }
+        assert_eq!(list(&grpc).await.len(), 1);
+
+        // Setting a table template creates the table
+        let by_region = template(vec![Part::Column("region".to_string())]);
+        let updated = grpc
+            .update_table_partition_template(Request::new(UpdateTablePartitionTemplateRequest {
+                namespace: "bananas".to_string(),
+                table: "cpu".to_string(),
+                partition_template: Some(by_region.clone()),
+            }))
+            .await
+            .expect("rpc request should succeed")
+            .into_inner();
+        assert_eq!(updated.partition_template, Some(by_region));
+
+        let status = grpc
+            .update_table_partition_template(Request::new(UpdateTablePartitionTemplateRequest {
+                namespace: "platanos".to_string(),
+                table: "cpu".to_string(),
+                partition_template: None,
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), Code::NotFound);
+
+        let mut repos = grpc.catalog.repositories().await;
+        let schema = get_schema_by_name("bananas", repos.as_mut()).await.unwrap();
+        assert_eq!(
+            schema.partition_template,
+            Some(PartitionTemplate::try_from(hourly).unwrap())
+        );
+        assert_eq!(
+            schema.partition_template_for("cpu"),
+            Some(&PartitionTemplate {
+                parts: vec![data_types::TemplatePart::Column("region".to_string())]
+            })
+        );
+        assert_eq!(schema.tables["cpu"].id.get(), updated.table_id);
+        assert_eq!(
+            schema.tables["cpu"].columns.keys().collect::<Vec<_>>(),
+            ["time"]
+        );
+
+        // Only changes to existing namespaces notify the observer
+        assert_eq!(*observer.changed.lock().unwrap(), ["bananas"]);
+    }
+}
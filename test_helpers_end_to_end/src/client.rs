@@ -5,7 +5,7 @@ use http::Response;
 use hyper::{Body, Client, Request};
 use influxdb_iox_client::{
     connection::Connection,
-    flight::generated_types::ReadInfo,
+    flight::generated_types::{QueryType, ReadInfo},
     write::generated_types::{DatabaseBatch, TableBatch, WriteRequest, WriteResponse},
     write_info::generated_types::{merge_responses, GetWriteInfoResponse, KafkaPartitionStatus},
 };
@@ -243,6 +243,8 @@ pub async fn try_run_query(
         .perform_query(ReadInfo {
             namespace_name: namespace,
             sql_query: sql,
+            params: vec![],
+            query_type: QueryType::Sql.into(),
         })
         .await?;
 